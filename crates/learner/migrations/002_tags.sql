@@ -0,0 +1,10 @@
+-- Free-form tags attached to papers, e.g. "to-read" or "important"
+CREATE TABLE IF NOT EXISTS tags (
+    paper_id INTEGER NOT NULL,
+    tag TEXT NOT NULL COLLATE NOCASE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY(paper_id) REFERENCES papers(id) ON DELETE CASCADE,
+    UNIQUE(paper_id, tag)
+) STRICT;
+
+CREATE INDEX IF NOT EXISTS idx_tags_tag ON tags(tag);
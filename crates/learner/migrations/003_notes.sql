@@ -0,0 +1,30 @@
+-- Free-text (Markdown) notes on papers, any number per paper
+CREATE TABLE IF NOT EXISTS notes (
+    id INTEGER PRIMARY KEY,
+    paper_id INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY(paper_id) REFERENCES papers(id) ON DELETE CASCADE
+) STRICT;
+
+CREATE INDEX IF NOT EXISTS idx_notes_paper_id ON notes(paper_id);
+
+-- Keep the text of all of a paper's notes in the search index
+CREATE TRIGGER IF NOT EXISTS notes_ai AFTER INSERT ON notes BEGIN
+    UPDATE papers_fts
+    SET notes = (SELECT group_concat(content, ' ') FROM notes WHERE paper_id = new.paper_id)
+    WHERE rowid = new.paper_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS notes_ad AFTER DELETE ON notes BEGIN
+    UPDATE papers_fts
+    SET notes = coalesce(
+        (SELECT group_concat(content, ' ') FROM notes WHERE paper_id = old.paper_id), '')
+    WHERE rowid = old.paper_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS notes_au AFTER UPDATE OF content ON notes BEGIN
+    UPDATE papers_fts
+    SET notes = (SELECT group_concat(content, ' ') FROM notes WHERE paper_id = new.paper_id)
+    WHERE rowid = new.paper_id;
+END;
@@ -0,0 +1,18 @@
+-- Named groups of papers, e.g. a reading list or the references of a chapter
+CREATE TABLE IF NOT EXISTS collections (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    description TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+) STRICT;
+
+CREATE TABLE IF NOT EXISTS collection_papers (
+    collection_id INTEGER NOT NULL,
+    paper_id INTEGER NOT NULL,
+    added_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY(collection_id) REFERENCES collections(id) ON DELETE CASCADE,
+    FOREIGN KEY(paper_id) REFERENCES papers(id) ON DELETE CASCADE,
+    UNIQUE(collection_id, paper_id)
+) STRICT;
+
+CREATE INDEX IF NOT EXISTS idx_collection_papers_paper_id ON collection_papers(paper_id);
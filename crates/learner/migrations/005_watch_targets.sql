@@ -0,0 +1,9 @@
+-- Categories whose newly submitted papers the daemon saves, e.g. 'cs.CR' on 'arxiv'
+CREATE TABLE IF NOT EXISTS watch_targets (
+    id INTEGER PRIMARY KEY,
+    category TEXT NOT NULL,
+    source TEXT NOT NULL,
+    last_checked TEXT,  -- when the daemon last fetched the category's new papers; NULL if never
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE(category, source)
+) STRICT;
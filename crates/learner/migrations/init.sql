@@ -6,11 +6,18 @@ CREATE TABLE IF NOT EXISTS papers (
     id INTEGER PRIMARY KEY,
     title TEXT NOT NULL,
     abstract_text TEXT NOT NULL,
+    raw_abstract TEXT,  -- the abstract as returned by the source, if cleaning removed markup
     publication_date TEXT NOT NULL,  -- Stored as ISO8601
     source TEXT NOT NULL,
     source_identifier TEXT NOT NULL,
     pdf_url TEXT,
     doi TEXT,
+    version TEXT,  -- e.g. 'v2' for arXiv, NULL for unversioned sources
+    categories TEXT,  -- comma-separated, primary first, e.g. 'cs.CR,cs.LG'; NULL if none
+    keywords TEXT,  -- JSON array, e.g. '["cs.CR","cs.LG"]'; NULL if none
+    reading_status TEXT NOT NULL DEFAULT 'unread',  -- 'unread', 'reading' or 'read'
+    started_reading_at TEXT,  -- when reading_status last became 'reading'; NULL if unread
+    finished_reading_at TEXT,  -- when reading_status became 'read'; NULL unless read
     metadata TEXT,  -- JSON storage
     created_at TEXT NOT NULL DEFAULT (datetime('now')),
     updated_at TEXT NOT NULL DEFAULT (datetime('now')),
@@ -21,6 +28,7 @@ CREATE TABLE IF NOT EXISTS authors (
     id INTEGER PRIMARY KEY,
     paper_id INTEGER NOT NULL,
     name TEXT NOT NULL,
+    normalized_name TEXT,  -- lowercased, without accents or extra whitespace, for matching
     affiliation TEXT,
     email TEXT,
     created_at TEXT NOT NULL DEFAULT (datetime('now')),
@@ -41,24 +49,61 @@ CREATE TABLE IF NOT EXISTS files (
     filename TEXT NOT NULL,
     download_status TEXT NOT NULL,  -- 'success', 'failed', 'pending'
     error_message TEXT,  -- NULL if successful
+    size INTEGER,  -- size of the file in bytes, NULL unless downloaded
     created_at TEXT NOT NULL DEFAULT (datetime('now')),
     updated_at TEXT NOT NULL DEFAULT (datetime('now')),
     FOREIGN KEY(paper_id) REFERENCES papers(id) ON DELETE CASCADE,
     UNIQUE(paper_id)  -- One file entry per paper
 ) STRICT;
 
--- Title-only search index
+-- Search index over titles, author names, notes and abstracts, one row per paper
+-- (rowid = papers.id).
+-- Authors and notes live in their own tables, so the index keeps its own copy of the text
+-- rather than reading it from an external content table. The notes column is kept up to date
+-- by triggers on the notes table (see 003_notes.sql).
 CREATE VIRTUAL TABLE IF NOT EXISTS papers_fts USING fts5(
     title,
-    content=papers,
-    content_rowid=id,
+    authors,  -- author names separated by spaces
+    notes,  -- the paper's note, or '' if it has none
+    abstract_text,
     tokenize='unicode61 remove_diacritics 1'
 );
 
--- Single trigger to maintain FTS index
+-- Rank title matches above author and note matches, and those above abstract matches
+INSERT INTO papers_fts(papers_fts, rank) VALUES ('rank', 'bm25(2.0, 1.0, 1.0, 0.5)');
+
+-- Triggers to maintain FTS index
 CREATE TRIGGER IF NOT EXISTS papers_ai AFTER INSERT ON papers BEGIN
-    INSERT INTO papers_fts(rowid, title)
-    VALUES (new.id, new.title);
+    INSERT INTO papers_fts(rowid, title, authors, notes, abstract_text)
+    VALUES (new.id, new.title, '', '', new.abstract_text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS papers_ad AFTER DELETE ON papers BEGIN
+    DELETE FROM papers_fts WHERE rowid = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS papers_au AFTER UPDATE OF title, abstract_text ON papers BEGIN
+    UPDATE papers_fts SET title = new.title, abstract_text = new.abstract_text
+    WHERE rowid = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS authors_ai AFTER INSERT ON authors BEGIN
+    UPDATE papers_fts
+    SET authors = (SELECT group_concat(name, ' ') FROM authors WHERE paper_id = new.paper_id)
+    WHERE rowid = new.paper_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS authors_ad AFTER DELETE ON authors BEGIN
+    UPDATE papers_fts
+    SET authors = coalesce(
+        (SELECT group_concat(name, ' ') FROM authors WHERE paper_id = old.paper_id), '')
+    WHERE rowid = old.paper_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS authors_au AFTER UPDATE OF name ON authors BEGIN
+    UPDATE papers_fts
+    SET authors = (SELECT group_concat(name, ' ') FROM authors WHERE paper_id = new.paper_id)
+    WHERE rowid = new.paper_id;
 END;
 
 -- Indexes for common queries
@@ -0,0 +1,417 @@
+//! Parsing of BibTeX files, for importing existing bibliographies.
+//!
+//! The parser understands the subset of BibTeX found in files exported by reference managers
+//! and written by hand: `@type{key, field = value, ...}` entries whose values are braced,
+//! quoted, bare numbers or macro names, optionally concatenated with `#`. `@comment`,
+//! `@preamble` and `@string` blocks are skipped, as is any text between entries. Macros are
+//! not expanded.
+//!
+//! Each entry can name the paper it cites, through [`BibtexEntry::identifier`], so that its
+//! authoritative metadata can be fetched from the source rather than trusted from the file.
+//!
+//! # Examples
+//!
+//! ```
+//! use learner::bibtex::parse_bibtex;
+//!
+//! let entries = parse_bibtex(
+//!   r#"@article{dean2008mapreduce,
+//!     title = {{MapReduce}: simplified data processing on large clusters},
+//!     doi = "10.1145/1327452.1327492",
+//!     year = 2008,
+//!   }"#,
+//! )?;
+//!
+//! assert_eq!(entries[0].key, "dean2008mapreduce");
+//! assert_eq!(
+//!   entries[0].field("title"),
+//!   Some("MapReduce: simplified data processing on large clusters")
+//! );
+//! assert_eq!(entries[0].doi().as_deref(), Some("10.1145/1327452.1327492"));
+//! # Ok::<(), learner::errors::LearnerError>(())
+//! ```
+
+use std::collections::BTreeMap;
+
+use super::*;
+use crate::paper::detect_source;
+
+/// Prefix of the DOIs arXiv registers for its papers with DataCite.
+const ARXIV_DOI_PREFIX: &str = "10.48550/arXiv.";
+
+/// Prefixes that may precede the DOI itself in a `doi` field.
+const DOI_PREFIXES: [&str; 5] =
+  ["https://doi.org/", "http://doi.org/", "https://dx.doi.org/", "http://dx.doi.org/", "doi:"];
+
+/// A single entry of a BibTeX file, such as an `@article`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BibtexEntry {
+  /// The entry type, lowercased (e.g. `"article"`, `"inproceedings"`)
+  pub entry_type: String,
+  /// The citation key
+  pub key:        String,
+  /// The entry's fields by lowercased name. Values have their braces removed and whitespace
+  /// collapsed.
+  pub fields:     BTreeMap<String, String>,
+}
+
+impl BibtexEntry {
+  /// Returns the value of a field, looked up case-insensitively.
+  pub fn field(&self, name: &str) -> Option<&str> {
+    self.fields.get(&name.to_lowercase()).map(String::as_str)
+  }
+
+  /// Returns the entry's DOI, without any `https://doi.org/` or `doi:` prefix.
+  pub fn doi(&self) -> Option<String> {
+    let mut doi = self.field("doi")?.trim();
+    for prefix in DOI_PREFIXES {
+      if doi.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix)) {
+        doi = doi[prefix.len()..].trim();
+        break;
+      }
+    }
+    (!doi.is_empty()).then(|| doi.to_string())
+  }
+
+  /// Returns the source and identifier of the paper the entry cites, if it names one.
+  ///
+  /// The first of these that names a paper is used:
+  /// - The `doi` field. arXiv's own DOIs (`10.48550/arXiv.<id>`) give the arXiv paper, as they
+  ///   aren't registered with Crossref
+  /// - The `eprint` field, when `archiveprefix` or `eprinttype` is `arXiv` or the value is an arXiv
+  ///   ID
+  /// - The `url` field, when it links to a paper on a supported source (e.g. an arXiv abstract
+  ///   page)
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use learner::{bibtex::parse_bibtex, paper::Source};
+  ///
+  /// let entries = parse_bibtex(
+  ///   "@misc{vaswani2017, eprint = {1706.03762}, archivePrefix = {arXiv}, primaryClass = {cs.CL}}",
+  /// )?;
+  /// assert_eq!(entries[0].identifier(), Some((Source::Arxiv, "1706.03762".to_string())));
+  /// # Ok::<(), learner::errors::LearnerError>(())
+  /// ```
+  pub fn identifier(&self) -> Option<(Source, String)> {
+    if let Some(doi) = self.doi() {
+      let arxiv = doi
+        .get(..ARXIV_DOI_PREFIX.len())
+        .filter(|start| start.eq_ignore_ascii_case(ARXIV_DOI_PREFIX));
+      return match arxiv {
+        Some(_) => Some((Source::Arxiv, doi[ARXIV_DOI_PREFIX.len()..].to_string())),
+        None => detect_source(&doi),
+      };
+    }
+
+    let archive = self.field("archiveprefix").or_else(|| self.field("eprinttype"));
+    if let Some(eprint) = self.field("eprint").map(str::trim) {
+      let detected = detect_source(eprint).filter(|(source, _)| *source == Source::Arxiv);
+      if detected.is_some() {
+        return detected;
+      }
+      if archive.is_some_and(|archive| archive.eq_ignore_ascii_case("arxiv")) {
+        return Some((Source::Arxiv, eprint.trim_start_matches("arXiv:").to_string()));
+      }
+    }
+
+    self.field("url").and_then(|url| detect_source(url.trim()))
+  }
+}
+
+/// Parses the entries of a BibTeX file, in the order they appear.
+///
+/// # Arguments
+///
+/// * `input` - The contents of a `.bib` file
+///
+/// # Errors
+///
+/// Returns [`LearnerError::InvalidBibtex`] if an entry is malformed, e.g. has unbalanced
+/// braces or a field without a value. The message includes the line of the problem.
+pub fn parse_bibtex(input: &str) -> Result<Vec<BibtexEntry>, LearnerError> {
+  let mut parser = Parser { input, pos: 0 };
+  let mut entries = Vec::new();
+
+  while let Some(offset) = input[parser.pos..].find('@') {
+    parser.pos += offset + 1;
+    let entry_type = parser.take_while(|c| c.is_ascii_alphanumeric()).to_lowercase();
+    parser.skip_whitespace();
+    let close = match parser.next() {
+      Some('{') => '}',
+      Some('(') => ')',
+      // A stray `@`, e.g. in an email address between entries
+      _ => continue,
+    };
+
+    match entry_type.as_str() {
+      "comment" | "preamble" | "string" => parser.skip_group(close)?,
+      _ => entries.push(parser.entry(entry_type, close)?),
+    }
+  }
+
+  Ok(entries)
+}
+
+/// Cursor over the text of a BibTeX file.
+struct Parser<'a> {
+  /// The whole file
+  input: &'a str,
+  /// Byte offset of the next character to read
+  pos:   usize,
+}
+
+impl Parser<'_> {
+  /// Returns the next character without consuming it.
+  fn peek(&self) -> Option<char> { self.input[self.pos..].chars().next() }
+
+  /// Consumes and returns the next character.
+  fn next(&mut self) -> Option<char> {
+    let c = self.peek()?;
+    self.pos += c.len_utf8();
+    Some(c)
+  }
+
+  /// Consumes characters while `predicate` holds, returning them.
+  fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &str {
+    let start = self.pos;
+    while self.peek().is_some_and(&predicate) {
+      self.next();
+    }
+    &self.input[start..self.pos]
+  }
+
+  /// Skips any whitespace.
+  fn skip_whitespace(&mut self) { self.take_while(char::is_whitespace); }
+
+  /// Builds an error pointing at the current line.
+  fn error(&self, message: &str) -> LearnerError {
+    let line = self.input[..self.pos].matches('\n').count() + 1;
+    LearnerError::InvalidBibtex(format!("{message} on line {line}"))
+  }
+
+  /// Skips the rest of a block opened just before, up to and including `close`.
+  fn skip_group(&mut self, close: char) -> Result<(), LearnerError> {
+    let mut depth = 0;
+    while let Some(c) = self.next() {
+      match c {
+        '{' => depth += 1,
+        '}' if depth > 0 => depth -= 1,
+        c if c == close && depth == 0 => return Ok(()),
+        _ => {},
+      }
+    }
+    Err(self.error("Unterminated block"))
+  }
+
+  /// Parses the key and fields of an entry whose opening delimiter was just consumed.
+  fn entry(&mut self, entry_type: String, close: char) -> Result<BibtexEntry, LearnerError> {
+    self.skip_whitespace();
+    let key = self.take_while(|c| c != ',' && c != close && !c.is_whitespace()).to_string();
+    let mut fields = BTreeMap::new();
+
+    loop {
+      self.skip_whitespace();
+      match self.next() {
+        Some(',') => {},
+        Some(c) if c == close => break,
+        _ => return Err(self.error(&format!("Expected `,` or `{close}` in entry {key:?}"))),
+      }
+
+      self.skip_whitespace();
+      if self.peek() == Some(close) {
+        // Trailing comma after the last field
+        self.next();
+        break;
+      }
+
+      let name = self.take_while(|c| c.is_ascii_alphanumeric() || "_-:.".contains(c));
+      if name.is_empty() {
+        return Err(self.error(&format!("Expected a field name in entry {key:?}")));
+      }
+      let name = name.to_lowercase();
+      self.skip_whitespace();
+      if self.next() != Some('=') {
+        return Err(self.error(&format!("Expected `=` after field {name:?}")));
+      }
+      let value = self.value(close)?;
+      fields.insert(name, value);
+    }
+
+    Ok(BibtexEntry { entry_type, key, fields })
+  }
+
+  /// Parses a field value, concatenating the parts joined by `#`.
+  fn value(&mut self, close: char) -> Result<String, LearnerError> {
+    let mut value = String::new();
+    loop {
+      self.skip_whitespace();
+      match self.peek() {
+        Some('{') => {
+          self.next();
+          value.push_str(&self.delimited('}')?);
+        },
+        Some('"') => {
+          self.next();
+          value.push_str(&self.delimited('"')?);
+        },
+        _ => {
+          let bare = self.take_while(|c| c != ',' && c != close && c != '#' && !c.is_whitespace());
+          if bare.is_empty() {
+            return Err(self.error("Expected a field value"));
+          }
+          value.push_str(bare);
+        },
+      }
+
+      self.skip_whitespace();
+      if self.peek() != Some('#') {
+        break;
+      }
+      self.next();
+    }
+
+    Ok(value.split_whitespace().collect::<Vec<_>>().join(" "))
+  }
+
+  /// Reads a braced or quoted value up to its closing `end` (`}` or `"`), whose opening
+  /// delimiter was just consumed. Inner braces are dropped from the returned text.
+  fn delimited(&mut self, end: char) -> Result<String, LearnerError> {
+    let mut text = String::new();
+    let mut depth = 0;
+    while let Some(c) = self.next() {
+      match c {
+        c if c == end && depth == 0 => return Ok(text),
+        '}' if depth == 0 => break,
+        '}' => depth -= 1,
+        '{' => depth += 1,
+        '\\' => {
+          text.push(c);
+          if let Some(escaped) = self.next() {
+            text.push(escaped);
+          }
+        },
+        c => text.push(c),
+      }
+    }
+    Err(self.error("Unbalanced braces in field value"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_entries() {
+    let input = r#"
+      Exported from a reference manager.
+
+      @comment{ignore {this} entirely}
+      @string{acm = "ACM"}
+
+      @Article{dean2008mapreduce,
+        Title     = {{MapReduce}: Simplified Data
+                     Processing on Large Clusters},
+        author    = "Dean, Jeffrey and Ghemawat, Sanjay",
+        publisher = acm # { Press},
+        year      = 2008,
+        DOI       = {https://doi.org/10.1145/1327452.1327492},
+      }
+
+      @misc(turing1936,
+        title = {On Computable Numbers, with an Application to the {\"E}ntscheidungsproblem}
+      )
+    "#;
+
+    let entries = parse_bibtex(input).unwrap();
+    assert_eq!(entries.len(), 2);
+
+    let entry = &entries[0];
+    assert_eq!(entry.entry_type, "article");
+    assert_eq!(entry.key, "dean2008mapreduce");
+    assert_eq!(
+      entry.field("title"),
+      Some("MapReduce: Simplified Data Processing on Large Clusters")
+    );
+    assert_eq!(entry.field("Author"), Some("Dean, Jeffrey and Ghemawat, Sanjay"));
+    assert_eq!(entry.field("publisher"), Some("acm Press"));
+    assert_eq!(entry.field("year"), Some("2008"));
+    assert_eq!(entry.doi().as_deref(), Some("10.1145/1327452.1327492"));
+
+    let entry = &entries[1];
+    assert_eq!(entry.entry_type, "misc");
+    assert_eq!(
+      entry.field("title"),
+      Some(r#"On Computable Numbers, with an Application to the \"Entscheidungsproblem"#)
+    );
+    assert_eq!(entry.doi(), None);
+  }
+
+  #[test]
+  fn test_doi_prefixes() {
+    for doi in
+      ["10.1000/xyz", "doi:10.1000/xyz", "DOI: 10.1000/xyz", "http://dx.doi.org/10.1000/xyz"]
+    {
+      let input = format!("@article{{key, doi = {{{doi}}}}}");
+      assert_eq!(parse_bibtex(&input).unwrap()[0].doi().as_deref(), Some("10.1000/xyz"));
+    }
+  }
+
+  #[test]
+  fn test_identifier() {
+    let identifier = |fields: &str| {
+      let entries = parse_bibtex(&format!("@misc{{key, {fields}}}")).unwrap();
+      entries[0].identifier()
+    };
+    let expected = |source: Source, id: &str| Some((source, id.to_string()));
+
+    assert_eq!(
+      identifier("doi = {10.1145/1327452.1327492}"),
+      expected(Source::DOI, "10.1145/1327452.1327492")
+    );
+    assert_eq!(
+      identifier("doi = {10.1101/2020.03.20.000001}"),
+      expected(Source::BioRxiv, "10.1101/2020.03.20.000001")
+    );
+    assert_eq!(
+      identifier("doi = {10.48550/arXiv.1706.03762}"),
+      expected(Source::Arxiv, "1706.03762")
+    );
+    assert_eq!(
+      identifier("eprint = {1706.03762}, archivePrefix = {arXiv}"),
+      expected(Source::Arxiv, "1706.03762")
+    );
+    assert_eq!(identifier("eprint = {hep-th/9901001}"), expected(Source::Arxiv, "hep-th/9901001"));
+    assert_eq!(
+      identifier("eprint = {arXiv:2301.07041}, eprinttype = {arxiv}"),
+      expected(Source::Arxiv, "2301.07041")
+    );
+    assert_eq!(
+      identifier("url = {https://eprint.iacr.org/2016/260}"),
+      expected(Source::IACR, "2016/260")
+    );
+    // The DOI is preferred over a preprint
+    assert_eq!(
+      identifier("doi = {10.1145/1327452.1327492}, eprint = {1706.03762}"),
+      expected(Source::DOI, "10.1145/1327452.1327492")
+    );
+
+    // Nothing naming a paper on a supported source
+    assert_eq!(identifier("title = {On Computable Numbers}"), None);
+    assert_eq!(identifier("eprint = {12345}, archivePrefix = {HAL}"), None);
+    assert_eq!(identifier("url = {https://example.com/paper.pdf}"), None);
+  }
+
+  #[test]
+  fn test_parse_errors() {
+    let result = parse_bibtex("@article{key,\n  title = {Unbalanced,\n}");
+    assert!(matches!(result, Err(LearnerError::InvalidBibtex(msg)) if msg.contains("line 3")));
+
+    let result = parse_bibtex("@article{key,\n  title {No equals sign}\n}");
+    assert!(matches!(result, Err(LearnerError::InvalidBibtex(msg)) if msg.contains("line 2")));
+
+    assert!(parse_bibtex("No entries here, just an email@example.com").unwrap().is_empty());
+  }
+}
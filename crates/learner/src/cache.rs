@@ -0,0 +1,291 @@
+//! A cache of recently fetched papers, to avoid fetching the same paper twice in a short time.
+//!
+//! A [`Cache`] keeps each paper as a JSON file in a directory, by default `cache/` next to the
+//! default database (see [`Cache::default_dir`]), keyed by its source and identifier. Entries
+//! are fresh for a time to live, an hour unless set with [`Cache::with_ttl`], after which they
+//! are fetched again. [`CachingArxivClient`] answers from the cache before asking arXiv, and
+//! writes what it fetches through to the cache.
+//!
+//! A cache entry that can't be read is treated as missing, and failing to write one only logs a
+//! warning, so the cache never makes a fetch fail.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use learner::cache::{Cache, CachingArxivClient};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let cache = Cache::new(Cache::default_dir()).with_ttl(Duration::from_secs(7200));
+//! let client = CachingArxivClient::new(cache);
+//!
+//! // Only the first fetch reaches arXiv
+//! let paper = client.fetch_paper("2301.07041").await?;
+//! let again = client.fetch_paper("2301.07041").await?;
+//! assert_eq!(paper.title, again.title);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{path::Path, time::Duration};
+
+use tracing::warn;
+
+use super::*;
+use crate::clients::ArxivClient;
+
+/// How long cached papers are used for, unless set with [`Cache::with_ttl`].
+pub const DEFAULT_TTL: Duration = Duration::from_secs(3600);
+
+/// A directory of recently fetched papers, each kept for a limited time.
+#[derive(Debug, Clone)]
+pub struct Cache {
+  /// The directory holding one JSON file per paper.
+  dir: PathBuf,
+  /// How long after being fetched a paper is used from the cache.
+  ttl: Duration,
+}
+
+/// A paper as stored in the cache.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+  /// When the paper was fetched from its source
+  fetched_at: DateTime<Utc>,
+  /// The paper as fetched
+  paper:      Paper,
+}
+
+impl Cache {
+  /// Creates a cache in `dir`, which is created when the first paper is cached, with the
+  /// default time to live of an hour.
+  pub fn new(dir: impl Into<PathBuf>) -> Self { Self { dir: dir.into(), ttl: DEFAULT_TTL } }
+
+  /// Uses cached papers for `ttl` after they were fetched. A zero `ttl` turns the cache off:
+  /// nothing is read from or written to it.
+  pub fn with_ttl(mut self, ttl: Duration) -> Self {
+    self.ttl = ttl;
+    self
+  }
+
+  /// The default cache directory, `cache/` next to [`Database::default_path`].
+  pub fn default_dir() -> PathBuf {
+    Database::default_path()
+      .parent()
+      .map_or_else(|| PathBuf::from("cache"), |dir| dir.join("cache"))
+  }
+
+  /// The directory the cache is kept in.
+  pub fn dir(&self) -> &Path { &self.dir }
+
+  /// How long cached papers are used for.
+  pub fn ttl(&self) -> Duration { self.ttl }
+
+  /// Returns the cached paper with the given source and identifier, if it was fetched within
+  /// the time to live.
+  pub fn get(&self, source: &Source, identifier: &str) -> Option<Paper> {
+    if self.ttl.is_zero() {
+      return None;
+    }
+    let path = self.path(source, identifier);
+    let entry: Entry = match std::fs::read(&path) {
+      Ok(bytes) => match serde_json::from_slice(&bytes) {
+        Ok(entry) => entry,
+        Err(e) => {
+          debug!("Ignoring unreadable cache entry {}: {e}", path.display());
+          return None;
+        },
+      },
+      Err(_) => return None,
+    };
+
+    let age = Utc::now().signed_duration_since(entry.fetched_at).to_std().unwrap_or_default();
+    if age < self.ttl {
+      debug!("Using cached {source} {identifier}, fetched {}s ago", age.as_secs());
+      Some(entry.paper)
+    } else {
+      None
+    }
+  }
+
+  /// Caches `paper` under its source and `identifier`, as fetched now.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Path`] if the cache directory or file can't be written.
+  pub fn put(&self, identifier: &str, paper: &Paper) -> Result<(), LearnerError> {
+    if self.ttl.is_zero() {
+      return Ok(());
+    }
+    std::fs::create_dir_all(&self.dir)?;
+    let entry = Entry { fetched_at: Utc::now(), paper: paper.clone() };
+    let json = serde_json::to_vec(&entry).map_err(std::io::Error::from)?;
+    std::fs::write(self.path(&paper.source, identifier), json)?;
+    Ok(())
+  }
+
+  /// Removes every cached paper, returning how many there were.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::Path`] if the cache directory can't be read or an entry can't be
+  /// removed. A cache directory that doesn't exist is empty.
+  pub fn clear(&self) -> Result<usize, LearnerError> {
+    let entries = match std::fs::read_dir(&self.dir) {
+      Ok(entries) => entries,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+      Err(e) => return Err(e.into()),
+    };
+    let mut removed = 0;
+    for entry in entries {
+      let path = entry?.path();
+      if path.extension().is_some_and(|extension| extension == "json") {
+        std::fs::remove_file(path)?;
+        removed += 1;
+      }
+    }
+    Ok(removed)
+  }
+
+  /// The file a paper is cached in, e.g. `arxiv_math%2F0601001.json`.
+  ///
+  /// Characters other than letters, digits, `.` and `-` are percent-encoded, so that every
+  /// identifier, such as a DOI, maps to its own file name.
+  fn path(&self, source: &Source, identifier: &str) -> PathBuf {
+    let mut name = format!("{source}_");
+    for byte in identifier.bytes() {
+      match byte {
+        b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'.' | b'-' => name.push(byte as char),
+        _ => name.push_str(&format!("%{byte:02X}")),
+      }
+    }
+    self.dir.join(format!("{name}.json"))
+  }
+}
+
+/// An [`ArxivClient`] that answers from a [`Cache`] before asking arXiv.
+///
+/// Papers fetched from arXiv are written through to the cache, keyed by the identifier they
+/// were requested with, so `2301.07041` and `2301.07041v2` are cached separately.
+pub struct CachingArxivClient {
+  /// The client papers missing from the cache are fetched with.
+  client: ArxivClient,
+  /// The cache papers are looked up in and written to.
+  cache:  Cache,
+}
+
+impl CachingArxivClient {
+  /// Creates a client fetching papers missing from `cache` with a default [`ArxivClient`].
+  pub fn new(cache: Cache) -> Self { Self::with_client(ArxivClient::new(), cache) }
+
+  /// Creates a client fetching papers missing from `cache` with `client`, e.g. one with a
+  /// different base URL or retry settings.
+  pub fn with_client(client: ArxivClient, cache: Cache) -> Self { Self { client, cache } }
+
+  /// The cache papers are looked up in.
+  pub fn cache(&self) -> &Cache { &self.cache }
+
+  /// Fetches a paper as [`ArxivClient::fetch_paper`] does, from the cache if it was fetched
+  /// within the cache's time to live.
+  ///
+  /// # Errors
+  ///
+  /// Returns whatever error [`ArxivClient::fetch_paper`] fails with on a cache miss. Failing to
+  /// write the paper to the cache is logged, not returned.
+  pub async fn fetch_paper(&self, identifier: &str) -> Result<Paper, LearnerError> {
+    if let Some(paper) = self.cache.get(&Source::Arxiv, identifier) {
+      return Ok(paper);
+    }
+    let paper = self.client.fetch_paper(identifier).await?;
+    if let Err(e) = self.cache.put(identifier, &paper) {
+      warn!("Failed to cache {} {identifier}: {e}", paper.source);
+    }
+    Ok(paper)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::Ordering;
+
+  use super::*;
+  use crate::clients::http::serve_responses;
+
+  /// A minimal arXiv API response.
+  const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry>
+    <id>http://arxiv.org/abs/2301.07041v2</id>
+    <published>2023-01-17T18:00:59Z</published>
+    <title>Verifiable Fully Homomorphic Encryption</title>
+    <summary>Fully Homomorphic Encryption (FHE) is seeing increasing real-world deployment.</summary>
+    <author>
+      <name>Alexander Viand</name>
+    </author>
+  </entry>
+</feed>"#;
+
+  #[tokio::test]
+  async fn test_fetches_once_within_ttl() {
+    let dir = tempdir().unwrap();
+    let (url, requests) = serve_responses(&[(200, FEED)]).await;
+    let client = CachingArxivClient::with_client(
+      ArxivClient::new().with_base_url(url.clone()).with_retry(0),
+      Cache::new(dir.path()),
+    );
+
+    let paper = client.fetch_paper("2301.07041").await.unwrap();
+    let again = client.fetch_paper("2301.07041").await.unwrap();
+    assert_eq!(paper.title, "Verifiable Fully Homomorphic Encryption");
+    assert_eq!(again.title, paper.title);
+    assert_eq!(again.authors.len(), 1);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    // Another identifier misses the cache
+    client.fetch_paper("2301.07041v2").await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    // Without a time to live, every fetch reaches arXiv
+    let client = CachingArxivClient::with_client(
+      ArxivClient::new().with_base_url(url).with_retry(0),
+      Cache::new(dir.path()).with_ttl(Duration::ZERO),
+    );
+    client.fetch_paper("2301.07041").await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+  }
+
+  #[test]
+  fn test_cache_entries() {
+    let dir = tempdir().unwrap();
+    let cache = Cache::new(dir.path().join("cache"));
+    assert_eq!(cache.clear().unwrap(), 0);
+
+    let paper: Paper = serde_json::from_value(serde_json::json!({
+      "title": "A Paper",
+      "authors": [],
+      "abstract_text": "",
+      "publication_date": "2024-01-01T00:00:00Z",
+      "source": "DOI",
+      "source_identifier": "10.1000/a_b",
+      "pdf_url": null,
+      "doi": null
+    }))
+    .unwrap();
+    cache.put("10.1000/a_b", &paper).unwrap();
+    cache.put("10.1000/a/b", &paper).unwrap();
+    assert!(cache.get(&Source::DOI, "10.1000/a_b").is_some());
+    assert!(cache.get(&Source::DOI, "10.1000/a/b").is_some());
+    assert!(cache.get(&Source::DOI, "10.1000/a").is_none());
+    assert!(cache.get(&Source::Arxiv, "10.1000/a_b").is_none());
+
+    // Expired and unreadable entries are misses
+    let expired = Entry { fetched_at: Utc::now() - chrono::Duration::hours(2), paper };
+    std::fs::write(cache.path(&Source::DOI, "old"), serde_json::to_vec(&expired).unwrap()).unwrap();
+    assert!(cache.get(&Source::DOI, "old").is_none());
+    std::fs::write(cache.path(&Source::DOI, "broken"), "{").unwrap();
+    assert!(cache.get(&Source::DOI, "broken").is_none());
+
+    assert_eq!(cache.clear().unwrap(), 4);
+    assert!(cache.get(&Source::DOI, "10.1000/a_b").is_none());
+  }
+}
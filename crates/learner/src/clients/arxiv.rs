@@ -5,7 +5,8 @@
 //! and old-style (math.AG/0601001) arXiv identifiers.
 //!
 //! The client uses arXiv's Atom feed API (http://export.arxiv.org/api/query) to fetch
-//! paper metadata in XML format.
+//! paper metadata in XML format, either for a single paper by identifier or for all papers
+//! matching a search query (see [`ArxivClient::search`]).
 //!
 //! # Examples
 //!
@@ -22,13 +23,15 @@
 //! # }
 //! ```
 
+use url::Url;
+
 use super::*;
 
 /// Internal representation of the arXiv API's Atom feed response.
 #[derive(Debug, Deserialize)]
 struct Feed {
-  /// A `Feed` from arXiv may contain multiple `Entry`s
-  #[serde(rename = "entry")]
+  /// A `Feed` from arXiv may contain multiple `Entry`s, or none if a search matched nothing
+  #[serde(rename = "entry", default)]
   entries: Vec<Entry>,
 }
 
@@ -40,25 +43,38 @@ struct Feed {
 ///
 /// Note: The current implementation only captures a subset of the available metadata.
 /// Future versions may expand this to include additional fields such as:
-/// - Categories/subjects
 /// - Comments
 /// - Journal references
-/// - Primary category
-/// - Version information
 #[derive(Debug, Deserialize)]
 struct Entry {
-  /// Paper title (may contain LaTeX markup)
-  title:     String,
+  /// Paper title (may contain LaTeX markup, removed with [`format::clean_latex`])
+  title:            String,
   /// List of paper authors
   #[serde(rename = "author")]
-  authors:   Vec<Author>,
+  authors:          Vec<Author>,
   /// Paper abstract (may contain LaTeX markup)
-  summary:   String,
+  summary:          String,
   /// Publication or last update date
-  published: DateTime<Utc>,
+  published:        DateTime<Utc>,
   /// arXiv URL (e.g., "https://arxiv.org/abs/2301.07041")
   #[serde(rename = "id")]
-  arxiv_url: String,
+  arxiv_url:        String,
+  /// The paper's primary subject category
+  #[serde(rename = "primary_category")]
+  primary_category: Option<Category>,
+  /// All of the paper's subject categories, including the primary one
+  #[serde(rename = "category", default)]
+  categories:       Vec<Category>,
+  /// DOI of the published version (`<arxiv:doi>`), when the authors have reported one
+  doi:              Option<String>,
+}
+
+/// A subject category from the arXiv taxonomy.
+#[derive(Debug, Deserialize)]
+struct Category {
+  /// Category identifier (e.g., "cs.CR")
+  #[serde(rename = "@term")]
+  term: String,
 }
 
 /// Internal representation of an author from arXiv's API response.
@@ -68,6 +84,69 @@ struct Author {
   name: String,
 }
 
+/// Order of the results of an [`ArxivClient::search`].
+///
+/// Parsing from a string accepts `relevance`, `submitted`, and `updated`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArxivSort {
+  /// Best matches first
+  #[default]
+  Relevance,
+  /// Most recently submitted first
+  Submitted,
+  /// Most recently updated first
+  Updated,
+}
+
+impl ArxivSort {
+  /// The value of the API's `sortBy` parameter for this order.
+  fn sort_by(&self) -> &'static str {
+    match self {
+      ArxivSort::Relevance => "relevance",
+      ArxivSort::Submitted => "submittedDate",
+      ArxivSort::Updated => "lastUpdatedDate",
+    }
+  }
+}
+
+impl FromStr for ArxivSort {
+  type Err = LearnerError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match &s.to_lowercase() as &str {
+      "relevance" => Ok(ArxivSort::Relevance),
+      "submitted" | "date" => Ok(ArxivSort::Submitted),
+      "updated" => Ok(ArxivSort::Updated),
+      s => Err(LearnerError::InvalidSort(s.to_owned())),
+    }
+  }
+}
+
+/// Options for an [`ArxivClient::search_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArxivSearchOptions {
+  /// Maximum number of papers to return. Defaults to 10.
+  pub max_results: usize,
+  /// Number of matching papers to skip, for fetching later pages. Defaults to 0.
+  pub start:       usize,
+  /// Order of the results. Defaults to [`ArxivSort::Relevance`].
+  pub sort:        ArxivSort,
+}
+
+/// Number of papers [`ArxivClient::list_new`] fetches per request.
+const LIST_NEW_PAGE_SIZE: usize = 100;
+
+/// Most requests one [`ArxivClient::list_new`] makes, so that a `since` far in the past
+/// doesn't page through a whole category.
+const LIST_NEW_MAX_PAGES: usize = 10;
+
+/// Field prefixes of the arXiv query syntax, e.g. `ti:` for titles.
+const QUERY_FIELDS: [&str; 9] = ["ti", "au", "abs", "cat", "all", "co", "jr", "rn", "id"];
+
+impl Default for ArxivSearchOptions {
+  fn default() -> Self { Self { max_results: 10, start: 0, sort: ArxivSort::default() } }
+}
+
 /// Client for interacting with the arXiv API.
 ///
 /// This client provides methods to fetch paper metadata from arXiv.org using their
@@ -91,14 +170,108 @@ struct Author {
 /// ```
 pub struct ArxivClient {
   /// Internal web client used to connect to the API.
-  client: reqwest::Client,
+  client:       reqwest::Client,
+  /// The base URL to use for the client.
+  base_url:     String,
+  /// Timeout and retry settings.
+  config:       ClientConfig,
+  /// Caps how often requests are sent, possibly shared with other clients.
+  rate_limiter: Arc<RateLimiter>,
 }
 
 impl ArxivClient {
   /// Creates a new arXiv client instance.
   ///
   /// Initializes an HTTP client that will be reused for all requests to the arXiv API.
-  pub fn new() -> Self { Self { client: reqwest::Client::new() } }
+  pub fn new() -> Self { Self::new_with_config(ClientConfig::default()) }
+
+  /// Creates a new arXiv client with custom timeout and retry settings.
+  pub fn new_with_config(config: ClientConfig) -> Self {
+    Self { config, ..Self::with_client(config.http_client()) }
+  }
+
+  /// Creates a new arXiv client that sends its requests through `client`, e.g. one configured
+  /// once with a proxy, TLS roots, or default headers and shared across sources.
+  ///
+  /// Requests are limited by `client`'s own timeout and retried as by
+  /// [`ClientConfig::default`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::{ArxivClient, DOIClient};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// // One connection pool, behind a proxy, for every source
+  /// let proxy = reqwest::Proxy::all("http://proxy.example.com:3128")?;
+  /// let http = reqwest::Client::builder().proxy(proxy).build()?;
+  /// let arxiv = ArxivClient::with_client(http.clone());
+  /// let doi = DOIClient::with_client(http);
+  /// let paper = arxiv.fetch_paper("2301.07041").await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_client(client: reqwest::Client) -> Self {
+    Self {
+      client,
+      base_url: "http://export.arxiv.org/api/query".to_string(),
+      config: ClientConfig::default(),
+      rate_limiter: Arc::default(),
+    }
+  }
+
+  /// Sends requests to a different endpoint, such as a mirror or a local test server, instead
+  /// of `http://export.arxiv.org/api/query`, keeping the other settings.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::ArxivClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let client = ArxivClient::new().with_base_url("http://localhost:8080/api/query");
+  /// let paper = client.fetch_paper("2301.07041").await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+    self.base_url = url.into();
+    self
+  }
+
+  /// Sets how many times to retry a request after a transient failure, keeping the other
+  /// settings. See [`ClientConfig::max_retries`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::ArxivClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let paper = ArxivClient::new().with_retry(5).fetch_paper("2301.07041").await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_retry(mut self, retries: u8) -> Self {
+    self.config.max_retries = retries.into();
+    self
+  }
+
+  /// Sends requests no faster than `limiter` allows, keeping the other settings. Clients given
+  /// the same limiter share its budget, e.g. to stay within a service's polite pool.
+  pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+    self.rate_limiter = limiter;
+    self
+  }
+
+  /// Sets the maximum time a single request may take, keeping the other settings. See
+  /// [`ClientConfig::timeout`]. A request that takes longer fails with
+  /// [`LearnerError::Timeout`]. This builds a new HTTP client, replacing one given to
+  /// `with_client`.
+  pub fn with_timeout(self, timeout: Duration) -> Self {
+    Self {
+      base_url: self.base_url,
+      rate_limiter: self.rate_limiter,
+      ..Self::new_with_config(ClientConfig { timeout, ..self.config })
+    }
+  }
 
   /// Fetches paper metadata from arXiv using its identifier.
   ///
@@ -108,6 +281,10 @@ impl ArxivClient {
   ///   - New format (e.g., "2301.07041")
   ///   - Old format (e.g., "math.AG/0601001")
   ///
+  ///   Either format may carry a version suffix (e.g., "2301.07041v2") to fetch that version
+  ///   rather than the latest one. The returned paper's `source_identifier` never includes the
+  ///   version; the fetched version is stored in [`Paper::version`] instead.
+  ///
   /// # Returns
   ///
   /// Returns a [`Result`] containing either:
@@ -138,40 +315,331 @@ impl ArxivClient {
   /// # }
   /// ```
   pub async fn fetch_paper(&self, identifier: &str) -> Result<Paper, LearnerError> {
-    let url = format!("http://export.arxiv.org/api/query?id_list={}&max_results=1", identifier);
+    let url = format!("{}?id_list={}&max_results=1", self.base_url, identifier);
 
     debug!("Fetching from arXiv via: {url}");
 
-    let response = self.client.get(&url).send().await?.text().await?;
+    self.rate_limiter.wait().await;
+    let response =
+      send_with_retry(&self.config, "arXiv", || self.client.get(&url)).await?.text().await?;
+
+    trace!("arXiv response: {response}");
+
+    parse_feed(&response, identifier)
+  }
+
+  /// Fetches several papers from arXiv in a single request.
+  ///
+  /// # Arguments
+  ///
+  /// * `identifiers` - arXiv paper identifiers, in any format accepted by
+  ///   [`ArxivClient::fetch_paper`]
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The papers found, in the order they were requested. Identifiers arXiv doesn't know are
+  ///   skipped rather than failing the whole batch.
+  /// - A [`LearnerError`] if the request or parsing fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::ArxivClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let papers = ArxivClient::new().fetch_papers(&["2301.07041", "1706.03762"]).await?;
+  /// assert_eq!(papers[0].source_identifier, "2301.07041");
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn fetch_papers(&self, identifiers: &[&str]) -> Result<Vec<Paper>, LearnerError> {
+    if identifiers.is_empty() {
+      return Ok(Vec::new());
+    }
+
+    let url = format!(
+      "{}?id_list={}&max_results={}",
+      self.base_url,
+      identifiers.join(","),
+      identifiers.len()
+    );
+
+    debug!("Fetching {} papers from arXiv via: {url}", identifiers.len());
+
+    self.rate_limiter.wait().await;
+    let response =
+      send_with_retry(&self.config, "arXiv", || self.client.get(&url)).await?.text().await?;
+
+    trace!("arXiv response: {response}");
+
+    parse_batch_feed(&response, identifiers)
+  }
+
+  /// Searches arXiv for papers matching a query, returning the most relevant ones.
+  ///
+  /// This is [`ArxivClient::search_with`] with the default options other than `max_results`.
+  ///
+  /// # Arguments
+  ///
+  /// * `query` - Words to look for in any field (e.g., "zero knowledge proofs"), or a query in
+  ///   arXiv's own syntax (e.g., "ti:snark AND au:groth")
+  /// * `max_results` - Maximum number of papers to return
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::ArxivClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let papers = ArxivClient::new().search("zero knowledge proofs", 10).await?;
+  /// for paper in papers {
+  ///   println!("{}: {}", paper.source_identifier, paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search(&self, query: &str, max_results: usize) -> Result<Vec<Paper>, LearnerError> {
+    self.search_with(query, &ArxivSearchOptions { max_results, ..Default::default() }).await
+  }
+
+  /// Searches arXiv for papers matching a query, with control over paging and ordering.
+  ///
+  /// A query made of plain words matches papers containing all of them in any field. A query
+  /// that already uses arXiv's field prefixes (such as `ti:`, `au:` or `cat:`) is sent as is.
+  ///
+  /// # Errors
+  ///
+  /// This function will return an error if:
+  /// - The network request fails
+  /// - The API response cannot be parsed
+  ///
+  /// A query matching nothing is not an error, and returns no papers.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::arxiv::{ArxivClient, ArxivSearchOptions, ArxivSort};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// // The second page of the newest matches
+  /// let options =
+  ///   ArxivSearchOptions { max_results: 20, start: 20, sort: ArxivSort::Submitted };
+  /// let papers = ArxivClient::new().search_with("cat:cs.CR AND all:lattice", &options).await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search_with(
+    &self,
+    query: &str,
+    options: &ArxivSearchOptions,
+  ) -> Result<Vec<Paper>, LearnerError> {
+    let url = Url::parse_with_params(&self.base_url, &[
+      ("search_query", search_query(query)),
+      ("start", options.start.to_string()),
+      ("max_results", options.max_results.to_string()),
+      ("sortBy", options.sort.sort_by().to_string()),
+      ("sortOrder", "descending".to_string()),
+    ])?;
+    debug!("Searching arXiv via: {url}");
+
+    self.rate_limiter.wait().await;
+    let response =
+      send_with_retry(&self.config, "arXiv", || self.client.get(url.clone())).await?.text().await?;
 
     trace!("arXiv response: {response}");
 
-    let feed: Feed = from_str(&response)
-      .map_err(|e| LearnerError::ApiError(format!("Failed to parse XML: {}", e)))?;
+    parse_search_feed(&response)
+  }
 
-    let entry = feed.entries.first().ok_or(LearnerError::NotFound)?;
+  /// Lists the papers submitted to an arXiv category since a given time, newest first.
+  ///
+  /// Pages through the category's newest submissions until reaching one submitted before
+  /// `since`, fetching at most a thousand papers.
+  ///
+  /// # Arguments
+  ///
+  /// * `category` - The arXiv category, e.g. "cs.CR" or "math.AG"
+  /// * `since` - Only papers submitted at or after this time are returned
+  ///
+  /// # Errors
+  ///
+  /// This function will return an error if:
+  /// - The network request fails
+  /// - The API response cannot be parsed
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::ArxivClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let since = chrono::Utc::now() - chrono::Duration::days(1);
+  /// for paper in ArxivClient::new().list_new("cs.CR", since).await? {
+  ///   println!("{}: {}", paper.source_identifier, paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn list_new(
+    &self,
+    category: &str,
+    since: DateTime<Utc>,
+  ) -> Result<Vec<Paper>, LearnerError> {
+    let query = format!("cat:{category}");
+    let mut papers = Vec::new();
+    for page in 0..LIST_NEW_MAX_PAGES {
+      let options = ArxivSearchOptions {
+        max_results: LIST_NEW_PAGE_SIZE,
+        start:       page * LIST_NEW_PAGE_SIZE,
+        sort:        ArxivSort::Submitted,
+      };
+      let batch = self.search_with(&query, &options).await?;
+      let last_page = batch.len() < LIST_NEW_PAGE_SIZE
+        || batch.iter().any(|paper| paper.publication_date < since);
+      papers.extend(batch.into_iter().filter(|paper| paper.publication_date >= since));
+      if last_page {
+        break;
+      }
+    }
+    Ok(papers)
+  }
+}
+
+/// Builds the API's `search_query` from a user query: plain words are each required to appear
+/// in some field, while a query using field prefixes (see [`QUERY_FIELDS`]) is kept as is.
+/// Colons in plain words, e.g. in a title like "Zero-knowledge: a survey", separate words like
+/// spaces do, as arXiv would read them as field syntax.
+fn search_query(query: &str) -> String {
+  let uses_fields = query.split_whitespace().any(|token| {
+    token
+      .trim_start_matches('(')
+      .split_once(':')
+      .is_some_and(|(field, _)| QUERY_FIELDS.contains(&field))
+  });
+  if uses_fields {
+    return query.to_string();
+  }
+  query
+    .split(|c: char| c.is_whitespace() || c == ':')
+    .filter(|word| !word.is_empty())
+    .map(|word| format!("all:{word}"))
+    .collect::<Vec<_>>()
+    .join(" AND ")
+}
+
+/// Parses an arXiv API response for `identifier` into a [`Paper`].
+fn parse_feed(response: &str, identifier: &str) -> Result<Paper, LearnerError> {
+  let feed: Feed = from_str(response)
+    .map_err(|e| LearnerError::ApiError(format!("Failed to parse XML: {}", e)))?;
+
+  let entry = feed.entries.first().ok_or(LearnerError::NotFound)?;
+  Ok(entry_to_paper(entry, identifier))
+}
+
+/// Parses an arXiv API response for several `identifiers` into a [`Paper`] per identifier found,
+/// in the order of `identifiers`.
+fn parse_batch_feed(response: &str, identifiers: &[&str]) -> Result<Vec<Paper>, LearnerError> {
+  let feed: Feed = from_str(response)
+    .map_err(|e| LearnerError::ApiError(format!("Failed to parse XML: {}", e)))?;
+
+  // Entries are matched to identifiers by their URL, e.g. ".../abs/2301.07041v2", since arXiv
+  // doesn't promise to return them in order and reports unknown identifiers as error entries
+  Ok(
+    identifiers
+      .iter()
+      .filter_map(|identifier| {
+        let (base_identifier, _) = split_version(identifier);
+        feed
+          .entries
+          .iter()
+          .find(|entry| {
+            entry
+              .arxiv_url
+              .split_once("/abs/")
+              .is_some_and(|(_, id)| split_version(id).0 == base_identifier)
+          })
+          .map(|entry| entry_to_paper(entry, identifier))
+      })
+      .collect(),
+  )
+}
+
+/// Parses an arXiv API search response into a [`Paper`] per entry.
+fn parse_search_feed(response: &str) -> Result<Vec<Paper>, LearnerError> {
+  let feed: Feed = from_str(response)
+    .map_err(|e| LearnerError::ApiError(format!("Failed to parse XML: {}", e)))?;
 
-    // Convert arXiv URL to PDF URL (just need to change /abs/ to /pdf/ and add .pdf)
-    let pdf_url = entry.arxiv_url.replace("/abs/", "/pdf/") + ".pdf";
+  Ok(
+    feed
+      .entries
+      .iter()
+      .map(|entry| {
+        // The entry's URL ends with its identifier, e.g. ".../abs/2301.07041v2"
+        let identifier = entry.arxiv_url.split_once("/abs/").map_or("", |(_, id)| id);
+        entry_to_paper(entry, identifier)
+      })
+      .collect(),
+  )
+}
+
+/// Converts a feed entry, fetched as `identifier`, into a [`Paper`].
+fn entry_to_paper(entry: &Entry, identifier: &str) -> Paper {
+  // The entry's URL names the version that was actually returned, e.g. ".../2301.07041v2"
+  let (base_identifier, requested_version) = split_version(identifier);
+  let (base_url, returned_version) = split_version(&entry.arxiv_url);
+  let version = returned_version.or(requested_version).map(str::to_string);
+
+  // Convert arXiv URL to PDF URL (change /abs/ to /pdf/ and add .pdf), pinned to the version
+  let pdf_url =
+    format!("{}{}.pdf", base_url.replace("/abs/", "/pdf/"), version.as_deref().unwrap_or(""));
+
+  // Primary category first, then the others in feed order
+  let mut categories: Vec<String> =
+    entry.primary_category.iter().map(|category| category.term.clone()).collect();
+  for category in &entry.categories {
+    if !categories.contains(&category.term) {
+      categories.push(category.term.clone());
+    }
+  }
+  // Cleaning the abstract includes `format::clean_latex`, which is all the title needs
+  let (abstract_text, raw_abstract) = clean_abstract(entry.summary.clone());
+
+  Paper {
+    title: format::clean_latex(&entry.title),
+    authors: entry
+      .authors
+      .iter()
+      .map(|author| crate::Author {
+        name:        author.name.clone(),
+        affiliation: None,
+        email:       None,
+      })
+      .collect(),
+    abstract_text,
+    raw_abstract,
+    publication_date: entry.published,
+    source: Source::Arxiv,
+    source_identifier: base_identifier.to_string(),
+    pdf_url: Some(pdf_url),
+    doi: entry.doi.as_ref().map(|doi| doi.trim().to_string()),
+    version,
+    keywords: categories.clone(),
+    categories,
+    tags: Vec::new(),
+    pdf_path: None,
+    reading_status: ReadingStatus::Unread,
+    added_at: None,
+  }
+}
 
-    Ok(Paper {
-      title:             entry.title.clone(),
-      authors:           entry
-        .authors
-        .iter()
-        .map(|author| crate::Author {
-          name:        author.name.clone(),
-          affiliation: None,
-          email:       None,
-        })
-        .collect(),
-      abstract_text:     entry.summary.clone(),
-      publication_date:  entry.published,
-      source:            Source::Arxiv,
-      source_identifier: identifier.to_string(),
-      pdf_url:           Some(pdf_url),
-      doi:               None, // We can add DOI extraction if needed
-    })
+/// Splits an arXiv identifier (or abstract URL) into the identifier without its version and
+/// the version suffix, if any.
+///
+/// For example, "2301.07041v2" becomes `("2301.07041", Some("v2"))`.
+pub(crate) fn split_version(identifier: &str) -> (&str, Option<&str>) {
+  let digits = identifier.trim_end_matches(|c: char| c.is_ascii_digit());
+  match digits.strip_suffix('v') {
+    Some(base)
+      if digits.len() < identifier.len() && base.ends_with(|c: char| c.is_ascii_digit()) =>
+      (base, Some(&identifier[base.len()..])),
+    _ => (identifier, None),
   }
 }
 
@@ -184,6 +652,291 @@ mod tests {
 
   use super::*;
 
+  #[test]
+  fn test_split_version() {
+    assert_eq!(split_version("2301.07041v2"), ("2301.07041", Some("v2")));
+    assert_eq!(split_version("2301.07041v12"), ("2301.07041", Some("v12")));
+    assert_eq!(split_version("2301.07041"), ("2301.07041", None));
+    assert_eq!(split_version("math/0601001v1"), ("math/0601001", Some("v1")));
+    assert_eq!(split_version("math/0601001"), ("math/0601001", None));
+    assert_eq!(
+      split_version("http://arxiv.org/abs/2301.07041v3"),
+      ("http://arxiv.org/abs/2301.07041", Some("v3"))
+    );
+  }
+
+  /// A trimmed arXiv API response for "Verifiable Fully Homomorphic Encryption".
+  const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="html">ArXiv Query: id_list=2301.07041</title>
+  <entry>
+    <id>http://arxiv.org/abs/2301.07041v2</id>
+    <updated>2023-09-19T11:43:49Z</updated>
+    <published>2023-01-17T18:00:59Z</published>
+    <title>Verifiable Fully Homomorphic Encryption</title>
+    <summary>Fully Homomorphic Encryption (FHE) is seeing increasing real-world deployment.</summary>
+    <author>
+      <name>Alexander Viand</name>
+    </author>
+    <author>
+      <name>Christian Knabenhans</name>
+    </author>
+    <link href="http://arxiv.org/abs/2301.07041v2" rel="alternate" type="text/html"/>
+    <link title="pdf" href="http://arxiv.org/pdf/2301.07041v2" rel="related" type="application/pdf"/>
+    <arxiv:primary_category xmlns:arxiv="http://arxiv.org/schemas/atom" term="cs.CR" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="cs.CR" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="cs.LG" scheme="http://arxiv.org/schemas/atom"/>
+  </entry>
+</feed>"#;
+
+  #[test]
+  fn test_parse_feed() {
+    let paper = parse_feed(FEED, "2301.07041").unwrap();
+
+    assert_eq!(paper.title, "Verifiable Fully Homomorphic Encryption");
+    assert_eq!(paper.authors.len(), 2);
+    assert_eq!(paper.source_identifier, "2301.07041");
+    assert_eq!(paper.version.as_deref(), Some("v2"));
+    assert_eq!(paper.pdf_url.as_deref(), Some("http://arxiv.org/pdf/2301.07041v2.pdf"));
+    assert_eq!(paper.categories, vec!["cs.CR", "cs.LG"]);
+    assert_eq!(paper.keywords, paper.categories);
+
+    // A requested version is kept even if the feed doesn't name one
+    let unversioned = FEED.replace("2301.07041v2</id>", "2301.07041</id>");
+    let paper = parse_feed(&unversioned, "2301.07041v1").unwrap();
+    assert_eq!(paper.source_identifier, "2301.07041");
+    assert_eq!(paper.version.as_deref(), Some("v1"));
+    assert_eq!(paper.pdf_url.as_deref(), Some("http://arxiv.org/pdf/2301.07041v1.pdf"));
+
+    let paper = parse_feed(&unversioned, "2301.07041").unwrap();
+    assert_eq!(paper.version, None);
+    assert_eq!(paper.pdf_url.as_deref(), Some("http://arxiv.org/pdf/2301.07041.pdf"));
+
+    assert_eq!(paper.doi, None);
+
+    let empty = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Empty</title></feed>"#;
+    assert!(parse_feed(empty, "2301.07041").is_err());
+  }
+
+  /// A trimmed arXiv API response for a paper whose journal version has a DOI.
+  const FEED_WITH_DOI: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry>
+    <id>http://arxiv.org/abs/1706.03762v7</id>
+    <published>2017-06-12T17:57:34Z</published>
+    <title>Attention Is All You Need</title>
+    <summary>The dominant sequence transduction models are based on complex recurrent networks.</summary>
+    <author>
+      <name>Ashish Vaswani</name>
+    </author>
+    <arxiv:doi xmlns:arxiv="http://arxiv.org/schemas/atom">10.48550/arXiv.1706.03762</arxiv:doi>
+    <link title="doi" href="http://dx.doi.org/10.48550/arXiv.1706.03762" rel="related"/>
+    <arxiv:primary_category xmlns:arxiv="http://arxiv.org/schemas/atom" term="cs.CL" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="cs.CL" scheme="http://arxiv.org/schemas/atom"/>
+  </entry>
+</feed>"#;
+
+  #[test]
+  fn test_parse_feed_doi() {
+    let paper = parse_feed(FEED_WITH_DOI, "1706.03762").unwrap();
+    assert_eq!(paper.title, "Attention Is All You Need");
+    assert_eq!(paper.doi.as_deref(), Some("10.48550/arXiv.1706.03762"));
+    assert_eq!(paper.version.as_deref(), Some("v7"));
+  }
+
+  #[tokio::test]
+  async fn test_parse_feed_cleans_latex() {
+    // arXiv wraps long titles and abstracts, indenting the continuation lines
+    let feed = FEED
+      .replace(
+        "<title>Verifiable Fully Homomorphic Encryption</title>",
+        "<title>Verifiable \\emph{Fully}\n  Homomorphic Encryption</title>",
+      )
+      .replace(
+        "Fully Homomorphic Encryption (FHE) is seeing increasing real-world deployment.",
+        "FHE over big\n  data costs $\\mathcal{O}(n)$ with \\textbf{no} loss.",
+      );
+    let paper = parse_feed(&feed, "2301.07041").unwrap();
+    assert_eq!(paper.title, "Verifiable Fully Homomorphic Encryption");
+    assert_eq!(paper.abstract_text, "FHE over big data costs \\mathcal{O}(n) with no loss.");
+    assert_eq!(
+      paper.raw_abstract.as_deref(),
+      Some("FHE over big\n  data costs $\\mathcal{O}(n)$ with \\textbf{no} loss.")
+    );
+
+    // A phrase split across lines can be searched for
+    let dir = tempfile::tempdir().unwrap();
+    let db = crate::database::Database::open(dir.path().join("test.db")).await.unwrap();
+    db.save_paper(&paper).await.unwrap();
+    let results = db.search_papers("\"big data\"").await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].source_identifier, "2301.07041");
+  }
+
+  /// A trimmed arXiv API search response with two entries.
+  const SEARCH_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/">
+  <title type="html">ArXiv Query: search_query=all:zero AND all:knowledge</title>
+  <opensearch:totalResults>2</opensearch:totalResults>
+  <entry>
+    <id>http://arxiv.org/abs/2301.07041v2</id>
+    <published>2023-01-17T18:00:59Z</published>
+    <title>Verifiable Fully Homomorphic Encryption</title>
+    <summary>Fully Homomorphic Encryption (FHE) is seeing increasing real-world deployment.</summary>
+    <author>
+      <name>Alexander Viand</name>
+    </author>
+    <category term="cs.CR" scheme="http://arxiv.org/schemas/atom"/>
+  </entry>
+  <entry>
+    <id>http://arxiv.org/abs/math/0601001v1</id>
+    <published>2005-12-31T21:00:00Z</published>
+    <title>An Old-Style Paper</title>
+    <summary>Old identifiers contain a slash.</summary>
+    <author>
+      <name>Some Mathematician</name>
+    </author>
+  </entry>
+</feed>"#;
+
+  #[test]
+  fn test_parse_search_feed() {
+    let papers = parse_search_feed(SEARCH_FEED).unwrap();
+    assert_eq!(papers.len(), 2);
+
+    assert_eq!(papers[0].title, "Verifiable Fully Homomorphic Encryption");
+    assert_eq!(papers[0].source_identifier, "2301.07041");
+    assert_eq!(papers[0].version.as_deref(), Some("v2"));
+    assert_eq!(papers[0].categories, vec!["cs.CR"]);
+
+    assert_eq!(papers[1].source_identifier, "math/0601001");
+    assert_eq!(papers[1].pdf_url.as_deref(), Some("http://arxiv.org/pdf/math/0601001v1.pdf"));
+
+    // A search matching nothing has no entries
+    let empty = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Empty</title></feed>"#;
+    assert!(parse_search_feed(empty).unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_search_query() {
+    assert_eq!(search_query("zero knowledge proofs"), "all:zero AND all:knowledge AND all:proofs");
+    assert_eq!(search_query("ti:snark AND au:groth"), "ti:snark AND au:groth");
+    assert_eq!(search_query("(ti:snark OR ti:stark)"), "(ti:snark OR ti:stark)");
+    // A colon in a title isn't a field
+    assert_eq!(
+      search_query("Zero-knowledge: a survey"),
+      "all:Zero-knowledge AND all:a AND all:survey"
+    );
+    assert_eq!(
+      search_query("note: 10:30 meeting"),
+      "all:note AND all:10 AND all:30 AND all:meeting"
+    );
+    assert_eq!(ArxivSort::from_str("Submitted").unwrap(), ArxivSort::Submitted);
+    assert!(matches!(ArxivSort::from_str("random"), Err(LearnerError::InvalidSort(_))));
+  }
+
+  #[tokio::test]
+  async fn test_list_new() {
+    use std::sync::atomic::Ordering;
+
+    let (url, requests) = http::serve_responses(&[(200, SEARCH_FEED)]).await;
+    let client = ArxivClient::new().with_base_url(url).with_timeout(Duration::from_secs(5));
+
+    let since = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+    let papers = client.list_new("cs.CR", since).await.unwrap();
+    assert_eq!(papers.len(), 1);
+    assert_eq!(papers[0].source_identifier, "2301.07041");
+    // The page reached papers older than `since`, so there was no need for another
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+  }
+
+  #[tokio::test]
+  async fn test_fetch_from_base_url() {
+    use std::sync::atomic::Ordering;
+
+    let (url, requests) = http::serve_responses(&[(200, FEED)]).await;
+    let client = ArxivClient::new().with_base_url(url).with_timeout(Duration::from_secs(5));
+
+    let paper = client.fetch_paper("2301.07041").await.unwrap();
+    assert_eq!(paper.title, "Verifiable Fully Homomorphic Encryption");
+    assert_eq!(paper.source_identifier, "2301.07041");
+    assert_eq!(paper.version.as_deref(), Some("v2"));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    let (url, _) = http::serve_responses(&[(200, SEARCH_FEED)]).await;
+    let papers = ArxivClient::new().with_base_url(url).search("homomorphic", 2).await.unwrap();
+    assert_eq!(papers.len(), 2);
+  }
+
+  #[tokio::test]
+  async fn test_shared_rate_limiter_paces_only_its_clients() {
+    use std::{sync::atomic::Ordering, time::Instant};
+
+    let interval = Duration::from_millis(300);
+    let (url, requests) = http::serve_responses(&[(200, FEED)]).await;
+    let limiter = Arc::new(RateLimiter::with_interval(interval));
+    let first = ArxivClient::new().with_base_url(&url).with_rate_limiter(Arc::clone(&limiter));
+    let second = ArxivClient::new().with_base_url(url).with_rate_limiter(limiter);
+    let (url, doi_requests) = http::serve_responses(&[(404, "Resource not found.")]).await;
+    let doi = DOIClient::new().with_base_url(url);
+
+    let start = Instant::now();
+    let (first, second, doi_elapsed) =
+      tokio::join!(first.fetch_paper("2301.07041"), second.fetch_paper("2301.07041"), async {
+        assert!(matches!(doi.fetch_paper("10.1145/0000000").await, Err(LearnerError::NotFound)));
+        start.elapsed()
+      });
+    assert_eq!(first.unwrap().source_identifier, "2301.07041");
+    assert_eq!(second.unwrap().source_identifier, "2301.07041");
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert_eq!(doi_requests.load(Ordering::SeqCst), 1);
+
+    // The arXiv clients take turns, while the DOI client, with a limiter of its own, doesn't
+    // wait for them
+    assert!(start.elapsed() >= interval);
+    assert!(doi_elapsed < interval, "{doi_elapsed:?}");
+  }
+
+  #[tokio::test]
+  async fn test_fetch_papers_in_request_order() {
+    use std::sync::atomic::Ordering;
+
+    let (url, requests) = http::serve_responses(&[(200, SEARCH_FEED)]).await;
+    let client = ArxivClient::new().with_base_url(url).with_timeout(Duration::from_secs(5));
+
+    // Requested in the opposite order to the feed, with an identifier arXiv doesn't know
+    let papers =
+      client.fetch_papers(&["math/0601001", "9999.99999", "2301.07041v2"]).await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+    assert_eq!(papers.len(), 2);
+    assert_eq!(papers[0].source_identifier, "math/0601001");
+    assert_eq!(papers[1].source_identifier, "2301.07041");
+    assert_eq!(papers[1].version.as_deref(), Some("v2"));
+
+    assert!(client.fetch_papers(&[]).await.unwrap().is_empty());
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+  }
+
+  #[tokio::test]
+  async fn test_with_client_uses_its_timeout() {
+    // A server that accepts connections but never answers
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+      let mut connections = Vec::new();
+      while let Ok((socket, _)) = listener.accept().await {
+        connections.push(socket);
+      }
+    });
+
+    let http = reqwest::Client::builder().timeout(Duration::from_millis(1)).build().unwrap();
+    let client = ArxivClient::with_client(http).with_base_url(url).with_retry(0);
+    let result = tokio::time::timeout(Duration::from_secs(5), client.fetch_paper("2301.07041"))
+      .await
+      .expect("the injected client's timeout should apply");
+    assert!(matches!(result, Err(LearnerError::Timeout(_))));
+  }
+
   #[traced_test]
   #[tokio::test]
   async fn test_arxiv_entry_fetch() {
@@ -196,5 +949,18 @@ mod tests {
     assert!(!paper.authors.is_empty());
     assert_eq!(paper.source, Source::Arxiv);
     assert_eq!(paper.source_identifier, "2301.07041");
+    assert!(!paper.categories.is_empty());
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_arxiv_fetch_papers() {
+    let client = ArxivClient::new();
+    let papers = client.fetch_papers(&["2301.07041", "1706.03762"]).await.unwrap();
+
+    assert_eq!(papers.len(), 2);
+    assert_eq!(papers[0].source_identifier, "2301.07041");
+    assert_eq!(papers[1].source_identifier, "1706.03762");
+    assert!(papers.iter().all(|paper| !paper.title.is_empty()));
   }
 }
@@ -0,0 +1,351 @@
+//! Client implementation for fetching preprints from bioRxiv and medRxiv.
+//!
+//! This module provides functionality to fetch preprint metadata from the bioRxiv Content API
+//! and convert it to the common [`Paper`] format. Both servers are run by Cold Spring Harbor
+//! Laboratory and share the `10.1101` DOI prefix, so preprints are identified by their DOI,
+//! e.g. `"10.1101/2020.03.20.000001"` (or `"10.1101/339747"` for older preprints).
+//!
+//! The client uses the Content API (https://api.biorxiv.org), which is free to use without an
+//! API key. A DOI is looked up on bioRxiv first, then on medRxiv.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::clients::BioRxivClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = BioRxivClient::new();
+//! let paper = client.fetch_paper("10.1101/2020.03.20.000001").await?;
+//!
+//! println!("Title: {}", paper.title);
+//! println!("Authors: {}", paper.authors.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::NaiveDate;
+
+use super::*;
+
+/// The preprint servers behind the Content API, in the order they are tried.
+const SERVERS: [&str; 2] = ["biorxiv", "medrxiv"];
+
+/// Internal representation of a Content API `details` response.
+#[derive(Debug, Deserialize)]
+struct BioRxivResponse {
+  /// One entry per version of the preprint, oldest first
+  #[serde(default)]
+  collection: Vec<BioRxivPreprint>,
+}
+
+/// A single version of a preprint from the Content API.
+#[derive(Debug, Deserialize)]
+struct BioRxivPreprint {
+  /// The preprint's DOI
+  doi:           String,
+  /// Preprint title
+  title:         String,
+  /// Authors as a single string, e.g. "Smith, J.; Doe, J. A."
+  #[serde(default)]
+  authors:       String,
+  /// Date this version was posted, in `YYYY-MM-DD` format
+  date:          String,
+  /// Version number of this entry, e.g. "2"
+  version:       String,
+  /// Subject area, e.g. "neuroscience"
+  category:      Option<String>,
+  /// Preprint abstract
+  #[serde(rename = "abstract", default)]
+  abstract_text: String,
+  /// DOI of the published journal article, or "NA" if not yet published
+  published:     Option<String>,
+  /// Server the preprint was posted to, "bioRxiv" or "medRxiv"
+  server:        Option<String>,
+}
+
+/// Client for fetching preprints from the bioRxiv Content API.
+///
+/// This client provides methods to fetch preprint metadata from bioRxiv and medRxiv by DOI. It
+/// handles the HTTP requests, JSON parsing, and conversion to the common [`Paper`] format.
+pub struct BioRxivClient {
+  /// Internal web client used to connect to the API.
+  client:   reqwest::Client,
+  /// The base URL to use for the client.
+  base_url: String,
+  /// Timeout and retry settings.
+  config:   ClientConfig,
+}
+
+impl BioRxivClient {
+  /// Creates a new bioRxiv client instance.
+  ///
+  /// Initializes an HTTP client for making requests to the Content API.
+  pub fn new() -> Self { Self::new_with_config(ClientConfig::default()) }
+
+  /// Creates a new bioRxiv client with custom timeout and retry settings.
+  pub fn new_with_config(config: ClientConfig) -> Self {
+    Self { config, ..Self::with_client(config.http_client()) }
+  }
+
+  /// Creates a new bioRxiv client that sends its requests through `client`, e.g. one configured
+  /// once with a proxy, TLS roots, or default headers and shared across sources.
+  ///
+  /// Requests are limited by `client`'s own timeout and retried as by
+  /// [`ClientConfig::default`].
+  pub fn with_client(client: reqwest::Client) -> Self {
+    Self {
+      client,
+      base_url: "https://api.biorxiv.org/details".to_string(),
+      config: ClientConfig::default(),
+    }
+  }
+
+  /// Sets how many times to retry a request after a transient failure, keeping the other
+  /// settings. See [`ClientConfig::max_retries`].
+  pub fn with_retry(mut self, retries: u8) -> Self {
+    self.config.max_retries = retries.into();
+    self
+  }
+
+  /// Sets the maximum time a single request may take, keeping the other settings. See
+  /// [`ClientConfig::timeout`]. A request that takes longer fails with
+  /// [`LearnerError::Timeout`]. This builds a new HTTP client, replacing one given to
+  /// `with_client`.
+  pub fn with_timeout(self, timeout: Duration) -> Self {
+    Self {
+      base_url: self.base_url,
+      ..Self::new_with_config(ClientConfig { timeout, ..self.config })
+    }
+  }
+
+  /// Fetches preprint metadata from bioRxiv or medRxiv using its DOI.
+  ///
+  /// The latest version of the preprint is returned.
+  ///
+  /// # Arguments
+  ///
+  /// * `doi` - A bioRxiv or medRxiv DOI (e.g., "10.1101/2020.03.20.000001")
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - A [`Paper`] with the fetched metadata
+  /// - A [`LearnerError`] if the fetch or parsing fails
+  ///
+  /// # Errors
+  ///
+  /// This function will return an error if:
+  /// - The network request fails
+  /// - The preprint is on neither server ([`LearnerError::NotFound`])
+  /// - The API response cannot be parsed
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::BioRxivClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let client = BioRxivClient::new();
+  /// let paper = client.fetch_paper("10.1101/2020.03.20.000001").await?;
+  ///
+  /// println!("Latest version: {:?}", paper.version);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn fetch_paper(&self, doi: &str) -> Result<Paper, LearnerError> {
+    for server in SERVERS {
+      let url = format!("{}/{}/{}/na/json", self.base_url, server, doi);
+      debug!("Fetching from {server} via: {url}");
+
+      let response = send_with_retry(&self.config, "bioRxiv", || self.client.get(&url)).await?;
+      let status = response.status();
+      debug!("bioRxiv response status: {status}");
+
+      let text = response.text().await?;
+      trace!("bioRxiv response: {text}");
+
+      if !status.is_success() {
+        return Err(LearnerError::ApiError(format!("bioRxiv returned {status}: {text}")));
+      }
+
+      if let Some(paper) = parse_paper(&text)? {
+        return Ok(paper);
+      }
+    }
+
+    Err(LearnerError::NotFound)
+  }
+}
+
+impl Default for BioRxivClient {
+  fn default() -> Self { Self::new() }
+}
+
+/// Parses a Content API `details` response into a [`Paper`], using the latest version.
+///
+/// Returns `Ok(None)` if the server has no preprint with the requested DOI.
+fn parse_paper(text: &str) -> Result<Option<Paper>, LearnerError> {
+  let response: BioRxivResponse = serde_json::from_str(text)
+    .map_err(|e| LearnerError::ApiError(format!("Failed to parse JSON: {}", e)))?;
+
+  let Some(preprint) = response.collection.into_iter().last() else {
+    return Ok(None);
+  };
+
+  let publication_date = NaiveDate::parse_from_str(&preprint.date, "%Y-%m-%d")
+    .ok()
+    .and_then(|date| date.and_hms_opt(0, 0, 0))
+    .map(|date| date.and_utc())
+    .ok_or_else(|| LearnerError::ApiError(format!("Invalid date: {}", preprint.date)))?;
+
+  let server = preprint.server.as_deref().unwrap_or("bioRxiv").to_lowercase();
+  let pdf_url =
+    format!("https://www.{}.org/content/{}v{}.full.pdf", server, preprint.doi, preprint.version);
+  let (abstract_text, raw_abstract) = clean_abstract(preprint.abstract_text);
+
+  Ok(Some(Paper {
+    title: collapse_whitespace(&preprint.title),
+    authors: parse_authors(&preprint.authors),
+    abstract_text,
+    raw_abstract,
+    publication_date,
+    source: Source::BioRxiv,
+    source_identifier: preprint.doi.clone(),
+    pdf_url: Some(pdf_url),
+    // Point at the journal article once the preprint has been published
+    doi: preprint.published.filter(|doi| doi != "NA" && !doi.is_empty()).or(Some(preprint.doi)),
+    version: Some(format!("v{}", preprint.version)),
+    categories: preprint.category.into_iter().filter(|c| !c.is_empty()).collect(),
+    keywords: Vec::new(),
+    tags: Vec::new(),
+    pdf_path: None,
+    reading_status: ReadingStatus::Unread,
+    added_at: None,
+  }))
+}
+
+/// Splits the Content API author string ("Smith, J.; Doe, J. A.") into authors, rewriting
+/// each name to "First Last" order.
+fn parse_authors(authors: &str) -> Vec<Author> {
+  authors
+    .split(';')
+    .map(|name| match name.split_once(',') {
+      Some((last, first)) => collapse_whitespace(&format!("{} {}", first, last)),
+      None => collapse_whitespace(name),
+    })
+    .filter(|name| !name.is_empty())
+    .map(|name| Author { name, affiliation: None, email: None })
+    .collect()
+}
+
+/// Trims text and collapses runs of whitespace (including newlines) into single spaces.
+fn collapse_whitespace(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A trimmed Content API response for a preprint with two versions.
+  const PAPER_RESPONSE: &str = r#"{
+    "messages": [{"status": "ok"}],
+    "collection": [
+      {
+        "doi": "10.1101/2020.03.20.000001",
+        "title": "A first draft",
+        "authors": "Smith, J.; Doe, J. A.",
+        "author_corresponding": "Jane Doe",
+        "author_corresponding_institution": "Example University",
+        "date": "2020-03-20",
+        "version": "1",
+        "type": "new results",
+        "license": "cc_by",
+        "category": "neuroscience",
+        "jatsxml": "https://www.biorxiv.org/content/early/2020/03/20/2020.03.20.000001.source.xml",
+        "abstract": "An early abstract.",
+        "published": "NA",
+        "server": "bioRxiv"
+      },
+      {
+        "doi": "10.1101/2020.03.20.000001",
+        "title": "Mapping neurons\n in the mouse cortex",
+        "authors": "Smith, J.; Doe, J. A.; Consortium",
+        "author_corresponding": "Jane Doe",
+        "author_corresponding_institution": "Example University",
+        "date": "2020-04-02",
+        "version": "2",
+        "type": "new results",
+        "license": "cc_by",
+        "category": "neuroscience",
+        "jatsxml": "https://www.biorxiv.org/content/early/2020/04/02/2020.03.20.000001.source.xml",
+        "abstract": "We map <i>neurons</i>\n in the mouse cortex.",
+        "published": "10.1038/s41586-020-0001-1",
+        "server": "bioRxiv"
+      }
+    ]
+  }"#;
+
+  #[test]
+  fn test_parse_paper() {
+    let paper = parse_paper(PAPER_RESPONSE).unwrap().unwrap();
+
+    assert_eq!(paper.title, "Mapping neurons in the mouse cortex");
+    assert_eq!(paper.authors.len(), 3);
+    assert_eq!(paper.authors[0].name, "J. Smith");
+    assert_eq!(paper.authors[1].name, "J. A. Doe");
+    assert_eq!(paper.authors[2].name, "Consortium");
+    assert_eq!(paper.abstract_text, "We map neurons in the mouse cortex.");
+    assert_eq!(paper.raw_abstract.as_deref(), Some("We map <i>neurons</i>\n in the mouse cortex."));
+    assert_eq!(paper.publication_date, Utc.with_ymd_and_hms(2020, 4, 2, 0, 0, 0).unwrap());
+    assert_eq!(paper.source, Source::BioRxiv);
+    assert_eq!(paper.source_identifier, "10.1101/2020.03.20.000001");
+    assert_eq!(paper.version.as_deref(), Some("v2"));
+    assert_eq!(paper.categories, vec!["neuroscience".to_string()]);
+    assert_eq!(
+      paper.pdf_url.as_deref(),
+      Some("https://www.biorxiv.org/content/10.1101/2020.03.20.000001v2.full.pdf")
+    );
+    assert_eq!(paper.doi.as_deref(), Some("10.1038/s41586-020-0001-1"));
+  }
+
+  #[test]
+  fn test_parse_paper_medrxiv_unpublished() {
+    let text = r#"{
+      "messages": [{"status": "ok"}],
+      "collection": [{
+        "doi": "10.1101/2021.01.01.21249999",
+        "title": "An unpublished medRxiv preprint",
+        "authors": "Lee, K.",
+        "date": "2021-01-03",
+        "version": "1",
+        "category": "epidemiology",
+        "abstract": "",
+        "published": "NA",
+        "server": "medRxiv"
+      }]
+    }"#;
+    let paper = parse_paper(text).unwrap().unwrap();
+
+    assert_eq!(paper.doi.as_deref(), Some("10.1101/2021.01.01.21249999"));
+    assert_eq!(
+      paper.pdf_url.as_deref(),
+      Some("https://www.medrxiv.org/content/10.1101/2021.01.01.21249999v1.full.pdf")
+    );
+    assert!(paper.abstract_text.is_empty());
+
+    // An unknown DOI returns an empty collection
+    let not_found = r#"{"messages": [{"status": "no posts found"}], "collection": []}"#;
+    assert!(parse_paper(not_found).unwrap().is_none());
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_biorxiv_entry_fetch() {
+    let client = BioRxivClient::new();
+    let paper = client.fetch_paper("10.1101/339747").await.unwrap();
+
+    assert!(!paper.title.is_empty());
+    assert!(!paper.authors.is_empty());
+    assert_eq!(paper.source, Source::BioRxiv);
+  }
+}
@@ -22,8 +22,25 @@
 //! # }
 //! ```
 
+use chrono::Datelike;
+use url::Url;
+
 use super::*;
 
+/// Environment variable giving the contact email sent to Crossref by clients that aren't given
+/// one with [`DOIClient::with_mailto`].
+pub const CROSSREF_EMAIL_ENV_VAR: &str = "LEARNER_CROSSREF_EMAIL";
+
+/// Environment variable read for the contact email when [`CROSSREF_EMAIL_ENV_VAR`] is not set,
+/// as other Crossref tools use it.
+pub const CROSSREF_MAILTO_ENV_VAR: &str = "CROSSREF_MAILTO";
+
+/// Application name and version at the start of the user agent sent to Crossref.
+const CROSSREF_USER_AGENT: &str = concat!("learner/", env!("CARGO_PKG_VERSION"));
+
+/// Project URL included in the user agent sent to Crossref.
+const PROJECT_URL: &str = "https://github.com/autoparallel/learner";
+
 /// Response structure from the Crossref API.
 #[derive(Debug, Deserialize)]
 struct CrossrefResponse {
@@ -35,8 +52,10 @@ struct CrossrefResponse {
 #[derive(Debug, Deserialize)]
 struct CrossrefWork {
   /// Paper titles (usually contains one item)
+  #[serde(default)]
   title:            Vec<String>,
   /// List of paper authors with their details
+  #[serde(default)]
   author:           Vec<CrossrefAuthor>,
   /// Paper abstract, which may not be available for all works
   #[serde(rename = "abstract")]
@@ -51,8 +70,16 @@ struct CrossrefWork {
   /// The paper's DOI
   #[serde(rename = "DOI")]
   doi:              String,
+  /// Earliest publication date known to Crossref
+  issued:           Option<CrossrefDate>,
   /// Creation date in Crossref's system (fallback for publication date)
   created:          Option<CrossrefDate>,
+  /// Subject areas of the journal or proceedings the work appeared in
+  #[serde(default)]
+  subject:          Vec<String>,
+  /// Crossref's relevance score, only given in search results
+  #[serde(default)]
+  score:            f64,
 }
 
 /// Author information from Crossref.
@@ -63,6 +90,7 @@ struct CrossrefAuthor {
   /// Author's family (last) name
   family:      Option<String>,
   /// List of author's affiliations
+  #[serde(default)]
   affiliation: Vec<CrossrefAffiliation>,
 }
 
@@ -82,6 +110,112 @@ struct CrossrefDate {
   date_parts: Vec<Vec<i32>>,
 }
 
+/// Response structure from a Crossref works search.
+#[derive(Debug, Deserialize)]
+struct CrossrefSearchResponse {
+  /// The search results container
+  message: CrossrefSearchMessage,
+}
+
+/// Response structure from a Crossref works search, with each work left unparsed so that one
+/// malformed work doesn't fail the whole search.
+#[derive(Debug, Deserialize)]
+struct CrossrefWorksResponse {
+  /// The search results container
+  message: CrossrefWorksMessage,
+}
+
+/// The works matching a Crossref search, as raw JSON.
+#[derive(Debug, Deserialize)]
+struct CrossrefWorksMessage {
+  /// Matching works, ordered by descending relevance score
+  items: Vec<serde_json::Value>,
+}
+
+/// The list of works matching a Crossref search.
+#[derive(Debug, Deserialize)]
+struct CrossrefSearchMessage {
+  /// Matching works, ordered by descending relevance score
+  items: Vec<CrossrefSearchItem>,
+}
+
+/// A single work in a Crossref search response.
+///
+/// Search results are frequently incomplete, so every field other than the DOI is optional.
+#[derive(Debug, Deserialize)]
+struct CrossrefSearchItem {
+  /// The work's DOI
+  #[serde(rename = "DOI")]
+  doi:             String,
+  /// Work titles (usually contains one item)
+  #[serde(default)]
+  title:           Vec<String>,
+  /// List of work authors
+  #[serde(default)]
+  author:          Vec<CrossrefAuthor>,
+  /// Crossref's relevance score for this result
+  #[serde(default)]
+  score:           f64,
+  /// Earliest publication date known to Crossref
+  issued:          Option<CrossrefDate>,
+  /// Container title, such as the journal or proceedings name
+  #[serde(rename = "container-title", default)]
+  container_title: Vec<String>,
+}
+
+/// A candidate work returned by a Crossref bibliographic search.
+///
+/// Candidates carry just enough metadata for a user to recognize the intended work. The
+/// full metadata can be fetched afterwards with [`DOIClient::fetch_paper`] using
+/// [`CrossrefMatch::doi`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrossrefMatch {
+  /// The work's DOI
+  pub doi:     String,
+  /// The work's title, or an empty string if Crossref has none
+  pub title:   String,
+  /// Author names in publication order
+  pub authors: Vec<String>,
+  /// Year of publication, if known
+  pub year:    Option<i32>,
+  /// Journal or proceedings the work appeared in, if known
+  pub venue:   Option<String>,
+  /// Crossref's relevance score, higher is better
+  pub score:   f64,
+}
+
+/// Minimum Crossref relevance score for a bibliographic search result to be accepted
+/// without confirmation.
+///
+/// Crossref scores are unnormalized, but a full reference string that names the intended work
+/// typically scores well above this while unrelated works fall well below it.
+pub const DEFAULT_MATCH_THRESHOLD: f64 = 60.0;
+
+/// Picks the best candidate from a bibliographic search if it is confident enough.
+///
+/// Returns the highest scoring match when its score is at least `threshold`, and `None`
+/// otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use learner::clients::doi::{select_match, CrossrefMatch, DEFAULT_MATCH_THRESHOLD};
+///
+/// let matches = vec![CrossrefMatch {
+///   doi:     "10.1145/1327452.1327492".into(),
+///   title:   "MapReduce".into(),
+///   authors: vec!["Jeffrey Dean".into()],
+///   year:    Some(2008),
+///   venue:   None,
+///   score:   112.0,
+/// }];
+/// assert!(select_match(&matches, DEFAULT_MATCH_THRESHOLD).is_some());
+/// assert!(select_match(&matches, 200.0).is_none());
+/// ```
+pub fn select_match(matches: &[CrossrefMatch], threshold: f64) -> Option<&CrossrefMatch> {
+  matches.iter().max_by(|a, b| a.score.total_cmp(&b.score)).filter(|m| m.score >= threshold)
+}
+
 /// Client for fetching paper metadata using DOIs via the Crossref API.
 ///
 /// This client provides methods to resolve DOIs and fetch associated metadata
@@ -89,14 +223,27 @@ struct CrossrefDate {
 /// and conversion of Crossref's rich metadata format to the common [`Paper`] structure.
 ///
 /// The client follows Crossref's best practices including:
-/// - Proper user agent identification
-/// - Rate limiting consideration
+/// - Proper user agent identification. Given a contact email (see [`DOIClient::with_mailto`]), the
+///   client includes it in its user agent and `mailto` query parameter, which routes requests to
+///   Crossref's more reliable "polite" pool
+/// - Rate limiting consideration: when Crossref answers `429 Too Many Requests`, the client waits
+///   as long as its `Retry-After` header asks before retrying, and a client reused for several
+///   requests keeps under the limit Crossref advertises in its `X-Rate-Limit-Limit` and
+///   `X-Rate-Limit-Interval` headers
 /// - Fallback date handling
 pub struct DOIClient {
   /// Internal web client used to connect to the API.
-  client:   reqwest::Client,
+  client:       reqwest::Client,
   /// The base URL to use for the client.
-  base_url: String,
+  base_url:     String,
+  /// Timeout and retry settings.
+  config:       ClientConfig,
+  /// Caps how often requests are sent, possibly shared with other clients.
+  rate_limiter: Arc<RateLimiter>,
+  /// Spaces out requests according to Crossref's advertised rate limit.
+  throttle:     Throttle,
+  /// Contact email sent with every request, if any.
+  mailto:       Option<String>,
 }
 
 impl DOIClient {
@@ -104,14 +251,87 @@ impl DOIClient {
   ///
   /// Initializes an HTTP client with appropriate headers for Crossref API access.
   /// The client will identify itself to Crossref with a user agent string as
-  /// required by their API terms of service.
-  pub fn new() -> Self {
+  /// required by their API terms of service, including the contact email in the
+  /// [`CROSSREF_EMAIL_ENV_VAR`] or, failing that, [`CROSSREF_MAILTO_ENV_VAR`] environment
+  /// variable if either is set.
+  pub fn new() -> Self { Self::new_with_config(ClientConfig::default()) }
+
+  /// Creates a new DOI client with custom timeout and retry settings.
+  pub fn new_with_config(config: ClientConfig) -> Self {
+    Self { config, ..Self::with_client(config.http_client()) }
+  }
+
+  /// Creates a new DOI client that sends its requests through `client`, e.g. one configured
+  /// once with a proxy, TLS roots, or default headers and shared across sources.
+  ///
+  /// Requests are limited by `client`'s own timeout and retried as by
+  /// [`ClientConfig::default`]. They still carry the user agent Crossref requires.
+  pub fn with_client(client: reqwest::Client) -> Self {
+    let mailto = std::env::var(CROSSREF_EMAIL_ENV_VAR)
+      .or_else(|_| std::env::var(CROSSREF_MAILTO_ENV_VAR))
+      .ok();
     Self {
-      client:   reqwest::Client::builder()
-                .user_agent("YourApp/1.0 (mailto:your@email.com)")  // Required by Crossref
-                .build()
-                .unwrap(),
+      client,
       base_url: "https://api.crossref.org/works".to_string(),
+      config: ClientConfig::default(),
+      rate_limiter: Arc::default(),
+      throttle: Throttle::default(),
+      mailto: mailto.map(|email| email.trim().to_string()).filter(|email| !email.is_empty()),
+    }
+  }
+
+  /// Sends requests to a different endpoint, such as a local test server, instead of
+  /// `https://api.crossref.org/works`, keeping the other settings.
+  pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+    self.base_url = url.into();
+    self
+  }
+
+  /// Sets the contact email Crossref can reach about this client's requests, keeping the other
+  /// settings.
+  ///
+  /// Crossref serves requests that identify a contact from its "polite" pool, which is more
+  /// reliable than the public one. The email replaces one from the environment.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::DOIClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let client = DOIClient::new().with_mailto("me@example.com");
+  /// let paper = client.fetch_paper("10.1145/1327452.1327492").await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn with_mailto(mut self, email: &str) -> Self {
+    self.mailto = Some(email.trim().to_string());
+    self
+  }
+
+  /// Sets how many times to retry a request after a transient failure, keeping the other
+  /// settings. See [`ClientConfig::max_retries`].
+  pub fn with_retry(mut self, retries: u8) -> Self {
+    self.config.max_retries = retries.into();
+    self
+  }
+
+  /// Sends requests no faster than `limiter` allows, keeping the other settings. Clients given
+  /// the same limiter share its budget, e.g. to stay within a service's polite pool.
+  pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+    self.rate_limiter = limiter;
+    self
+  }
+
+  /// Sets the maximum time a single request may take, keeping the other settings. See
+  /// [`ClientConfig::timeout`]. A request that takes longer fails with
+  /// [`LearnerError::Timeout`]. This builds a new HTTP client, replacing one given to
+  /// `with_client`.
+  pub fn with_timeout(self, timeout: Duration) -> Self {
+    Self {
+      base_url: self.base_url,
+      rate_limiter: self.rate_limiter,
+      mailto: self.mailto,
+      ..Self::new_with_config(ClientConfig { timeout, ..self.config })
     }
   }
 
@@ -151,6 +371,7 @@ impl DOIClient {
   ///
   /// This function will return an error if:
   /// - The network request fails
+  /// - Crossref is still rate limiting after all retries ([`LearnerError::RateLimited`])
   /// - The API response cannot be parsed
   /// - Required metadata fields are missing
   /// - No valid publication date can be determined
@@ -176,7 +397,7 @@ impl DOIClient {
     let url = format!("{}/{}", self.base_url, doi);
     debug!("Fetching from Crossref via: {}", url);
 
-    let response = self.client.get(&url).send().await?;
+    let response = self.get(&url).await?;
     let status = response.status();
     debug!("Crossref response status: {}", status);
 
@@ -186,8 +407,13 @@ impl DOIClient {
     let response: CrossrefResponse = serde_json::from_str(&text)
       .map_err(|e| LearnerError::ApiError(format!("Failed to parse JSON: {}", e)))?;
 
-    let work = response.message;
+    self.work_into_paper(response.message, doi.to_string())
+  }
 
+  /// Converts a Crossref work into a [`Paper`] identified by `identifier`.
+  ///
+  /// Fails with [`LearnerError::ApiError`] if the work has no title or no usable date.
+  fn work_into_paper(&self, work: CrossrefWork, identifier: String) -> Result<Paper, LearnerError> {
     debug!("Published print: {:?}", work.published_print);
     debug!("Published online: {:?}", work.published_online);
     debug!("Created: {:?}", work.created);
@@ -201,14 +427,8 @@ impl DOIClient {
       .author
       .into_iter()
       .map(|author| {
-        let name = match (author.given, author.family) {
-          (Some(given), Some(family)) => format!("{} {}", given, family),
-          (Some(given), None) => given,
-          (None, Some(family)) => family,
-          (None, None) => "Unknown".to_string(),
-        };
-
         let affiliation = author.affiliation.first().and_then(|aff| aff.name.clone());
+        let name = author.full_name();
 
         Author { name, affiliation, email: None }
       })
@@ -220,6 +440,7 @@ impl DOIClient {
       .as_ref()
       .and_then(|d| self.parse_date(d))
       .or_else(|| work.published_online.as_ref().and_then(|d| self.parse_date(d)))
+      .or_else(|| work.issued.as_ref().and_then(|d| self.parse_date(d)))
       .or_else(|| work.created.as_ref().and_then(|d| self.parse_date(d)))
       .ok_or_else(|| {
         LearnerError::ApiError(format!(
@@ -227,18 +448,256 @@ impl DOIClient {
           work.published_print, work.published_online, work.created
         ))
       })?;
+    let (abstract_text, raw_abstract) = clean_abstract(work.abstract_text.unwrap_or_default());
 
     Ok(Paper {
       title,
       authors,
-      abstract_text: work.abstract_text.unwrap_or_default(),
+      abstract_text,
+      raw_abstract,
       publication_date,
       source: Source::DOI,
-      source_identifier: doi.to_string(),
+      source_identifier: identifier,
       pdf_url: work.url,
       doi: Some(work.doi),
+      version: None,
+      categories: Vec::new(),
+      keywords: work.subject,
+      tags: Vec::new(),
+      pdf_path: None,
+      reading_status: ReadingStatus::Unread,
+      added_at: None,
     })
   }
+
+  /// Searches Crossref for works matching a free-form reference string.
+  ///
+  /// This uses Crossref's bibliographic query, which is designed for matching full citation
+  /// strings such as `"Dean, J., Ghemawat, S.: MapReduce: simplified data processing on large
+  /// clusters. CACM 2008"` against their registered works.
+  ///
+  /// # Arguments
+  ///
+  /// * `reference` - The citation or reference text to search for
+  /// * `rows` - Maximum number of candidates to return
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The candidate works, ordered by descending score (possibly empty)
+  /// - A [`LearnerError`] if the request or parsing fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::{doi::select_match, DOIClient};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let client = DOIClient::new();
+  /// let matches =
+  ///   client.search_bibliographic("Dean, Ghemawat: MapReduce: simplified data processing", 5).await?;
+  ///
+  /// if let Some(best) = select_match(&matches, learner::clients::doi::DEFAULT_MATCH_THRESHOLD) {
+  ///   let paper = client.fetch_paper(&best.doi).await?;
+  ///   println!("Title: {}", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search_bibliographic(
+    &self,
+    reference: &str,
+    rows: usize,
+  ) -> Result<Vec<CrossrefMatch>, LearnerError> {
+    let mut url = Url::parse(&self.base_url)?;
+    url
+      .query_pairs_mut()
+      .append_pair("query.bibliographic", reference)
+      .append_pair("rows", &rows.to_string());
+    debug!("Searching Crossref via: {}", url);
+
+    let response = self.get(url.as_str()).await?;
+    debug!("Crossref search response status: {}", response.status());
+
+    let text = response.text().await?;
+    trace!("Crossref search response: {}", text);
+
+    parse_search_response(&text)
+  }
+
+  /// Searches Crossref for works matching a title or other free text, returning their full
+  /// metadata.
+  ///
+  /// Like [`DOIClient::search_bibliographic`] this uses Crossref's bibliographic query, but each
+  /// result is converted into a [`Paper`] identified by its DOI, ready to be saved. Results are
+  /// ordered by Crossref's relevance score, best first. Works Crossref has no title or date for
+  /// are left out rather than failing the search.
+  ///
+  /// # Arguments
+  ///
+  /// * `query` - The title, or any words from the reference, to search for
+  /// * `rows` - Maximum number of works to ask Crossref for
+  ///
+  /// # Errors
+  ///
+  /// Returns a [`LearnerError`] if the request fails or the response isn't a Crossref work list.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::DOIClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let papers = DOIClient::new().search("mapreduce simplified data processing", 5).await?;
+  /// for paper in &papers {
+  ///   println!("{} ({})", paper.title, paper.source_identifier);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search(&self, query: &str, rows: usize) -> Result<Vec<Paper>, LearnerError> {
+    let mut url = Url::parse(&self.base_url)?;
+    url
+      .query_pairs_mut()
+      .append_pair("query.bibliographic", query)
+      .append_pair("rows", &rows.to_string());
+    debug!("Searching Crossref via: {}", url);
+
+    let response = self.get(url.as_str()).await?;
+    debug!("Crossref search response status: {}", response.status());
+
+    let text = response.text().await?;
+    trace!("Crossref search response: {}", text);
+
+    let response: CrossrefWorksResponse = serde_json::from_str(&text)
+      .map_err(|e| LearnerError::ApiError(format!("Failed to parse JSON: {}", e)))?;
+    let mut works = response
+      .message
+      .items
+      .into_iter()
+      .filter_map(|item| {
+        let work: CrossrefWork = serde_json::from_value(item)
+          .map_err(|e| debug!("Skipping unreadable Crossref work: {e}"))
+          .ok()?;
+        let (score, doi) = (work.score, work.doi.clone());
+        match self.work_into_paper(work, doi.clone()) {
+          Ok(paper) => Some((score, paper)),
+          Err(e) => {
+            debug!("Skipping Crossref work {doi}: {e}");
+            None
+          },
+        }
+      })
+      .collect::<Vec<_>>();
+    works.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    Ok(works.into_iter().map(|(_, paper)| paper).collect())
+  }
+
+  /// Looks up the DOI a paper was published under, such as the journal version of an arXiv
+  /// preprint whose feed doesn't give one.
+  ///
+  /// Crossref is searched for the paper's authors, title, and year, and a candidate is only
+  /// accepted if it scores at least [`DEFAULT_MATCH_THRESHOLD`] and has the same title, ignoring
+  /// case and punctuation, so that a similar paper's DOI is never picked up by mistake.
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The DOI of the published version, or `None` if Crossref has no confident match
+  /// - A [`LearnerError`] if the request or parsing fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::{ArxivClient, DOIClient};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let mut paper = ArxivClient::new().fetch_paper("1706.03762").await?;
+  /// if paper.doi.is_none() {
+  ///   paper.doi = DOIClient::new().find_published_doi(&paper).await?;
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn find_published_doi(&self, paper: &Paper) -> Result<Option<String>, LearnerError> {
+    let authors = paper.authors.iter().map(|author| author.name.as_str()).collect::<Vec<_>>();
+    let reference =
+      format!("{}: {}, {}", authors.join(", "), paper.title, paper.publication_date.year());
+    let matches = self.search_bibliographic(&reference, 5).await?;
+    Ok(published_match(&paper.title, &matches).map(|candidate| candidate.doi.clone()))
+  }
+
+  /// Sends a GET request, keeping under Crossref's advertised rate limit.
+  async fn get(&self, url: &str) -> Result<reqwest::Response, LearnerError> {
+    self.throttle.wait().await;
+    self.rate_limiter.wait().await;
+    let response = send_with_retry(&self.config, "Crossref", || self.request(url)).await?;
+    self.throttle.update(response.headers());
+    Ok(response)
+  }
+
+  /// Builds a GET request identifying this client, and its contact email if it has one, the way
+  /// Crossref asks: `learner/<version> (<url>; mailto:<email>)` and a `mailto` query parameter.
+  fn request(&self, url: &str) -> reqwest::RequestBuilder {
+    let user_agent = match &self.mailto {
+      Some(email) => format!("{CROSSREF_USER_AGENT} ({PROJECT_URL}; mailto:{email})"),
+      None => format!("{CROSSREF_USER_AGENT} ({PROJECT_URL})"),
+    };
+    let request = self.client.get(url).header(reqwest::header::USER_AGENT, user_agent);
+    match &self.mailto {
+      Some(email) => request.query(&[("mailto", email)]),
+      None => request,
+    }
+  }
+}
+
+/// Picks the best scoring candidate that clears [`DEFAULT_MATCH_THRESHOLD`] and has `title`,
+/// ignoring case and punctuation.
+fn published_match<'a>(title: &str, matches: &'a [CrossrefMatch]) -> Option<&'a CrossrefMatch> {
+  let normalize = |title: &str| {
+    title
+      .split(|c: char| !c.is_alphanumeric())
+      .filter(|word| !word.is_empty())
+      .map(str::to_lowercase)
+      .collect::<Vec<_>>()
+  };
+  let title = normalize(title);
+  matches
+    .iter()
+    .filter(|candidate| candidate.score >= DEFAULT_MATCH_THRESHOLD)
+    .filter(|candidate| normalize(&candidate.title) == title)
+    .max_by(|a, b| a.score.total_cmp(&b.score))
+}
+
+/// Parses the body of a Crossref works search into candidate matches.
+fn parse_search_response(text: &str) -> Result<Vec<CrossrefMatch>, LearnerError> {
+  let response: CrossrefSearchResponse = serde_json::from_str(text)
+    .map_err(|e| LearnerError::ApiError(format!("Failed to parse JSON: {}", e)))?;
+
+  Ok(
+    response
+      .message
+      .items
+      .into_iter()
+      .map(|item| CrossrefMatch {
+        doi:     item.doi,
+        title:   item.title.into_iter().next().unwrap_or_default(),
+        authors: item.author.into_iter().map(CrossrefAuthor::full_name).collect(),
+        year:    item.issued.and_then(|d| d.date_parts.first()?.first().copied()),
+        venue:   item.container_title.into_iter().next(),
+        score:   item.score,
+      })
+      .collect(),
+  )
+}
+
+impl CrossrefAuthor {
+  /// Joins the author's given and family names into a single display name.
+  fn full_name(self) -> String {
+    match (self.given, self.family) {
+      (Some(given), Some(family)) => format!("{} {}", given, family),
+      (Some(given), None) => given,
+      (None, Some(family)) => family,
+      (None, None) => "Unknown".to_string(),
+    }
+  }
 }
 
 impl Default for DOIClient {
@@ -267,4 +726,223 @@ mod tests {
 
     Ok(())
   }
+
+  /// A trimmed Crossref response for a bibliographic query on the MapReduce paper.
+  const SEARCH_RESPONSE: &str = r#"{
+    "status": "ok",
+    "message-type": "work-list",
+    "message": {
+      "total-results": 3,
+      "items": [
+        {
+          "DOI": "10.1145/1327452.1327492",
+          "title": ["MapReduce: simplified data processing on large clusters"],
+          "author": [
+            {"given": "Jeffrey", "family": "Dean", "affiliation": []},
+            {"given": "Sanjay", "family": "Ghemawat", "affiliation": []}
+          ],
+          "container-title": ["Communications of the ACM"],
+          "issued": {"date-parts": [[2008, 1]]},
+          "score": 118.4
+        },
+        {
+          "DOI": "10.1145/1629175.1629198",
+          "title": ["MapReduce: a flexible data processing tool"],
+          "author": [{"given": "Jeffrey", "family": "Dean"}],
+          "issued": {"date-parts": [[2010, 1]]},
+          "score": 52.1
+        },
+        {
+          "DOI": "10.5555/unknown",
+          "score": 12.0
+        }
+      ]
+    }
+  }"#;
+
+  /// A minimal Crossref response for a single work.
+  const WORK_RESPONSE: &str = r#"{
+    "status": "ok",
+    "message-type": "work",
+    "message": {
+      "DOI": "10.1145/1327452.1327492",
+      "title": ["MapReduce: simplified data processing on large clusters"],
+      "author": [{"given": "Jeffrey", "family": "Dean"}, {"given": "Sanjay", "family": "Ghemawat"}],
+      "created": {"date-parts": [[2008, 1, 1]]},
+      "URL": "https://doi.org/10.1145/1327452.1327492",
+      "subject": ["General Computer Science"]
+    }
+  }"#;
+
+  #[tokio::test]
+  async fn test_fetch_times_out() {
+    let mut client = DOIClient::new().with_retry(0).with_timeout(std::time::Duration::from_secs(1));
+    // A non-routable address, so connecting never completes
+    client.base_url = "http://10.255.255.1/works".to_string();
+
+    let start = std::time::Instant::now();
+    let result = client.fetch_paper("10.1145/1327452.1327492").await;
+    assert!(matches!(result, Err(LearnerError::Timeout(_) | LearnerError::Network(_))));
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+  }
+
+  #[tokio::test]
+  async fn test_fetch_retries_server_errors() {
+    use std::{sync::atomic::Ordering, time::Duration};
+
+    let (url, requests) =
+      http::serve_responses(&[(503, ""), (503, ""), (200, WORK_RESPONSE)]).await;
+    let config = ClientConfig { base_delay: Duration::from_millis(1), ..ClientConfig::default() };
+    let mut client = DOIClient::new_with_config(config).with_retry(2);
+    client.base_url = url;
+
+    let paper = client.fetch_paper("10.1145/1327452.1327492").await.unwrap();
+    assert_eq!(paper.title, "MapReduce: simplified data processing on large clusters");
+    assert_eq!(paper.authors.len(), 2);
+    assert_eq!(paper.keywords, ["General Computer Science"]);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+    // A missing DOI is reported as such, without retrying
+    let (url, requests) = http::serve_responses(&[(404, "Resource not found.")]).await;
+    client.base_url = url;
+    assert!(matches!(client.fetch_paper("10.1145/0000000").await, Err(LearnerError::NotFound)));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+  }
+
+  #[tokio::test]
+  async fn test_fetch_waits_out_rate_limit() {
+    use std::{sync::atomic::Ordering, time::Duration};
+
+    let (url, requests) = http::serve_responses_with_headers(vec![
+      (429, "Retry-After: 1\r\n", ""),
+      (200, "X-Rate-Limit-Limit: 50\r\nX-Rate-Limit-Interval: 1s\r\n", WORK_RESPONSE),
+    ])
+    .await;
+    let config = ClientConfig { base_delay: Duration::from_millis(1), ..ClientConfig::default() };
+    let mut client = DOIClient::new_with_config(config).with_retry(1);
+    client.base_url = url;
+
+    let start = std::time::Instant::now();
+    let paper = client.fetch_paper("10.1145/1327452.1327492").await.unwrap();
+    assert_eq!(paper.title, "MapReduce: simplified data processing on large clusters");
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert!(start.elapsed() >= Duration::from_secs(1));
+
+    // Out of retries, the rate limit is reported as such
+    let (url, _) = http::serve_responses(&[(429, "")]).await;
+    client.base_url = url;
+    let result = client.fetch_paper("10.1145/1327452.1327492").await;
+    assert!(matches!(result, Err(LearnerError::RateLimited(service)) if service == "Crossref"));
+  }
+
+  #[tokio::test]
+  async fn test_find_published_doi() {
+    let (url, _) = http::serve_responses(&[(200, SEARCH_RESPONSE)]).await;
+    let mut client = DOIClient::new().with_retry(0);
+    client.base_url = url;
+
+    // A preprint of a paper Crossref knows, titled slightly differently
+    let mut paper = Paper {
+      title:             "MapReduce - Simplified Data Processing on Large Clusters".to_string(),
+      authors:           vec![Author {
+        name:        "Jeffrey Dean".to_string(),
+        affiliation: None,
+        email:       None,
+      }],
+      abstract_text:     String::new(),
+      raw_abstract:      None,
+      publication_date:  Utc.with_ymd_and_hms(2004, 12, 6, 0, 0, 0).unwrap(),
+      source:            Source::Arxiv,
+      source_identifier: "0000.00000".to_string(),
+      pdf_url:           None,
+      doi:               None,
+      version:           None,
+      categories:        Vec::new(),
+      keywords:          Vec::new(),
+      tags:              Vec::new(),
+      pdf_path:          None,
+      reading_status:    ReadingStatus::Unread,
+      added_at:          None,
+    };
+    let doi = client.find_published_doi(&paper).await.unwrap();
+    assert_eq!(doi.as_deref(), Some("10.1145/1327452.1327492"));
+
+    // A paper that was never published isn't given a similar paper's DOI
+    paper.title = "MapReduce for streaming data".to_string();
+    assert_eq!(client.find_published_doi(&paper).await.unwrap(), None);
+
+    // Nor is one only matched with a low score
+    paper.title = "MapReduce: a flexible data processing tool".to_string();
+    assert_eq!(client.find_published_doi(&paper).await.unwrap(), None);
+  }
+
+  #[tokio::test]
+  async fn test_search() {
+    let (url, _) = http::serve_responses(&[(200, SEARCH_RESPONSE)]).await;
+    let mut client = DOIClient::new().with_retry(0);
+    client.base_url = url;
+
+    // The work without a title or date is skipped
+    let papers = client.search("mapreduce simplified data processing", 5).await.unwrap();
+    assert_eq!(papers.len(), 2);
+    assert_eq!(papers[0].title, "MapReduce: simplified data processing on large clusters");
+    assert_eq!(papers[0].source, Source::DOI);
+    assert_eq!(papers[0].source_identifier, "10.1145/1327452.1327492");
+    assert_eq!(papers[0].doi.as_deref(), Some("10.1145/1327452.1327492"));
+    assert_eq!(papers[0].authors.len(), 2);
+    assert_eq!(papers[0].publication_date, Utc.with_ymd_and_hms(2008, 1, 1, 0, 0, 0).unwrap());
+    assert_eq!(papers[1].source_identifier, "10.1145/1629175.1629198");
+  }
+
+  #[test]
+  fn test_mailto() {
+    let client = DOIClient::new().with_mailto("me@example.com");
+    let request = client.request("https://api.crossref.org/works?query=x").build().unwrap();
+    assert_eq!(
+      request.url().as_str(),
+      "https://api.crossref.org/works?query=x&mailto=me%40example.com"
+    );
+    let user_agent = request.headers()[reqwest::header::USER_AGENT].to_str().unwrap();
+    assert!(user_agent.starts_with("learner/"));
+    assert!(user_agent.ends_with("; mailto:me@example.com)"));
+
+    // Without an email, the client is honest about who it is and sends no mailto
+    let client = DOIClient { mailto: None, ..DOIClient::new() };
+    let request = client.request("https://api.crossref.org/works/10.1145/1").build().unwrap();
+    assert_eq!(request.url().query(), None);
+    let user_agent = request.headers()[reqwest::header::USER_AGENT].to_str().unwrap();
+    assert_eq!(user_agent, format!("{CROSSREF_USER_AGENT} ({PROJECT_URL})"));
+  }
+
+  #[test]
+  fn test_parse_search_response() {
+    let matches = parse_search_response(SEARCH_RESPONSE).unwrap();
+    assert_eq!(matches.len(), 3);
+
+    assert_eq!(matches[0].doi, "10.1145/1327452.1327492");
+    assert_eq!(matches[0].title, "MapReduce: simplified data processing on large clusters");
+    assert_eq!(matches[0].authors, vec!["Jeffrey Dean", "Sanjay Ghemawat"]);
+    assert_eq!(matches[0].year, Some(2008));
+    assert_eq!(matches[0].venue.as_deref(), Some("Communications of the ACM"));
+
+    // Sparse results still parse
+    assert!(matches[2].title.is_empty());
+    assert!(matches[2].authors.is_empty());
+    assert_eq!(matches[2].year, None);
+  }
+
+  #[test]
+  fn test_select_match_threshold() {
+    let matches = parse_search_response(SEARCH_RESPONSE).unwrap();
+
+    let best = select_match(&matches, DEFAULT_MATCH_THRESHOLD).unwrap();
+    assert_eq!(best.doi, "10.1145/1327452.1327492");
+
+    // Nothing clears a threshold above the top score
+    assert!(select_match(&matches, 120.0).is_none());
+
+    // Only weak candidates remain once the correct work is gone
+    assert!(select_match(&matches[1..], DEFAULT_MATCH_THRESHOLD).is_none());
+    assert!(select_match(&[], DEFAULT_MATCH_THRESHOLD).is_none());
+  }
 }
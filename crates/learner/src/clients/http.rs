@@ -0,0 +1,653 @@
+//! HTTP behavior shared by all clients: timeouts, retries, and the client used for PDF
+//! downloads.
+//!
+//! Paper sources occasionally time out, drop connections, or answer with a server error or a
+//! rate limit. Rather than failing straight away, the clients send their requests through
+//! [`send_with_retry`], which retries these transient failures with exponential backoff (see
+//! [`retry`]). How hard to try is controlled by a [`ClientConfig`], which every client accepts
+//! through its `new_with_config` constructor, or just the number of retries through its
+//! `with_retry` builder method.
+//!
+//! To keep to a service's request budget, e.g. arXiv's or Crossref's "polite pool", clients can
+//! share a [`RateLimiter`] through their `with_rate_limiter` builder method.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use learner::clients::{ArxivClient, ClientConfig};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! // Be more patient than the default with a flaky connection
+//! let config = ClientConfig {
+//!   max_retries: 5,
+//!   base_delay:  Duration::from_secs(1),
+//!   timeout:     Duration::from_secs(60),
+//! };
+//! let paper = ArxivClient::new_with_config(config).fetch_paper("2301.07041").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+  collections::hash_map::RandomState,
+  future::Future,
+  hash::{BuildHasher, Hasher},
+  sync::Mutex,
+  time::{Duration, Instant},
+};
+
+use lazy_static::lazy_static;
+use reqwest::{header::HeaderMap, redirect, RequestBuilder, Response, StatusCode};
+use tracing::warn;
+
+use super::*;
+
+/// User agent sent with PDF downloads, so publishers can tell who is asking.
+const USER_AGENT: &str =
+  concat!("learner/", env!("CARGO_PKG_VERSION"), " (+https://github.com/autoparallel/learner)");
+
+/// Longest wait honored from a `Retry-After` header. A service asking for longer is retried
+/// after this long instead, rather than stalling a whole batch.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Most redirects followed for a PDF download. Publishers often bounce a PDF link through a
+/// couple of hops (e.g. DOI resolver, then CDN), but never this many.
+const MAX_PDF_REDIRECTS: usize = 10;
+
+lazy_static! {
+  /// Client shared by all PDF downloads that aren't given one explicitly.
+  static ref PDF_CLIENT: reqwest::Client = ClientConfig::default().pdf_client();
+}
+
+/// The client used by [`Paper::download_pdf`], with the default timeout, learner's user agent,
+/// and a limit on redirects.
+pub fn pdf_client() -> &'static reqwest::Client { &PDF_CLIENT }
+
+/// Timeout and retry settings for a client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientConfig {
+  /// How many times to retry a request after a transient failure. With the default of 3, a
+  /// request is attempted at most four times.
+  pub max_retries: u32,
+  /// Delay before the first retry. Each further retry waits twice as long as the previous
+  /// one. Every delay is randomly shortened or lengthened by up to 20%, so that clients
+  /// failing at the same time don't all retry at the same time.
+  pub base_delay:  Duration,
+  /// Maximum time a single attempt may take, from connecting to reading the whole response.
+  /// Connecting alone is limited to the same time. Defaults to 30 seconds, or the number of
+  /// seconds in the [`TIMEOUT_ENV_VAR`] environment variable if set.
+  pub timeout:     Duration,
+}
+
+impl Default for ClientConfig {
+  fn default() -> Self {
+    Self {
+      max_retries: 3,
+      base_delay:  Duration::from_millis(500),
+      timeout:     parse_timeout(std::env::var(TIMEOUT_ENV_VAR).ok().as_deref())
+        .unwrap_or(DEFAULT_TIMEOUT),
+    }
+  }
+}
+
+/// Environment variable overriding the default request timeout, in whole seconds.
+pub const TIMEOUT_ENV_VAR: &str = "LEARNER_HTTP_TIMEOUT_SECS";
+
+/// Request timeout used unless [`TIMEOUT_ENV_VAR`] says otherwise.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Parses the value of [`TIMEOUT_ENV_VAR`], ignoring (with a warning) anything that isn't a
+/// positive number of seconds.
+fn parse_timeout(value: Option<&str>) -> Option<Duration> {
+  let value = value?;
+  match value.trim().parse::<u64>() {
+    Ok(seconds) if seconds > 0 => Some(Duration::from_secs(seconds)),
+    _ => {
+      warn!("Ignoring {TIMEOUT_ENV_VAR}={value:?}, expected a positive number of seconds");
+      None
+    },
+  }
+}
+
+impl ClientConfig {
+  /// Starts building an HTTP client that applies this configuration's timeout.
+  pub(crate) fn client_builder(&self) -> reqwest::ClientBuilder {
+    reqwest::Client::builder().connect_timeout(self.timeout).timeout(self.timeout)
+  }
+
+  /// Builds an HTTP client applying this configuration's timeout.
+  pub(crate) fn http_client(&self) -> reqwest::Client {
+    self.client_builder().build().expect("HTTP client with a timeout should always build")
+  }
+
+  /// Builds an HTTP client for downloading PDFs, applying this configuration's timeout.
+  ///
+  /// Unlike the API clients, it identifies itself with learner's user agent and follows a
+  /// limited number of redirects. Pass it to [`Paper::download_pdf_with`] to download with
+  /// a custom timeout.
+  pub fn pdf_client(&self) -> reqwest::Client {
+    self
+      .client_builder()
+      .user_agent(USER_AGENT)
+      .redirect(redirect::Policy::limited(MAX_PDF_REDIRECTS))
+      .build()
+      .expect("PDF client should always build")
+  }
+
+  /// How long to wait before retry number `retry` (starting from 0).
+  pub(crate) fn backoff(&self, retry: u32) -> Duration {
+    let exponential = self.base_delay.saturating_mul(1 << retry.min(16));
+    // A factor between 0.8 and 1.2
+    let jitter = 0.8 + (RandomState::new().build_hasher().finish() % 1001) as f64 / 2500.0;
+    exponential.mul_f64(jitter)
+  }
+}
+
+/// Runs `operation`, retrying it according to `config` while it fails with a transient
+/// error: a [`LearnerError::Timeout`], or a [`LearnerError::Network`] error for a failed
+/// connection or a `429 Too Many Requests` or `5xx` status (as produced by
+/// [`reqwest::Response::error_for_status`]).
+///
+/// Any other result, including other errors, is returned straight away. In particular, an
+/// [`LearnerError::ApiError`] or a `400`/`404` status is never retried.
+///
+/// The clients use this for all of their requests; it is public so that code making its own
+/// requests to paper sources can retry them the same way.
+///
+/// # Arguments
+///
+/// * `config` - How many times to retry, and how long to wait in between
+/// * `service` - Name of the service, used in logs (e.g. "arXiv")
+/// * `operation` - Starts a new attempt each time it is called
+///
+/// # Examples
+///
+/// ```no_run
+/// use learner::{
+///   clients::{retry, ClientConfig},
+///   errors::LearnerError,
+/// };
+///
+/// # async fn example() -> Result<(), LearnerError> {
+/// let listing = retry(&ClientConfig::default(), "arXiv", || async {
+///   let response = reqwest::get("https://arxiv.org/list/cs.CR/new").await?;
+///   Ok::<_, LearnerError>(response.error_for_status()?.text().await?)
+/// })
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn retry<T, F, Fut>(
+  config: &ClientConfig,
+  service: &str,
+  operation: F,
+) -> Result<T, LearnerError>
+where
+  F: FnMut() -> Fut,
+  Fut: Future<Output = Result<T, LearnerError>>,
+{
+  retry_with_delay(config, service, operation, |retry| config.backoff(retry)).await
+}
+
+/// Like [`retry`], but waits `delay(retry)` before retry number `retry` (starting from 0).
+pub(crate) async fn retry_with_delay<T, F, Fut>(
+  config: &ClientConfig,
+  service: &str,
+  mut operation: F,
+  delay: impl Fn(u32) -> Duration,
+) -> Result<T, LearnerError>
+where
+  F: FnMut() -> Fut,
+  Fut: Future<Output = Result<T, LearnerError>>,
+{
+  let mut retry = 0;
+  loop {
+    match operation().await {
+      Err(e) if is_transient(&e) && retry < config.max_retries => {
+        let delay = delay(retry);
+        retry += 1;
+        warn!(
+          "Request to {service} failed ({e}), retrying in {delay:?} ({retry}/{})",
+          config.max_retries
+        );
+        tokio::time::sleep(delay).await;
+      },
+      result => return result,
+    }
+  }
+}
+
+/// Whether a request that failed with `error` may succeed if tried again.
+fn is_transient(error: &LearnerError) -> bool {
+  match error {
+    LearnerError::Timeout(_) => true,
+    LearnerError::Network(error) =>
+      error.is_connect()
+        || error
+          .status()
+          .is_some_and(|status| status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()),
+    _ => false,
+  }
+}
+
+/// Sends a request, retrying transient failures according to `config`.
+///
+/// Timeouts, connection errors, `429 Too Many Requests` and `5xx` responses are retried (see
+/// [`retry`]). If a `429` or `503` response says how long to wait in a `Retry-After` header,
+/// the next attempt waits that long (up to a minute) instead of the usual backoff. Everything
+/// else is returned straight away: in particular, `404 Not Found`
+/// becomes [`LearnerError::NotFound`] without retrying. Other responses, including other error
+/// statuses, are returned for the caller to interpret.
+///
+/// # Arguments
+///
+/// * `config` - How many times to retry, and how long to wait in between
+/// * `service` - Name of the service, used in errors and logs (e.g. "arXiv")
+/// * `request` - Builds the request to send, called once per attempt
+///
+/// # Errors
+///
+/// Once out of retries, returns [`LearnerError::RateLimited`] if the service was still
+/// rate limiting, [`LearnerError::ApiError`] for a server error, or the last timeout or
+/// network error.
+pub(crate) async fn send_with_retry(
+  config: &ClientConfig,
+  service: &str,
+  request: impl Fn() -> RequestBuilder,
+) -> Result<Response, LearnerError> {
+  // The wait requested by the last response, if any, taking the place of the next backoff
+  let requested_delay = Mutex::new(None);
+  let result = retry_with_delay(
+    config,
+    service,
+    || async {
+      let response = request().send().await?;
+      match response.status() {
+        StatusCode::NOT_FOUND => Err(LearnerError::NotFound),
+        status if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() => {
+          if matches!(status, StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE) {
+            *requested_delay.lock().unwrap_or_else(|e| e.into_inner()) =
+              retry_after(response.headers());
+          }
+          Err(response.error_for_status().unwrap_err().into())
+        },
+        _ => Ok(response),
+      }
+    },
+    |retry| {
+      let requested = requested_delay.lock().unwrap_or_else(|e| e.into_inner()).take();
+      requested.unwrap_or_else(|| config.backoff(retry))
+    },
+  )
+  .await;
+
+  // Out of retries: report rate limiting and server errors as such
+  match result {
+    Err(LearnerError::Network(e)) => Err(match e.status() {
+      Some(StatusCode::TOO_MANY_REQUESTS) => LearnerError::RateLimited(service.to_string()),
+      Some(status) => LearnerError::ApiError(format!("{service} returned {status}")),
+      None => LearnerError::Network(e),
+    }),
+    result => result,
+  }
+}
+
+/// Reads how long a response asks the client to wait from its `Retry-After` header, given
+/// either in seconds or as an HTTP date. The wait is capped at [`MAX_RETRY_AFTER`].
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+  let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+  let delay = match value.parse::<u64>() {
+    Ok(seconds) => Duration::from_secs(seconds),
+    Err(_) => {
+      let date = DateTime::parse_from_rfc2822(value).ok()?;
+      (date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or_default()
+    },
+  };
+  Some(delay.min(MAX_RETRY_AFTER))
+}
+
+/// Spaces out requests to a service that advertises its rate limit.
+///
+/// Some services (notably Crossref) send `X-Rate-Limit-Limit` and `X-Rate-Limit-Interval`
+/// headers saying how many requests they allow per interval, e.g. `50` per `1s`. A client
+/// reused for a batch of requests calls [`Throttle::update`] with each response's headers
+/// and [`Throttle::wait`] before each request, so it stays under the limit instead of being
+/// answered with `429 Too Many Requests`.
+#[derive(Debug, Default)]
+pub(crate) struct Throttle {
+  /// The advertised interval between requests, and when the next request may be sent
+  state: Mutex<(Option<Duration>, Option<Instant>)>,
+}
+
+impl Throttle {
+  /// Waits until the next request may be sent according to the last advertised limit,
+  /// reserving the slot.
+  pub(crate) async fn wait(&self) {
+    let slot = {
+      let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+      let (Some(interval), next) = &mut *state else { return };
+      let now = Instant::now();
+      let slot = next.map_or(now, |next| next.max(now));
+      *next = Some(slot + *interval);
+      slot
+    };
+    tokio::time::sleep_until(slot.into()).await;
+  }
+
+  /// Reads the advertised rate limit from a response's headers, if it has one.
+  pub(crate) fn update(&self, headers: &HeaderMap) {
+    let header = |name: &str| headers.get(name)?.to_str().ok().map(str::trim);
+    let Some(limit) = header("x-rate-limit-limit").and_then(|l| l.parse::<u32>().ok()) else {
+      return;
+    };
+    let Some(interval) = header("x-rate-limit-interval").and_then(parse_interval) else {
+      return;
+    };
+    if limit > 0 {
+      self.state.lock().unwrap_or_else(|e| e.into_inner()).0 = Some(interval / limit);
+    }
+  }
+}
+
+/// Caps how often requests are sent, shared by every client given the same limiter.
+///
+/// Each call to [`RateLimiter::wait`] reserves the next free slot, so concurrent requests are
+/// spaced out evenly rather than sent in bursts. The default limiter does not hold requests
+/// back.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use learner::clients::{ArxivClient, IACRClient, RateLimiter};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// // At most two requests per second between both clients
+/// let limiter = Arc::new(RateLimiter::per_second(2.0));
+/// let arxiv = ArxivClient::new().with_rate_limiter(Arc::clone(&limiter));
+/// let iacr = IACRClient::new().with_rate_limiter(limiter);
+/// let paper = arxiv.fetch_paper("2301.07041").await?;
+/// let other = iacr.fetch_paper("2016/260").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+  /// The least time between two requests.
+  interval:  Duration,
+  /// When the next request may be sent.
+  next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+  /// Creates a limiter that sends at most `requests` requests per second. A rate that is not
+  /// positive does not hold requests back.
+  pub fn per_second(requests: f64) -> Self {
+    let interval =
+      if requests > 0.0 { Duration::from_secs_f64(1.0 / requests) } else { Duration::ZERO };
+    Self::with_interval(interval)
+  }
+
+  /// Creates a limiter that leaves at least `interval` between two requests, e.g. the three
+  /// seconds arXiv asks for.
+  pub fn with_interval(interval: Duration) -> Self {
+    Self { interval, next_slot: Mutex::new(None) }
+  }
+
+  /// Waits until the next request may be sent, reserving the slot.
+  pub async fn wait(&self) {
+    if self.interval.is_zero() {
+      return;
+    }
+    let slot = {
+      let mut next = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+      let now = Instant::now();
+      let slot = next.map_or(now, |next| next.max(now));
+      *next = Some(slot + self.interval);
+      slot
+    };
+    tokio::time::sleep_until(slot.into()).await;
+  }
+}
+
+/// Parses a rate limit interval such as `1s` or `500ms`.
+fn parse_interval(interval: &str) -> Option<Duration> {
+  if let Some(millis) = interval.strip_suffix("ms") {
+    return millis.parse().ok().map(Duration::from_millis);
+  }
+  let seconds = interval.strip_suffix('s').unwrap_or(interval);
+  seconds.parse().ok().map(Duration::from_secs)
+}
+
+/// Serves canned HTTP responses on a local port, for testing clients without the network.
+///
+/// Each `(status, body)` pair is served in turn to one request, with the last one repeated for
+/// any further requests. Returns the server's base URL and a count of the requests received.
+#[cfg(test)]
+pub(crate) async fn serve_responses(
+  responses: &'static [(u16, &'static str)],
+) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+  let responses = responses.iter().map(|&(status, body)| (status, "", body)).collect();
+  serve_responses_with_headers(responses).await
+}
+
+/// Like [`serve_responses`], but each response also carries the given header lines (e.g.
+/// `"Retry-After: 1\r\n"`).
+#[cfg(test)]
+pub(crate) async fn serve_responses_with_headers(
+  responses: Vec<(u16, &'static str, &'static str)>,
+) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+  use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  };
+
+  use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+  };
+
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let url = format!("http://{}", listener.local_addr().unwrap());
+  let requests = Arc::new(AtomicUsize::new(0));
+
+  let counter = Arc::clone(&requests);
+  tokio::spawn(async move {
+    while let Ok((mut socket, _)) = listener.accept().await {
+      let served = counter.fetch_add(1, Ordering::SeqCst);
+      let (status, headers, body) = responses[served.min(responses.len() - 1)];
+      let mut buffer = [0; 4096];
+      let _ = socket.read(&mut buffer).await;
+      let response = format!(
+        "HTTP/1.1 {status} Status\r\n{headers}Content-Length: {}\r\nConnection: \
+         close\r\n\r\n{body}",
+        body.len()
+      );
+      let _ = socket.write_all(response.as_bytes()).await;
+    }
+  });
+
+  (url, requests)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::Ordering;
+
+  use super::*;
+
+  /// A config that retries quickly, for tests.
+  fn fast_config(max_retries: u32) -> ClientConfig {
+    ClientConfig { max_retries, base_delay: Duration::from_millis(1), ..ClientConfig::default() }
+  }
+
+  #[tokio::test]
+  async fn test_retries_server_errors() {
+    let (url, requests) = serve_responses(&[(503, ""), (500, ""), (200, "ok")]).await;
+    let client = reqwest::Client::new();
+
+    let response = send_with_retry(&fast_config(2), "test", || client.get(&url)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+  }
+
+  #[tokio::test]
+  async fn test_gives_up_after_max_retries() {
+    let (url, requests) = serve_responses(&[(429, "")]).await;
+    let client = reqwest::Client::new();
+
+    let result = send_with_retry(&fast_config(2), "test", || client.get(&url)).await;
+    assert!(matches!(result, Err(LearnerError::RateLimited(service)) if service == "test"));
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+    let (url, _) = serve_responses(&[(502, "")]).await;
+    let result = send_with_retry(&fast_config(0), "test", || client.get(&url)).await;
+    assert!(matches!(result, Err(LearnerError::ApiError(_))));
+  }
+
+  #[tokio::test]
+  async fn test_not_found_is_not_retried() {
+    let (url, requests) = serve_responses(&[(404, ""), (200, "ok")]).await;
+    let client = reqwest::Client::new();
+
+    let result = send_with_retry(&fast_config(2), "test", || client.get(&url)).await;
+    assert!(matches!(result, Err(LearnerError::NotFound)));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    // Other client errors are left to the caller
+    let (url, requests) = serve_responses(&[(400, "")]).await;
+    let response = send_with_retry(&fast_config(2), "test", || client.get(&url)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+  }
+
+  #[tokio::test]
+  async fn test_retry_only_retries_network_errors() {
+    let mut attempts = 0;
+    let result: Result<(), _> = retry(&fast_config(3), "test", || {
+      attempts += 1;
+      async { Err(LearnerError::InvalidIdentifier) }
+    })
+    .await;
+    assert!(matches!(result, Err(LearnerError::InvalidIdentifier)));
+    assert_eq!(attempts, 1);
+  }
+
+  #[tokio::test]
+  async fn test_timeout() {
+    // A server that accepts connections but never answers
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+      let mut connections = Vec::new();
+      while let Ok((socket, _)) = listener.accept().await {
+        connections.push(socket);
+      }
+    });
+
+    let config = ClientConfig { timeout: Duration::from_millis(200), ..fast_config(1) };
+    let client = config.http_client();
+    let result = tokio::time::timeout(
+      Duration::from_secs(5),
+      send_with_retry(&config, "test", || client.get(&url)),
+    )
+    .await
+    .expect("the client should give up on its own");
+    assert!(matches!(result, Err(LearnerError::Timeout(_))));
+  }
+
+  #[tokio::test]
+  async fn test_honors_retry_after() {
+    let (url, requests) =
+      serve_responses_with_headers(vec![(429, "Retry-After: 1\r\n", ""), (200, "", "ok")]).await;
+    let client = reqwest::Client::new();
+
+    let start = Instant::now();
+    let response = send_with_retry(&fast_config(1), "test", || client.get(&url)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert!(start.elapsed() >= Duration::from_secs(1));
+  }
+
+  #[test]
+  fn test_retry_after() {
+    let headers = |value: &str| {
+      let mut headers = HeaderMap::new();
+      headers.insert(reqwest::header::RETRY_AFTER, value.parse().unwrap());
+      headers
+    };
+    assert_eq!(retry_after(&headers("2")), Some(Duration::from_secs(2)));
+    assert_eq!(retry_after(&headers("86400")), Some(MAX_RETRY_AFTER));
+    assert_eq!(retry_after(&headers("Wed, 21 Oct 2015 07:28:00 GMT")), Some(Duration::ZERO));
+    assert_eq!(retry_after(&headers("soon")), None);
+    assert_eq!(retry_after(&HeaderMap::new()), None);
+  }
+
+  #[tokio::test]
+  async fn test_throttle_follows_advertised_limit() {
+    let throttle = Throttle::default();
+    let start = Instant::now();
+
+    // No limit advertised yet, so requests aren't held back
+    throttle.wait().await;
+    throttle.wait().await;
+    assert!(start.elapsed() < Duration::from_millis(50));
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-rate-limit-limit", "10".parse().unwrap());
+    headers.insert("x-rate-limit-interval", "1s".parse().unwrap());
+    throttle.update(&headers);
+
+    let start = Instant::now();
+    for _ in 0..3 {
+      throttle.wait().await;
+    }
+    assert!(start.elapsed() >= Duration::from_millis(200));
+  }
+
+  #[tokio::test]
+  async fn test_rate_limiter_spaces_requests() {
+    let (url, requests) = serve_responses(&[(200, "ok")]).await;
+    let client = reqwest::Client::new();
+    let limiter = RateLimiter::per_second(20.0);
+
+    let start = Instant::now();
+    for _ in 0..10 {
+      limiter.wait().await;
+      send_with_retry(&fast_config(0), "test", || client.get(&url)).await.unwrap();
+    }
+    assert_eq!(requests.load(Ordering::SeqCst), 10);
+    // The first request goes straight out, and each later one waits its 50ms turn
+    assert!(start.elapsed() >= Duration::from_millis(450));
+
+    // Without a rate, requests aren't held back
+    let unlimited = RateLimiter::default();
+    let start = Instant::now();
+    for _ in 0..10 {
+      unlimited.wait().await;
+    }
+    assert!(start.elapsed() < Duration::from_millis(50));
+  }
+
+  #[test]
+  fn test_parse_timeout() {
+    assert_eq!(parse_timeout(Some("5")), Some(Duration::from_secs(5)));
+    assert_eq!(parse_timeout(Some(" 120 ")), Some(Duration::from_secs(120)));
+    assert_eq!(parse_timeout(Some("0")), None);
+    assert_eq!(parse_timeout(Some("soon")), None);
+    assert_eq!(parse_timeout(None), None);
+  }
+
+  #[test]
+  fn test_backoff_grows_exponentially() {
+    let config = ClientConfig { base_delay: Duration::from_millis(100), ..ClientConfig::default() };
+    for retry in 0..4 {
+      let delay = config.backoff(retry);
+      let exponential = Duration::from_millis(100 << retry);
+      assert!(delay >= exponential.mul_f64(0.8) && delay <= exponential.mul_f64(1.2));
+    }
+  }
+}
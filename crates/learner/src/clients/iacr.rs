@@ -22,17 +22,51 @@
 //! # }
 //! ```
 
+use url::Url;
+
 use super::*;
 
+/// How many days back [`IACRClient::search`] looks for papers.
+pub const SEARCH_WINDOW_DAYS: i64 = 365;
+
+/// How many days back each step of [`IACRClient::search`] reaches, so that a search finding
+/// enough papers among the recent ones doesn't list the whole window.
+const SEARCH_STEPS_DAYS: [i64; 3] = [30, 90, SEARCH_WINDOW_DAYS];
+
+/// Prefix of the OAI-PMH identifiers of ePrint papers, before the "YYYY/NNNN" identifier.
+const OAI_IDENTIFIER_PREFIX: &str = "oai:eprint.iacr.org:";
+
 /// Root response structure for the OAI-PMH protocol.
 #[derive(Debug, Deserialize)]
 #[serde(rename = "OAI-PMH")]
 struct OAIPMHResponse {
   /// The requested record, if found
   #[serde(rename = "GetRecord")]
-  get_record: Option<GetRecord>,
+  get_record:   Option<GetRecord>,
+  /// A page of records, in answer to `ListRecords`
+  #[serde(rename = "ListRecords")]
+  list_records: Option<ListRecords>,
   /// Error details, if the request failed
-  error:      Option<OAIError>,
+  error:        Option<OAIError>,
+}
+
+/// A page of records from a `ListRecords` request.
+#[derive(Debug, Deserialize)]
+struct ListRecords {
+  /// The records on this page
+  #[serde(rename = "record", default)]
+  records:          Vec<Record>,
+  /// Token to request the next page with, empty or missing on the last page
+  #[serde(rename = "resumptionToken")]
+  resumption_token: Option<ResumptionToken>,
+}
+
+/// Token continuing a `ListRecords` request on its next page.
+#[derive(Debug, Deserialize)]
+struct ResumptionToken {
+  /// The token, empty once there are no more pages
+  #[serde(rename = "$text", default)]
+  token: String,
 }
 
 /// Error information from the OAI-PMH response.
@@ -56,8 +90,17 @@ struct GetRecord {
 /// Metadata record container.
 #[derive(Debug, Deserialize)]
 struct Record {
-  /// The metadata in Dublin Core format
-  metadata: Metadata,
+  /// The record's identifier, such as "oai:eprint.iacr.org:2023/123"
+  header:   Header,
+  /// The metadata in Dublin Core format, missing for withdrawn papers
+  metadata: Option<Metadata>,
+}
+
+/// Header of a record.
+#[derive(Debug, Deserialize)]
+struct Header {
+  /// The record's OAI-PMH identifier
+  identifier: String,
 }
 
 /// Container for Dublin Core metadata.
@@ -101,17 +144,64 @@ struct DublinCore {
 /// format "YYYY/NNNN".
 pub struct IACRClient {
   /// Internal web client used to connect to the API.
-  client:   reqwest::Client,
+  client:       reqwest::Client,
   /// The base URL to use for the client.
-  base_url: String,
+  base_url:     String,
+  /// Timeout and retry settings.
+  config:       ClientConfig,
+  /// Caps how often requests are sent, possibly shared with other clients.
+  rate_limiter: Arc<RateLimiter>,
 }
 
 impl IACRClient {
   /// Creates a new IACR client instance.
   ///
   /// Initializes an HTTP client for making requests to IACR's OAI-PMH endpoint.
-  pub fn new() -> Self {
-    Self { client: reqwest::Client::new(), base_url: "https://eprint.iacr.org/oai".to_string() }
+  pub fn new() -> Self { Self::new_with_config(ClientConfig::default()) }
+
+  /// Creates a new IACR client with custom timeout and retry settings.
+  pub fn new_with_config(config: ClientConfig) -> Self {
+    Self { config, ..Self::with_client(config.http_client()) }
+  }
+
+  /// Creates a new IACR client that sends its requests through `client`, e.g. one configured
+  /// once with a proxy, TLS roots, or default headers and shared across sources.
+  ///
+  /// Requests are limited by `client`'s own timeout and retried as by
+  /// [`ClientConfig::default`].
+  pub fn with_client(client: reqwest::Client) -> Self {
+    Self {
+      client,
+      base_url: "https://eprint.iacr.org/oai".to_string(),
+      config: ClientConfig::default(),
+      rate_limiter: Arc::default(),
+    }
+  }
+
+  /// Sets how many times to retry a request after a transient failure, keeping the other
+  /// settings. See [`ClientConfig::max_retries`].
+  pub fn with_retry(mut self, retries: u8) -> Self {
+    self.config.max_retries = retries.into();
+    self
+  }
+
+  /// Sends requests no faster than `limiter` allows, keeping the other settings. Clients given
+  /// the same limiter share its budget, e.g. to stay within a service's polite pool.
+  pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+    self.rate_limiter = limiter;
+    self
+  }
+
+  /// Sets the maximum time a single request may take, keeping the other settings. See
+  /// [`ClientConfig::timeout`]. A request that takes longer fails with
+  /// [`LearnerError::Timeout`]. This builds a new HTTP client, replacing one given to
+  /// `with_client`.
+  pub fn with_timeout(self, timeout: Duration) -> Self {
+    Self {
+      base_url: self.base_url,
+      rate_limiter: self.rate_limiter,
+      ..Self::new_with_config(ClientConfig { timeout, ..self.config })
+    }
   }
 
   /// Fetches paper metadata from IACR using its identifier.
@@ -154,19 +244,151 @@ impl IACRClient {
   /// ```
   pub async fn fetch_paper(&self, identifier: &str) -> Result<Paper, LearnerError> {
     // IACR identifiers are in the format "YYYY/NNNN"
-    let parts: Vec<&str> = identifier.split('/').collect();
-    if parts.len() != 2 {
+    if identifier.split('/').count() != 2 {
       return Err(LearnerError::InvalidIdentifier);
     }
 
     let url = format!(
-      "{}?verb=GetRecord&identifier=oai:eprint.iacr.org:{}&metadataPrefix=oai_dc",
+      "{}?verb=GetRecord&identifier={OAI_IDENTIFIER_PREFIX}{}&metadataPrefix=oai_dc",
       self.base_url, identifier
     );
 
     debug!("Fetching from IACR via OAI-PMH: {url}");
 
-    let response = self.client.get(&url).send().await?;
+    let oai_response = self.request(&url).await?;
+    if let Some(error) = oai_response.error {
+      return Err(LearnerError::ApiError(format!(
+        "OAI-PMH error: {} - {}",
+        error.code, error.message
+      )));
+    }
+
+    let metadata = oai_response
+      .get_record
+      .and_then(|get_record| get_record.record.metadata)
+      .ok_or_else(|| LearnerError::ApiError("No record found".to_string()))?;
+
+    into_paper(metadata.dublin_core, identifier)
+  }
+
+  /// Searches the ePrint Archive for papers matching a query.
+  ///
+  /// OAI-PMH has no search of its own, so this lists the records added or updated recently with
+  /// `ListRecords` and keeps the papers whose title, authors, or abstract contain every word of
+  /// `query`, ignoring case. The records of the last 30 days are listed first, then those of
+  /// the 60 days before, and so on back to [`SEARCH_WINDOW_DAYS`] days, stopping once
+  /// `max_results` papers match. Matches are returned newest first.
+  ///
+  /// # Arguments
+  ///
+  /// * `query` - Words to look for (e.g., "threshold signatures")
+  /// * `max_results` - Maximum number of papers to return
+  ///
+  /// # Errors
+  ///
+  /// This function will return an error if:
+  /// - The network request fails
+  /// - The XML response cannot be parsed
+  /// - The OAI-PMH response contains an error other than there being no records
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::IACRClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let papers = IACRClient::new().search("threshold signatures", 10).await?;
+  /// for paper in papers {
+  ///   println!("{}: {}", paper.source_identifier, paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search(&self, query: &str, max_results: usize) -> Result<Vec<Paper>, LearnerError> {
+    let words = query.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>();
+    if words.is_empty() || max_results == 0 {
+      return Ok(Vec::new());
+    }
+
+    let now = Utc::now();
+    let mut papers = Vec::<Paper>::new();
+    // Each step lists the days before the previous one, which its `until` date overlaps by one
+    let mut until: Option<String> = None;
+    for days in SEARCH_STEPS_DAYS {
+      let from = (now - chrono::Duration::days(days)).format("%Y-%m-%d").to_string();
+      let mut params =
+        vec![("verb", "ListRecords"), ("metadataPrefix", "oai_dc"), ("from", from.as_str())];
+      if let Some(until) = &until {
+        params.push(("until", until.as_str()));
+      }
+      let url = Url::parse_with_params(&self.base_url, &params)?;
+
+      for paper in self.list_matching(url, &words).await? {
+        if !papers.iter().any(|seen| seen.source_identifier == paper.source_identifier) {
+          papers.push(paper);
+        }
+      }
+      if papers.len() >= max_results {
+        break;
+      }
+      until = Some(from);
+    }
+
+    papers.sort_by_key(|paper| std::cmp::Reverse(paper.publication_date));
+    papers.truncate(max_results);
+    Ok(papers)
+  }
+
+  /// Lists the records `url` asks for, following resumption tokens until the last page, and
+  /// returns the papers matching every one of `words`.
+  async fn list_matching(
+    &self,
+    mut url: Url,
+    words: &[String],
+  ) -> Result<Vec<Paper>, LearnerError> {
+    let mut papers = Vec::new();
+    loop {
+      debug!("Listing IACR records via OAI-PMH: {url}");
+      let oai_response = self.request(url.as_str()).await?;
+      match oai_response.error {
+        Some(error) if error.code == "noRecordsMatch" => break,
+        Some(error) =>
+          return Err(LearnerError::ApiError(format!(
+            "OAI-PMH error: {} - {}",
+            error.code, error.message
+          ))),
+        None => {},
+      }
+      let Some(list) = oai_response.list_records else { break };
+
+      for record in list.records {
+        let Some(identifier) = record.header.identifier.strip_prefix(OAI_IDENTIFIER_PREFIX) else {
+          continue;
+        };
+        let Some(metadata) = record.metadata else { continue };
+        match into_paper(metadata.dublin_core, identifier) {
+          Ok(paper) if matches_words(&paper, words) => papers.push(paper),
+          Ok(_) => {},
+          Err(e) => debug!("Skipping IACR record {identifier}: {e}"),
+        }
+      }
+
+      match list.resumption_token.map(|token| token.token).filter(|token| !token.trim().is_empty())
+      {
+        Some(token) =>
+          url = Url::parse_with_params(&self.base_url, &[
+            ("verb", "ListRecords"),
+            ("resumptionToken", token.trim()),
+          ])?,
+        None => break,
+      }
+    }
+    Ok(papers)
+  }
+
+  /// Sends an OAI-PMH request and parses its response, leaving any OAI-PMH error to the caller.
+  async fn request(&self, url: &str) -> Result<OAIPMHResponse, LearnerError> {
+    self.rate_limiter.wait().await;
+    let response = send_with_retry(&self.config, "IACR", || self.client.get(url)).await?;
 
     let text = response.text().await?;
     debug!("IACR OAI-PMH response: {}", text);
@@ -182,49 +404,58 @@ impl IACRClient {
 
     debug!("Cleaned XML: {}", text);
 
-    let oai_response: OAIPMHResponse =
-      from_str(&text).map_err(|e| LearnerError::ApiError(format!("Failed to parse XML: {}", e)))?;
+    from_str(&text).map_err(|e| LearnerError::ApiError(format!("Failed to parse XML: {}", e)))
+  }
+}
 
-    if let Some(error) = oai_response.error {
-      return Err(LearnerError::ApiError(format!(
-        "OAI-PMH error: {} - {}",
-        error.code, error.message
-      )));
-    }
+/// Whether every one of `words`, already lowercased, appears in the paper's title, authors, or
+/// abstract.
+fn matches_words(paper: &Paper, words: &[String]) -> bool {
+  let authors = paper.authors.iter().map(|author| author.name.as_str()).collect::<Vec<_>>();
+  let text =
+    format!("{} {} {}", paper.title, authors.join(" "), paper.abstract_text).to_lowercase();
+  words.iter().all(|word| text.contains(word.as_str()))
+}
 
-    let record = oai_response
-      .get_record
-      .ok_or_else(|| LearnerError::ApiError("No record found".to_string()))?
-      .record;
-
-    let dc = record.metadata.dublin_core;
-
-    // Try to find a URL-style identifier starting with https://eprint.iacr.org/
-    let doi = dc.identifiers.iter().find(|id| id.starts_with("https://eprint.iacr.org/")).cloned();
-
-    // Parse the earliest date (creation date)
-    let publication_date = dc
-      .dates
-      .first()
-      .and_then(|date_str| DateTime::parse_from_rfc3339(date_str).ok())
-      .map(|dt| dt.with_timezone(&Utc))
-      .ok_or_else(|| LearnerError::ApiError("Invalid date format".to_string()))?;
-
-    Ok(Paper {
-      title: dc.title,
-      authors: dc
-        .creators
-        .into_iter()
-        .map(|name| Author { name, affiliation: None, email: None })
-        .collect(),
-      abstract_text: dc.description,
-      publication_date,
-      source: Source::IACR,
-      source_identifier: identifier.to_string(),
-      pdf_url: Some(format!("https://eprint.iacr.org/{}/{}.pdf", parts[0], parts[1])),
-      doi,
-    })
-  }
+/// Converts a record's Dublin Core metadata into the [`Paper`] with the given "YYYY/NNNN"
+/// identifier.
+fn into_paper(dc: DublinCore, identifier: &str) -> Result<Paper, LearnerError> {
+  let (year, number) = identifier.split_once('/').ok_or(LearnerError::InvalidIdentifier)?;
+
+  // Try to find a URL-style identifier starting with https://eprint.iacr.org/
+  let doi = dc.identifiers.iter().find(|id| id.starts_with("https://eprint.iacr.org/")).cloned();
+
+  // Parse the earliest date (creation date)
+  let publication_date = dc
+    .dates
+    .first()
+    .and_then(|date_str| DateTime::parse_from_rfc3339(date_str).ok())
+    .map(|dt| dt.with_timezone(&Utc))
+    .ok_or_else(|| LearnerError::ApiError("Invalid date format".to_string()))?;
+  let (abstract_text, raw_abstract) = clean_abstract(dc.description);
+
+  Ok(Paper {
+    title: dc.title,
+    authors: dc
+      .creators
+      .into_iter()
+      .map(|name| Author { name, affiliation: None, email: None })
+      .collect(),
+    abstract_text,
+    raw_abstract,
+    publication_date,
+    source: Source::IACR,
+    source_identifier: identifier.to_string(),
+    pdf_url: Some(format!("https://eprint.iacr.org/{year}/{number}.pdf")),
+    doi,
+    version: None,
+    categories: Vec::new(),
+    keywords: Vec::new(),
+    tags: Vec::new(),
+    pdf_path: None,
+    reading_status: ReadingStatus::Unread,
+    added_at: None,
+  })
 }
 
 impl Default for IACRClient {
@@ -250,4 +481,97 @@ mod tests {
     assert_eq!(paper.source, Source::IACR);
     assert_eq!(paper.source_identifier, "2016/260");
   }
+
+  /// The first page of a `ListRecords` response, with a withdrawn paper and a resumption token.
+  const LIST_PAGE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/">
+  <ListRecords>
+    <record>
+      <header><identifier>oai:eprint.iacr.org:2024/100</identifier></header>
+      <metadata>
+        <oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/">
+          <dc:title>Threshold Signatures from Lattices</dc:title>
+          <dc:creator>Alice</dc:creator>
+          <dc:description>We build threshold signatures.</dc:description>
+          <dc:date>2024-01-20T00:00:00Z</dc:date>
+          <dc:identifier>https://eprint.iacr.org/2024/100</dc:identifier>
+        </oai_dc:dc>
+      </metadata>
+    </record>
+    <record>
+      <header><identifier>oai:eprint.iacr.org:2024/101</identifier></header>
+      <metadata>
+        <oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/">
+          <dc:title>Faster Hash Functions</dc:title>
+          <dc:creator>Bob</dc:creator>
+          <dc:description>Hashing, but faster.</dc:description>
+          <dc:date>2024-01-21T00:00:00Z</dc:date>
+          <dc:identifier>https://eprint.iacr.org/2024/101</dc:identifier>
+        </oai_dc:dc>
+      </metadata>
+    </record>
+    <record>
+      <header status="deleted"><identifier>oai:eprint.iacr.org:2024/102</identifier></header>
+    </record>
+    <resumptionToken cursor="0" completeListSize="4">page2</resumptionToken>
+  </ListRecords>
+</OAI-PMH>"#;
+
+  /// The last page of a `ListRecords` response.
+  const LAST_LIST_PAGE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/">
+  <ListRecords>
+    <record>
+      <header><identifier>oai:eprint.iacr.org:2024/200</identifier></header>
+      <metadata>
+        <oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/">
+          <dc:title>Robust Signatures</dc:title>
+          <dc:creator>Carol</dc:creator>
+          <dc:description>Threshold variants of robust signatures.</dc:description>
+          <dc:date>2024-03-01T00:00:00Z</dc:date>
+          <dc:identifier>https://eprint.iacr.org/2024/200</dc:identifier>
+        </oai_dc:dc>
+      </metadata>
+    </record>
+    <resumptionToken cursor="3" completeListSize="4"/>
+  </ListRecords>
+</OAI-PMH>"#;
+
+  #[tokio::test]
+  async fn test_search() {
+    use std::sync::atomic::Ordering;
+
+    // Twelve pages to the most recent step, the last without a resumption token, which is all
+    // the later steps get
+    let pages = || {
+      std::iter::repeat((200, "", LIST_PAGE)).take(11).chain([(200, "", LAST_LIST_PAGE)]).collect()
+    };
+    let (url, requests) = http::serve_responses_with_headers(pages()).await;
+    let client = IACRClient { base_url: url, ..IACRClient::new().with_retry(0) };
+
+    // Every word has to match, in the title, authors, or abstract; newest first, each once
+    let papers = client.search("Threshold SIGNATURES", 10).await.unwrap();
+    let identifiers = papers.iter().map(|p| p.source_identifier.as_str()).collect::<Vec<_>>();
+    assert_eq!(identifiers, ["2024/200", "2024/100"]);
+    assert_eq!(papers[1].title, "Threshold Signatures from Lattices");
+    assert_eq!(papers[1].pdf_url.as_deref(), Some("https://eprint.iacr.org/2024/100.pdf"));
+    // Every page of the first step, then a page for each of the two further steps
+    assert_eq!(requests.load(Ordering::SeqCst), 14);
+
+    // Enough matches in the most recent records end the search there
+    let (url, requests) = http::serve_responses_with_headers(pages()).await;
+    let client = IACRClient { base_url: url, ..IACRClient::new().with_retry(0) };
+    let papers = client.search("signatures", 1).await.unwrap();
+    assert_eq!(papers[0].source_identifier, "2024/200");
+    assert_eq!(requests.load(Ordering::SeqCst), 12);
+
+    // No records in the window is no results, not an error
+    let (url, _) = http::serve_responses(&[(
+      200,
+      r#"<OAI-PMH><error code="noRecordsMatch">No records</error></OAI-PMH>"#,
+    )])
+    .await;
+    let client = IACRClient { base_url: url, ..IACRClient::new().with_retry(0) };
+    assert!(client.search("signatures", 10).await.unwrap().is_empty());
+  }
 }
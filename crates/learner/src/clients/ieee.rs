@@ -0,0 +1,229 @@
+//! Client implementation for papers linked from IEEE Xplore.
+//!
+//! IEEE Xplore (https://ieeexplore.ieee.org) identifies documents by an article number, as in
+//! `https://ieeexplore.ieee.org/document/771073`, rather than by DOI. The metadata IEEE embeds in
+//! each document page gives the DOI, so this client looks it up there and then fetches the paper
+//! through [`DOIClient`], returning it as a [`Source::DOI`] paper like any other DOI.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::clients::IEEEClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = IEEEClient::new();
+//! let paper = client.fetch_paper("https://ieeexplore.ieee.org/document/771073").await?;
+//!
+//! println!("Title: {}", paper.title);
+//! println!("DOI: {}", paper.source_identifier);
+//! # Ok(())
+//! # }
+//! ```
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+use super::*;
+
+lazy_static! {
+  /// The DOI in the document metadata of an IEEE Xplore page, e.g. `"doi":"10.1109/5.771073"`.
+  static ref DOCUMENT_DOI: Regex =
+    Regex::new(r#""doi"\s*:\s*"(10\.\d{4,9}/[^"\s]+)""#).unwrap();
+  /// An IEEE Xplore article number.
+  static ref ARTICLE_NUMBER: Regex = Regex::new(r"^\d+$").unwrap();
+}
+
+/// Client for fetching papers from IEEE Xplore URLs by their DOI.
+pub struct IEEEClient {
+  /// Internal web client used to connect to IEEE Xplore.
+  client:   reqwest::Client,
+  /// The base URL to use for the client.
+  base_url: String,
+  /// Timeout and retry settings.
+  config:   ClientConfig,
+  /// Client the paper is fetched with once its DOI is known.
+  doi:      DOIClient,
+}
+
+impl IEEEClient {
+  /// Creates a new IEEE Xplore client instance.
+  pub fn new() -> Self { Self::new_with_config(ClientConfig::default()) }
+
+  /// Creates a new IEEE Xplore client with custom timeout and retry settings, used for both
+  /// IEEE Xplore and Crossref.
+  pub fn new_with_config(config: ClientConfig) -> Self {
+    Self {
+      config,
+      doi: DOIClient::new_with_config(config),
+      ..Self::with_client(config.http_client())
+    }
+  }
+
+  /// Creates a new IEEE Xplore client that sends its requests through `client`, e.g. one
+  /// configured once with a proxy, TLS roots, or default headers and shared across sources.
+  ///
+  /// Requests are limited by `client`'s own timeout and retried as by
+  /// [`ClientConfig::default`].
+  pub fn with_client(client: reqwest::Client) -> Self {
+    Self {
+      doi: DOIClient::with_client(client.clone()),
+      client,
+      base_url: "https://ieeexplore.ieee.org".to_string(),
+      config: ClientConfig::default(),
+    }
+  }
+
+  /// Sends requests to a different endpoint, such as a local test server, instead of
+  /// `https://ieeexplore.ieee.org`, keeping the other settings.
+  pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+    self.base_url = url.into();
+    self
+  }
+
+  /// Fetches papers from Crossref with `client` once their DOI is known, e.g. one with a
+  /// contact email set by [`DOIClient::with_mailto`].
+  pub fn with_doi_client(mut self, client: DOIClient) -> Self {
+    self.doi = client;
+    self
+  }
+
+  /// Looks up the DOI of an IEEE Xplore document.
+  ///
+  /// # Arguments
+  ///
+  /// * `identifier` - An IEEE Xplore URL (e.g., "https://ieeexplore.ieee.org/document/771073") or
+  ///   article number (e.g., "771073")
+  ///
+  /// # Errors
+  ///
+  /// This function will return an error if:
+  /// - The identifier is not an IEEE Xplore URL or article number
+  /// - The network request fails, or IEEE Xplore answers with an error status
+  /// - The document page doesn't give a DOI, as for some conference front matter
+  pub async fn resolve_doi(&self, identifier: &str) -> Result<String, LearnerError> {
+    let number = article_number(identifier).ok_or(LearnerError::InvalidIdentifier)?;
+    let url = format!("{}/document/{number}", self.base_url);
+    debug!("Looking up DOI on IEEE Xplore via: {url}");
+
+    let response = send_with_retry(&self.config, "IEEE Xplore", || self.client.get(&url)).await?;
+    let status = response.status();
+    debug!("IEEE Xplore response status: {status}");
+
+    let text = response.text().await?;
+    trace!("IEEE Xplore response: {text}");
+
+    if !status.is_success() {
+      return Err(LearnerError::ApiError(format!("IEEE Xplore returned {status}")));
+    }
+    DOCUMENT_DOI
+      .captures(&text)
+      .map(|captures| captures[1].to_string())
+      .ok_or_else(|| LearnerError::ApiError(format!("IEEE Xplore document {number} has no DOI")))
+  }
+
+  /// Fetches the paper for an IEEE Xplore document through its DOI.
+  ///
+  /// # Arguments
+  ///
+  /// * `identifier` - An IEEE Xplore URL or article number, as for [`IEEEClient::resolve_doi`]
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - A [`Paper`] with [`Source::DOI`] as its source and the DOI as its identifier
+  /// - A [`LearnerError`] if looking up the DOI or fetching the paper fails
+  pub async fn fetch_paper(&self, identifier: &str) -> Result<Paper, LearnerError> {
+    let doi = self.resolve_doi(identifier).await?;
+    self.doi.fetch_paper(&doi).await
+  }
+}
+
+impl Default for IEEEClient {
+  fn default() -> Self { Self::new() }
+}
+
+/// Extracts the article number from an IEEE Xplore URL or article number.
+///
+/// Accepts URLs like "https://ieeexplore.ieee.org/document/771073",
+/// "https://ieeexplore.ieee.org/abstract/document/771073/" or
+/// "https://ieeexplore.ieee.org/stamp/stamp.jsp?arnumber=771073".
+pub(crate) fn article_number(identifier: &str) -> Option<String> {
+  let identifier = identifier.trim();
+  if ARTICLE_NUMBER.is_match(identifier) {
+    return Some(identifier.to_string());
+  }
+
+  let url = Url::parse(identifier).ok()?;
+  if !matches!(url.host_str(), Some("ieeexplore.ieee.org" | "www.ieeexplore.ieee.org")) {
+    return None;
+  }
+  let segments: Vec<_> = url.path_segments()?.filter(|segment| !segment.is_empty()).collect();
+  let number = match segments.as_slice() {
+    [.., "document", number] => number.to_string(),
+    _ => url.query_pairs().find(|(key, _)| key == "arnumber")?.1.into_owned(),
+  };
+  ARTICLE_NUMBER.is_match(&number).then_some(number)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clients::http::serve_responses;
+
+  /// The part of an IEEE Xplore document page holding its metadata.
+  const DOCUMENT_PAGE: &str = r#"<html><head><script type="text/javascript">
+    xplGlobal.document.metadata={"userInfo":{},"articleNumber":"771073","doi":"10.1109/5.771073","title":"Gradient-based learning"};
+  </script></head></html>"#;
+
+  /// A minimal Crossref response for the same document.
+  const WORK_RESPONSE: &str = r#"{
+    "status": "ok",
+    "message-type": "work",
+    "message": {
+      "DOI": "10.1109/5.771073",
+      "title": ["Gradient-based learning applied to document recognition"],
+      "author": [{"given": "Y.", "family": "Lecun"}, {"given": "L.", "family": "Bottou"}],
+      "created": {"date-parts": [[1998, 11, 1]]}
+    }
+  }"#;
+
+  #[test]
+  fn test_article_number() {
+    for input in [
+      "771073",
+      "https://ieeexplore.ieee.org/document/771073",
+      "https://ieeexplore.ieee.org/document/771073/",
+      "https://ieeexplore.ieee.org/abstract/document/771073",
+      "https://ieeexplore.ieee.org/stamp/stamp.jsp?tp=&arnumber=771073",
+    ] {
+      assert_eq!(article_number(input).as_deref(), Some("771073"), "{input}");
+    }
+    assert_eq!(article_number("https://ieeexplore.ieee.org/document/abc"), None);
+    assert_eq!(article_number("https://example.com/document/771073"), None);
+    assert_eq!(article_number("10.1109/5.771073"), None);
+  }
+
+  #[tokio::test]
+  async fn test_fetch_paper_through_doi() {
+    static RESPONSES: &[(u16, &str)] = &[(200, DOCUMENT_PAGE), (200, WORK_RESPONSE)];
+    let (url, _) = serve_responses(RESPONSES).await;
+    let client = IEEEClient::new()
+      .with_base_url(url.clone())
+      .with_doi_client(DOIClient::new().with_base_url(url).with_retry(0));
+
+    let paper = client.fetch_paper("https://ieeexplore.ieee.org/document/771073").await.unwrap();
+    assert_eq!(paper.title, "Gradient-based learning applied to document recognition");
+    assert_eq!(paper.source, Source::DOI);
+    assert_eq!(paper.source_identifier, "10.1109/5.771073");
+
+    static NO_DOI: &[(u16, &str)] = &[(200, "<html></html>")];
+    let (url, _) = serve_responses(NO_DOI).await;
+    let client = IEEEClient::new().with_base_url(url);
+    assert!(matches!(client.resolve_doi("771073").await, Err(LearnerError::ApiError(_))));
+    assert!(matches!(
+      client.resolve_doi("not a document").await,
+      Err(LearnerError::InvalidIdentifier)
+    ));
+  }
+}
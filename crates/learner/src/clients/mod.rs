@@ -11,6 +11,20 @@
 //! - [`arxiv`] - Client for the arXiv.org preprint server
 //! - [`iacr`] - Client for the International Association for Cryptologic Research
 //! - [`doi`] - Client for resolving Digital Object Identifiers (DOIs)
+//! - [`ieee`] - Client for IEEE Xplore documents, fetched through their DOIs
+//! - [`semantic_scholar`] - Client for the Semantic Scholar Graph API
+//! - [`pubmed`] - Client for PubMed, through the NCBI E-utilities
+//! - [`biorxiv`] - Client for the bioRxiv and medRxiv preprint servers
+//! - [`openalex`] - Client for the OpenAlex catalog of scholarly works
+//! - [`ssrn`] - Client for the SSRN preprint repository, read from its landing pages
+//!
+//! All clients give up on requests taking longer than 30 seconds, and retry transient failures
+//! (timeouts, connection errors, rate limiting and server errors) with exponential backoff.
+//! See [`http`] and [`ClientConfig`] to tune this, and [`retry`](mod@retry) to retry other
+//! operations the same way.
+//!
+//! Every client implements [`PaperSource`], and [`SourceRegistry`] lets further sources be
+//! registered alongside them; see [`source`].
 //!
 //! # Examples
 //!
@@ -30,14 +44,44 @@
 //! # }
 //! ```
 
+use std::{sync::Arc, time::Duration};
+
 use quick_xml::de::from_str;
 
 pub mod arxiv;
+pub mod biorxiv;
 pub mod doi;
+pub mod http;
 pub mod iacr;
+pub mod ieee;
+pub mod openalex;
+pub mod pubmed;
+pub mod retry;
+pub mod semantic_scholar;
+pub mod source;
+pub mod ssrn;
 
 pub use arxiv::ArxivClient;
+pub use biorxiv::BioRxivClient;
 pub use doi::DOIClient;
+pub use http::{retry, ClientConfig, RateLimiter};
+use http::{send_with_retry, Throttle};
 pub use iacr::IACRClient;
+pub use ieee::IEEEClient;
+pub use openalex::OpenAlexClient;
+pub use pubmed::PubMedClient;
+pub use semantic_scholar::SemanticScholarClient;
+pub use source::{PaperFuture, PaperSource, SourceClient, SourceRegistry};
+pub use ssrn::SSRNClient;
 
 use super::*;
+
+/// Cleans up an abstract as returned by a source with [`format::clean_abstract`].
+///
+/// Returns the cleaned abstract, along with the original for [`Paper::raw_abstract`] if cleaning
+/// changed more than its whitespace.
+pub(crate) fn clean_abstract(raw: String) -> (String, Option<String>) {
+  let cleaned = format::clean_abstract(&raw);
+  let changed = cleaned.split_whitespace().ne(raw.split_whitespace());
+  (cleaned, changed.then_some(raw))
+}
@@ -0,0 +1,492 @@
+//! Client implementation for fetching papers from OpenAlex.
+//!
+//! This module provides functionality to fetch paper metadata from OpenAlex
+//! (https://api.openalex.org), a free and open catalog of scholarly works, and convert it to
+//! the common [`Paper`] format. Works are identified by their OpenAlex Work ID, such as
+//! `"W2741809807"`, but can also be looked up by DOI, arXiv ID, or PubMed ID.
+//!
+//! Besides fetching single works, the client can search the catalog for discovery, narrowing
+//! results with OpenAlex's filter syntax (e.g. `"is_oa:true,publication_year:2023"`).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::clients::OpenAlexClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = OpenAlexClient::new();
+//! let paper = client.fetch_paper("W2741809807").await?;
+//!
+//! println!("Title: {}", paper.title);
+//! println!("Authors: {}", paper.authors.len());
+//!
+//! // Search for open-access papers
+//! let papers = client.search("zero knowledge proofs", "is_oa:true", 10).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use lazy_static::lazy_static;
+use regex::Regex;
+use url::Url;
+
+use super::*;
+use crate::paper::detect_source;
+
+/// The most results OpenAlex returns for a single page of a search.
+const MAX_PER_PAGE: usize = 200;
+
+/// Internal representation of a work from the OpenAlex API.
+#[derive(Debug, Deserialize)]
+struct OpenAlexWork {
+  /// The work's OpenAlex URL, e.g. `https://openalex.org/W2741809807`
+  id: Option<String>,
+  /// The work's DOI as a `https://doi.org/` URL, if it has one
+  doi: Option<String>,
+  /// Work title, which is missing for some records
+  title: Option<String>,
+  /// Publication date in `YYYY-MM-DD` format, if known
+  publication_date: Option<String>,
+  /// Year of publication, used when the full date is unknown
+  publication_year: Option<i32>,
+  /// Authors in byline order, with their institutions
+  #[serde(default)]
+  authorships: Vec<OpenAlexAuthorship>,
+  /// The abstract as a map from each word to the positions it appears at
+  abstract_inverted_index: Option<HashMap<String, Vec<usize>>>,
+  /// The best open-access copy of the work, if there is one
+  best_oa_location: Option<OpenAlexLocation>,
+  /// Where the work was published
+  primary_location: Option<OpenAlexLocation>,
+  /// Research concepts associated with the work, most relevant first
+  #[serde(default)]
+  concepts: Vec<OpenAlexConcept>,
+  /// Keywords describing the work
+  #[serde(default)]
+  keywords: Vec<OpenAlexConcept>,
+}
+
+/// An author's contribution to a work.
+#[derive(Debug, Deserialize)]
+struct OpenAlexAuthorship {
+  /// The author
+  author:       OpenAlexAuthor,
+  /// Institutions the author was affiliated with for the work
+  #[serde(default)]
+  institutions: Vec<OpenAlexInstitution>,
+}
+
+/// Author information from OpenAlex.
+#[derive(Debug, Deserialize)]
+struct OpenAlexAuthor {
+  /// Author's full name
+  display_name: Option<String>,
+}
+
+/// Institution information from OpenAlex.
+#[derive(Debug, Deserialize)]
+struct OpenAlexInstitution {
+  /// Institution name
+  display_name: Option<String>,
+}
+
+/// A place a work is hosted.
+#[derive(Debug, Deserialize)]
+struct OpenAlexLocation {
+  /// Direct URL to the PDF at this location, if known
+  pdf_url: Option<String>,
+}
+
+/// A concept or keyword attached to a work.
+#[derive(Debug, Deserialize)]
+struct OpenAlexConcept {
+  /// Human-readable name
+  display_name: String,
+}
+
+/// A page of search results.
+#[derive(Debug, Deserialize)]
+struct OpenAlexResults {
+  /// The works on this page
+  results: Vec<OpenAlexWork>,
+}
+
+/// Client for fetching papers from the OpenAlex API.
+///
+/// This client provides methods to fetch work metadata by OpenAlex Work ID, DOI, arXiv ID, or
+/// PubMed ID, and to search the catalog. It handles the HTTP requests, JSON parsing, and
+/// conversion to the common [`Paper`] format.
+///
+/// Fetched papers are always identified by their OpenAlex Work ID, whatever identifier they
+/// were looked up by.
+pub struct OpenAlexClient {
+  /// Internal web client used to connect to the API.
+  client:   reqwest::Client,
+  /// The base URL to use for the client.
+  base_url: String,
+  /// Timeout and retry settings.
+  config:   ClientConfig,
+}
+
+impl OpenAlexClient {
+  /// Creates a new OpenAlex client instance.
+  ///
+  /// Initializes an HTTP client for making requests to the OpenAlex API.
+  pub fn new() -> Self { Self::new_with_config(ClientConfig::default()) }
+
+  /// Creates a new OpenAlex client with custom timeout and retry settings.
+  pub fn new_with_config(config: ClientConfig) -> Self {
+    Self { config, ..Self::with_client(config.http_client()) }
+  }
+
+  /// Creates a new OpenAlex client that sends its requests through `client`, e.g. one
+  /// configured once with a proxy, TLS roots, or default headers and shared across sources.
+  ///
+  /// Requests are limited by `client`'s own timeout and retried as by
+  /// [`ClientConfig::default`].
+  pub fn with_client(client: reqwest::Client) -> Self {
+    Self {
+      client,
+      base_url: "https://api.openalex.org/works".to_string(),
+      config: ClientConfig::default(),
+    }
+  }
+
+  /// Sends requests to a different endpoint, such as a local test server, instead of
+  /// `https://api.openalex.org/works`, keeping the other settings.
+  pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+    self.base_url = url.into();
+    self
+  }
+
+  /// Sets how many times to retry a request after a transient failure, keeping the other
+  /// settings. See [`ClientConfig::max_retries`].
+  pub fn with_retry(mut self, retries: u8) -> Self {
+    self.config.max_retries = retries.into();
+    self
+  }
+
+  /// Sets the maximum time a single request may take, keeping the other settings. See
+  /// [`ClientConfig::timeout`]. A request that takes longer fails with
+  /// [`LearnerError::Timeout`]. This builds a new HTTP client, replacing one given to
+  /// `with_client`.
+  pub fn with_timeout(self, timeout: Duration) -> Self {
+    Self {
+      base_url: self.base_url,
+      ..Self::new_with_config(ClientConfig { timeout, ..self.config })
+    }
+  }
+
+  /// Fetches work metadata from OpenAlex.
+  ///
+  /// # Arguments
+  ///
+  /// * `identifier` - One of:
+  ///   - An OpenAlex Work ID (e.g., "W2741809807")
+  ///   - A DOI (e.g., "10.1145/1327452.1327492")
+  ///   - An arXiv ID (e.g., "2301.07041"), looked up by its arXiv DOI
+  ///   - A PubMed ID (e.g., "31452104")
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - A [`Paper`] with the fetched metadata, identified by its OpenAlex Work ID
+  /// - A [`LearnerError`] if the fetch or parsing fails
+  ///
+  /// # Errors
+  ///
+  /// This function will return an error if:
+  /// - The network request fails
+  /// - The work is not found
+  /// - The API response cannot be parsed
+  /// - The work has no title, or no publication date or year
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::OpenAlexClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let client = OpenAlexClient::new();
+  /// let paper = client.fetch_paper("10.1145/1327452.1327492").await?;
+  ///
+  /// println!("OpenAlex ID: {}", paper.source_identifier);
+  /// if let Some(pdf_url) = paper.pdf_url {
+  ///   println!("Open-access PDF available at: {}", pdf_url);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn fetch_paper(&self, identifier: &str) -> Result<Paper, LearnerError> {
+    let url = format!("{}/{}", self.base_url, work_key(identifier));
+    debug!("Fetching from OpenAlex via: {url}");
+
+    let text = self.get(&url).await?;
+    let work: OpenAlexWork = serde_json::from_str(&text)
+      .map_err(|e| LearnerError::ApiError(format!("Failed to parse JSON: {}", e)))?;
+    parse_work(work, identifier)
+  }
+
+  /// Searches OpenAlex for works matching a query.
+  ///
+  /// # Arguments
+  ///
+  /// * `query` - The search terms, matched against titles, abstracts, and full text
+  /// * `filter` - An OpenAlex filter narrowing the results (e.g.,
+  ///   `"is_oa:true,publication_year:2023"`), or an empty string for none
+  /// * `per_page` - Maximum number of papers to return, at most 200
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The matching papers, most relevant first, which may be empty. Works without a title or
+  ///   publication date are left out
+  /// - A [`LearnerError`] if the request or parsing fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::OpenAlexClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let client = OpenAlexClient::new();
+  /// for paper in client.search("lattice cryptography", "is_oa:true", 10).await? {
+  ///   println!("{} ({})", paper.title, paper.source_identifier);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search(
+    &self,
+    query: &str,
+    filter: &str,
+    per_page: usize,
+  ) -> Result<Vec<Paper>, LearnerError> {
+    let per_page = per_page.min(MAX_PER_PAGE).to_string();
+    let mut params = vec![("search", query), ("per-page", &per_page)];
+    if !filter.is_empty() {
+      params.push(("filter", filter));
+    }
+    let url = Url::parse_with_params(&self.base_url, &params)?;
+    debug!("Searching OpenAlex via: {url}");
+
+    let text = self.get(url.as_str()).await?;
+    let results: OpenAlexResults = serde_json::from_str(&text)
+      .map_err(|e| LearnerError::ApiError(format!("Failed to parse JSON: {}", e)))?;
+
+    Ok(
+      results
+        .results
+        .into_iter()
+        .filter_map(|work| {
+          let id = work.id.clone().unwrap_or_default();
+          parse_work(work, &id).map_err(|e| debug!("Skipping OpenAlex work {id}: {e}")).ok()
+        })
+        .collect(),
+    )
+  }
+
+  /// Sends a GET request, mapping error statuses to [`LearnerError`]s.
+  async fn get(&self, url: &str) -> Result<String, LearnerError> {
+    let response = send_with_retry(&self.config, "OpenAlex", || self.client.get(url)).await?;
+    let status = response.status();
+    debug!("OpenAlex response status: {status}");
+
+    let text = response.text().await?;
+    trace!("OpenAlex response: {text}");
+
+    if !status.is_success() {
+      return Err(LearnerError::ApiError(format!("OpenAlex returned {status}: {text}")));
+    }
+    Ok(text)
+  }
+}
+
+impl Default for OpenAlexClient {
+  fn default() -> Self { Self::new() }
+}
+
+/// Turns an identifier into the key the works endpoint looks it up by, e.g. `doi:10.1145/...`
+/// for a DOI. Identifiers of other forms are passed through unchanged, so OpenAlex's own
+/// prefixes such as `mag:` also work.
+fn work_key(identifier: &str) -> String {
+  lazy_static! {
+    static ref ARXIV_VERSION: Regex = Regex::new(r"v\d+$").unwrap();
+  }
+
+  let identifier = identifier.trim();
+  match detect_source(identifier) {
+    Some((Source::OpenAlex, id)) => id,
+    Some((Source::DOI | Source::BioRxiv, doi)) => format!("doi:{doi}"),
+    // arXiv registers a DOI for every paper, which is how OpenAlex knows them
+    Some((Source::Arxiv, id)) => format!("doi:10.48550/arXiv.{}", ARXIV_VERSION.replace(&id, "")),
+    Some((Source::PubMed, pmid)) => format!("pmid:{pmid}"),
+    _ => identifier.to_string(),
+  }
+}
+
+/// Rebuilds an abstract from OpenAlex's inverted index, which maps each word to the positions
+/// it appears at.
+fn rebuild_abstract(index: &HashMap<String, Vec<usize>>) -> String {
+  let mut words = index
+    .iter()
+    .flat_map(|(word, positions)| positions.iter().map(move |&position| (position, word.as_str())))
+    .collect::<Vec<_>>();
+  words.sort_unstable();
+  words.into_iter().map(|(_, word)| word).collect::<Vec<_>>().join(" ")
+}
+
+/// Converts an OpenAlex work into a [`Paper`], identified by its Work ID, or `identifier` if
+/// the work doesn't give one.
+fn parse_work(work: OpenAlexWork, identifier: &str) -> Result<Paper, LearnerError> {
+  let title = work
+    .title
+    .filter(|title| !title.trim().is_empty())
+    .ok_or_else(|| LearnerError::ApiError("No title found".to_string()))?;
+
+  // Prefer the full publication date, falling back to January 1st of the publication year
+  let publication_date = work
+    .publication_date
+    .as_deref()
+    .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+    .or_else(|| work.publication_year.and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1)))
+    .and_then(|date| date.and_hms_opt(0, 0, 0))
+    .map(|date| date.and_utc())
+    .ok_or_else(|| LearnerError::ApiError("No publication date found".to_string()))?;
+
+  let source_identifier = work
+    .id
+    .as_deref()
+    .map(|id| id.trim_start_matches("https://openalex.org/"))
+    .unwrap_or(identifier)
+    .to_string();
+
+  // The best open-access copy is most likely to be freely downloadable
+  let pdf_url = [work.best_oa_location, work.primary_location]
+    .into_iter()
+    .flatten()
+    .find_map(|location| location.pdf_url.filter(|url| !url.is_empty()));
+  let (abstract_text, raw_abstract) =
+    clean_abstract(work.abstract_inverted_index.as_ref().map(rebuild_abstract).unwrap_or_default());
+
+  Ok(Paper {
+    title,
+    authors: work
+      .authorships
+      .into_iter()
+      .filter_map(|authorship| {
+        let affiliation =
+          authorship.institutions.into_iter().find_map(|institution| institution.display_name);
+        authorship.author.display_name.map(|name| Author { name, affiliation, email: None })
+      })
+      .collect(),
+    abstract_text,
+    raw_abstract,
+    publication_date,
+    source: Source::OpenAlex,
+    source_identifier,
+    pdf_url,
+    doi: work.doi.map(|doi| doi.trim_start_matches("https://doi.org/").to_string()),
+    version: None,
+    categories: work.concepts.into_iter().map(|concept| concept.display_name).collect(),
+    keywords: work.keywords.into_iter().map(|keyword| keyword.display_name).collect(),
+    tags: Vec::new(),
+    pdf_path: None,
+    reading_status: ReadingStatus::Unread,
+    added_at: None,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clients::http::serve_responses;
+
+  /// A trimmed OpenAlex work response for "MapReduce".
+  const WORK_RESPONSE: &str = r#"{
+    "id": "https://openalex.org/W2173213060",
+    "doi": "https://doi.org/10.1145/1327452.1327492",
+    "title": "MapReduce",
+    "publication_date": "2008-01-01",
+    "publication_year": 2008,
+    "authorships": [
+      {"author": {"id": "https://openalex.org/A1", "display_name": "Jeffrey Dean"},
+       "institutions": [{"id": "https://openalex.org/I1", "display_name": "Google (United States)"}]},
+      {"author": {"id": "https://openalex.org/A2", "display_name": "Sanjay Ghemawat"},
+       "institutions": []}
+    ],
+    "abstract_inverted_index": {"MapReduce": [0], "is": [1], "a": [2], "programming": [3], "model": [4]},
+    "best_oa_location": null,
+    "primary_location": {"pdf_url": "https://dl.acm.org/doi/pdf/10.1145/1327452.1327492"},
+    "concepts": [{"display_name": "Computer science", "score": 0.9}, {"display_name": "Distributed computing", "score": 0.5}],
+    "keywords": [{"display_name": "Cluster computing"}],
+    "cited_by_count": 20000
+  }"#;
+
+  #[test]
+  fn test_parse_work() {
+    let work = serde_json::from_str(WORK_RESPONSE).unwrap();
+    let paper = parse_work(work, "10.1145/1327452.1327492").unwrap();
+
+    assert_eq!(paper.title, "MapReduce");
+    assert_eq!(paper.authors.len(), 2);
+    assert_eq!(paper.authors[0].name, "Jeffrey Dean");
+    assert_eq!(paper.authors[0].affiliation.as_deref(), Some("Google (United States)"));
+    assert_eq!(paper.authors[1].affiliation, None);
+    assert_eq!(paper.abstract_text, "MapReduce is a programming model");
+    assert_eq!(paper.publication_date, Utc.with_ymd_and_hms(2008, 1, 1, 0, 0, 0).unwrap());
+    assert_eq!(paper.source, Source::OpenAlex);
+    assert_eq!(paper.source_identifier, "W2173213060");
+    assert_eq!(
+      paper.pdf_url.as_deref(),
+      Some("https://dl.acm.org/doi/pdf/10.1145/1327452.1327492")
+    );
+    assert_eq!(paper.doi.as_deref(), Some("10.1145/1327452.1327492"));
+    assert_eq!(paper.categories, vec!["Computer science", "Distributed computing"]);
+    assert_eq!(paper.keywords, vec!["Cluster computing"]);
+
+    let untitled = serde_json::from_str(r#"{"title": null, "publication_year": 2020}"#).unwrap();
+    assert!(matches!(parse_work(untitled, "W1"), Err(LearnerError::ApiError(_))));
+  }
+
+  #[test]
+  fn test_work_key() {
+    assert_eq!(work_key("W2741809807"), "W2741809807");
+    assert_eq!(work_key("https://openalex.org/W2741809807"), "W2741809807");
+    assert_eq!(work_key("10.1145/1327452.1327492"), "doi:10.1145/1327452.1327492");
+    assert_eq!(work_key("2301.07041v2"), "doi:10.48550/arXiv.2301.07041");
+    assert_eq!(work_key("31452104"), "pmid:31452104");
+    assert_eq!(work_key("mag:2741809807"), "mag:2741809807");
+  }
+
+  #[tokio::test]
+  async fn test_search_skips_incomplete_works() {
+    static RESPONSES: &[(u16, &str)] = &[(
+      200,
+      r#"{"meta": {"count": 2}, "results": [
+        {"id": "https://openalex.org/W1", "title": "Complete", "publication_year": 2021},
+        {"id": "https://openalex.org/W2", "title": null, "publication_year": 2021}
+      ]}"#,
+    )];
+    let (url, requests) = serve_responses(RESPONSES).await;
+    let client = OpenAlexClient::new().with_base_url(url);
+
+    let papers = client.search("anything", "is_oa:true", 10).await.unwrap();
+    assert_eq!(papers.len(), 1);
+    assert_eq!(papers[0].source_identifier, "W1");
+    assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_openalex_entry_fetch() {
+    let client = OpenAlexClient::new();
+    let paper = client.fetch_paper("W2741809807").await.unwrap();
+
+    assert!(!paper.title.is_empty());
+    assert!(!paper.authors.is_empty());
+    assert_eq!(paper.source, Source::OpenAlex);
+    assert_eq!(paper.source_identifier, "W2741809807");
+  }
+}
@@ -0,0 +1,623 @@
+//! Client implementation for fetching papers from PubMed.
+//!
+//! This module provides functionality to fetch biomedical papers from PubMed using the NCBI
+//! E-utilities API (https://eutils.ncbi.nlm.nih.gov/entrez/eutils). Papers are identified by
+//! their PubMed ID (PMID), a number of up to eight digits such as `"31452104"`.
+//!
+//! Articles are fetched as PubMed XML with EFetch, and searches are run with ESearch. Without
+//! an API key, NCBI allows up to three requests per second.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::clients::PubMedClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = PubMedClient::new();
+//! let paper = client.fetch_paper("31452104").await?;
+//!
+//! println!("Title: {}", paper.title);
+//! println!("Authors: {}", paper.authors.len());
+//!
+//! // Search PubMed
+//! let papers = client.search("crispr off-target effects", 5).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::NaiveDate;
+use quick_xml::{
+  events::{BytesStart, Event},
+  Reader,
+};
+use url::Url;
+
+use super::*;
+
+/// A PubMed article, with journal details that don't fit in a [`Paper`].
+#[derive(Debug, Clone)]
+pub struct PubMedArticle {
+  /// The article's metadata
+  pub paper:   Paper,
+  /// Title of the journal the article was published in
+  pub journal: Option<String>,
+  /// ISSN of the journal the article was published in
+  pub issn:    Option<String>,
+}
+
+/// Response of the ESearch endpoint in JSON mode.
+#[derive(Debug, Deserialize)]
+struct ESearchResponse {
+  /// The search results
+  esearchresult: ESearchResult,
+}
+
+/// The results of an ESearch query.
+#[derive(Debug, Deserialize)]
+struct ESearchResult {
+  /// PMIDs of the matching articles, best match first
+  #[serde(default)]
+  idlist: Vec<String>,
+}
+
+/// Client for fetching papers from PubMed through the NCBI E-utilities.
+///
+/// This client provides methods to fetch paper metadata by PMID and to search PubMed. It
+/// handles the HTTP requests, XML parsing, and conversion to the common [`Paper`] format.
+pub struct PubMedClient {
+  /// Internal web client used to connect to the API.
+  client:   reqwest::Client,
+  /// The base URL of the E-utilities.
+  base_url: String,
+  /// Timeout and retry settings.
+  config:   ClientConfig,
+}
+
+impl PubMedClient {
+  /// Creates a new PubMed client instance.
+  ///
+  /// Initializes an HTTP client for making requests to the E-utilities.
+  pub fn new() -> Self { Self::new_with_config(ClientConfig::default()) }
+
+  /// Creates a new PubMed client with custom timeout and retry settings.
+  pub fn new_with_config(config: ClientConfig) -> Self {
+    Self { config, ..Self::with_client(config.http_client()) }
+  }
+
+  /// Creates a new PubMed client that sends its requests through `client`, e.g. one configured
+  /// once with a proxy, TLS roots, or default headers and shared across sources.
+  ///
+  /// Requests are limited by `client`'s own timeout and retried as by
+  /// [`ClientConfig::default`].
+  pub fn with_client(client: reqwest::Client) -> Self {
+    Self {
+      client,
+      base_url: "https://eutils.ncbi.nlm.nih.gov/entrez/eutils".to_string(),
+      config: ClientConfig::default(),
+    }
+  }
+
+  /// Sets how many times to retry a request after a transient failure, keeping the other
+  /// settings. See [`ClientConfig::max_retries`].
+  pub fn with_retry(mut self, retries: u8) -> Self {
+    self.config.max_retries = retries.into();
+    self
+  }
+
+  /// Sets the maximum time a single request may take, keeping the other settings. See
+  /// [`ClientConfig::timeout`]. A request that takes longer fails with
+  /// [`LearnerError::Timeout`]. This builds a new HTTP client, replacing one given to
+  /// `with_client`.
+  pub fn with_timeout(self, timeout: Duration) -> Self {
+    Self {
+      base_url: self.base_url,
+      ..Self::new_with_config(ClientConfig { timeout, ..self.config })
+    }
+  }
+
+  /// Fetches paper metadata from PubMed using its PMID.
+  ///
+  /// # Arguments
+  ///
+  /// * `pmid` - A PubMed ID (e.g., "31452104")
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - A [`Paper`] with the fetched metadata
+  /// - A [`LearnerError`] if the fetch or parsing fails
+  ///
+  /// # Errors
+  ///
+  /// This function will return an error if:
+  /// - The network request fails
+  /// - No article has the given PMID
+  /// - The API rate limit has been reached ([`LearnerError::RateLimited`])
+  /// - The XML response cannot be parsed
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::PubMedClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let client = PubMedClient::new();
+  /// let paper = client.fetch_paper("31452104").await?;
+  /// println!("DOI: {:?}", paper.doi);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn fetch_paper(&self, pmid: &str) -> Result<Paper, LearnerError> {
+    Ok(self.fetch_article(pmid).await?.paper)
+  }
+
+  /// Fetches an article from PubMed using its PMID, including its journal details.
+  ///
+  /// This is [`PubMedClient::fetch_paper`] for callers that also need the journal title or
+  /// ISSN.
+  ///
+  /// # Errors
+  ///
+  /// Fails in the same cases as [`PubMedClient::fetch_paper`].
+  pub async fn fetch_article(&self, pmid: &str) -> Result<PubMedArticle, LearnerError> {
+    self.fetch_articles(&[pmid]).await?.into_iter().next().ok_or(LearnerError::NotFound)
+  }
+
+  /// Searches PubMed and fetches the best matching papers.
+  ///
+  /// The query uses PubMed's search syntax, so field tags such as `[au]` or `[ti]` work as on
+  /// the PubMed website.
+  ///
+  /// # Arguments
+  ///
+  /// * `query` - The search terms
+  /// * `max_results` - Maximum number of papers to return
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The matching papers, best match first, which may be empty
+  /// - A [`LearnerError`] if a request or parsing fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::PubMedClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let client = PubMedClient::new();
+  /// for paper in client.search("doudna[au] crispr", 10).await? {
+  ///   println!("{} ({})", paper.title, paper.source_identifier);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search(&self, query: &str, max_results: usize) -> Result<Vec<Paper>, LearnerError> {
+    let url = Url::parse_with_params(&format!("{}/esearch.fcgi", self.base_url), &[
+      ("db", "pubmed"),
+      ("term", query),
+      ("retmax", &max_results.to_string()),
+      ("retmode", "json"),
+    ])?;
+    debug!("Searching PubMed via: {url}");
+
+    let text = self.get(url).await?;
+    let response: ESearchResponse = serde_json::from_str(&text)
+      .map_err(|e| LearnerError::ApiError(format!("Failed to parse JSON: {}", e)))?;
+
+    let pmids = response.esearchresult.idlist;
+    if pmids.is_empty() {
+      return Ok(Vec::new());
+    }
+    let pmids = pmids.iter().map(String::as_str).collect::<Vec<_>>();
+    let articles = self.fetch_articles(&pmids).await?;
+    Ok(articles.into_iter().map(|article| article.paper).collect())
+  }
+
+  /// Fetches several articles with a single EFetch request.
+  async fn fetch_articles(&self, pmids: &[&str]) -> Result<Vec<PubMedArticle>, LearnerError> {
+    let url = Url::parse_with_params(&format!("{}/efetch.fcgi", self.base_url), &[
+      ("db", "pubmed"),
+      ("id", &pmids.join(",")),
+      ("retmode", "xml"),
+    ])?;
+    debug!("Fetching from PubMed via: {url}");
+
+    let text = self.get(url).await?;
+    parse_articles(&text)
+  }
+
+  /// Sends a GET request, mapping error statuses to [`LearnerError`]s.
+  async fn get(&self, url: Url) -> Result<String, LearnerError> {
+    let response = send_with_retry(&self.config, "PubMed", || self.client.get(url.clone())).await?;
+    let status = response.status();
+    debug!("PubMed response status: {status}");
+
+    let text = response.text().await?;
+    trace!("PubMed response: {text}");
+
+    if !status.is_success() {
+      return Err(LearnerError::ApiError(format!("PubMed returned {status}: {text}")));
+    }
+    Ok(text)
+  }
+}
+
+impl Default for PubMedClient {
+  fn default() -> Self { Self::new() }
+}
+
+/// Fields of an article collected while reading the XML.
+#[derive(Default)]
+struct ArticleFields {
+  /// The article's PMID
+  pmid:          String,
+  /// Article title, which may be split around inline markup
+  title:         String,
+  /// Abstract sections, each prefixed with its label (e.g. "METHODS: ") if it has one
+  abstract_text: Vec<String>,
+  /// Authors read so far
+  authors:       Vec<Author>,
+  /// The author currently being read
+  author:        Option<AuthorFields>,
+  /// `PubDate` year
+  year:          String,
+  /// `PubDate` month, either a number or an abbreviated name
+  month:         String,
+  /// `PubDate` day
+  day:           String,
+  /// Free-form `MedlineDate` (e.g. "2019 Jan-Feb"), used when there's no `Year`
+  medline_date:  String,
+  /// Journal title
+  journal:       String,
+  /// Journal ISSN
+  issn:          String,
+  /// Article DOI
+  doi:           String,
+}
+
+/// Fields of an author collected while reading the XML.
+#[derive(Default)]
+struct AuthorFields {
+  /// Family name
+  last_name:       String,
+  /// Given names
+  fore_name:       String,
+  /// Name of a group author, used instead of the other names
+  collective_name: String,
+  /// The author's first listed affiliation
+  affiliation:     String,
+  /// Number of affiliations seen so far
+  affiliations:    usize,
+}
+
+/// Parses a PubMed XML `PubmedArticleSet` into its articles.
+///
+/// Titles and abstracts may contain inline markup such as `<i>`, which is reduced to its text.
+fn parse_articles(xml: &str) -> Result<Vec<PubMedArticle>, LearnerError> {
+  let xml_error = |e: quick_xml::Error| LearnerError::ApiError(format!("Failed to parse XML: {e}"));
+
+  let mut reader = Reader::from_str(xml);
+  let mut path: Vec<String> = Vec::new();
+  let mut article: Option<ArticleFields> = None;
+  let mut article_id_type = String::new();
+  let mut articles = Vec::new();
+
+  loop {
+    match reader.read_event().map_err(xml_error)? {
+      Event::Start(start) => {
+        let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+        match (name.as_str(), article.as_mut()) {
+          ("PubmedArticle", _) => article = Some(ArticleFields::default()),
+          ("Author", Some(fields)) => fields.author = Some(AuthorFields::default()),
+          ("AffiliationInfo", Some(ArticleFields { author: Some(author), .. })) =>
+            author.affiliations += 1,
+          ("AbstractText", Some(fields)) => {
+            let label = attribute(&start, "Label")?;
+            fields.abstract_text.push(label.map(|l| format!("{l}: ")).unwrap_or_default());
+          },
+          ("ArticleId", _) => article_id_type = attribute(&start, "IdType")?.unwrap_or_default(),
+          _ => {},
+        }
+        path.push(name);
+      },
+      Event::End(_) => {
+        let name = path.pop().unwrap_or_default();
+        match (name.as_str(), article.as_mut()) {
+          ("PubmedArticle", Some(_)) =>
+            if let Some(fields) = article.take() {
+              articles.push(fields.finish()?);
+            },
+          ("Author", Some(fields)) =>
+            if let Some(author) = fields.author.take().and_then(AuthorFields::finish) {
+              fields.authors.push(author);
+            },
+          _ => {},
+        }
+      },
+      Event::Text(text) => {
+        let Some(fields) = article.as_mut() else { continue };
+        let text = text.unescape().map_err(xml_error)?;
+        fields.push_text(&path, &article_id_type, &text);
+      },
+      Event::Eof => break,
+      _ => {},
+    }
+  }
+
+  Ok(articles)
+}
+
+/// Reads an attribute of an element, if present.
+fn attribute(start: &BytesStart, name: &str) -> Result<Option<String>, LearnerError> {
+  let attribute = start
+    .try_get_attribute(name)
+    .map_err(|e| LearnerError::ApiError(format!("Failed to parse XML: {e}")))?;
+  attribute
+    .map(|a| a.unescape_value().map(|v| v.into_owned()))
+    .transpose()
+    .map_err(|e| LearnerError::ApiError(format!("Failed to parse XML: {e}")))
+}
+
+impl ArticleFields {
+  /// Stores text found at `path` (the names of the enclosing elements, outermost first).
+  fn push_text(&mut self, path: &[String], article_id_type: &str, text: &str) {
+    let within = |name: &str| path.iter().any(|p| p == name);
+    let parent = |depth: usize| path.len().checked_sub(depth + 1).map(|i| path[i].as_str());
+
+    if within("ArticleTitle") {
+      self.title.push_str(text);
+    } else if within("AbstractText") {
+      if let Some(section) = self.abstract_text.last_mut() {
+        section.push_str(text);
+      }
+    } else if let Some(author) = self.author.as_mut() {
+      let target = match parent(0) {
+        Some("LastName") => &mut author.last_name,
+        Some("ForeName") => &mut author.fore_name,
+        Some("CollectiveName") => &mut author.collective_name,
+        // Only the first affiliation is kept
+        Some("Affiliation") if author.affiliations == 1 => &mut author.affiliation,
+        _ => return,
+      };
+      target.push_str(text);
+    } else {
+      let target = match (parent(1), parent(0)) {
+        (Some("MedlineCitation"), Some("PMID")) if self.pmid.is_empty() => &mut self.pmid,
+        (Some("PubDate"), Some("Year")) => &mut self.year,
+        (Some("PubDate"), Some("Month")) => &mut self.month,
+        (Some("PubDate"), Some("Day")) => &mut self.day,
+        (Some("PubDate"), Some("MedlineDate")) => &mut self.medline_date,
+        (Some("Journal"), Some("Title")) => &mut self.journal,
+        (Some("Journal"), Some("ISSN")) if self.issn.is_empty() => &mut self.issn,
+        (Some("ArticleIdList"), Some("ArticleId"))
+          if article_id_type == "doi" && within("PubmedData") =>
+          &mut self.doi,
+        _ => return,
+      };
+      target.push_str(text);
+    }
+  }
+
+  /// Builds the article from the collected fields.
+  fn finish(self) -> Result<PubMedArticle, LearnerError> {
+    let publication_date = self.publication_date().ok_or_else(|| {
+      LearnerError::ApiError(format!("No publication date found for PMID {}", self.pmid))
+    })?;
+    let non_empty = |s: String| Some(collapse_whitespace(&s)).filter(|s| !s.is_empty());
+    // Each section of a structured abstract is a paragraph
+    let (abstract_text, raw_abstract) = clean_abstract(self.abstract_text.join("\n\n"));
+
+    let paper = Paper {
+      title: collapse_whitespace(&self.title),
+      authors: self.authors,
+      abstract_text,
+      raw_abstract,
+      publication_date,
+      source: Source::PubMed,
+      source_identifier: self.pmid.trim().to_string(),
+      pdf_url: None,
+      doi: non_empty(self.doi),
+      version: None,
+      categories: Vec::new(),
+      keywords: Vec::new(),
+      tags: Vec::new(),
+      pdf_path: None,
+      reading_status: ReadingStatus::Unread,
+      added_at: None,
+    };
+    Ok(PubMedArticle { paper, journal: non_empty(self.journal), issn: non_empty(self.issn) })
+  }
+
+  /// Works out the publication date from `PubDate`, defaulting a missing month or day to the
+  /// first.
+  fn publication_date(&self) -> Option<DateTime<Utc>> {
+    // `MedlineDate` is free-form, but starts with the year and usually a month name
+    let mut medline = self.medline_date.split_whitespace();
+    let (year, month, day) = if self.year.trim().is_empty() {
+      (medline.next().unwrap_or_default(), medline.next().unwrap_or_default(), "")
+    } else {
+      (self.year.trim(), self.month.trim(), self.day.trim())
+    };
+
+    let year = year.parse().ok()?;
+    let month = parse_month(month).unwrap_or(1);
+    let day = day.parse().unwrap_or(1);
+    NaiveDate::from_ymd_opt(year, month, day)
+      .or_else(|| NaiveDate::from_ymd_opt(year, month, 1))
+      .and_then(|date| date.and_hms_opt(0, 0, 0))
+      .map(|date| date.and_utc())
+  }
+}
+
+impl AuthorFields {
+  /// Builds the author, or `None` if no name was given.
+  fn finish(self) -> Option<Author> {
+    let name = if self.collective_name.trim().is_empty() {
+      collapse_whitespace(&format!("{} {}", self.fore_name, self.last_name))
+    } else {
+      collapse_whitespace(&self.collective_name)
+    };
+    let affiliation = Some(collapse_whitespace(&self.affiliation)).filter(|a| !a.is_empty());
+    (!name.is_empty()).then_some(Author { name, affiliation, email: None })
+  }
+}
+
+/// Parses a PubMed month, which is either a number ("03") or an abbreviated name ("Mar").
+fn parse_month(month: &str) -> Option<u32> {
+  const MONTHS: [&str; 12] =
+    ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+
+  month.parse().ok().filter(|m| (1..=12).contains(m)).or_else(|| {
+    let prefix = month.get(..3)?.to_lowercase();
+    MONTHS.iter().position(|m| *m == prefix).map(|i| i as u32 + 1)
+  })
+}
+
+/// Trims text and collapses runs of whitespace (including newlines) into single spaces.
+fn collapse_whitespace(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A trimmed EFetch response with one structured abstract and one MedlineDate article.
+  const ARTICLES_RESPONSE: &str = r#"<?xml version="1.0" ?>
+<!DOCTYPE PubmedArticleSet PUBLIC "-//NLM//DTD PubMedArticle, 1st January 2024//EN" "https://dtd.nlm.nih.gov/ncbi/pubmed/out/pubmed_240101.dtd">
+<PubmedArticleSet>
+  <PubmedArticle>
+    <MedlineCitation Status="MEDLINE" Owner="NLM">
+      <PMID Version="1">31452104</PMID>
+      <Article PubModel="Print-Electronic">
+        <Journal>
+          <ISSN IssnType="Electronic">1476-4687</ISSN>
+          <JournalIssue CitedMedium="Internet">
+            <Volume>573</Volume>
+            <PubDate>
+              <Year>2019</Year>
+              <Month>Sep</Month>
+              <Day>05</Day>
+            </PubDate>
+          </JournalIssue>
+          <Title>Nature</Title>
+        </Journal>
+        <ArticleTitle>Base editing of <i>PCSK9</i> &amp; lipid levels.</ArticleTitle>
+        <Abstract>
+          <AbstractText Label="BACKGROUND">Gene editing
+            is promising.</AbstractText>
+          <AbstractText Label="RESULTS">LDL was reduced by 60%.</AbstractText>
+        </Abstract>
+        <AuthorList CompleteYN="Y">
+          <Author ValidYN="Y">
+            <LastName>Doudna</LastName>
+            <ForeName>Jennifer A</ForeName>
+            <Initials>JA</Initials>
+            <AffiliationInfo>
+              <Affiliation>University of California, Berkeley, CA, USA.</Affiliation>
+            </AffiliationInfo>
+            <AffiliationInfo>
+              <Affiliation>Howard Hughes Medical Institute, USA.</Affiliation>
+            </AffiliationInfo>
+          </Author>
+          <Author ValidYN="Y">
+            <CollectiveName>CRISPR Consortium</CollectiveName>
+          </Author>
+        </AuthorList>
+      </Article>
+      <CommentsCorrectionsList>
+        <CommentsCorrections RefType="Cites">
+          <RefSource>Science. 2012</RefSource>
+          <PMID Version="1">22745249</PMID>
+        </CommentsCorrections>
+      </CommentsCorrectionsList>
+    </MedlineCitation>
+    <PubmedData>
+      <ArticleIdList>
+        <ArticleId IdType="pubmed">31452104</ArticleId>
+        <ArticleId IdType="doi">10.1038/s41586-019-1711-4</ArticleId>
+      </ArticleIdList>
+    </PubmedData>
+  </PubmedArticle>
+  <PubmedArticle>
+    <MedlineCitation Status="MEDLINE" Owner="NLM">
+      <PMID Version="1">10000001</PMID>
+      <Article PubModel="Print">
+        <Journal>
+          <JournalIssue CitedMedium="Print">
+            <PubDate>
+              <MedlineDate>1998 Dec-1999 Jan</MedlineDate>
+            </PubDate>
+          </JournalIssue>
+          <Title>Journal of Examples</Title>
+        </Journal>
+        <ArticleTitle>An article without an abstract.</ArticleTitle>
+      </Article>
+    </MedlineCitation>
+  </PubmedArticle>
+</PubmedArticleSet>"#;
+
+  #[test]
+  fn test_parse_articles() {
+    let articles = parse_articles(ARTICLES_RESPONSE).unwrap();
+    assert_eq!(articles.len(), 2);
+
+    let article = &articles[0];
+    assert_eq!(article.journal.as_deref(), Some("Nature"));
+    assert_eq!(article.issn.as_deref(), Some("1476-4687"));
+
+    let paper = &article.paper;
+    assert_eq!(paper.title, "Base editing of PCSK9 & lipid levels.");
+    assert_eq!(paper.source, Source::PubMed);
+    assert_eq!(paper.source_identifier, "31452104");
+    assert_eq!(paper.doi.as_deref(), Some("10.1038/s41586-019-1711-4"));
+    assert_eq!(paper.publication_date, Utc.with_ymd_and_hms(2019, 9, 5, 0, 0, 0).unwrap());
+    assert_eq!(
+      paper.abstract_text,
+      "BACKGROUND: Gene editing is promising.\n\nRESULTS: LDL was reduced by 60%."
+    );
+
+    assert_eq!(paper.authors.len(), 2);
+    assert_eq!(paper.authors[0].name, "Jennifer A Doudna");
+    assert_eq!(
+      paper.authors[0].affiliation.as_deref(),
+      Some("University of California, Berkeley, CA, USA.")
+    );
+    assert_eq!(paper.authors[1].name, "CRISPR Consortium");
+    assert_eq!(paper.authors[1].affiliation, None);
+  }
+
+  #[test]
+  fn test_parse_articles_sparse_record() {
+    let articles = parse_articles(ARTICLES_RESPONSE).unwrap();
+    let article = &articles[1];
+
+    assert_eq!(article.issn, None);
+    assert_eq!(article.paper.source_identifier, "10000001");
+    assert_eq!(article.paper.publication_date, Utc.with_ymd_and_hms(1998, 12, 1, 0, 0, 0).unwrap());
+    assert!(article.paper.abstract_text.is_empty());
+    assert!(article.paper.authors.is_empty());
+    assert_eq!(article.paper.doi, None);
+
+    // An unknown PMID returns an empty article set
+    assert!(parse_articles("<PubmedArticleSet></PubmedArticleSet>").unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_parse_month() {
+    assert_eq!(parse_month("Sep"), Some(9));
+    assert_eq!(parse_month("03"), Some(3));
+    assert_eq!(parse_month("13"), None);
+    assert_eq!(parse_month(""), None);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_pubmed_entry_fetch() {
+    let client = PubMedClient::new();
+    let paper = client.fetch_paper("31452104").await.unwrap();
+
+    assert!(!paper.title.is_empty());
+    assert!(!paper.authors.is_empty());
+    assert_eq!(paper.source, Source::PubMed);
+  }
+}
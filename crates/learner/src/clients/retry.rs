@@ -0,0 +1,91 @@
+//! Retrying operations that fail transiently, with exponential backoff.
+//!
+//! The clients retry their own requests (see [`http`]); [`with_retry`] retries
+//! any other operation the same way, for code making its own requests to paper sources
+//! without setting up a [`ClientConfig`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use learner::{clients::retry::with_retry, errors::LearnerError};
+//!
+//! # async fn example() -> Result<(), LearnerError> {
+//! // At most three attempts, waiting about 0.5s and then 1s between them
+//! let listing = with_retry(3, Duration::from_millis(500), || async {
+//!   let response = reqwest::get("https://arxiv.org/list/cs.CR/new").await?;
+//!   Ok::<_, LearnerError>(response.error_for_status()?.text().await?)
+//! })
+//! .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+
+use super::*;
+
+/// Runs `f`, trying it up to `max_attempts` times in all while it fails with a transient error.
+///
+/// Errors are retried as by [`retry`](fn@super::retry): timeouts, failed connections, and `429`
+/// or `5xx` statuses, while any other result is returned straight away. The first retry waits
+/// `base_delay`, and each further one twice as long as the previous, give or take 20%.
+///
+/// # Arguments
+///
+/// * `max_attempts` - The most times to call `f`; `0` is taken as `1`
+/// * `base_delay` - Delay before the first retry
+/// * `f` - Starts a new attempt each time it is called
+///
+/// # Errors
+///
+/// Returns the error of the last attempt if none succeeds.
+pub async fn with_retry<T, F, Fut>(
+  max_attempts: u32,
+  base_delay: Duration,
+  f: F,
+) -> Result<T, LearnerError>
+where
+  F: FnMut() -> Fut,
+  Fut: Future<Output = Result<T, LearnerError>>,
+{
+  let config =
+    ClientConfig { max_retries: max_attempts.saturating_sub(1), base_delay, ..Default::default() };
+  http::retry_with_delay(&config, "the service", f, |retry| config.backoff(retry)).await
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  use super::*;
+
+  #[tokio::test]
+  async fn test_with_retry_succeeds_after_transient_failures() {
+    let (url, requests) =
+      http::serve_responses(&[(503, "busy"), (503, "busy"), (200, "listing")]).await;
+    let calls = AtomicUsize::new(0);
+
+    let listing = with_retry(5, Duration::from_millis(1), || async {
+      calls.fetch_add(1, Ordering::SeqCst);
+      let response = reqwest::get(url.as_str()).await?;
+      Ok::<_, LearnerError>(response.error_for_status()?.text().await?)
+    })
+    .await
+    .unwrap();
+    assert_eq!(listing, "listing");
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+    // Out of attempts, the last error is returned
+    let (url, requests) = http::serve_responses(&[(503, "busy")]).await;
+    let result = with_retry(2, Duration::from_millis(1), || async {
+      let response = reqwest::get(url.as_str()).await?;
+      Ok::<_, LearnerError>(response.error_for_status()?.text().await?)
+    })
+    .await;
+    assert!(matches!(result, Err(LearnerError::Network(_))));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+  }
+}
@@ -0,0 +1,558 @@
+//! Client implementation for fetching papers from Semantic Scholar.
+//!
+//! This module provides functionality to fetch paper metadata from the Semantic Scholar
+//! Graph API and convert it to the common [`Paper`] format. Papers are identified by their
+//! Semantic Scholar paper ID, a 40 character hexadecimal string such as
+//! `"204e3073870fae3d05bcbc2f6a8e263d9b72e776"`.
+//!
+//! The client uses the Graph API (https://api.semanticscholar.org/graph/v1) which is free to
+//! use without an API key, subject to a shared rate limit. Papers related to a given one are
+//! found with the Recommendations API (https://api.semanticscholar.org/recommendations/v1), see
+//! [`SemanticScholarClient::recommendations`] and [`Paper::find_related`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::clients::SemanticScholarClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SemanticScholarClient::new();
+//! let paper = client.fetch_paper("204e3073870fae3d05bcbc2f6a8e263d9b72e776").await?;
+//!
+//! println!("Title: {}", paper.title);
+//! println!("Authors: {}", paper.authors.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::NaiveDate;
+
+use super::*;
+
+/// Fields requested from the Graph API for each paper.
+const FIELDS: &str =
+  "paperId,title,abstract,authors,year,publicationDate,externalIds,openAccessPdf";
+
+/// The most papers the Recommendations API returns for one request.
+pub const MAX_RECOMMENDATIONS: usize = 500;
+
+/// Internal representation of a paper from the Graph API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct S2Paper {
+  /// Semantic Scholar paper ID
+  paper_id:         Option<String>,
+  /// Paper title
+  title:            String,
+  /// Paper abstract, which is not available for all papers
+  #[serde(rename = "abstract")]
+  abstract_text:    Option<String>,
+  /// List of paper authors
+  #[serde(default)]
+  authors:          Vec<S2Author>,
+  /// Year of publication, used when the full date is unknown
+  year:             Option<i32>,
+  /// Publication date in `YYYY-MM-DD` format, if known
+  publication_date: Option<String>,
+  /// Identifiers of the paper in other systems (DOI, arXiv, ...)
+  external_ids:     Option<S2ExternalIds>,
+  /// Location of an open-access PDF, if one is known
+  open_access_pdf:  Option<S2OpenAccessPdf>,
+}
+
+/// Author information from the Graph API.
+#[derive(Debug, Deserialize)]
+struct S2Author {
+  /// Author's full name
+  name: String,
+}
+
+/// External identifiers of a paper.
+#[derive(Debug, Deserialize)]
+struct S2ExternalIds {
+  /// The paper's DOI, if it has one
+  #[serde(rename = "DOI")]
+  doi: Option<String>,
+}
+
+/// Open-access PDF information.
+#[derive(Debug, Deserialize)]
+struct S2OpenAccessPdf {
+  /// Direct URL to the PDF
+  url: Option<String>,
+}
+
+/// A paper's ID, as returned by the Graph API when only `paperId` is requested.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct S2PaperId {
+  /// Semantic Scholar paper ID
+  paper_id: String,
+}
+
+/// A response from the Recommendations API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct S2Recommendations {
+  /// The recommended papers, most related first
+  recommended_papers: Vec<S2Paper>,
+}
+
+/// Client for fetching papers from the Semantic Scholar Graph API.
+///
+/// This client provides methods to fetch paper metadata by Semantic Scholar paper ID. It
+/// handles the HTTP requests, JSON parsing, and conversion to the common [`Paper`] format.
+///
+/// The public API is rate limited. Rate limited requests are retried with backoff, and if the
+/// limit is still hit the client returns [`LearnerError::RateLimited`] rather than a generic
+/// API error, so callers can back off further.
+pub struct SemanticScholarClient {
+  /// Internal web client used to connect to the API.
+  client:   reqwest::Client,
+  /// The base URL of the API, under which the Graph and Recommendations APIs are found.
+  base_url: String,
+  /// Timeout and retry settings.
+  config:   ClientConfig,
+}
+
+impl SemanticScholarClient {
+  /// Creates a new Semantic Scholar client instance.
+  ///
+  /// Initializes an HTTP client for making requests to the Graph API.
+  pub fn new() -> Self { Self::new_with_config(ClientConfig::default()) }
+
+  /// Creates a new Semantic Scholar client with custom timeout and retry settings.
+  pub fn new_with_config(config: ClientConfig) -> Self {
+    Self { config, ..Self::with_client(config.http_client()) }
+  }
+
+  /// Creates a new Semantic Scholar client that sends its requests through `client`, e.g. one
+  /// configured once with a proxy, TLS roots, or default headers and shared across sources.
+  ///
+  /// Requests are limited by `client`'s own timeout and retried as by
+  /// [`ClientConfig::default`].
+  pub fn with_client(client: reqwest::Client) -> Self {
+    Self {
+      client,
+      base_url: "https://api.semanticscholar.org".to_string(),
+      config: ClientConfig::default(),
+    }
+  }
+
+  /// Sends requests to a different endpoint, such as a local test server, instead of
+  /// `https://api.semanticscholar.org`, keeping the other settings.
+  pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+    self.base_url = url.into();
+    self
+  }
+
+  /// Sets how many times to retry a request after a transient failure, keeping the other
+  /// settings. See [`ClientConfig::max_retries`].
+  pub fn with_retry(mut self, retries: u8) -> Self {
+    self.config.max_retries = retries.into();
+    self
+  }
+
+  /// Sets the maximum time a single request may take, keeping the other settings. See
+  /// [`ClientConfig::timeout`]. A request that takes longer fails with
+  /// [`LearnerError::Timeout`]. This builds a new HTTP client, replacing one given to
+  /// `with_client`.
+  pub fn with_timeout(self, timeout: Duration) -> Self {
+    Self {
+      base_url: self.base_url,
+      ..Self::new_with_config(ClientConfig { timeout, ..self.config })
+    }
+  }
+
+  /// Fetches paper metadata from Semantic Scholar using its paper ID.
+  ///
+  /// # Arguments
+  ///
+  /// * `identifier` - A Semantic Scholar paper ID (e.g.,
+  ///   "204e3073870fae3d05bcbc2f6a8e263d9b72e776")
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - A [`Paper`] with the fetched metadata
+  /// - A [`LearnerError`] if the fetch or parsing fails
+  ///
+  /// # Errors
+  ///
+  /// This function will return an error if:
+  /// - The network request fails
+  /// - The paper is not found
+  /// - The API rate limit has been reached ([`LearnerError::RateLimited`])
+  /// - The API response cannot be parsed
+  /// - No publication date or year is available
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::SemanticScholarClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let client = SemanticScholarClient::new();
+  /// let paper = client.fetch_paper("204e3073870fae3d05bcbc2f6a8e263d9b72e776").await?;
+  ///
+  /// if let Some(pdf_url) = paper.pdf_url {
+  ///   println!("Open-access PDF available at: {}", pdf_url);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn fetch_paper(&self, identifier: &str) -> Result<Paper, LearnerError> {
+    let url = format!("{}/graph/v1/paper/{}?fields={}", self.base_url, identifier, FIELDS);
+    debug!("Fetching from Semantic Scholar via: {url}");
+
+    let response =
+      send_with_retry(&self.config, "Semantic Scholar", || self.client.get(&url)).await?;
+    let status = response.status();
+    debug!("Semantic Scholar response status: {status}");
+
+    let text = response.text().await?;
+    trace!("Semantic Scholar response: {text}");
+
+    if !status.is_success() {
+      return Err(LearnerError::ApiError(format!("Semantic Scholar returned {status}: {text}")));
+    }
+
+    parse_paper(&text, identifier)
+  }
+
+  /// Finds papers related to a paper, as recommended by Semantic Scholar.
+  ///
+  /// # Arguments
+  ///
+  /// * `paper_id` - The paper, by Semantic Scholar paper ID or by an ID in another system the Graph
+  ///   API accepts, such as `"ARXIV:2301.07041"`, `"DOI:10.1145/1327452.1327492"` or
+  ///   `"PMID:31452104"` (see [`lookup_id`])
+  /// * `limit` - The most papers to return, at most [`MAX_RECOMMENDATIONS`]
+  ///
+  /// # Returns
+  ///
+  /// Returns the recommended papers, most related first, as [`Source::SemanticScholar`]
+  /// papers. Recommendations without a publication date are left out.
+  ///
+  /// # Errors
+  ///
+  /// This function will return an error if:
+  /// - The network request fails
+  /// - The paper is not known to Semantic Scholar ([`LearnerError::NotFound`])
+  /// - The API rate limit has been reached ([`LearnerError::RateLimited`])
+  /// - The API response cannot be parsed
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::SemanticScholarClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let client = SemanticScholarClient::new();
+  /// for paper in client.recommendations("ARXIV:1706.03762", 5).await? {
+  ///   println!("{}", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn recommendations(
+    &self,
+    paper_id: &str,
+    limit: usize,
+  ) -> Result<Vec<Paper>, LearnerError> {
+    if limit == 0 {
+      return Ok(Vec::new());
+    }
+    let paper_id = self.resolve_paper_id(paper_id).await?;
+
+    let url = format!(
+      "{}/recommendations/v1/papers/?fields={}&limit={}",
+      self.base_url,
+      FIELDS,
+      limit.min(MAX_RECOMMENDATIONS)
+    );
+    let body = serde_json::json!({ "positivePaperIds": [paper_id], "negativePaperIds": [] });
+    debug!("Fetching recommendations from Semantic Scholar via: {url}");
+
+    let response =
+      send_with_retry(&self.config, "Semantic Scholar", || self.client.post(&url).json(&body))
+        .await?;
+    let status = response.status();
+    debug!("Semantic Scholar response status: {status}");
+
+    let text = response.text().await?;
+    trace!("Semantic Scholar response: {text}");
+
+    if !status.is_success() {
+      return Err(LearnerError::ApiError(format!("Semantic Scholar returned {status}: {text}")));
+    }
+
+    parse_recommendations(&text, limit)
+  }
+
+  /// Looks up the Semantic Scholar paper ID of a paper given by an ID the Graph API accepts,
+  /// as the Recommendations API only takes Semantic Scholar paper IDs.
+  async fn resolve_paper_id(&self, paper_id: &str) -> Result<String, LearnerError> {
+    if is_paper_id(paper_id) {
+      return Ok(paper_id.to_string());
+    }
+    let url = format!("{}/graph/v1/paper/{}?fields=paperId", self.base_url, paper_id);
+    debug!("Looking up Semantic Scholar paper ID via: {url}");
+
+    let response =
+      send_with_retry(&self.config, "Semantic Scholar", || self.client.get(&url)).await?;
+    let status = response.status();
+    let text = response.text().await?;
+    trace!("Semantic Scholar response: {text}");
+
+    if !status.is_success() {
+      return Err(LearnerError::ApiError(format!("Semantic Scholar returned {status}: {text}")));
+    }
+    let found: S2PaperId = serde_json::from_str(&text)
+      .map_err(|e| LearnerError::ApiError(format!("Failed to parse JSON: {}", e)))?;
+    Ok(found.paper_id)
+  }
+}
+
+/// The ID the Graph API knows a paper from `source` by, such as `"ARXIV:2301.07041"` for an
+/// arXiv paper, or `None` for sources Semantic Scholar doesn't index by their own identifiers,
+/// such as IACR.
+///
+/// # Examples
+///
+/// ```
+/// use learner::{clients::semantic_scholar::lookup_id, paper::Source};
+///
+/// assert_eq!(lookup_id(&Source::Arxiv, "2301.07041v2").as_deref(), Some("ARXIV:2301.07041"));
+/// assert_eq!(
+///   lookup_id(&Source::DOI, "10.1145/1327452.1327492").as_deref(),
+///   Some("DOI:10.1145/1327452.1327492")
+/// );
+/// assert_eq!(lookup_id(&Source::IACR, "2023/123"), None);
+/// ```
+pub fn lookup_id(source: &Source, identifier: &str) -> Option<String> {
+  match source {
+    Source::SemanticScholar => Some(identifier.to_string()),
+    Source::Arxiv => Some(format!("ARXIV:{}", arxiv::split_version(identifier).0)),
+    Source::DOI | Source::BioRxiv => Some(format!("DOI:{identifier}")),
+    Source::PubMed => Some(format!("PMID:{identifier}")),
+    Source::IACR | Source::OpenAlex | Source::SSRN => None,
+  }
+}
+
+/// Whether `id` is a Semantic Scholar paper ID, a 40 character hexadecimal string.
+fn is_paper_id(id: &str) -> bool { id.len() == 40 && id.chars().all(|c| c.is_ascii_hexdigit()) }
+
+impl Default for SemanticScholarClient {
+  fn default() -> Self { Self::new() }
+}
+
+/// Parses a Graph API paper response into a [`Paper`].
+fn parse_paper(text: &str, identifier: &str) -> Result<Paper, LearnerError> {
+  let paper: S2Paper = serde_json::from_str(text)
+    .map_err(|e| LearnerError::ApiError(format!("Failed to parse JSON: {}", e)))?;
+  into_paper(paper, identifier)
+}
+
+/// Parses a Recommendations API response into at most `limit` papers, leaving out those
+/// without a paper ID or publication date.
+fn parse_recommendations(text: &str, limit: usize) -> Result<Vec<Paper>, LearnerError> {
+  let response: S2Recommendations = serde_json::from_str(text)
+    .map_err(|e| LearnerError::ApiError(format!("Failed to parse JSON: {}", e)))?;
+  Ok(
+    response
+      .recommended_papers
+      .into_iter()
+      .filter_map(|paper| {
+        let id = paper.paper_id.clone()?;
+        into_paper(paper, &id).map_err(|e| debug!("Skipping recommendation {id}: {e}")).ok()
+      })
+      .take(limit)
+      .collect(),
+  )
+}
+
+/// Converts a Graph API paper into a [`Paper`] identified by `identifier`.
+fn into_paper(paper: S2Paper, identifier: &str) -> Result<Paper, LearnerError> {
+  // Prefer the full publication date, falling back to January 1st of the publication year
+  let publication_date = paper
+    .publication_date
+    .as_deref()
+    .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+    .or_else(|| paper.year.and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1)))
+    .and_then(|date| date.and_hms_opt(0, 0, 0))
+    .map(|date| date.and_utc())
+    .ok_or_else(|| LearnerError::ApiError("No publication date found".to_string()))?;
+  let (abstract_text, raw_abstract) = clean_abstract(paper.abstract_text.unwrap_or_default());
+
+  Ok(Paper {
+    title: paper.title,
+    authors: paper
+      .authors
+      .into_iter()
+      .map(|author| Author { name: author.name, affiliation: None, email: None })
+      .collect(),
+    abstract_text,
+    raw_abstract,
+    publication_date,
+    source: Source::SemanticScholar,
+    source_identifier: identifier.to_string(),
+    pdf_url: paper.open_access_pdf.and_then(|pdf| pdf.url).filter(|url| !url.is_empty()),
+    doi: paper.external_ids.and_then(|ids| ids.doi),
+    version: None,
+    categories: Vec::new(),
+    keywords: Vec::new(),
+    tags: Vec::new(),
+    pdf_path: None,
+    reading_status: ReadingStatus::Unread,
+    added_at: None,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A trimmed Graph API response for "Attention is All you Need".
+  const PAPER_RESPONSE: &str = r#"{
+    "paperId": "204e3073870fae3d05bcbc2f6a8e263d9b72e776",
+    "externalIds": {"DBLP": "journals/corr/VaswaniSPUJGKP17", "ArXiv": "1706.03762", "DOI": "10.48550/arXiv.1706.03762"},
+    "title": "Attention is All you Need",
+    "abstract": "The dominant sequence transduction models are based on complex recurrent networks.",
+    "year": 2017,
+    "publicationDate": "2017-06-12",
+    "openAccessPdf": {"url": "https://arxiv.org/pdf/1706.03762", "status": "GREEN"},
+    "authors": [
+      {"authorId": "40348417", "name": "Ashish Vaswani"},
+      {"authorId": "1846258", "name": "Noam M. Shazeer"}
+    ]
+  }"#;
+
+  #[test]
+  fn test_parse_paper() {
+    let paper = parse_paper(PAPER_RESPONSE, "204e3073870fae3d05bcbc2f6a8e263d9b72e776").unwrap();
+
+    assert_eq!(paper.title, "Attention is All you Need");
+    assert_eq!(paper.authors.len(), 2);
+    assert_eq!(paper.authors[0].name, "Ashish Vaswani");
+    assert!(paper.abstract_text.starts_with("The dominant sequence"));
+    assert_eq!(paper.publication_date, Utc.with_ymd_and_hms(2017, 6, 12, 0, 0, 0).unwrap());
+    assert_eq!(paper.source, Source::SemanticScholar);
+    assert_eq!(paper.source_identifier, "204e3073870fae3d05bcbc2f6a8e263d9b72e776");
+    assert_eq!(paper.pdf_url.as_deref(), Some("https://arxiv.org/pdf/1706.03762"));
+    assert_eq!(paper.doi.as_deref(), Some("10.48550/arXiv.1706.03762"));
+  }
+
+  #[test]
+  fn test_parse_paper_missing_fields() {
+    let text = r#"{
+      "paperId": "0123456789abcdef0123456789abcdef01234567",
+      "title": "A Sparse Record",
+      "abstract": null,
+      "year": 2020,
+      "publicationDate": null,
+      "externalIds": null,
+      "openAccessPdf": null,
+      "authors": []
+    }"#;
+    let paper = parse_paper(text, "0123456789abcdef0123456789abcdef01234567").unwrap();
+
+    assert!(paper.abstract_text.is_empty());
+    assert_eq!(paper.publication_date, Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap());
+    assert_eq!(paper.pdf_url, None);
+    assert_eq!(paper.doi, None);
+
+    let undated = r#"{"title": "Undated", "authors": []}"#;
+    assert!(matches!(parse_paper(undated, "id"), Err(LearnerError::ApiError(_))));
+  }
+
+  /// A trimmed Recommendations API response.
+  const RECOMMENDATIONS_RESPONSE: &str = r#"{
+    "recommendedPapers": [
+      {
+        "paperId": "df2b0e26d0599ce3e70df8a9da02e51594e0e992",
+        "externalIds": {"ArXiv": "1810.04805", "DOI": "10.18653/v1/N19-1423"},
+        "title": "BERT: Pre-training of Deep Bidirectional Transformers for Language Understanding",
+        "abstract": null,
+        "year": 2019,
+        "publicationDate": "2019-06-01",
+        "openAccessPdf": null,
+        "authors": [{"authorId": "39172707", "name": "Jacob Devlin"}]
+      },
+      {
+        "paperId": "0123456789abcdef0123456789abcdef01234567",
+        "title": "An Undated Preprint",
+        "year": null,
+        "publicationDate": null,
+        "authors": []
+      },
+      {
+        "paperId": "9405cc0d6169988371b2755e573cc28650d14dfe",
+        "externalIds": {"ArXiv": "1910.10683"},
+        "title": "Exploring the Limits of Transfer Learning",
+        "year": 2019,
+        "publicationDate": null,
+        "authors": [{"authorId": "2402716", "name": "Colin Raffel"}]
+      }
+    ]
+  }"#;
+
+  #[tokio::test]
+  async fn test_recommendations() {
+    use std::sync::atomic::Ordering;
+
+    static RESPONSES: &[(u16, &str)] = &[
+      (200, r#"{"paperId": "204e3073870fae3d05bcbc2f6a8e263d9b72e776"}"#),
+      (200, RECOMMENDATIONS_RESPONSE),
+    ];
+    let (url, requests) = crate::clients::http::serve_responses(RESPONSES).await;
+    let client = SemanticScholarClient::new().with_base_url(url).with_retry(0);
+
+    let papers = client.recommendations("ARXIV:1706.03762", 5).await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    // The undated recommendation is left out
+    assert_eq!(papers.len(), 2);
+    assert!(papers[0].title.starts_with("BERT"));
+    assert_eq!(papers[0].source, Source::SemanticScholar);
+    assert_eq!(papers[0].source_identifier, "df2b0e26d0599ce3e70df8a9da02e51594e0e992");
+    assert_eq!(papers[0].doi.as_deref(), Some("10.18653/v1/N19-1423"));
+    assert_eq!(papers[1].publication_date, Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 0).unwrap());
+
+    // Semantic Scholar paper IDs need no lookup, and the limit is respected
+    let papers = client.recommendations("204e3073870fae3d05bcbc2f6a8e263d9b72e776", 1).await;
+    assert_eq!(papers.unwrap().len(), 1);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+    assert!(client.recommendations("ARXIV:1706.03762", 0).await.unwrap().is_empty());
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+    static NOT_FOUND: &[(u16, &str)] = &[(404, r#"{"error": "Paper not found"}"#)];
+    let (url, _) = crate::clients::http::serve_responses(NOT_FOUND).await;
+    let client = SemanticScholarClient::new().with_base_url(url).with_retry(0);
+    assert!(matches!(
+      client.recommendations("ARXIV:0000.00000", 5).await,
+      Err(LearnerError::NotFound)
+    ));
+  }
+
+  #[test]
+  fn test_lookup_id() {
+    assert_eq!(lookup_id(&Source::Arxiv, "2301.07041v2").as_deref(), Some("ARXIV:2301.07041"));
+    assert_eq!(lookup_id(&Source::Arxiv, "math/0601001").as_deref(), Some("ARXIV:math/0601001"));
+    assert_eq!(lookup_id(&Source::PubMed, "31452104").as_deref(), Some("PMID:31452104"));
+    assert_eq!(
+      lookup_id(&Source::BioRxiv, "10.1101/2020.03.20.000001").as_deref(),
+      Some("DOI:10.1101/2020.03.20.000001")
+    );
+    assert_eq!(lookup_id(&Source::SSRN, "4123456"), None);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_semantic_scholar_entry_fetch() {
+    let client = SemanticScholarClient::new();
+    let paper = client.fetch_paper("204e3073870fae3d05bcbc2f6a8e263d9b72e776").await.unwrap();
+
+    assert!(paper.title.to_lowercase().starts_with("attention is all you need"));
+    assert!(!paper.authors.is_empty());
+    assert_eq!(paper.source, Source::SemanticScholar);
+    assert_eq!(paper.source_identifier, "204e3073870fae3d05bcbc2f6a8e263d9b72e776");
+  }
+}
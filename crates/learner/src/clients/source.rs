@@ -0,0 +1,332 @@
+//! A common interface over paper sources, and a registry of them.
+//!
+//! Every client implements [`PaperSource`]: it recognizes the inputs it can handle and fetches
+//! papers for them. A [`SourceRegistry`] holds sources in order and fetches from the first one
+//! that recognizes an input. [`Paper::new`] consults the global registry, which holds the
+//! built-in sources followed by any registered with [`SourceRegistry::register`] (or
+//! [`Paper::register_client`]), so a custom source, e.g. an institutional repository, works
+//! wherever papers are fetched by input. Code generic over sources can take any
+//! `impl PaperSource`, and tell which [`Source`] its papers come from with
+//! [`PaperSource::source`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::{
+//!   clients::{PaperFuture, PaperSource, SourceRegistry},
+//!   paper::Paper,
+//! };
+//!
+//! struct Repository;
+//!
+//! impl PaperSource for Repository {
+//!   fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+//!     Box::pin(async move { todo!("fetch {id} from the repository") })
+//!   }
+//!
+//!   fn matches(&self, input: &str) -> Option<String> {
+//!     input.strip_prefix("repo:").map(str::to_string)
+//!   }
+//! }
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! SourceRegistry::register(Repository);
+//! let paper = Paper::new("repo:1234").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+  future::Future,
+  pin::Pin,
+  sync::{Arc, RwLock},
+};
+
+use lazy_static::lazy_static;
+
+use super::*;
+use crate::paper::detect_source;
+
+/// The future returned by [`PaperSource::fetch_paper`].
+pub type PaperFuture<'a> = Pin<Box<dyn Future<Output = Result<Paper, LearnerError>> + Send + 'a>>;
+
+/// A system papers can be fetched from, such as arXiv or an institutional repository.
+pub trait PaperSource: Send + Sync {
+  /// Fetches the paper with the given identifier, as returned by [`PaperSource::matches`].
+  fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a>;
+
+  /// Recognizes an input for this source, such as a URL or identifier, returning the
+  /// identifier to fetch it by, or `None` if the input is not for this source.
+  fn matches(&self, input: &str) -> Option<String>;
+
+  /// The [`Source`] of the papers this source fetches, or `None` for sources without one of
+  /// their own, such as most custom sources.
+  ///
+  /// This is an `Option` because [`Source`] is a closed enum of the built-in sources: a custom
+  /// source such as an institutional repository has no variant to report, and shouldn't have to
+  /// pretend to be one of them.
+  fn source(&self) -> Option<Source> { None }
+}
+
+/// [`PaperSource`] under the name it was first proposed with.
+pub use self::PaperSource as SourceClient;
+
+lazy_static! {
+  /// Sources consulted by [`Paper::new`].
+  static ref GLOBAL: RwLock<SourceRegistry> = RwLock::new(SourceRegistry::default());
+}
+
+/// An ordered list of [`PaperSource`]s, fetching each input from the first that recognizes it.
+///
+/// The default registry holds the built-in sources, which recognize the same inputs as
+/// [`Paper::new`] always has. Built-in sources never claim the same input, so sources added
+/// after them only see inputs none of them recognizes.
+#[derive(Clone)]
+pub struct SourceRegistry {
+  /// The sources, in the order they are consulted.
+  sources: Vec<Arc<dyn PaperSource>>,
+}
+
+impl SourceRegistry {
+  /// Creates a registry without any sources.
+  pub fn new() -> Self { Self { sources: Vec::new() } }
+
+  /// Adds a source, to be consulted after those already in the registry.
+  pub fn push(&mut self, source: impl PaperSource + 'static) -> &mut Self {
+    self.sources.push(Arc::new(source));
+    self
+  }
+
+  /// Adds a source to the global registry consulted by [`Paper::new`], after the built-in
+  /// sources and any registered before it.
+  pub fn register(source: impl PaperSource + 'static) {
+    GLOBAL.write().expect("source registry lock is never poisoned").push(source);
+  }
+
+  /// Returns a copy of the global registry consulted by [`Paper::new`].
+  pub fn global() -> Self { GLOBAL.read().expect("source registry lock is never poisoned").clone() }
+
+  /// Finds the first source that recognizes `input`.
+  ///
+  /// # Returns
+  ///
+  /// The source and the identifier to fetch from it, or `None` if no source recognizes the
+  /// input.
+  pub fn resolve(&self, input: &str) -> Option<(Arc<dyn PaperSource>, String)> {
+    self.sources.iter().find_map(|source| source.matches(input).map(|id| (Arc::clone(source), id)))
+  }
+
+  /// Fetches a paper from the first source that recognizes `input`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::InvalidIdentifier`] if no source recognizes the input, and
+  /// otherwise whatever error the source's fetch fails with.
+  pub async fn fetch_paper(&self, input: &str) -> Result<Paper, LearnerError> {
+    let (source, id) = self.resolve(input).ok_or(LearnerError::InvalidIdentifier)?;
+    source.fetch_paper(&id).await
+  }
+}
+
+impl Default for SourceRegistry {
+  fn default() -> Self {
+    let mut registry = Self::new();
+    registry
+      .push(ArxivClient::new())
+      .push(IACRClient::new())
+      .push(DOIClient::new())
+      .push(SemanticScholarClient::new())
+      .push(PubMedClient::new())
+      .push(BioRxivClient::new())
+      .push(OpenAlexClient::new())
+      .push(SSRNClient::new())
+      .push(IEEEClient::new());
+    registry
+  }
+}
+
+/// Recognizes the inputs [`Paper::new`] has always routed to `source`.
+fn builtin_match(input: &str, source: Source) -> Option<String> {
+  detect_source(input).filter(|(detected, _)| *detected == source).map(|(_, id)| id)
+}
+
+impl PaperSource for ArxivClient {
+  fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+    Box::pin(ArxivClient::fetch_paper(self, id))
+  }
+
+  fn matches(&self, input: &str) -> Option<String> { builtin_match(input, Source::Arxiv) }
+
+  fn source(&self) -> Option<Source> { Some(Source::Arxiv) }
+}
+
+impl PaperSource for IACRClient {
+  fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+    Box::pin(IACRClient::fetch_paper(self, id))
+  }
+
+  fn matches(&self, input: &str) -> Option<String> { builtin_match(input, Source::IACR) }
+
+  fn source(&self) -> Option<Source> { Some(Source::IACR) }
+}
+
+impl PaperSource for DOIClient {
+  fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+    Box::pin(DOIClient::fetch_paper(self, id))
+  }
+
+  fn matches(&self, input: &str) -> Option<String> { builtin_match(input, Source::DOI) }
+
+  fn source(&self) -> Option<Source> { Some(Source::DOI) }
+}
+
+impl PaperSource for SemanticScholarClient {
+  fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+    Box::pin(SemanticScholarClient::fetch_paper(self, id))
+  }
+
+  fn matches(&self, input: &str) -> Option<String> { builtin_match(input, Source::SemanticScholar) }
+
+  fn source(&self) -> Option<Source> { Some(Source::SemanticScholar) }
+}
+
+impl PaperSource for PubMedClient {
+  fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+    Box::pin(PubMedClient::fetch_paper(self, id))
+  }
+
+  fn matches(&self, input: &str) -> Option<String> { builtin_match(input, Source::PubMed) }
+
+  fn source(&self) -> Option<Source> { Some(Source::PubMed) }
+}
+
+impl PaperSource for BioRxivClient {
+  fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+    Box::pin(BioRxivClient::fetch_paper(self, id))
+  }
+
+  fn matches(&self, input: &str) -> Option<String> { builtin_match(input, Source::BioRxiv) }
+
+  fn source(&self) -> Option<Source> { Some(Source::BioRxiv) }
+}
+
+impl PaperSource for OpenAlexClient {
+  fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+    Box::pin(OpenAlexClient::fetch_paper(self, id))
+  }
+
+  fn matches(&self, input: &str) -> Option<String> { builtin_match(input, Source::OpenAlex) }
+
+  fn source(&self) -> Option<Source> { Some(Source::OpenAlex) }
+}
+
+impl PaperSource for SSRNClient {
+  fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+    Box::pin(SSRNClient::fetch_paper(self, id))
+  }
+
+  fn matches(&self, input: &str) -> Option<String> { builtin_match(input, Source::SSRN) }
+
+  fn source(&self) -> Option<Source> { Some(Source::SSRN) }
+}
+
+impl PaperSource for IEEEClient {
+  fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+    Box::pin(IEEEClient::fetch_paper(self, id))
+  }
+
+  // Bare article numbers can't be told apart from PubMed IDs, so only URLs are recognized
+  fn matches(&self, input: &str) -> Option<String> {
+    url::Url::parse(input).ok().and_then(|_| ieee::article_number(input))
+  }
+
+  // Papers are fetched through their DOI
+  fn source(&self) -> Option<Source> { Some(Source::DOI) }
+}
+
+impl PaperSource for crate::cache::CachingArxivClient {
+  fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+    Box::pin(crate::cache::CachingArxivClient::fetch_paper(self, id))
+  }
+
+  fn matches(&self, input: &str) -> Option<String> { builtin_match(input, Source::Arxiv) }
+
+  fn source(&self) -> Option<Source> { Some(Source::Arxiv) }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Fetches `id` through any client, written against the [`SourceClient`] name.
+  async fn fetch_with(client: &impl SourceClient, id: &str) -> Result<Paper, LearnerError> {
+    client.fetch_paper(id).await
+  }
+
+  /// A source for inputs like `inst:42`, returning a fixed paper without any network access.
+  struct InstitutionSource;
+
+  impl PaperSource for InstitutionSource {
+    fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+      Box::pin(async move {
+        Ok(Paper {
+          title:             format!("Institutional report {id}"),
+          authors:           Vec::new(),
+          abstract_text:     String::new(),
+          raw_abstract:      None,
+          publication_date:  Utc::now(),
+          source:            Source::DOI,
+          source_identifier: id.to_string(),
+          pdf_url:           None,
+          doi:               None,
+          version:           None,
+          categories:        Vec::new(),
+          keywords:          Vec::new(),
+          tags:              Vec::new(),
+          pdf_path:          None,
+          reading_status:    ReadingStatus::Unread,
+          added_at:          None,
+        })
+      })
+    }
+
+    fn matches(&self, input: &str) -> Option<String> {
+      input.strip_prefix("inst:").map(str::to_string)
+    }
+  }
+
+  #[tokio::test]
+  async fn test_custom_source() {
+    let mut registry = SourceRegistry::default();
+    assert!(registry.resolve("inst:42").is_none());
+    registry.push(InstitutionSource);
+
+    let paper = registry.fetch_paper("inst:42").await.unwrap();
+    assert_eq!(paper.title, "Institutional report 42");
+    assert_eq!(paper.source_identifier, "42");
+    assert_eq!(fetch_with(&InstitutionSource, "42").await.unwrap().title, paper.title);
+
+    // Built-in sources still recognize what they always have
+    let (_, id) = registry.resolve("https://arxiv.org/abs/2301.07041v2").unwrap();
+    assert_eq!(id, "2301.07041v2");
+    let (_, id) = registry.resolve("https://ieeexplore.ieee.org/document/771073").unwrap();
+    assert_eq!(id, "771073");
+    assert!(matches!(
+      registry.fetch_paper("not an identifier").await,
+      Err(LearnerError::InvalidIdentifier)
+    ));
+
+    // Built-in sources report their source, custom ones need not
+    let (source, _) = registry.resolve("2301.07041").unwrap();
+    assert_eq!(source.source(), Some(Source::Arxiv));
+    let (source, _) = registry.resolve("https://ieeexplore.ieee.org/document/771073").unwrap();
+    assert_eq!(source.source(), Some(Source::DOI));
+    let (source, _) = registry.resolve("inst:42").unwrap();
+    assert_eq!(source.source(), None);
+
+    // Registered globally, the source is consulted by `Paper::new`
+    Paper::register_client(InstitutionSource);
+    let paper = Paper::new("inst:7").await.unwrap();
+    assert_eq!(paper.title, "Institutional report 7");
+  }
+}
@@ -0,0 +1,352 @@
+//! Client implementation for fetching papers from SSRN.
+//!
+//! This module provides functionality to fetch paper metadata from SSRN (https://ssrn.com), the
+//! preprint repository for economics, law and the social sciences, and convert it to the common
+//! [`Paper`] format. SSRN has no public API, so metadata is read from the citation `<meta>`
+//! tags (`citation_title`, `citation_author`, `citation_online_date`, ...) of each paper's
+//! landing page. Papers are identified by their numeric abstract ID, such as `"4123456"`.
+//!
+//! SSRN only serves PDFs through its download page, which may require signing in, so papers
+//! from SSRN have no PDF URL unless the landing page names one directly.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::clients::SSRNClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SSRNClient::new();
+//! let paper = client.fetch_paper("https://ssrn.com/abstract=4123456").await?;
+//!
+//! println!("Title: {}", paper.title);
+//! println!("Authors: {}", paper.authors.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::NaiveDate;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::*;
+use crate::paper::detect_source;
+
+lazy_static! {
+  /// A `<meta>` tag.
+  static ref META_TAG: Regex = Regex::new(r"(?i)<meta\s[^>]*>").unwrap();
+  /// An attribute within a tag, with its value in single or double quotes.
+  static ref ATTRIBUTE: Regex =
+    Regex::new(r#"([\w:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+  /// An SSRN abstract ID.
+  static ref ABSTRACT_ID: Regex = Regex::new(r"^\d+$").unwrap();
+}
+
+/// Client for fetching papers from SSRN landing pages.
+///
+/// This client fetches a paper's landing page, reads its citation metadata, and converts it to
+/// the common [`Paper`] format.
+pub struct SSRNClient {
+  /// Internal web client used to connect to SSRN.
+  client:   reqwest::Client,
+  /// The base URL to use for the client.
+  base_url: String,
+  /// Timeout and retry settings.
+  config:   ClientConfig,
+}
+
+impl SSRNClient {
+  /// Creates a new SSRN client instance.
+  ///
+  /// Initializes an HTTP client for fetching SSRN landing pages.
+  pub fn new() -> Self { Self::new_with_config(ClientConfig::default()) }
+
+  /// Creates a new SSRN client with custom timeout and retry settings.
+  pub fn new_with_config(config: ClientConfig) -> Self {
+    Self { config, ..Self::with_client(config.http_client()) }
+  }
+
+  /// Creates a new SSRN client that sends its requests through `client`, e.g. one configured
+  /// once with a proxy, TLS roots, or default headers and shared across sources.
+  ///
+  /// Requests are limited by `client`'s own timeout and retried as by
+  /// [`ClientConfig::default`].
+  pub fn with_client(client: reqwest::Client) -> Self {
+    Self {
+      client,
+      base_url: "https://papers.ssrn.com/sol3/papers.cfm".to_string(),
+      config: ClientConfig::default(),
+    }
+  }
+
+  /// Sends requests to a different endpoint, such as a local test server, instead of
+  /// `https://papers.ssrn.com/sol3/papers.cfm`, keeping the other settings.
+  pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+    self.base_url = url.into();
+    self
+  }
+
+  /// Sets how many times to retry a request after a transient failure, keeping the other
+  /// settings. See [`ClientConfig::max_retries`].
+  pub fn with_retry(mut self, retries: u8) -> Self {
+    self.config.max_retries = retries.into();
+    self
+  }
+
+  /// Sets the maximum time a single request may take, keeping the other settings. See
+  /// [`ClientConfig::timeout`]. A request that takes longer fails with
+  /// [`LearnerError::Timeout`]. This builds a new HTTP client, replacing one given to
+  /// `with_client`.
+  pub fn with_timeout(self, timeout: Duration) -> Self {
+    Self {
+      base_url: self.base_url,
+      ..Self::new_with_config(ClientConfig { timeout, ..self.config })
+    }
+  }
+
+  /// Fetches paper metadata from SSRN.
+  ///
+  /// # Arguments
+  ///
+  /// * `identifier` - One of:
+  ///   - An SSRN abstract ID (e.g., "4123456"), optionally prefixed with `ssrn:`
+  ///   - An SSRN URL (e.g., "https://ssrn.com/abstract=4123456" or
+  ///     "https://papers.ssrn.com/sol3/papers.cfm?abstract_id=4123456")
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - A [`Paper`] with the fetched metadata. Its `pdf_url` is `None` unless the landing page links
+  ///   a PDF directly
+  /// - A [`LearnerError`] if the fetch or parsing fails
+  ///
+  /// # Errors
+  ///
+  /// This function will return an error if:
+  /// - The identifier is not an SSRN abstract ID or URL
+  /// - The network request fails, or SSRN answers with an error status
+  /// - The landing page has no title or online date in its citation metadata
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::clients::SSRNClient;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let client = SSRNClient::new();
+  /// let paper = client.fetch_paper("4123456").await?;
+  /// println!("SSRN paper: {}", paper.title);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn fetch_paper(&self, identifier: &str) -> Result<Paper, LearnerError> {
+    let id = abstract_id(identifier).ok_or(LearnerError::InvalidIdentifier)?;
+    let url = format!("{}?abstract_id={id}", self.base_url);
+    debug!("Fetching from SSRN via: {url}");
+
+    let response = send_with_retry(&self.config, "SSRN", || self.client.get(&url)).await?;
+    let status = response.status();
+    debug!("SSRN response status: {status}");
+
+    let text = response.text().await?;
+    trace!("SSRN response: {text}");
+
+    if !status.is_success() {
+      return Err(LearnerError::ApiError(format!("SSRN returned {status}")));
+    }
+    parse_landing_page(&text, &id)
+  }
+}
+
+impl Default for SSRNClient {
+  fn default() -> Self { Self::new() }
+}
+
+/// Extracts the abstract ID from anything [`SSRNClient::fetch_paper`] accepts.
+fn abstract_id(identifier: &str) -> Option<String> {
+  let identifier = identifier.trim();
+  if ABSTRACT_ID.is_match(identifier) {
+    return Some(identifier.to_string());
+  }
+  match detect_source(identifier) {
+    Some((Source::SSRN, id)) => Some(id),
+    _ => None,
+  }
+}
+
+/// Reads the `<meta>` tags of an HTML page as `(name, content)` pairs, in page order.
+///
+/// Tags are named by their `name` or, as for Open Graph tags, `property` attribute, and their
+/// content has its character references decoded.
+fn meta_tags(html: &str) -> Vec<(String, String)> {
+  META_TAG
+    .find_iter(html)
+    .filter_map(|tag| {
+      let (mut name, mut content) = (None, None);
+      for attribute in ATTRIBUTE.captures_iter(tag.as_str()) {
+        let value = attribute.get(2).or(attribute.get(3)).map_or("", |value| value.as_str());
+        match attribute[1].to_lowercase().as_str() {
+          "name" | "property" => name = Some(value.to_lowercase()),
+          "content" => content = Some(format::decode_entities(value).trim().to_string()),
+          _ => {},
+        }
+      }
+      Some((name?, content?))
+    })
+    .collect()
+}
+
+/// Converts the citation metadata of an SSRN landing page into a [`Paper`].
+fn parse_landing_page(html: &str, id: &str) -> Result<Paper, LearnerError> {
+  let tags = meta_tags(html);
+  let first = |names: &[&str]| {
+    names.iter().find_map(|name| {
+      tags
+        .iter()
+        .find(|(tag, content)| tag == name && !content.is_empty())
+        .map(|(_, content)| content.clone())
+    })
+  };
+
+  let title = first(&["citation_title"])
+    .map(|title| format::clean_latex(&title))
+    .ok_or_else(|| LearnerError::ApiError("No title found".to_string()))?;
+
+  // SSRN writes dates like 2022/05/31
+  let publication_date = first(&["citation_online_date", "citation_publication_date"])
+    .and_then(|date| {
+      NaiveDate::parse_from_str(&date, "%Y/%m/%d")
+        .or_else(|_| NaiveDate::parse_from_str(&date, "%Y-%m-%d"))
+        .ok()
+    })
+    .and_then(|date| date.and_hms_opt(0, 0, 0))
+    .map(|date| date.and_utc())
+    .ok_or_else(|| LearnerError::ApiError("No publication date found".to_string()))?;
+
+  // Authors are listed as "Last, First"
+  let authors = tags
+    .iter()
+    .filter(|(name, content)| name == "citation_author" && !content.is_empty())
+    .map(|(_, name)| Author {
+      name:        match name.split_once(',') {
+        Some((last, first)) if !first.trim().is_empty() =>
+          format!("{} {}", first.trim(), last.trim()),
+        _ => name.clone(),
+      },
+      affiliation: None,
+      email:       None,
+    })
+    .collect();
+
+  let keywords = first(&["citation_keywords"])
+    .map(|keywords| {
+      keywords
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|keyword| !keyword.is_empty())
+        .map(str::to_string)
+        .collect()
+    })
+    .unwrap_or_default();
+  let (abstract_text, raw_abstract) = clean_abstract(
+    first(&["citation_abstract", "og:description", "description"]).unwrap_or_default(),
+  );
+
+  Ok(Paper {
+    title,
+    authors,
+    abstract_text,
+    raw_abstract,
+    publication_date,
+    source: Source::SSRN,
+    source_identifier: id.to_string(),
+    pdf_url: first(&["citation_pdf_url"]),
+    doi: first(&["citation_doi"]),
+    version: None,
+    categories: Vec::new(),
+    keywords,
+    tags: Vec::new(),
+    pdf_path: None,
+    reading_status: ReadingStatus::Unread,
+    added_at: None,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clients::http::serve_responses;
+
+  /// A trimmed SSRN landing page.
+  const LANDING_PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Law &amp; Economics of Platforms by Jane Roe, John Q. Public :: SSRN</title>
+  <meta name="citation_title" content="Law &amp; Economics of Platforms">
+  <meta name="citation_author" content="Roe, Jane">
+  <meta name="citation_author" content="Public, John Q.">
+  <meta name="citation_author" content="OECD">
+  <meta name="citation_online_date" content="2022/05/31">
+  <meta name="citation_publication_date" content="2022/06/15">
+  <meta name="citation_doi" content="10.2139/ssrn.4123456">
+  <meta name="citation_keywords" content="platforms, antitrust; regulation">
+  <meta property="og:description" content="We study   platform &lt;b&gt;markets&lt;/b&gt;.">
+  <meta name='description' content='A shorter summary.'>
+</head>
+<body></body>
+</html>"#;
+
+  #[test]
+  fn test_parse_landing_page() {
+    let paper = parse_landing_page(LANDING_PAGE, "4123456").unwrap();
+
+    assert_eq!(paper.title, "Law & Economics of Platforms");
+    let authors: Vec<_> = paper.authors.iter().map(|author| author.name.as_str()).collect();
+    assert_eq!(authors, ["Jane Roe", "John Q. Public", "OECD"]);
+    assert_eq!(paper.publication_date, Utc.with_ymd_and_hms(2022, 5, 31, 0, 0, 0).unwrap());
+    assert_eq!(paper.abstract_text, "We study platform markets.");
+    assert_eq!(paper.source, Source::SSRN);
+    assert_eq!(paper.source_identifier, "4123456");
+    assert_eq!(paper.doi.as_deref(), Some("10.2139/ssrn.4123456"));
+    assert_eq!(paper.keywords, ["platforms", "antitrust", "regulation"]);
+    assert_eq!(paper.pdf_url, None);
+
+    let untitled = LANDING_PAGE.replace("citation_title", "og:title");
+    assert!(matches!(parse_landing_page(&untitled, "4123456"), Err(LearnerError::ApiError(_))));
+  }
+
+  #[test]
+  fn test_abstract_id() {
+    assert_eq!(abstract_id("4123456").as_deref(), Some("4123456"));
+    assert_eq!(abstract_id("ssrn:4123456").as_deref(), Some("4123456"));
+    assert_eq!(abstract_id("https://ssrn.com/abstract=4123456").as_deref(), Some("4123456"));
+    assert_eq!(
+      abstract_id("https://papers.ssrn.com/sol3/papers.cfm?abstract_id=4123456").as_deref(),
+      Some("4123456")
+    );
+    assert_eq!(abstract_id("2301.07041"), None);
+  }
+
+  #[tokio::test]
+  async fn test_fetch_paper_from_landing_page() {
+    static RESPONSES: &[(u16, &str)] = &[(200, LANDING_PAGE), (404, "Not Found")];
+    let (url, _) = serve_responses(RESPONSES).await;
+    let client = SSRNClient::new().with_base_url(url).with_retry(0);
+
+    let paper = client.fetch_paper("https://ssrn.com/abstract=4123456").await.unwrap();
+    assert_eq!(paper.title, "Law & Economics of Platforms");
+    assert!(matches!(client.fetch_paper("4123457").await, Err(LearnerError::NotFound)));
+    assert!(matches!(client.fetch_paper("not an id").await, Err(LearnerError::InvalidIdentifier)));
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_ssrn_entry_fetch() {
+    let client = SSRNClient::new();
+    let paper = client.fetch_paper("4123456").await.unwrap();
+
+    assert!(!paper.title.is_empty());
+    assert!(!paper.authors.is_empty());
+    assert_eq!(paper.source, Source::SSRN);
+    assert_eq!(paper.source_identifier, "4123456");
+  }
+}
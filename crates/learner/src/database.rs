@@ -30,12 +30,227 @@
 //! # }
 //! ```
 
-use std::path::Path;
+use std::{
+  collections::{BTreeMap, HashMap},
+  path::Path,
+  time::{Duration, Instant},
+};
 
-use rusqlite::params;
+use rusqlite::{params, types::Value, OptionalExtension};
 use tokio_rusqlite::Connection;
+use tracing::warn;
 
 use super::*;
+use crate::{
+  paper::{
+    fetch_from_source, to_bibtex_bibliography, to_html_document, to_markdown_document,
+    to_ris_records, validate_pdf, PaperGrouping, DEFAULT_FILENAME_TEMPLATE,
+  },
+  progress::{report, ProgressSink},
+};
+
+/// Searches taking at least this long are logged as warnings.
+pub const SLOW_QUERY_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// Title similarity from which [`Database::find_duplicates`] takes two papers to be the same.
+pub const DEFAULT_DUPLICATE_THRESHOLD: f64 = 0.85;
+
+/// Number of papers processed per transaction by bulk operations.
+const BATCH_SIZE: usize = 100;
+
+/// Progress stage reported by [`Database::import_papers`].
+const IMPORT_STAGE: &str = "importing papers";
+
+/// Progress stage reported by [`Database::export_papers`].
+const EXPORT_STAGE: &str = "exporting papers";
+
+/// Adds papers, with their author names, notes and abstracts, to the search index, a page at a time
+/// (`?1` papers from offset `?2`).
+const INDEX_PAPERS_SQL: &str = "INSERT INTO papers_fts(rowid, title, authors, notes, \
+                                abstract_text)
+  SELECT id, title,
+         coalesce((SELECT group_concat(name, ' ') FROM authors WHERE paper_id = papers.id), ''),
+         coalesce((SELECT group_concat(content, ' ') FROM notes WHERE paper_id = papers.id), ''),
+         abstract_text
+  FROM papers ORDER BY id LIMIT ?1 OFFSET ?2";
+
+/// Progress stage reported by [`Database::find_duplicates`], after loading the papers under
+/// the [`Database::export_papers`] stage.
+const DUPLICATES_STAGE: &str = "comparing titles";
+
+/// Progress stage reported by [`Database::rebuild_search_index`].
+const REINDEX_STAGE: &str = "rebuilding search index";
+
+/// First progress stage reported by [`Database::collect_garbage`].
+const GC_FILES_STAGE: &str = "checking files";
+
+/// Second progress stage reported by [`Database::collect_garbage`].
+const GC_COMPACT_STAGE: &str = "compacting database";
+
+/// Field by which [`Database::list_papers`] orders papers.
+///
+/// Parsing from a string accepts `title`, `date`, and `source`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PaperSort {
+  /// Alphabetically by title, ignoring case
+  Title,
+  /// By publication date
+  #[default]
+  Date,
+  /// Grouped by source, then by source identifier
+  Source,
+}
+
+impl PaperSort {
+  /// The direction this field is most naturally listed in: newest first for dates,
+  /// alphabetically otherwise.
+  pub fn default_ascending(&self) -> bool { !matches!(self, PaperSort::Date) }
+
+  /// Returns the SQL `ORDER BY` expression for this sort order.
+  ///
+  /// Every order ends with a unique column so pages never overlap.
+  fn order_by(&self, ascending: bool) -> String {
+    let direction = if ascending { "ASC" } else { "DESC" };
+    match self {
+      PaperSort::Title => format!("title COLLATE NOCASE {direction}, id {direction}"),
+      PaperSort::Date => format!("publication_date {direction}, id {direction}"),
+      PaperSort::Source => format!("source {direction}, source_identifier {direction}"),
+    }
+  }
+
+  /// Sorts papers already loaded from the database, e.g. search results, in the same order
+  /// [`Database::list_papers`] would list them. Papers that tie keep their order.
+  pub fn sort_papers(&self, papers: &mut [Paper], ascending: bool) {
+    papers.sort_by(|a, b| {
+      let order = match self {
+        PaperSort::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+        PaperSort::Date => a.publication_date.cmp(&b.publication_date),
+        PaperSort::Source =>
+          (a.source.as_str(), &a.source_identifier).cmp(&(b.source.as_str(), &b.source_identifier)),
+      };
+      if ascending {
+        order
+      } else {
+        order.reverse()
+      }
+    });
+  }
+}
+
+impl FromStr for PaperSort {
+  type Err = LearnerError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match &s.to_lowercase() as &str {
+      "title" => Ok(PaperSort::Title),
+      "date" => Ok(PaperSort::Date),
+      "source" => Ok(PaperSort::Source),
+      s => Err(LearnerError::InvalidSort(s.to_owned())),
+    }
+  }
+}
+
+/// A paper's reading status and when it changed, as returned by
+/// [`Database::get_reading_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadingProgress {
+  /// How far along reading the paper is
+  pub status:      ReadingStatus,
+  /// When the paper was last marked as [`ReadingStatus::Reading`], unless it is unread
+  pub started_at:  Option<DateTime<Utc>>,
+  /// When the paper was marked as [`ReadingStatus::Read`], if it is read
+  pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// A note on a paper, as returned by [`Database::add_note`] and [`Database::get_notes`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Note {
+  /// The note's database ID, used to delete it
+  pub id:         i64,
+  /// The text of the note, typically Markdown
+  pub content:    String,
+  /// When the note was added
+  pub created_at: DateTime<Utc>,
+}
+
+/// An author and how many papers by them are in the database, as returned by
+/// [`Database::list_authors`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuthorCount {
+  /// The author's name, as first saved
+  pub name:        String,
+  /// Number of papers by the author
+  pub paper_count: usize,
+}
+
+/// A named group of papers, as returned by [`Database::create_collection`] and
+/// [`Database::list_collections`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Collection {
+  /// The collection's database ID
+  pub id:          i64,
+  /// The collection's name, unique regardless of case, e.g. `"Cryptography"`
+  pub name:        String,
+  /// What the collection is for, if given
+  pub description: Option<String>,
+  /// Number of papers in the collection
+  pub paper_count: usize,
+}
+
+/// A category whose newly submitted papers the daemon saves, as returned by
+/// [`Database::list_watch_targets`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WatchTarget {
+  /// The category in its source, e.g. `"cs.CR"` for arXiv
+  pub category:     String,
+  /// The source system the category belongs to
+  pub source:       Source,
+  /// When the category's new papers were last fetched, or `None` if they never were
+  pub last_checked: Option<DateTime<Utc>>,
+}
+
+/// The result of a [`Database::import_papers`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+  /// Number of papers newly saved to the database
+  pub imported: usize,
+  /// Number of papers skipped because they, or a paper with the same DOI, were already in the
+  /// database
+  pub skipped:  usize,
+}
+
+/// The state of a paper's downloaded PDF, as checked by [`Database::verify_pdfs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdfStatus {
+  /// The file is a complete PDF
+  Valid,
+  /// The file was recorded as downloaded but no longer exists
+  Missing,
+  /// The file is not a complete PDF (see [`validate_pdf`]), e.g. a saved error page or an
+  /// interrupted download
+  Invalid,
+}
+
+/// Summary counts over the papers in the database, as returned by
+/// [`Database::get_statistics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DatabaseStats {
+  /// Number of papers in the database
+  pub total_papers:    usize,
+  /// Number of distinct authors across all papers, telling names apart like
+  /// [`Database::list_authors`]
+  pub total_authors:   usize,
+  /// Number of papers from each source. Sources without papers are absent.
+  pub by_source:       HashMap<Source, usize>,
+  /// Number of papers published in each year
+  pub by_year:         BTreeMap<i32, usize>,
+  /// Number of papers added to the database in each month, keyed like `"2024-01"`
+  pub added_by_month:  BTreeMap<String, usize>,
+  /// Number of papers whose PDF has been downloaded successfully
+  pub papers_with_pdf: usize,
+  /// Size of the database file in bytes, not counting downloaded PDFs
+  pub database_size:   u64,
+}
 
 /// Handle for interacting with the paper database.
 ///
@@ -50,6 +265,122 @@ pub struct Database {
   conn: Connection,
 }
 
+/// A full-text search over the paper database.
+///
+/// The query describes both which papers match and which page of the matches to return. The
+/// same filter is used to count the total number of matches, so paging through results never
+/// changes the reported total.
+///
+/// # Examples
+///
+/// ```
+/// use learner::database::SearchQuery;
+///
+/// // First 20 papers mentioning "neural"
+/// let query = SearchQuery::new("neural").limit(20);
+///
+/// // The next 20
+/// let query = query.offset(20);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+  /// The search text using FTS5 syntax
+  pub text:   String,
+  /// Maximum number of papers to return, or `None` for all matches
+  pub limit:  Option<usize>,
+  /// Number of matches to skip before returning papers
+  pub offset: usize,
+  /// Only match papers published at or after this time
+  pub from:   Option<DateTime<Utc>>,
+  /// Only match papers published at or before this time
+  pub to:     Option<DateTime<Utc>>,
+  /// Only match papers with this reading status
+  pub status: Option<ReadingStatus>,
+}
+
+impl SearchQuery {
+  /// Creates a query matching `text` that returns every match.
+  pub fn new(text: &str) -> Self { Self { text: text.to_string(), ..Default::default() } }
+
+  /// Limits the number of papers returned.
+  pub fn limit(mut self, limit: usize) -> Self {
+    self.limit = Some(limit);
+    self
+  }
+
+  /// Skips the first `offset` matches.
+  pub fn offset(mut self, offset: usize) -> Self {
+    self.offset = offset;
+    self
+  }
+
+  /// Only matches papers published between `from` and `to`, inclusive.
+  ///
+  /// Either bound may be left open. When only `from` is given, papers published up to now
+  /// match.
+  pub fn published_between(
+    mut self,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+  ) -> Self {
+    self.to = to.or_else(|| from.map(|_| Utc::now()));
+    self.from = from;
+    self
+  }
+
+  /// Only matches papers with the given reading status.
+  pub fn status(mut self, status: ReadingStatus) -> Self {
+    self.status = Some(status);
+    self
+  }
+
+  /// Builds the shared `FROM ... WHERE ...` clause and its parameters.
+  ///
+  /// Both the page query and the count query are built from this clause so the two can never
+  /// disagree about which papers match.
+  fn filter(&self) -> (String, Vec<Value>) {
+    let mut clause = "FROM papers p
+                 JOIN papers_fts f ON p.id = f.rowid
+                 WHERE papers_fts MATCH ?1"
+      .to_string();
+    // Make search case-insensitive
+    let mut params = vec![Value::Text(self.text.to_lowercase())];
+
+    // Dates are bound in the format rusqlite stores them in, so they compare as text
+    for (op, date) in [(">=", self.from), ("<=", self.to)] {
+      if let Some(date) = date {
+        params.push(Value::Text(date.format("%F %T%.f%:z").to_string()));
+        clause.push_str(&format!(" AND p.publication_date {op} ?{}", params.len()));
+      }
+    }
+    if let Some(status) = self.status {
+      params.push(Value::Text(status.as_str().to_string()));
+      clause.push_str(&format!(" AND p.reading_status = ?{}", params.len()));
+    }
+    (clause, params)
+  }
+
+  /// Builds the `LIMIT ... OFFSET ...` clause for the requested page.
+  fn page_clause(&self) -> String {
+    match (self.limit, self.offset) {
+      (Some(limit), offset) => format!("LIMIT {limit} OFFSET {offset}"),
+      (None, 0) => String::new(),
+      (None, offset) => format!("LIMIT -1 OFFSET {offset}"),
+    }
+  }
+}
+
+/// The result of a [`Database::search`].
+#[derive(Debug, Clone)]
+pub struct SearchOutcome {
+  /// The requested page of matching papers, ordered by relevance
+  pub papers:        Vec<Paper>,
+  /// Total number of papers matching the query, ignoring the limit and offset
+  pub total_matches: usize,
+  /// Wall-clock time taken to run the search
+  pub elapsed:       Duration,
+}
+
 impl Database {
   /// Opens an existing database or creates a new one at the specified path.
   ///
@@ -87,10 +418,33 @@ impl Database {
     // Initialize schema
     conn
       .call(|conn| {
+        let reindex = drop_outdated_search_index(conn)?;
         conn.execute_batch(include_str!(concat!(
           env!("CARGO_MANIFEST_DIR"),
           "/migrations/init.sql"
         )))?;
+        migrate(conn)?;
+        conn.execute_batch(include_str!(concat!(
+          env!("CARGO_MANIFEST_DIR"),
+          "/migrations/002_tags.sql"
+        )))?;
+        migrate_single_notes(conn)?;
+        conn.execute_batch(include_str!(concat!(
+          env!("CARGO_MANIFEST_DIR"),
+          "/migrations/003_notes.sql"
+        )))?;
+        conn.execute_batch(include_str!(concat!(
+          env!("CARGO_MANIFEST_DIR"),
+          "/migrations/004_collections.sql"
+        )))?;
+        conn.execute_batch(include_str!(concat!(
+          env!("CARGO_MANIFEST_DIR"),
+          "/migrations/005_watch_targets.sql"
+        )))?;
+        // Only now that every table exists can the index be filled again
+        if reindex {
+          conn.execute(INDEX_PAPERS_SQL, params![-1, 0])?;
+        }
         Ok(())
       })
       .await?;
@@ -125,6 +479,16 @@ impl Database {
   ///
   /// The operation is performed in a transaction to ensure data consistency.
   ///
+  /// Each paper is stored once per source and identifier. Saving a newer version of a stored
+  /// paper (e.g. arXiv `v2` over `v1`) updates the stored record in place, keeping its ID,
+  /// tags and PDF. Saving the same or an older version fails with a duplicate error (see
+  /// [`LearnerError::is_duplicate_error`]).
+  ///
+  /// A paper is also stored once per DOI: saving a paper whose DOI, compared regardless of case,
+  /// belongs to a paper saved under another source or identifier (e.g. the Crossref record of
+  /// an arXiv preprint) fails with [`LearnerError::Duplicate`]. This holds for newer versions
+  /// too, which may have gained a DOI since the stored one.
+  ///
   /// # Arguments
   ///
   /// * `paper` - The paper to save
@@ -132,7 +496,7 @@ impl Database {
   /// # Returns
   ///
   /// Returns a [`Result`] containing either:
-  /// - The database ID of the saved paper
+  /// - The database ID of the saved (or updated) paper
   /// - A [`LearnerError`] if the save operation fails
   ///
   /// # Examples
@@ -153,546 +517,4507 @@ impl Database {
       .conn
       .call(move |conn| {
         let tx = conn.transaction()?;
+        let existing: Option<(i64, Option<String>)> = tx
+          .query_row(
+            "SELECT id, version FROM papers WHERE source = ?1 AND source_identifier = ?2",
+            params![paper.source.to_string(), &paper.source_identifier],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+          )
+          .optional()?;
 
-        // Insert paper
-        let paper_id = {
-          let mut stmt = tx.prepare_cached(
-            "INSERT INTO papers (
-                            title, abstract_text, publication_date, 
-                            source, source_identifier, pdf_url, doi
-                        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-                        RETURNING id",
-          )?;
-
-          stmt.query_row(
-            params![
-              &paper.title,
-              &paper.abstract_text,
-              &paper.publication_date,
-              paper.source.to_string(),
-              &paper.source_identifier,
-              &paper.pdf_url,
-              &paper.doi,
-            ],
-            |row| row.get::<_, i64>(0),
-          )?
+        let paper_id = match existing {
+          Some((id, version)) if is_newer_version(paper.version.as_deref(), version.as_deref()) => {
+            if let Err(e) = replace_paper_keeping_dois_unique(&tx, id, &paper)? {
+              return Ok(Err(e));
+            }
+            id
+          },
+          // Inserting reports the unique constraint violation for duplicates
+          Some(_) => insert_paper(&tx, &paper)?,
+          None => {
+            if let Some((source, source_identifier)) = find_doi(&tx, paper.doi.as_deref(), None)? {
+              return Ok(Err(LearnerError::Duplicate(format!("{source} {source_identifier}"))));
+            }
+            insert_paper(&tx, &paper)?
+          },
         };
-
-        // Insert authors
-        {
-          let mut stmt = tx.prepare_cached(
-            "INSERT INTO authors (paper_id, name, affiliation, email)
-                         VALUES (?1, ?2, ?3, ?4)",
-          )?;
-
-          for author in &paper.authors {
-            stmt.execute(params![paper_id, &author.name, &author.affiliation, &author.email,])?;
-          }
-        }
-
         tx.commit()?;
-        Ok(paper_id)
+        Ok(Ok(paper_id))
       })
-      .await
-      .map_err(LearnerError::from)
+      .await?
   }
 
-  /// Retrieves a paper using its source and identifier.
+  /// Replaces the stored metadata and authors of a paper with those of `paper`.
   ///
-  /// This method looks up a paper based on its origin (e.g., arXiv, DOI)
-  /// and its source-specific identifier. It also fetches all associated
-  /// author information.
+  /// The stored paper is found by `paper`'s source and identifier. Its ID, tags, and PDF
+  /// records are kept. Use this when a paper's metadata has changed at the source, e.g. a
+  /// corrected title or a newly assigned DOI.
   ///
   /// # Arguments
   ///
-  /// * `source` - The paper's source system (arXiv, IACR, DOI)
-  /// * `source_id` - The source-specific identifier
+  /// * `paper` - The paper with updated metadata
   ///
   /// # Returns
   ///
   /// Returns a [`Result`] containing either:
-  /// - `Some(Paper)` if found
-  /// - `None` if no matching paper exists
-  /// - A [`LearnerError`] if the query fails
+  /// - The database ID of the updated paper
+  /// - [`LearnerError::NotFound`] if the paper is not in the database, [`LearnerError::Duplicate`]
+  ///   if its new DOI is already saved for another paper, or another [`LearnerError`] if the update
+  ///   fails
   ///
   /// # Examples
   ///
   /// ```no_run
-  /// # use learner::{database::Database, paper::Source};
+  /// # use learner::{database::Database, paper::Paper};
   /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
   /// let db = Database::open("papers.db").await?;
-  /// if let Some(paper) = db.get_paper_by_source_id(&Source::Arxiv, "2301.07041").await? {
-  ///   println!("Found paper: {}", paper.title);
-  /// }
+  /// let paper = Paper::new("10.1145/1327452.1327492").await?;
+  /// db.update_paper(&paper).await?;
   /// # Ok(())
   /// # }
   /// ```
-  pub async fn get_paper_by_source_id(
-    &self,
-    source: &Source,
-    source_id: &str,
-  ) -> Result<Option<Paper>, LearnerError> {
-    // Clone the values before moving into the async closure
-    let source = source.to_string();
-    let source_id = source_id.to_string();
-
+  pub async fn update_paper(&self, paper: &Paper) -> Result<i64, LearnerError> {
+    let paper = paper.clone();
     self
       .conn
       .call(move |conn| {
-        let mut paper_stmt = conn.prepare_cached(
-          "SELECT id, title, abstract_text, publication_date, source,
-                            source_identifier, pdf_url, doi
-                     FROM papers 
-                     WHERE source = ?1 AND source_identifier = ?2",
-        )?;
-
-        let mut author_stmt = conn.prepare_cached(
-          "SELECT name, affiliation, email
-                     FROM authors
-                     WHERE paper_id = ?",
-        )?;
-
-        let paper_result = paper_stmt.query_row(params![source, source_id], |row| {
-          Ok(Paper {
-            title:             row.get(1)?,
-            abstract_text:     row.get(2)?,
-            publication_date:  row.get(3)?,
-            source:            Source::from_str(&row.get::<_, String>(4)?).map_err(|e| {
-              rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e))
-            })?,
-            source_identifier: row.get(5)?,
-            pdf_url:           row.get(6)?,
-            doi:               row.get(7)?,
-            authors:           Vec::new(), // Filled in below
-          })
-        });
-
-        match paper_result {
-          Ok(mut paper) => {
-            let paper_id: i64 =
-              paper_stmt.query_row(params![source, source_id], |row| row.get(0))?;
-
-            let authors = author_stmt.query_map([paper_id], |row| {
-              Ok(Author {
-                name:        row.get(0)?,
-                affiliation: row.get(1)?,
-                email:       row.get(2)?,
-              })
-            })?;
-
-            paper.authors = authors.collect::<Result<Vec<_>, _>>()?;
-            Ok(Some(paper))
-          },
-          Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-          Err(e) => Err(e.into()),
+        let tx = conn.transaction()?;
+        let Some(paper_id) = find_paper_id(&tx, &paper)? else {
+          return Ok(Err(LearnerError::NotFound));
+        };
+        if let Err(e) = replace_paper_keeping_dois_unique(&tx, paper_id, &paper)? {
+          return Ok(Err(e));
         }
+        tx.commit()?;
+        Ok(Ok(paper_id))
       })
-      .await
-      .map_err(LearnerError::from)
+      .await?
   }
 
-  /// Searches for papers using full-text search.
-  ///
-  /// This method uses SQLite's FTS5 module to perform full-text search across:
-  /// - Paper titles
-  /// - Paper abstracts
+  /// Saves a paper, or updates the stored copy if it is already in the database.
   ///
-  /// Results are ordered by relevance using FTS5's built-in ranking algorithm.
+  /// Unlike [`Database::save_paper`], this never fails because the paper already exists: its
+  /// stored metadata and authors are replaced instead, as with [`Database::update_paper`].
   ///
   /// # Arguments
   ///
-  /// * `query` - The search query using FTS5 syntax
+  /// * `paper` - The paper to save
   ///
   /// # Returns
   ///
   /// Returns a [`Result`] containing either:
-  /// - A vector of matching papers
-  /// - A [`LearnerError`] if the search fails
+  /// - The database ID of the paper, and `true` if it was newly inserted or `false` if an existing
+  ///   paper was updated
+  /// - [`LearnerError::Duplicate`] if the paper's DOI is already saved for another paper, or
+  ///   another [`LearnerError`] if the operation fails
   ///
   /// # Examples
   ///
   /// ```no_run
+  /// # use learner::{database::Database, paper::Paper};
   /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-  /// let db = learner::database::Database::open("papers.db").await?;
-  ///
-  /// // Simple word search
-  /// let papers = db.search_papers("quantum").await?;
-  ///
-  /// // Phrase search
-  /// let papers = db.search_papers("\"neural networks\"").await?;
-  ///
-  /// // Complex query
-  /// let papers = db.search_papers("machine learning NOT regression").await?;
+  /// let db = Database::open("papers.db").await?;
+  /// let paper = Paper::new("2301.07041").await?;
+  /// let (id, inserted) = db.save_or_update_paper(&paper).await?;
+  /// println!("{} paper {id}", if inserted { "Saved" } else { "Updated" });
   /// # Ok(())
   /// # }
   /// ```
-  pub async fn search_papers(&self, query: &str) -> Result<Vec<Paper>, LearnerError> {
-    let query = query.to_lowercase(); // Make search case-insensitive
-
+  pub async fn save_or_update_paper(&self, paper: &Paper) -> Result<(i64, bool), LearnerError> {
+    let paper = paper.clone();
     self
       .conn
       .call(move |conn| {
-        // First get all paper IDs matching the search
-        let mut id_stmt = conn.prepare_cached(
-          "SELECT p.id
-                 FROM papers p
-                 JOIN papers_fts f ON p.id = f.rowid
-                 WHERE papers_fts MATCH ?1 
-                 ORDER BY rank",
-        )?;
-
-        // Collect matching IDs first
-        let paper_ids: Vec<i64> =
-          id_stmt.query_map([&query], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
-
-        let mut papers = Vec::new();
-
-        // Now fetch complete paper data for each ID
-        for paper_id in paper_ids {
-          // Get paper details
-          let mut paper_stmt = conn.prepare_cached(
-            "SELECT title, abstract_text, publication_date,
-                            source, source_identifier, pdf_url, doi
-                     FROM papers 
-                     WHERE id = ?",
-          )?;
-
-          let paper = paper_stmt.query_row([paper_id], |row| {
-            Ok(Paper {
-              title:             row.get(0)?,
-              abstract_text:     row.get(1)?,
-              publication_date:  row.get(2)?,
-              source:            Source::from_str(&row.get::<_, String>(3)?).map_err(|e| {
-                rusqlite::Error::FromSqlConversionFailure(
-                  3,
-                  rusqlite::types::Type::Text,
-                  Box::new(e),
-                )
-              })?,
-              source_identifier: row.get(4)?,
-              pdf_url:           row.get(5)?,
-              doi:               row.get(6)?,
-              authors:           Vec::new(),
-            })
-          })?;
-
-          // Get authors for this paper
-          let mut author_stmt = conn.prepare_cached(
-            "SELECT name, affiliation, email
-                     FROM authors
-                     WHERE paper_id = ?",
-          )?;
-
-          let authors = author_stmt
-            .query_map([paper_id], |row| {
-              Ok(Author {
-                name:        row.get(0)?,
-                affiliation: row.get(1)?,
-                email:       row.get(2)?,
-              })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
-
-          // Create the complete paper with authors
-          let mut paper = paper;
-          paper.authors = authors;
-          papers.push(paper);
-        }
-
-        Ok(papers)
+        let tx = conn.transaction()?;
+        let result = match find_paper_id(&tx, &paper)? {
+          Some(paper_id) => {
+            if let Err(e) = replace_paper_keeping_dois_unique(&tx, paper_id, &paper)? {
+              return Ok(Err(e));
+            }
+            (paper_id, false)
+          },
+          None => {
+            if let Some((source, source_identifier)) = find_doi(&tx, paper.doi.as_deref(), None)? {
+              return Ok(Err(LearnerError::Duplicate(format!("{source} {source_identifier}"))));
+            }
+            (insert_paper(&tx, &paper)?, true)
+          },
+        };
+        tx.commit()?;
+        Ok(Ok(result))
       })
-      .await
-      .map_err(LearnerError::from)
+      .await?
   }
 
-  /// Returns the default path for PDF storage.
+  /// Refreshes a stored paper's metadata from its source, e.g. after a new arXiv version.
   ///
-  /// The path is constructed as follows:
-  /// - On Unix: `~/Documents/learner/papers`
-  /// - On macOS: `~/Documents/learner/papers`
-  /// - On Windows: `Documents\learner\papers`
-  /// - Fallback: `./papers` in the current directory
+  /// The paper is fetched again with the client for `source`, and its title, abstract,
+  /// publication date, PDF URL, DOI, version, categories, keywords, and authors are replaced as
+  /// in [`Database::update_paper`]. Its tags, notes, reading status, and PDF records are kept,
+  /// and its `updated_at` time is set to now.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The paper as now stored
+  /// - [`LearnerError::NotFound`] if the paper is not in the database, or another [`LearnerError`]
+  ///   if it cannot be fetched or updated
   ///
   /// # Examples
   ///
   /// ```no_run
-  /// let path = learner::database::Database::default_pdf_path();
-  /// println!("PDFs will be stored at: {}", path.display());
+  /// # use learner::{database::Database, paper::Source};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// let paper = db.update_paper_metadata(&Source::Arxiv, "2301.07041").await?;
+  /// println!("Now at version {:?}", paper.version);
+  /// # Ok(())
+  /// # }
   /// ```
-  pub fn default_pdf_path() -> PathBuf {
-    dirs::document_dir().unwrap_or_else(|| PathBuf::from(".")).join("learner").join("papers")
+  pub async fn update_paper_metadata(
+    &self,
+    source: &Source,
+    source_id: &str,
+  ) -> Result<Paper, LearnerError> {
+    // Check first, so missing papers aren't fetched for nothing
+    if self.get_paper_id(source, source_id).await?.is_none() {
+      return Err(LearnerError::NotFound);
+    }
+
+    // Update the row that was asked for, even if the source reports the paper under a
+    // differently formatted identifier
+    let mut paper = fetch_from_source(source, source_id).await?;
+    paper.source = source.clone();
+    paper.source_identifier = source_id.to_string();
+    self.update_paper(&paper).await?;
+
+    self.get_paper_by_source_id(source, source_id).await?.ok_or(LearnerError::NotFound)
   }
 
-  /// Sets a configuration value in the database.
+  /// Gets when a paper's metadata was last changed, by [`Database::update_paper`] or
+  /// [`Database::update_paper_metadata`], or else when it was saved.
   ///
   /// # Arguments
   ///
-  /// * `key` - The configuration key
-  /// * `value` - The value to store
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
   ///
   /// # Returns
   ///
-  /// Returns a [`Result`] indicating success or failure
-  pub async fn set_config(&self, key: &str, value: &str) -> Result<(), LearnerError> {
-    let key = key.to_string();
-    let value = value.to_string();
+  /// Returns a [`Result`] containing either:
+  /// - The time of the last change, or `None` if the paper is not in the database
+  /// - A [`LearnerError`] if the query fails
+  pub async fn get_updated_at(
+    &self,
+    source: &Source,
+    source_id: &str,
+  ) -> Result<Option<DateTime<Utc>>, LearnerError> {
+    let source = source.to_string();
+    let source_id = source_id.to_string();
+
     self
       .conn
       .call(move |conn| {
         Ok(
           conn
-            .execute("INSERT OR REPLACE INTO config (key, value) VALUES (?1, ?2)", params![
-              key, value
-            ])
-            .map(|_| ()),
+            .query_row(
+              "SELECT updated_at FROM papers WHERE source = ?1 AND source_identifier = ?2",
+              params![source, source_id],
+              |row| row.get(0),
+            )
+            .optional()?,
         )
       })
-      .await?
+      .await
       .map_err(LearnerError::from)
   }
 
-  /// Gets a configuration value from the database.
+  /// Retrieves a paper using its source and identifier.
+  ///
+  /// This method looks up a paper based on its origin (e.g., arXiv, DOI)
+  /// and its source-specific identifier. It also fetches all associated
+  /// author information.
   ///
   /// # Arguments
   ///
-  /// * `key` - The configuration key to retrieve
+  /// * `source` - The paper's source system (arXiv, IACR, DOI)
+  /// * `source_id` - The source-specific identifier
   ///
   /// # Returns
   ///
   /// Returns a [`Result`] containing either:
-  /// - Some(String) with the configuration value
-  /// - None if the key doesn't exist
-  pub async fn get_config(&self, key: &str) -> Result<Option<String>, LearnerError> {
-    let key = key.to_string();
-    self
-      .conn
-      .call(move |conn| {
-        let mut stmt = conn.prepare_cached("SELECT value FROM config WHERE key = ?1")?;
+  /// - `Some(Paper)` if found
+  /// - `None` if no matching paper exists
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, paper::Source};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// if let Some(paper) = db.get_paper_by_source_id(&Source::Arxiv, "2301.07041").await? {
+  ///   println!("Found paper: {}", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn get_paper_by_source_id(
+    &self,
+    source: &Source,
+    source_id: &str,
+  ) -> Result<Option<Paper>, LearnerError> {
+    // Clone the values before moving into the async closure
+    let source = source.to_string();
+    let source_id = source_id.to_string();
 
-        let result = stmt.query_row([key], |row| row.get::<_, String>(0));
+    self
+      .conn
+      .call(move |conn| {
+        let paper_id: Option<i64> = conn
+          .query_row(
+            "SELECT id FROM papers WHERE source = ?1 AND source_identifier = ?2",
+            params![source, source_id],
+            |row| row.get(0),
+          )
+          .optional()?;
+        Ok(paper_id.map(|id| load_paper(conn, id)).transpose()?)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
 
-        match result {
-          Ok(value) => Ok(Some(value)),
-          Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-          Err(e) => Err(e.into()),
-        }
+  /// Retrieves a paper by its DOI, whichever source it was saved from.
+  ///
+  /// A paper saved from arXiv or another source keeps the DOI it was published under, so it
+  /// can be found by that DOI as well as by its source and identifier. DOIs are compared
+  /// regardless of case.
+  ///
+  /// # Arguments
+  ///
+  /// * `doi` - The DOI to look up (e.g. "10.1145/1327452.1327492")
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - `Some(Paper)` if a paper with this DOI is saved
+  /// - `None` if no paper has this DOI
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// if let Some(paper) = db.get_paper_by_doi("10.1145/1327452.1327492").await? {
+  ///   println!("Saved from {} as {}", paper.source, paper.source_identifier);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn get_paper_by_doi(&self, doi: &str) -> Result<Option<Paper>, LearnerError> {
+    let doi = doi.trim().to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let paper_id: Option<i64> = conn
+          .query_row(
+            "SELECT id FROM papers WHERE doi = ?1 COLLATE NOCASE ORDER BY id LIMIT 1",
+            [doi],
+            |row| row.get(0),
+          )
+          .optional()?;
+        Ok(paper_id.map(|id| load_paper(conn, id)).transpose()?)
       })
       .await
       .map_err(LearnerError::from)
   }
 
-  /// Records a PDF file location and status for a paper.
+  /// Gets the database ID of a paper.
   ///
   /// # Arguments
   ///
-  /// * `paper_id` - The database ID of the paper
-  /// * `path` - Full path to the file
-  /// * `filename` - The filename
-  /// * `status` - Download status ('success', 'failed', 'pending')
-  /// * `error` - Optional error message if download failed
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
   ///
   /// # Returns
   ///
-  /// Returns a [`Result`] containing the file ID on success
-  pub async fn record_pdf(
+  /// Returns a [`Result`] containing either:
+  /// - The paper's ID, for use with e.g. [`Database::record_pdf`], or `None` if it is not in the
+  ///   database
+  /// - A [`LearnerError`] if the query fails
+  pub async fn get_paper_id(
     &self,
-    paper_id: i64,
-    path: PathBuf,
-    filename: String,
-    status: &str,
-    error: Option<String>,
-  ) -> Result<i64, LearnerError> {
-    let path_str = path.to_string_lossy().to_string();
-    let status = status.to_string();
+    source: &Source,
+    source_id: &str,
+  ) -> Result<Option<i64>, LearnerError> {
+    let source = source.to_string();
+    let source_id = source_id.to_string();
 
     self
       .conn
       .call(move |conn| {
-        let tx = conn.transaction()?;
+        Ok(
+          conn
+            .query_row(
+              "SELECT id FROM papers WHERE source = ?1 AND source_identifier = ?2",
+              params![source, source_id],
+              |row| row.get(0),
+            )
+            .optional()?,
+        )
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
 
-        let id = tx.query_row(
-          "INSERT OR REPLACE INTO files (
-                      paper_id, path, filename, download_status, error_message
-                  ) VALUES (?1, ?2, ?3, ?4, ?5)
-                  RETURNING id",
-          params![paper_id, path_str, filename, status, error],
-          |row| row.get(0),
-        )?;
+  /// Lists papers in the database one page at a time.
+  ///
+  /// Unlike [`Database::search_papers`], this returns every paper regardless of content,
+  /// in the requested order.
+  ///
+  /// # Arguments
+  ///
+  /// * `order_by` - The field to order papers by
+  /// * `ascending` - Whether to list in ascending order (oldest, A to Z) or descending order
+  ///   (newest, Z to A). [`PaperSort::default_ascending`] gives the usual direction.
+  /// * `limit` - Maximum number of papers to return
+  /// * `offset` - Number of papers to skip before the first one returned
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The requested page of papers, including their authors
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Database, PaperSort};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  ///
+  /// // The 20 most recently published papers
+  /// let papers = db.list_papers(PaperSort::Date, false, 20, 0).await?;
+  ///
+  /// // The next 20
+  /// let papers = db.list_papers(PaperSort::Date, false, 20, 20).await?;
+  ///
+  /// // The first 20 alphabetically
+  /// let papers = db.list_papers(PaperSort::Title, true, 20, 0).await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn list_papers(
+    &self,
+    order_by: PaperSort,
+    ascending: bool,
+    limit: usize,
+    offset: usize,
+  ) -> Result<Vec<Paper>, LearnerError> {
+    self
+      .conn
+      .call(move |conn| {
+        let mut stmt = conn.prepare_cached(&format!(
+          "SELECT id FROM papers ORDER BY {} LIMIT ?1 OFFSET ?2",
+          order_by.order_by(ascending)
+        ))?;
+        let paper_ids = stmt
+          .query_map(params![limit as i64, offset as i64], |row| row.get(0))?
+          .collect::<Result<Vec<i64>, _>>()?;
+
+        Ok(paper_ids.into_iter().map(|id| load_paper(conn, id)).collect::<Result<Vec<_>, _>>()?)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Lists the papers with a given tag one page at a time.
+  ///
+  /// This is [`Database::list_papers`] restricted to papers tagged with `tag`.
+  ///
+  /// # Arguments
+  ///
+  /// * `tag` - The tag to look for, normalized like in [`Database::add_tag`]
+  /// * `order_by` - The field to order papers by
+  /// * `ascending` - Whether to list in ascending or descending order
+  /// * `limit` - Maximum number of papers to return
+  /// * `offset` - Number of papers to skip before the first one returned
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The requested page of tagged papers, including their authors and tags
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Database, PaperSort};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// let papers = db.list_papers_by_tag("crypto", PaperSort::Title, true, 20, 0).await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn list_papers_by_tag(
+    &self,
+    tag: &str,
+    order_by: PaperSort,
+    ascending: bool,
+    limit: usize,
+    offset: usize,
+  ) -> Result<Vec<Paper>, LearnerError> {
+    let tag = tag.trim().to_lowercase();
+
+    self
+      .conn
+      .call(move |conn| {
+        let mut stmt = conn.prepare_cached(&format!(
+          "SELECT id FROM papers
+           WHERE id IN (SELECT paper_id FROM tags WHERE tag = ?3)
+           ORDER BY {} LIMIT ?1 OFFSET ?2",
+          order_by.order_by(ascending)
+        ))?;
+        let paper_ids = stmt
+          .query_map(params![limit as i64, offset as i64, tag], |row| row.get(0))?
+          .collect::<Result<Vec<i64>, _>>()?;
+
+        Ok(paper_ids.into_iter().map(|id| load_paper(conn, id)).collect::<Result<Vec<_>, _>>()?)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Lists the papers with a given reading status one page at a time.
+  ///
+  /// This is [`Database::list_papers`] restricted to papers with reading status `status`.
+  ///
+  /// # Arguments
+  ///
+  /// * `status` - The reading status to look for
+  /// * `order_by` - The field to order papers by
+  /// * `ascending` - Whether to list in ascending or descending order
+  /// * `limit` - Maximum number of papers to return
+  /// * `offset` - Number of papers to skip before the first one returned
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The requested page of papers, including their authors and tags
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::{Database, PaperSort}, paper::ReadingStatus};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// let papers =
+  ///   db.list_papers_by_status(ReadingStatus::Unread, PaperSort::Date, false, 20, 0).await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn list_papers_by_status(
+    &self,
+    status: ReadingStatus,
+    order_by: PaperSort,
+    ascending: bool,
+    limit: usize,
+    offset: usize,
+  ) -> Result<Vec<Paper>, LearnerError> {
+    self
+      .conn
+      .call(move |conn| {
+        let mut stmt = conn.prepare_cached(&format!(
+          "SELECT id FROM papers WHERE reading_status = ?3 ORDER BY {} LIMIT ?1 OFFSET ?2",
+          order_by.order_by(ascending)
+        ))?;
+        let paper_ids = stmt
+          .query_map(params![limit as i64, offset as i64, status.as_str()], |row| row.get(0))?
+          .collect::<Result<Vec<i64>, _>>()?;
+
+        Ok(paper_ids.into_iter().map(|id| load_paper(conn, id)).collect::<Result<Vec<_>, _>>()?)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Lists the papers most recently saved to the database, newest first.
+  ///
+  /// Papers are ordered by [`Paper::added_at`], which is set when a paper is first saved and
+  /// kept when it is updated.
+  ///
+  /// # Arguments
+  ///
+  /// * `limit` - Maximum number of papers to return
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - Up to `limit` papers, including their authors and tags
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// for paper in db.recent_papers(10).await? {
+  ///   println!("{}", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn recent_papers(&self, limit: usize) -> Result<Vec<Paper>, LearnerError> {
+    self
+      .conn
+      .call(move |conn| {
+        let mut stmt = conn
+          .prepare_cached("SELECT id FROM papers ORDER BY created_at DESC, id DESC LIMIT ?1")?;
+        let paper_ids =
+          stmt.query_map([limit as i64], |row| row.get(0))?.collect::<Result<Vec<i64>, _>>()?;
+
+        Ok(paper_ids.into_iter().map(|id| load_paper(conn, id)).collect::<Result<Vec<_>, _>>()?)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Removes a paper and its associated data from the database.
+  ///
+  /// This method will:
+  /// 1. Delete the paper from the papers table
+  /// 2. Delete its authors and PDF records (via cascading foreign keys)
+  /// 3. Remove it from the full-text search index
+  ///
+  /// The operation is performed in a transaction to ensure data consistency.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system (arXiv, IACR, DOI)
+  /// * `source_id` - The source-specific identifier
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - `true` if the paper was removed
+  /// - `false` if no matching paper exists
+  /// - A [`LearnerError`] if the removal fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, paper::Source};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// if db.remove_paper(&Source::IACR, "2016/260").await? {
+  ///   println!("Removed paper");
+  /// } else {
+  ///   println!("Paper not found");
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn remove_paper(&self, source: &Source, source_id: &str) -> Result<bool, LearnerError> {
+    let source = source.to_string();
+    let source_id = source_id.to_string();
 
+    self
+      .conn
+      .call(move |conn| {
+        let tx = conn.transaction()?;
+        let removed = tx
+          .execute("DELETE FROM papers WHERE source = ?1 AND source_identifier = ?2", params![
+            source, source_id
+          ])?;
         tx.commit()?;
-        Ok(id)
+        Ok(removed > 0)
       })
       .await
-      .map_err(LearnerError::from)
-  }
+      .map_err(LearnerError::from)
+  }
+
+  /// Searches for papers using full-text search.
+  ///
+  /// This method uses SQLite's FTS5 module to perform full-text search across:
+  /// - Paper titles
+  /// - Author names
+  /// - Notes
+  /// - Abstracts
+  ///
+  /// Results are ordered by relevance using FTS5's built-in ranking algorithm, with title
+  /// matches weighted above author and note matches, and those above abstract matches. Restrict
+  /// a term to one field with FTS5 column filters, e.g. `authors:groth` or `title:lattice`.
+  ///
+  /// This is a thin wrapper around [`Database::search`] that returns every match and discards
+  /// the count and timing information.
+  ///
+  /// # Arguments
+  ///
+  /// * `query` - The search query using FTS5 syntax
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - A vector of matching papers
+  /// - A [`LearnerError`] if the search fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = learner::database::Database::open("papers.db").await?;
+  ///
+  /// // Simple word search
+  /// let papers = db.search_papers("quantum").await?;
+  ///
+  /// // Phrase search
+  /// let papers = db.search_papers("\"neural networks\"").await?;
+  ///
+  /// // Complex query
+  /// let papers = db.search_papers("machine learning NOT regression").await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search_papers(&self, query: &str) -> Result<Vec<Paper>, LearnerError> {
+    Ok(self.search(&SearchQuery::new(query)).await?.papers)
+  }
+
+  /// Searches for papers using full-text search, keeping only those published in a date range.
+  ///
+  /// Like [`Database::search_papers`], but filtered on [`Paper::publication_date`] as described
+  /// in [`SearchQuery::published_between`]: both bounds are inclusive, either may be `None` to
+  /// leave it open, and when only `from` is given papers published up to now match.
+  ///
+  /// # Arguments
+  ///
+  /// * `query` - The search query using FTS5 syntax
+  /// * `from` - Earliest publication date to match
+  /// * `to` - Latest publication date to match
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - A vector of matching papers, ordered by relevance
+  /// - A [`LearnerError`] if the search fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use chrono::{TimeZone, Utc};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = learner::database::Database::open("papers.db").await?;
+  ///
+  /// let from = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+  /// let to = Utc.with_ymd_and_hms(2023, 12, 31, 23, 59, 59).unwrap();
+  /// let papers = db.search_papers_in_date_range("snark", Some(from), Some(to)).await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search_papers_in_date_range(
+    &self,
+    query: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+  ) -> Result<Vec<Paper>, LearnerError> {
+    Ok(self.search(&SearchQuery::new(query).published_between(from, to)).await?.papers)
+  }
+
+  /// Runs a full-text search and reports the total number of matches and the time taken.
+  ///
+  /// The total is computed with a `COUNT` query sharing the same filter as the page query, so
+  /// [`SearchOutcome::total_matches`] reflects every match even when
+  /// [`SearchQuery::limit`] restricts the papers returned.
+  ///
+  /// # Arguments
+  ///
+  /// * `query` - The [`SearchQuery`] describing the search text and page to return
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - A [`SearchOutcome`] with the requested page of papers and the total match count
+  /// - A [`LearnerError`] if the search fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Database, SearchQuery};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  ///
+  /// let outcome = db.search(&SearchQuery::new("neural").limit(20)).await?;
+  /// println!(
+  ///   "showing {} of {} ({} ms)",
+  ///   outcome.papers.len(),
+  ///   outcome.total_matches,
+  ///   outcome.elapsed.as_millis()
+  /// );
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search(&self, query: &SearchQuery) -> Result<SearchOutcome, LearnerError> {
+    let query = query.clone();
+    let start = Instant::now();
+
+    let (papers, total_matches) = self
+      .conn
+      .call(move |conn| {
+        let (filter, params) = query.filter();
+
+        // Count every match using the same filter as the page query
+        let total_matches: i64 = conn.query_row(
+          &format!("SELECT COUNT(*) {filter}"),
+          rusqlite::params_from_iter(params.iter()),
+          |row| row.get(0),
+        )?;
+
+        let mut id_stmt =
+          conn.prepare(&format!("SELECT p.id {filter} ORDER BY rank {}", query.page_clause()))?;
+
+        // Collect matching IDs first
+        let paper_ids: Vec<i64> = id_stmt
+          .query_map(rusqlite::params_from_iter(params.iter()), |row| row.get(0))?
+          .collect::<Result<Vec<_>, _>>()?;
+
+        // Now fetch complete paper data for each ID
+        let papers = paper_ids
+          .into_iter()
+          .map(|paper_id| load_paper(conn, paper_id))
+          .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((papers, total_matches as usize))
+      })
+      .await?;
+
+    let elapsed = start.elapsed();
+    if elapsed >= SLOW_QUERY_THRESHOLD {
+      warn!("Slow search query ({} ms, {} matches)", elapsed.as_millis(), total_matches);
+    }
+
+    Ok(SearchOutcome { papers, total_matches, elapsed })
+  }
+
+  /// Saves many papers at once, skipping any that are already in the database, including
+  /// papers whose DOI is already saved under another source or identifier.
+  ///
+  /// Papers are inserted in batches, each in its own transaction, and progress is reported to
+  /// `progress` after every batch under the `"importing papers"` stage.
+  ///
+  /// # Arguments
+  ///
+  /// * `papers` - The papers to save
+  /// * `progress` - Optional sink receiving progress updates
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - An [`ImportSummary`] with the number of papers imported and skipped
+  /// - A [`LearnerError`] if a database operation fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, paper::Paper};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// let papers = vec![Paper::new("2301.07041").await?, Paper::new("2016/260").await?];
+  ///
+  /// let summary = db.import_papers(&papers, None).await?;
+  /// println!("Imported {}, skipped {}", summary.imported, summary.skipped);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn import_papers(
+    &self,
+    papers: &[Paper],
+    progress: Option<&dyn ProgressSink>,
+  ) -> Result<ImportSummary, LearnerError> {
+    let total = papers.len();
+    let mut summary = ImportSummary::default();
+    report(progress, 0, total, IMPORT_STAGE);
+
+    for batch in papers.chunks(BATCH_SIZE) {
+      let batch = batch.to_vec();
+      let (imported, skipped) = self
+        .conn
+        .call(move |conn| {
+          let tx = conn.transaction()?;
+          let (mut imported, mut skipped) = (0, 0);
+          for paper in &batch {
+            if find_doi(&tx, paper.doi.as_deref(), None)?.is_some() {
+              skipped += 1;
+              continue;
+            }
+            match insert_paper(&tx, paper) {
+              Ok(_) => imported += 1,
+              Err(rusqlite::Error::SqliteFailure(error, _))
+                if error.code == rusqlite::ErrorCode::ConstraintViolation =>
+                skipped += 1,
+              Err(e) => return Err(e.into()),
+            }
+          }
+          tx.commit()?;
+          Ok((imported, skipped))
+        })
+        .await?;
+
+      summary.imported += imported;
+      summary.skipped += skipped;
+      report(progress, summary.imported + summary.skipped, total, IMPORT_STAGE);
+    }
+
+    Ok(summary)
+  }
+
+  /// Loads every paper in the database, for example to export the library.
+  ///
+  /// Papers are loaded in batches and progress is reported to `progress` after every batch
+  /// under the `"exporting papers"` stage.
+  ///
+  /// # Arguments
+  ///
+  /// * `progress` - Optional sink receiving progress updates
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - Every paper in the database, in insertion order
+  /// - A [`LearnerError`] if a database operation fails
+  pub async fn export_papers(
+    &self,
+    progress: Option<&dyn ProgressSink>,
+  ) -> Result<Vec<Paper>, LearnerError> {
+    let ids: Vec<i64> = self
+      .conn
+      .call(|conn| {
+        let mut stmt = conn.prepare_cached("SELECT id FROM papers ORDER BY id")?;
+        let ids = stmt.query_map([], |row| row.get(0))?.collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+      })
+      .await?;
+
+    let total = ids.len();
+    let mut papers = Vec::with_capacity(total);
+    report(progress, 0, total, EXPORT_STAGE);
+
+    for batch in ids.chunks(BATCH_SIZE) {
+      let batch = batch.to_vec();
+      let loaded = self
+        .conn
+        .call(move |conn| {
+          Ok(batch.into_iter().map(|id| load_paper(conn, id)).collect::<Result<Vec<_>, _>>()?)
+        })
+        .await?;
+      papers.extend(loaded);
+      report(progress, papers.len(), total, EXPORT_STAGE);
+    }
+
+    Ok(papers)
+  }
+
+  /// Finds pairs of papers whose titles are so alike they are probably the same paper, such as
+  /// a preprint on arXiv and its published version, or the same paper on arXiv and IACR.
+  ///
+  /// Every pair of papers is compared with [`format::title_similarity`], so this takes time
+  /// quadratic in the size of the library.
+  ///
+  /// # Arguments
+  ///
+  /// * `similarity_threshold` - The lowest similarity, from `0.0` to `1.0`, for a pair to be
+  ///   returned; [`DEFAULT_DUPLICATE_THRESHOLD`] is a good start
+  /// * `progress` - Optional sink receiving progress updates, under the `"exporting papers"` stage
+  ///   while loading the papers and then `"comparing titles"` for each paper compared
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The pairs at or above the threshold with their similarity, most similar first. Within a
+  ///   pair, the paper saved first comes first.
+  /// - A [`LearnerError`] if a database operation fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Database, DEFAULT_DUPLICATE_THRESHOLD};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// let pairs = db.find_duplicates(DEFAULT_DUPLICATE_THRESHOLD, None).await?;
+  /// for (first, second, similarity) in pairs {
+  ///   println!("{similarity:.2}: {} / {}", first.title, second.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn find_duplicates(
+    &self,
+    similarity_threshold: f64,
+    progress: Option<&dyn ProgressSink>,
+  ) -> Result<Vec<(Paper, Paper, f64)>, LearnerError> {
+    let papers = self.export_papers(progress).await?;
+
+    let total = papers.len();
+    report(progress, 0, total, DUPLICATES_STAGE);
+    let mut pairs = Vec::new();
+    for (i, first) in papers.iter().enumerate() {
+      for second in &papers[i + 1..] {
+        let similarity = format::title_similarity(&first.title, &second.title);
+        if similarity >= similarity_threshold {
+          pairs.push((first.clone(), second.clone(), similarity));
+        }
+      }
+      report(progress, i + 1, total, DUPLICATES_STAGE);
+    }
+    pairs.sort_by(|a, b| b.2.total_cmp(&a.2));
+    Ok(pairs)
+  }
+
+  /// Exports every paper in the database as a BibTeX bibliography.
+  ///
+  /// Entries are formatted with [`Paper::to_bibtex`], in insertion order, and separated by
+  /// blank lines. Citation keys shared by several papers are disambiguated with `a`, `b`, ...
+  /// suffixes (see [`to_bibtex_bibliography`]).
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The bibliography, which is empty if the database has no papers
+  /// - A [`LearnerError`] if loading the papers fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// std::fs::write("refs.bib", db.export_bibtex().await?)?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn export_bibtex(&self) -> Result<String, LearnerError> {
+    let papers = self.export_papers(None).await?;
+    Ok(to_bibtex_bibliography(&papers))
+  }
+
+  /// Exports every paper in the database as RIS records, for import into reference managers
+  /// such as Zotero, Mendeley, or EndNote.
+  ///
+  /// Records are formatted with [`Paper::to_ris`], in insertion order, and separated by blank
+  /// lines (see [`to_ris_records`]).
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The records, which are empty if the database has no papers
+  /// - A [`LearnerError`] if loading the papers fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// std::fs::write("refs.ris", db.export_ris().await?)?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn export_ris(&self) -> Result<String, LearnerError> {
+    let papers = self.export_papers(None).await?;
+    Ok(to_ris_records(&papers))
+  }
+
+  /// Exports every paper in the database as a Markdown document, e.g. for publishing a reading
+  /// list.
+  ///
+  /// Papers are grouped under headings by `group_by` and ordered within each group by
+  /// `order_by` (see [`to_markdown_document`]).
+  ///
+  /// # Arguments
+  ///
+  /// * `group_by` - How to group the papers under headings
+  /// * `order_by` - The field to order papers by within each group
+  /// * `ascending` - Whether to order papers in ascending or descending order
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The document, which only has a title if the database has no papers
+  /// - A [`LearnerError`] if loading the papers fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::{Database, PaperSort}, paper::PaperGrouping};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// let markdown = db.export_markdown(PaperGrouping::Tag, PaperSort::Date, false).await?;
+  /// std::fs::write("papers.md", markdown)?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn export_markdown(
+    &self,
+    group_by: PaperGrouping,
+    order_by: PaperSort,
+    ascending: bool,
+  ) -> Result<String, LearnerError> {
+    let mut papers = self.export_papers(None).await?;
+    order_by.sort_papers(&mut papers, ascending);
+    Ok(to_markdown_document(&papers, group_by))
+  }
+
+  /// Exports every paper in the database as a self-contained HTML page, e.g. for publishing a
+  /// reading list.
+  ///
+  /// This is [`Database::export_markdown`] as HTML (see [`to_html_document`]).
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::{Database, PaperSort}, paper::PaperGrouping};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// let html = db.export_html(PaperGrouping::Year, PaperSort::Title, true).await?;
+  /// std::fs::write("papers.html", html)?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn export_html(
+    &self,
+    group_by: PaperGrouping,
+    order_by: PaperSort,
+    ascending: bool,
+  ) -> Result<String, LearnerError> {
+    let mut papers = self.export_papers(None).await?;
+    order_by.sort_papers(&mut papers, ascending);
+    Ok(to_html_document(&papers, group_by))
+  }
+
+  /// Rebuilds the full-text search index from the stored papers.
+  ///
+  /// This is only needed if the index has become out of sync with the papers table, for
+  /// example after editing the database by hand. The index is cleared and refilled in a single
+  /// transaction, so searches keep seeing the old index until the new one is complete, and a
+  /// failed rebuild leaves it as it was. Progress is reported to `progress` after every batch of
+  /// papers under the `"rebuilding search index"` stage.
+  ///
+  /// # Arguments
+  ///
+  /// * `progress` - Optional sink receiving progress updates
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] indicating success or failure
+  pub async fn rebuild_search_index(
+    &self,
+    progress: Option<&dyn ProgressSink>,
+  ) -> Result<(), LearnerError> {
+    // The transaction runs on the connection's thread, which sends its progress back here
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let rebuild = self.conn.call(move |conn| {
+      let tx = conn.transaction()?;
+      tx.execute("DELETE FROM papers_fts", [])?;
+      let total: i64 = tx.query_row("SELECT COUNT(*) FROM papers", [], |row| row.get(0))?;
+      let total = total as usize;
+
+      let mut done = 0;
+      let _ = sender.send((done, total));
+      while done < total {
+        let indexed = tx.execute(INDEX_PAPERS_SQL, params![BATCH_SIZE as i64, done as i64])?;
+        if indexed == 0 {
+          break;
+        }
+        done += indexed;
+        let _ = sender.send((done, total));
+      }
+
+      tx.commit()?;
+      Ok(())
+    });
+    let forward = async {
+      while let Some((done, total)) = receiver.recv().await {
+        report(progress, done, total, REINDEX_STAGE);
+      }
+    };
+
+    let (result, ()) = tokio::join!(rebuild, forward);
+    Ok(result?)
+  }
+
+  /// Cleans up stale data and compacts the database file.
+  ///
+  /// This method will:
+  /// 1. Remove records of successfully downloaded PDFs whose files no longer exist on disk,
+  ///    reporting progress under the `"checking files"` stage
+  /// 2. Compact the database file, reported under the `"compacting database"` stage
+  ///
+  /// # Arguments
+  ///
+  /// * `progress` - Optional sink receiving progress updates
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing the number of stale file records removed
+  pub async fn collect_garbage(
+    &self,
+    progress: Option<&dyn ProgressSink>,
+  ) -> Result<usize, LearnerError> {
+    let files: Vec<(i64, PathBuf)> = self
+      .conn
+      .call(|conn| {
+        let mut stmt =
+          conn.prepare_cached("SELECT id, path FROM files WHERE download_status = 'success'")?;
+        let files = stmt
+          .query_map([], |row| Ok((row.get(0)?, PathBuf::from(row.get::<_, String>(1)?))))?
+          .collect::<Result<Vec<_>, _>>()?;
+        Ok(files)
+      })
+      .await?;
+
+    let total = files.len();
+    let mut stale = Vec::new();
+    report(progress, 0, total, GC_FILES_STAGE);
+    for (i, (id, path)) in files.into_iter().enumerate() {
+      if !path.exists() {
+        stale.push(id);
+      }
+      if (i + 1) % BATCH_SIZE == 0 || i + 1 == total {
+        report(progress, i + 1, total, GC_FILES_STAGE);
+      }
+    }
+
+    let removed = stale.len();
+    report(progress, 0, 1, GC_COMPACT_STAGE);
+    self
+      .conn
+      .call(move |conn| {
+        let tx = conn.transaction()?;
+        for id in stale {
+          tx.execute("DELETE FROM files WHERE id = ?1", [id])?;
+        }
+        tx.commit()?;
+        conn.execute_batch("VACUUM")?;
+        Ok(())
+      })
+      .await?;
+    report(progress, 1, 1, GC_COMPACT_STAGE);
+
+    Ok(removed)
+  }
+
+  /// Returns the default path for PDF storage.
+  ///
+  /// The path is constructed as follows:
+  /// - On Unix: `~/Documents/learner/papers`
+  /// - On macOS: `~/Documents/learner/papers`
+  /// - On Windows: `Documents\learner\papers`
+  /// - Fallback: `./papers` in the current directory
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// let path = learner::database::Database::default_pdf_path();
+  /// println!("PDFs will be stored at: {}", path.display());
+  /// ```
+  pub fn default_pdf_path() -> PathBuf {
+    dirs::document_dir().unwrap_or_else(|| PathBuf::from(".")).join("learner").join("papers")
+  }
+
+  /// Sets a configuration value in the database.
+  ///
+  /// # Arguments
+  ///
+  /// * `key` - The configuration key
+  /// * `value` - The value to store
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] indicating success or failure
+  pub async fn set_config(&self, key: &str, value: &str) -> Result<(), LearnerError> {
+    let key = key.to_string();
+    let value = value.to_string();
+    self
+      .conn
+      .call(move |conn| {
+        Ok(
+          conn
+            .execute("INSERT OR REPLACE INTO config (key, value) VALUES (?1, ?2)", params![
+              key, value
+            ])
+            .map(|_| ()),
+        )
+      })
+      .await?
+      .map_err(LearnerError::from)
+  }
+
+  /// Gets a configuration value from the database.
+  ///
+  /// # Arguments
+  ///
+  /// * `key` - The configuration key to retrieve
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - Some(String) with the configuration value
+  /// - None if the key doesn't exist
+  pub async fn get_config(&self, key: &str) -> Result<Option<String>, LearnerError> {
+    let key = key.to_string();
+    self
+      .conn
+      .call(move |conn| {
+        let mut stmt = conn.prepare_cached("SELECT value FROM config WHERE key = ?1")?;
+
+        let result = stmt.query_row([key], |row| row.get::<_, String>(0));
+
+        match result {
+          Ok(value) => Ok(Some(value)),
+          Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+          Err(e) => Err(e.into()),
+        }
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Returns the directory PDFs are stored in.
+  ///
+  /// This is the `pdf_dir` configuration value if one is set (e.g. by `learnerd init`), and
+  /// [`Database::default_pdf_path`] otherwise. The directory may not exist yet.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// let pdf_dir = db.get_pdf_dir().await?;
+  /// println!("PDFs are stored at: {}", pdf_dir.display());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn get_pdf_dir(&self) -> Result<PathBuf, LearnerError> {
+    Ok(self.get_config("pdf_dir").await?.map_or_else(Self::default_pdf_path, PathBuf::from))
+  }
+
+  /// Gets the template downloaded PDFs are named after.
+  ///
+  /// This is the `pdf_filename_template` config value if set, or
+  /// [`DEFAULT_FILENAME_TEMPLATE`](crate::paper::DEFAULT_FILENAME_TEMPLATE) otherwise. See
+  /// [`Paper::format_filename`] for the placeholders.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// db.set_config("pdf_filename_template", "{year}_{first_author}_{title}").await?;
+  /// assert_eq!(db.get_pdf_filename_template().await?, "{year}_{first_author}_{title}");
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn get_pdf_filename_template(&self) -> Result<String, LearnerError> {
+    Ok(
+      self
+        .get_config("pdf_filename_template")
+        .await?
+        .unwrap_or_else(|| DEFAULT_FILENAME_TEMPLATE.to_string()),
+    )
+  }
+
+  /// Records a PDF file location and status for a paper.
+  ///
+  /// The path is stored as an absolute path, along with the file's size if it exists. A
+  /// successful download is returned as [`Paper::pdf_path`] by papers loaded from the database.
+  ///
+  /// # Arguments
+  ///
+  /// * `paper_id` - The database ID of the paper
+  /// * `path` - Path to the file, made absolute relative to the current directory
+  /// * `filename` - The filename
+  /// * `status` - Download status ('success', 'failed', 'pending')
+  /// * `error` - Optional error message if download failed
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing the file ID on success
+  pub async fn record_pdf(
+    &self,
+    paper_id: i64,
+    path: PathBuf,
+    filename: String,
+    status: &str,
+    error: Option<String>,
+  ) -> Result<i64, LearnerError> {
+    let path = std::path::absolute(&path)?;
+    let size = std::fs::metadata(&path).ok().map(|metadata| metadata.len() as i64);
+    let path_str = path.to_string_lossy().to_string();
+    let status = status.to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let tx = conn.transaction()?;
+
+        let id = tx.query_row(
+          "INSERT OR REPLACE INTO files (
+                      paper_id, path, filename, download_status, error_message, size
+                  ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                  RETURNING id",
+          params![paper_id, path_str, filename, status, error, size],
+          |row| row.get(0),
+        )?;
+
+        tx.commit()?;
+        Ok(id)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Records where a paper's PDF was downloaded to, looking the paper up by its source.
+  ///
+  /// This is [`Database::record_pdf`] for a successful download, for callers that only know the
+  /// paper's source and identifier. The path is then returned as [`Paper::pdf_path`] by
+  /// [`Database::get_paper_by_source_id`], [`Database::search_papers`] and the other reads.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier within that source
+  /// * `path` - Path to the downloaded file
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::NotFound`] if no paper matches, or a database error.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, paper::Source};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// db.set_pdf_path(&Source::Arxiv, "2301.07041", "papers/fhe.pdf".into()).await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn set_pdf_path(
+    &self,
+    source: &Source,
+    source_id: &str,
+    path: PathBuf,
+  ) -> Result<(), LearnerError> {
+    let paper_id = self.get_paper_id(source, source_id).await?.ok_or(LearnerError::NotFound)?;
+    let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    self.record_pdf(paper_id, path, filename, "success", None).await?;
+    Ok(())
+  }
+
+  /// Gets the PDF status for a paper.
+  ///
+  /// # Arguments
+  ///
+  /// * `paper_id` - The database ID of the paper
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - Some((PathBuf, String, String, Option<String>)) with the path, filename, status, and error
+  /// - None if no PDF entry exists
+  pub async fn get_pdf_status(
+    &self,
+    paper_id: i64,
+  ) -> Result<Option<(PathBuf, String, String, Option<String>)>, LearnerError> {
+    self
+      .conn
+      .call(move |conn| {
+        let mut stmt = conn.prepare_cached(
+          "SELECT path, filename, download_status, error_message FROM files 
+                   WHERE paper_id = ?1",
+        )?;
+
+        let result = stmt.query_row([paper_id], |row| {
+          Ok((
+            PathBuf::from(row.get::<_, String>(0)?),
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Option<String>>(3)?,
+          ))
+        });
+
+        match result {
+          Ok(info) => Ok(Some(info)),
+          Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+          Err(e) => Err(e.into()),
+        }
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Checks the downloaded PDF of every paper that has one.
+  ///
+  /// A paper's PDF is looked for where its download was recorded (see
+  /// [`Paper::download_and_record_pdf`]), or otherwise under its usual name in `pdf_dir` (see
+  /// [`Database::get_pdf_filename_template`]), as for PDFs downloaded before their location was
+  /// recorded. Papers without a recorded download or a file under their usual name have not been
+  /// downloaded and are left out.
+  ///
+  /// # Arguments
+  ///
+  /// * `pdf_dir` - The directory PDFs are stored in, e.g. from [`Database::get_pdf_dir`]
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - Each downloaded paper with the state of its PDF, in insertion order
+  /// - A [`LearnerError`] if loading the papers or reading a file fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::{Database, PdfStatus};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// for (paper, status) in db.verify_pdfs(&db.get_pdf_dir().await?).await? {
+  ///   if status != PdfStatus::Valid {
+  ///     println!("{}: {:?}", paper.title, status);
+  ///   }
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn verify_pdfs(&self, pdf_dir: &Path) -> Result<Vec<(Paper, PdfStatus)>, LearnerError> {
+    let template = self.get_pdf_filename_template().await?;
+    let mut results = Vec::new();
+    for paper in self.export_papers(None).await? {
+      let path = match &paper.pdf_path {
+        Some(path) => path.clone(),
+        None => {
+          let path = pdf_dir.join(format!("{}.pdf", paper.format_filename(&template, Some(50))));
+          if !path.exists() {
+            continue;
+          }
+          path
+        },
+      };
+
+      let status = match std::fs::read(&path) {
+        Ok(bytes) if validate_pdf(&bytes) => PdfStatus::Valid,
+        Ok(_) => PdfStatus::Invalid,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => PdfStatus::Missing,
+        Err(e) => return Err(e.into()),
+      };
+      results.push((paper, status));
+    }
+    Ok(results)
+  }
+
+  /// Finds the papers whose PDF can be downloaded but hasn't been, in the order they were saved.
+  ///
+  /// These are the papers with a [`Paper::pdf_url`] but no recorded download (see
+  /// [`Paper::download_and_record_pdf`]), so [`Paper::pdf_path`] is `None` for all of them.
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The papers missing their PDF
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open(Database::default_path()).await?;
+  /// let pdf_dir = db.get_pdf_dir().await?;
+  /// for paper in db.papers_missing_pdf().await? {
+  ///   paper.download_and_record_pdf(&db, pdf_dir.clone()).await?;
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn papers_missing_pdf(&self) -> Result<Vec<Paper>, LearnerError> {
+    self
+      .conn
+      .call(|conn| {
+        let ids = conn
+          .prepare_cached(
+            "SELECT id FROM papers
+             WHERE pdf_url IS NOT NULL
+               AND id NOT IN (SELECT paper_id FROM files WHERE download_status = 'success')
+             ORDER BY id",
+          )?
+          .query_map([], |row| row.get(0))?
+          .collect::<Result<Vec<i64>, _>>()?;
+
+        let papers =
+          ids.into_iter().map(|id| load_paper(conn, id)).collect::<Result<Vec<_>, _>>()?;
+        Ok(papers)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Attaches a tag to a paper.
+  ///
+  /// Tags are free-form labels such as `"to-read"` or `"ml-theory"`. They are normalized by
+  /// trimming surrounding whitespace and lowercasing, so `" Important"` and `"important"` are
+  /// the same tag.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
+  /// * `tag` - The tag to attach
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - `true` if the tag was attached, `false` if the paper already had it
+  /// - A [`LearnerError`] if the tag is empty, the paper is not in the database
+  ///   ([`LearnerError::NotFound`]), or the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, paper::Source};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// db.add_tag(&Source::Arxiv, "2301.07041", "important").await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn add_tag(
+    &self,
+    source: &Source,
+    source_id: &str,
+    tag: &str,
+  ) -> Result<bool, LearnerError> {
+    let tag = normalize_tag(tag)?;
+    let source = source.to_string();
+    let source_id = source_id.to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let paper_id: Option<i64> = conn
+          .query_row(
+            "SELECT id FROM papers WHERE source = ?1 AND source_identifier = ?2",
+            params![source, source_id],
+            |row| row.get(0),
+          )
+          .optional()?;
+        let Some(paper_id) = paper_id else {
+          return Ok(Err(LearnerError::NotFound));
+        };
+
+        let added = conn
+          .execute("INSERT OR IGNORE INTO tags (paper_id, tag) VALUES (?1, ?2)", params![
+            paper_id, tag
+          ])?;
+        Ok(Ok(added > 0))
+      })
+      .await?
+  }
+
+  /// Detaches a tag from a paper.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
+  /// * `tag` - The tag to detach, compared case-insensitively
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - `true` if the tag was detached, `false` if the paper didn't have it or doesn't exist
+  /// - A [`LearnerError`] if the query fails
+  pub async fn remove_tag(
+    &self,
+    source: &Source,
+    source_id: &str,
+    tag: &str,
+  ) -> Result<bool, LearnerError> {
+    let tag = tag.trim().to_lowercase();
+    let source = source.to_string();
+    let source_id = source_id.to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let removed = conn.execute(
+          "DELETE FROM tags
+           WHERE tag = ?3
+             AND paper_id = (SELECT id FROM papers WHERE source = ?1 AND source_identifier = ?2)",
+          params![source, source_id, tag],
+        )?;
+        Ok(removed > 0)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Gets the tags attached to a paper, in alphabetical order.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The paper's tags, which is empty if it has none or is not in the database
+  /// - A [`LearnerError`] if the query fails
+  pub async fn get_tags(
+    &self,
+    source: &Source,
+    source_id: &str,
+  ) -> Result<Vec<String>, LearnerError> {
+    let source = source.to_string();
+    let source_id = source_id.to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let mut stmt = conn.prepare_cached(
+          "SELECT tags.tag FROM tags
+           JOIN papers ON papers.id = tags.paper_id
+           WHERE papers.source = ?1 AND papers.source_identifier = ?2
+           ORDER BY tags.tag",
+        )?;
+        let tags = stmt
+          .query_map(params![source, source_id], |row| row.get(0))?
+          .collect::<Result<Vec<String>, _>>()?;
+        Ok(tags)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Finds every paper with a given tag, in the order they were saved.
+  ///
+  /// # Arguments
+  ///
+  /// * `tag` - The tag to look for, normalized like in [`Database::add_tag`]
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The tagged papers, including their authors
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// for paper in db.search_by_tag("to-read").await? {
+  ///   println!("{}", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search_by_tag(&self, tag: &str) -> Result<Vec<Paper>, LearnerError> {
+    let tag = tag.trim().to_lowercase();
+
+    self
+      .conn
+      .call(move |conn| {
+        let ids = conn
+          .prepare_cached("SELECT paper_id FROM tags WHERE tag = ?1 ORDER BY paper_id")?
+          .query_map([tag], |row| row.get(0))?
+          .collect::<Result<Vec<i64>, _>>()?;
+
+        let papers =
+          ids.into_iter().map(|id| load_paper(conn, id)).collect::<Result<Vec<_>, _>>()?;
+        Ok(papers)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Finds every paper with an author whose name contains `name`, in the order they were
+  /// saved.
+  ///
+  /// Matching is case-insensitive (for ASCII letters), so `"tur"` matches "Alan Turing". Unlike
+  /// [`Database::search_papers`], this matches the text literally rather than as an FTS5 query.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - Part of an author's name
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The matching papers, including all of their authors
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// for paper in db.search_by_author("Turing").await? {
+  ///   println!("{}", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search_by_author(&self, name: &str) -> Result<Vec<Paper>, LearnerError> {
+    let pattern = format!("%{}%", escape_like(name.trim()));
+
+    self
+      .conn
+      .call(move |conn| {
+        let ids = conn
+          .prepare_cached(
+            "SELECT DISTINCT paper_id FROM authors
+             WHERE name LIKE ?1 ESCAPE '\\'
+             ORDER BY paper_id",
+          )?
+          .query_map([pattern], |row| row.get(0))?
+          .collect::<Result<Vec<i64>, _>>()?;
+
+        let papers =
+          ids.into_iter().map(|id| load_paper(conn, id)).collect::<Result<Vec<_>, _>>()?;
+        Ok(papers)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Finds every paper by an author, in the order they were saved.
+  ///
+  /// Unlike [`Database::search_by_author`], this matches whole names, and tolerates differences
+  /// in case, accents, and whitespace (see [`format::normalize_name`]), so `"ivan damgard"`
+  /// finds papers by "Ivan Damgård".
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - The author's full name
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The author's papers, including all of their authors
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// for paper in db.get_papers_by_author("jens groth").await? {
+  ///   println!("{}", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn get_papers_by_author(&self, name: &str) -> Result<Vec<Paper>, LearnerError> {
+    let normalized = format::normalize_name(name);
+
+    self
+      .conn
+      .call(move |conn| {
+        let ids = conn
+          .prepare_cached(
+            "SELECT DISTINCT paper_id FROM authors WHERE normalized_name = ?1 ORDER BY paper_id",
+          )?
+          .query_map([normalized], |row| row.get(0))?
+          .collect::<Result<Vec<i64>, _>>()?;
+
+        let papers =
+          ids.into_iter().map(|id| load_paper(conn, id)).collect::<Result<Vec<_>, _>>()?;
+        Ok(papers)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Lists the distinct authors in the database, most prolific first, then alphabetically.
+  ///
+  /// Spellings of a name that differ only in case, accents, or whitespace count as one author
+  /// (see [`format::normalize_name`]), listed under the spelling saved first.
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - Each author with the number of papers by them
+  /// - A [`LearnerError`] if the query fails
+  pub async fn list_authors(&self) -> Result<Vec<AuthorCount>, LearnerError> {
+    self
+      .conn
+      .call(|conn| {
+        // SQLite takes `name` from the row holding `MIN(id)`, i.e. the first one saved
+        let mut stmt = conn.prepare_cached(
+          "SELECT name, MIN(id), COUNT(DISTINCT paper_id) AS paper_count
+           FROM authors
+           GROUP BY normalized_name
+           ORDER BY paper_count DESC, normalized_name",
+        )?;
+        let authors = stmt
+          .query_map([], |row| {
+            Ok(AuthorCount {
+              name:        row.get(0)?,
+              paper_count: row.get::<_, i64>(2)? as usize,
+            })
+          })?
+          .collect::<Result<Vec<_>, _>>()?;
+        Ok(authors)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Finds every paper with a given keyword, in the order they were saved.
+  ///
+  /// The keyword must match one of the paper's [`Paper::keywords`] in full, ignoring case
+  /// (for ASCII letters), so `"cs.cr"` finds papers in arXiv's `cs.CR` category but `"cs"`
+  /// finds nothing.
+  ///
+  /// # Arguments
+  ///
+  /// * `keyword` - The keyword to look for, e.g. an arXiv category or Crossref subject area
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The matching papers, including their authors
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// for paper in db.search_by_keyword("cs.CR").await? {
+  ///   println!("{}", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn search_by_keyword(&self, keyword: &str) -> Result<Vec<Paper>, LearnerError> {
+    let keyword = keyword.trim().to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let ids = conn
+          .prepare_cached(
+            "SELECT DISTINCT papers.id FROM papers, json_each(papers.keywords)
+             WHERE json_each.value = ?1 COLLATE NOCASE
+             ORDER BY papers.id",
+          )?
+          .query_map([keyword], |row| row.get(0))?
+          .collect::<Result<Vec<i64>, _>>()?;
+
+        let papers =
+          ids.into_iter().map(|id| load_paper(conn, id)).collect::<Result<Vec<_>, _>>()?;
+        Ok(papers)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Counts the papers in the database by source, by publication year, and by the month they
+  /// were added, along with their authors and the size of the database.
+  ///
+  /// The counts come from aggregate queries, so no papers are loaded.
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The [`DatabaseStats`] for the database
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::database::Database;
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// let stats = db.get_statistics().await?;
+  /// println!("{} papers, {} with PDFs", stats.total_papers, stats.papers_with_pdf);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn get_statistics(&self) -> Result<DatabaseStats, LearnerError> {
+    self
+      .conn
+      .call(|conn| {
+        let mut stmt = conn.prepare_cached(
+          "SELECT papers.source,
+                  CAST(substr(papers.publication_date, 1, 4) AS INTEGER) AS year,
+                  COUNT(*),
+                  COUNT(files.id)
+           FROM papers
+           LEFT JOIN files
+             ON files.paper_id = papers.id AND files.download_status = 'success'
+           GROUP BY papers.source, year",
+        )?;
+
+        let mut stats = DatabaseStats::default();
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+          let source = Source::from_str(&row.get::<_, String>(0)?).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+          })?;
+          let year: i32 = row.get(1)?;
+          let count = row.get::<_, i64>(2)? as usize;
+
+          stats.total_papers += count;
+          *stats.by_source.entry(source).or_default() += count;
+          *stats.by_year.entry(year).or_default() += count;
+          stats.papers_with_pdf += row.get::<_, i64>(3)? as usize;
+        }
+
+        stats.added_by_month = conn
+          .prepare_cached(
+            "SELECT substr(created_at, 1, 7) AS month, COUNT(*) FROM papers GROUP BY month",
+          )?
+          .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?
+          .collect::<Result<_, _>>()?;
+        stats.total_authors =
+          conn.query_row("SELECT COUNT(DISTINCT normalized_name) FROM authors", [], |row| {
+            row.get::<_, i64>(0)
+          })? as usize;
+        stats.database_size = conn.query_row(
+          "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+          [],
+          |row| row.get::<_, i64>(0),
+        )? as u64;
+
+        Ok(stats)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Sets how far along reading a paper is.
+  ///
+  /// The times reading started and finished are kept up to date: marking a paper as
+  /// [`ReadingStatus::Reading`] records when reading started, marking it as
+  /// [`ReadingStatus::Read`] records when it finished, and marking it as
+  /// [`ReadingStatus::Unread`] clears both. Setting the status a paper already has changes
+  /// nothing.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
+  /// * `status` - The new reading status
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] indicating success, or a [`LearnerError`] if the paper is not in the
+  /// database ([`LearnerError::NotFound`]) or the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, paper::{ReadingStatus, Source}};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// db.set_reading_status(&Source::Arxiv, "2301.07041", ReadingStatus::Reading).await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn set_reading_status(
+    &self,
+    source: &Source,
+    source_id: &str,
+    status: ReadingStatus,
+  ) -> Result<(), LearnerError> {
+    let source = source.to_string();
+    let source_id = source_id.to_string();
+    let now = Utc::now();
+
+    self
+      .conn
+      .call(move |conn| {
+        // The right-hand sides see the status before the update
+        let updated = conn.execute(
+          "UPDATE papers SET
+             started_reading_at = CASE
+               WHEN ?3 = 'unread' THEN NULL
+               WHEN ?3 = 'reading' AND reading_status != 'reading' THEN ?4
+               ELSE started_reading_at
+             END,
+             finished_reading_at = CASE
+               WHEN ?3 != 'read' THEN NULL
+               WHEN reading_status != 'read' THEN ?4
+               ELSE finished_reading_at
+             END,
+             reading_status = ?3
+           WHERE source = ?1 AND source_identifier = ?2",
+          params![source, source_id, status.as_str(), now],
+        )?;
+        Ok(if updated == 0 { Err(LearnerError::NotFound) } else { Ok(()) })
+      })
+      .await?
+  }
+
+  /// Gets how far along reading a paper is, and when that changed.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The paper's [`ReadingProgress`], or `None` if it is not in the database
+  /// - A [`LearnerError`] if the query fails
+  pub async fn get_reading_status(
+    &self,
+    source: &Source,
+    source_id: &str,
+  ) -> Result<Option<ReadingProgress>, LearnerError> {
+    let source = source.to_string();
+    let source_id = source_id.to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        Ok(
+          conn
+            .query_row(
+              "SELECT reading_status, started_reading_at, finished_reading_at FROM papers
+               WHERE source = ?1 AND source_identifier = ?2",
+              params![source, source_id],
+              |row| {
+                Ok(ReadingProgress {
+                  status:      ReadingStatus::from_str(&row.get::<_, String>(0)?).map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                      0,
+                      rusqlite::types::Type::Text,
+                      Box::new(e),
+                    )
+                  })?,
+                  started_at:  row.get(1)?,
+                  finished_at: row.get(2)?,
+                })
+              },
+            )
+            .optional()?,
+        )
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Adds a free-text note to a paper, e.g. a reading note in Markdown.
+  ///
+  /// A paper can have any number of notes. They are part of the full-text index, so
+  /// [`Database::search_papers`] finds papers by the content of their notes. Notes are kept when
+  /// the paper's metadata is updated, and deleted along with the paper.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
+  /// * `content` - The text of the note
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The new [`Note`]
+  /// - [`LearnerError::NotFound`] if the paper is not in the database, or another [`LearnerError`]
+  ///   if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, paper::Source};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// db.add_note(&Source::Arxiv, "2301.07041", "Nice construction in §4").await?;
+  /// assert_eq!(db.search_papers("construction").await?.len(), 1);
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn add_note(
+    &self,
+    source: &Source,
+    source_id: &str,
+    content: &str,
+  ) -> Result<Note, LearnerError> {
+    let source = source.to_string();
+    let source_id = source_id.to_string();
+    let content = content.to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let note = conn
+          .query_row(
+            "INSERT INTO notes (paper_id, content)
+             SELECT id, ?3 FROM papers WHERE source = ?1 AND source_identifier = ?2
+             RETURNING id, content, created_at",
+            params![source, source_id, content],
+            |row| {
+              Ok(Note { id: row.get(0)?, content: row.get(1)?, created_at: row.get(2)? })
+            },
+          )
+          .optional()?;
+        Ok(note.ok_or(LearnerError::NotFound))
+      })
+      .await?
+  }
+
+  /// Gets the notes on a paper, oldest first.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The paper's notes, which is empty if it has none or is not in the database
+  /// - A [`LearnerError`] if the query fails
+  pub async fn get_notes(
+    &self,
+    source: &Source,
+    source_id: &str,
+  ) -> Result<Vec<Note>, LearnerError> {
+    let source = source.to_string();
+    let source_id = source_id.to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let mut stmt = conn.prepare_cached(
+          "SELECT notes.id, notes.content, notes.created_at FROM notes
+           JOIN papers ON papers.id = notes.paper_id
+           WHERE papers.source = ?1 AND papers.source_identifier = ?2
+           ORDER BY notes.created_at, notes.id",
+        )?;
+        let notes = stmt
+          .query_map(params![source, source_id], |row| {
+            Ok(Note { id: row.get(0)?, content: row.get(1)?, created_at: row.get(2)? })
+          })?
+          .collect::<Result<Vec<_>, _>>()?;
+        Ok(notes)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Deletes a note.
+  ///
+  /// # Arguments
+  ///
+  /// * `note_id` - The note's [`Note::id`], as shown by [`Database::get_notes`]
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - `true` if the note was deleted, `false` if there is no such note
+  /// - A [`LearnerError`] if the query fails
+  pub async fn delete_note(&self, note_id: i64) -> Result<bool, LearnerError> {
+    self
+      .conn
+      .call(move |conn| Ok(conn.execute("DELETE FROM notes WHERE id = ?1", [note_id])? > 0))
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Creates a named collection of papers.
+  ///
+  /// Collection names are trimmed, and compared regardless of case, so `"Cryptography"` and
+  /// `"cryptography"` are the same collection.
+  ///
+  /// # Arguments
+  ///
+  /// * `name` - The collection's name
+  /// * `description` - What the collection is for, if anything
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The new, empty [`Collection`]
+  /// - [`LearnerError::InvalidCollection`] if the name is empty, [`LearnerError::CollectionExists`]
+  ///   if the name is taken, or another [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, paper::Source};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// db.create_collection("Cryptography", Some("Papers for the survey")).await?;
+  /// db.add_paper_to_collection(&Source::Arxiv, "2301.07041", "Cryptography").await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn create_collection(
+    &self,
+    name: &str,
+    description: Option<&str>,
+  ) -> Result<Collection, LearnerError> {
+    let name = normalize_collection(name)?;
+    let description = description.map(str::to_string);
+
+    self
+      .conn
+      .call(move |conn| {
+        let id = conn
+          .query_row(
+            "INSERT INTO collections (name, description) VALUES (?1, ?2)
+             ON CONFLICT (name) DO NOTHING
+             RETURNING id",
+            params![name, description],
+            |row| row.get(0),
+          )
+          .optional()?;
+        let Some(id) = id else {
+          return Ok(Err(LearnerError::CollectionExists(name)));
+        };
+        Ok(Ok(Collection { id, name, description, paper_count: 0 }))
+      })
+      .await?
+  }
+
+  /// Adds a paper to a collection.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
+  /// * `collection` - The collection's name, compared regardless of case
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - `true` if the paper was added, `false` if it was already in the collection
+  /// - [`LearnerError::NotFound`] if the paper is not in the database,
+  ///   [`LearnerError::CollectionNotFound`] if the collection doesn't exist, or another
+  ///   [`LearnerError`] if the query fails
+  pub async fn add_paper_to_collection(
+    &self,
+    source: &Source,
+    source_id: &str,
+    collection: &str,
+  ) -> Result<bool, LearnerError> {
+    let source = source.to_string();
+    let source_id = source_id.to_string();
+    let collection = collection.trim().to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let paper_id: Option<i64> = conn
+          .query_row(
+            "SELECT id FROM papers WHERE source = ?1 AND source_identifier = ?2",
+            params![source, source_id],
+            |row| row.get(0),
+          )
+          .optional()?;
+        let Some(paper_id) = paper_id else {
+          return Ok(Err(LearnerError::NotFound));
+        };
+        let Some(collection_id) = find_collection_id(conn, &collection)? else {
+          return Ok(Err(LearnerError::CollectionNotFound(collection)));
+        };
+
+        let added = conn.execute(
+          "INSERT OR IGNORE INTO collection_papers (collection_id, paper_id) VALUES (?1, ?2)",
+          params![collection_id, paper_id],
+        )?;
+        Ok(Ok(added > 0))
+      })
+      .await?
+  }
+
+  /// Removes a paper from a collection. The paper itself stays in the database.
+  ///
+  /// # Arguments
+  ///
+  /// * `source` - The paper's source system
+  /// * `source_id` - The paper's identifier in that source
+  /// * `collection` - The collection's name, compared regardless of case
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - `true` if the paper was removed, `false` if it wasn't in the collection or doesn't exist
+  /// - [`LearnerError::CollectionNotFound`] if the collection doesn't exist, or another
+  ///   [`LearnerError`] if the query fails
+  pub async fn remove_paper_from_collection(
+    &self,
+    source: &Source,
+    source_id: &str,
+    collection: &str,
+  ) -> Result<bool, LearnerError> {
+    let source = source.to_string();
+    let source_id = source_id.to_string();
+    let collection = collection.trim().to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let Some(collection_id) = find_collection_id(conn, &collection)? else {
+          return Ok(Err(LearnerError::CollectionNotFound(collection)));
+        };
+        let removed = conn.execute(
+          "DELETE FROM collection_papers
+           WHERE collection_id = ?1
+             AND paper_id = (SELECT id FROM papers WHERE source = ?2 AND source_identifier = ?3)",
+          params![collection_id, source, source_id],
+        )?;
+        Ok(Ok(removed > 0))
+      })
+      .await?
+  }
+
+  /// Lists every collection, in alphabetical order, with how many papers each holds.
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The collections, which is empty if none were created
+  /// - A [`LearnerError`] if the query fails
+  pub async fn list_collections(&self) -> Result<Vec<Collection>, LearnerError> {
+    self
+      .conn
+      .call(|conn| {
+        let mut stmt = conn.prepare_cached(
+          "SELECT collections.id, collections.name, collections.description,
+                  COUNT(collection_papers.paper_id)
+           FROM collections
+           LEFT JOIN collection_papers ON collection_papers.collection_id = collections.id
+           GROUP BY collections.id
+           ORDER BY collections.name",
+        )?;
+        let collections = stmt
+          .query_map([], |row| {
+            Ok(Collection {
+              id:          row.get(0)?,
+              name:        row.get(1)?,
+              description: row.get(2)?,
+              paper_count: row.get::<_, i64>(3)? as usize,
+            })
+          })?
+          .collect::<Result<Vec<_>, _>>()?;
+        Ok(collections)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Gets the papers in a collection, in the order they were added to it.
+  ///
+  /// # Arguments
+  ///
+  /// * `collection` - The collection's name, compared regardless of case
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The collection's papers, including their authors
+  /// - [`LearnerError::CollectionNotFound`] if the collection doesn't exist, or another
+  ///   [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, paper::to_bibtex_bibliography};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// let papers = db.get_collection_papers("Cryptography").await?;
+  /// std::fs::write("cryptography.bib", to_bibtex_bibliography(&papers))?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn get_collection_papers(&self, collection: &str) -> Result<Vec<Paper>, LearnerError> {
+    let collection = collection.trim().to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let Some(collection_id) = find_collection_id(conn, &collection)? else {
+          return Ok(Err(LearnerError::CollectionNotFound(collection)));
+        };
+        let ids = conn
+          .prepare_cached(
+            "SELECT paper_id FROM collection_papers WHERE collection_id = ?1 ORDER BY rowid",
+          )?
+          .query_map([collection_id], |row| row.get(0))?
+          .collect::<Result<Vec<i64>, _>>()?;
+
+        let papers =
+          ids.into_iter().map(|id| load_paper(conn, id)).collect::<Result<Vec<_>, _>>()?;
+        Ok(Ok(papers))
+      })
+      .await?
+  }
+
+  /// Adds a category to those whose newly submitted papers the daemon saves.
+  ///
+  /// # Arguments
+  ///
+  /// * `category` - The category in its source, e.g. `"cs.CR"` for arXiv
+  /// * `source` - The source system the category belongs to
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - `true` if the category was added, `false` if it was already watched
+  /// - A [`LearnerError`] if the query fails
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{database::Database, paper::Source};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = Database::open("papers.db").await?;
+  /// db.add_watch_target("cs.CR", &Source::Arxiv).await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn add_watch_target(
+    &self,
+    category: &str,
+    source: &Source,
+  ) -> Result<bool, LearnerError> {
+    let category = category.trim().to_string();
+    let source = source.to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let added = conn.execute(
+          "INSERT INTO watch_targets (category, source) VALUES (?1, ?2)
+           ON CONFLICT (category, source) DO NOTHING",
+          params![category, source],
+        )?;
+        Ok(added > 0)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Stops watching a category. Papers already saved from it stay in the database.
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - `true` if the category was removed, `false` if it wasn't watched
+  /// - A [`LearnerError`] if the query fails
+  pub async fn remove_watch_target(
+    &self,
+    category: &str,
+    source: &Source,
+  ) -> Result<bool, LearnerError> {
+    let category = category.trim().to_string();
+    let source = source.to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        let removed = conn
+          .execute("DELETE FROM watch_targets WHERE category = ?1 AND source = ?2", params![
+            category, source
+          ])?;
+        Ok(removed > 0)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Lists the watched categories, grouped by source.
+  ///
+  /// # Returns
+  ///
+  /// Returns a [`Result`] containing either:
+  /// - The watched categories, with when each was last checked
+  /// - A [`LearnerError`] if the query fails
+  pub async fn list_watch_targets(&self) -> Result<Vec<WatchTarget>, LearnerError> {
+    self
+      .conn
+      .call(|conn| {
+        let targets = conn
+          .prepare_cached(
+            "SELECT category, source, last_checked FROM watch_targets ORDER BY source, category",
+          )?
+          .query_map([], |row| {
+            Ok(WatchTarget {
+              category:     row.get(0)?,
+              source:       Source::from_str(&row.get::<_, String>(1)?).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                  1,
+                  rusqlite::types::Type::Text,
+                  Box::new(e),
+                )
+              })?,
+              last_checked: row.get(2)?,
+            })
+          })?
+          .collect::<Result<Vec<_>, _>>()?;
+        Ok(targets)
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+
+  /// Records when a watched category's new papers were fetched, so that the next check only
+  /// looks for papers submitted since.
+  ///
+  /// Does nothing if the category isn't watched.
+  pub async fn set_watch_target_checked(
+    &self,
+    category: &str,
+    source: &Source,
+    checked_at: DateTime<Utc>,
+  ) -> Result<(), LearnerError> {
+    let category = category.to_string();
+    let source = source.to_string();
+
+    self
+      .conn
+      .call(move |conn| {
+        conn.execute(
+          "UPDATE watch_targets SET last_checked = ?3 WHERE category = ?1 AND source = ?2",
+          params![category, source, checked_at],
+        )?;
+        Ok(())
+      })
+      .await
+      .map_err(LearnerError::from)
+  }
+}
+
+/// Looks up the source and identifier of the paper saved with `doi`, regardless of case,
+/// other than the paper with the database ID `except`.
+fn find_doi(
+  conn: &rusqlite::Connection,
+  doi: Option<&str>,
+  except: Option<i64>,
+) -> Result<Option<(String, String)>, rusqlite::Error> {
+  let Some(doi) = doi else { return Ok(None) };
+  conn
+    .prepare_cached(
+      "SELECT source, source_identifier FROM papers WHERE doi = ?1 COLLATE NOCASE AND id IS NOT ?2",
+    )?
+    .query_row(params![doi, except], |row| Ok((row.get(0)?, row.get(1)?)))
+    .optional()
+}
+
+/// Replaces the stored paper with the database ID `paper_id` with `paper`, unless `paper`'s DOI
+/// is already saved for another paper.
+fn replace_paper_keeping_dois_unique(
+  tx: &rusqlite::Transaction,
+  paper_id: i64,
+  paper: &Paper,
+) -> Result<Result<(), LearnerError>, rusqlite::Error> {
+  if let Some((source, source_identifier)) = find_doi(tx, paper.doi.as_deref(), Some(paper_id))? {
+    return Ok(Err(LearnerError::Duplicate(format!("{source} {source_identifier}"))));
+  }
+  replace_paper(tx, paper_id, paper)?;
+  Ok(Ok(()))
+}
+
+/// Trims a collection name, rejecting names that are empty once trimmed.
+fn normalize_collection(name: &str) -> Result<String, LearnerError> {
+  let normalized = name.trim();
+  if normalized.is_empty() {
+    return Err(LearnerError::InvalidCollection(name.to_string()));
+  }
+  Ok(normalized.to_string())
+}
+
+/// Looks up a collection's database ID by name, regardless of case.
+fn find_collection_id(
+  conn: &rusqlite::Connection,
+  name: &str,
+) -> Result<Option<i64>, rusqlite::Error> {
+  conn
+    .prepare_cached("SELECT id FROM collections WHERE name = ?1")?
+    .query_row([name], |row| row.get(0))
+    .optional()
+}
+
+/// Escapes the `LIKE` wildcards `%` and `_` (and the escape character `\` itself) in `text`.
+fn escape_like(text: &str) -> String {
+  text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// Trims and lowercases a tag, rejecting tags that are empty once trimmed.
+fn normalize_tag(tag: &str) -> Result<String, LearnerError> {
+  let normalized = tag.trim().to_lowercase();
+  if normalized.is_empty() {
+    return Err(LearnerError::InvalidTag(tag.to_string()));
+  }
+  Ok(normalized)
+}
+
+/// Inserts a paper and its authors, returning the new paper's database ID.
+///
+/// The caller is responsible for wrapping this in a transaction.
+fn insert_paper(conn: &rusqlite::Connection, paper: &Paper) -> Result<i64, rusqlite::Error> {
+  let paper_id = conn
+    .prepare_cached(
+      "INSERT INTO papers (
+                    title, abstract_text, publication_date, 
+                    source, source_identifier, pdf_url, doi, version, categories, keywords,
+                    reading_status, raw_abstract
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+                RETURNING id",
+    )?
+    .query_row(
+      params![
+        &paper.title,
+        &paper.abstract_text,
+        &paper.publication_date,
+        paper.source.to_string(),
+        &paper.source_identifier,
+        &paper.pdf_url,
+        &paper.doi,
+        &paper.version,
+        join_categories(&paper.categories),
+        encode_keywords(&paper.keywords),
+        paper.reading_status.as_str(),
+        &paper.raw_abstract,
+      ],
+      |row| row.get::<_, i64>(0),
+    )?;
+
+  insert_authors(conn, paper_id, &paper.authors)?;
+
+  // Tags carried over from an export; blank ones are dropped
+  let mut stmt =
+    conn.prepare_cached("INSERT OR IGNORE INTO tags (paper_id, tag) VALUES (?1, ?2)")?;
+  for tag in paper.tags.iter().filter_map(|tag| normalize_tag(tag).ok()) {
+    stmt.execute(params![paper_id, tag])?;
+  }
+
+  Ok(paper_id)
+}
+
+/// Looks up the database ID of the stored paper with the same source and identifier as `paper`.
+fn find_paper_id(
+  conn: &rusqlite::Connection,
+  paper: &Paper,
+) -> Result<Option<i64>, rusqlite::Error> {
+  conn
+    .query_row(
+      "SELECT id FROM papers WHERE source = ?1 AND source_identifier = ?2",
+      params![paper.source.to_string(), &paper.source_identifier],
+      |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Inserts a paper's authors, along with their names normalized for matching (see
+/// [`format::normalize_name`]).
+///
+/// The caller is responsible for wrapping this in a transaction.
+fn insert_authors(
+  conn: &rusqlite::Connection,
+  paper_id: i64,
+  authors: &[Author],
+) -> Result<(), rusqlite::Error> {
+  let mut stmt = conn.prepare_cached(
+    "INSERT INTO authors (paper_id, name, normalized_name, affiliation, email)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+  )?;
+  for author in authors {
+    stmt.execute(params![
+      paper_id,
+      &author.name,
+      format::normalize_name(&author.name),
+      &author.affiliation,
+      &author.email
+    ])?;
+  }
+  Ok(())
+}
+
+/// Replaces a stored paper's metadata and authors with those of `paper`.
+///
+/// The caller is responsible for wrapping this in a transaction.
+fn replace_paper(
+  conn: &rusqlite::Connection,
+  paper_id: i64,
+  paper: &Paper,
+) -> Result<(), rusqlite::Error> {
+  conn.execute(
+    "UPDATE papers
+     SET title = ?2, abstract_text = ?3, publication_date = ?4, pdf_url = ?5, doi = ?6,
+         version = ?7, categories = ?8, keywords = ?9, raw_abstract = ?10,
+         updated_at = datetime('now')
+     WHERE id = ?1",
+    params![
+      paper_id,
+      &paper.title,
+      &paper.abstract_text,
+      &paper.publication_date,
+      &paper.pdf_url,
+      &paper.doi,
+      &paper.version,
+      join_categories(&paper.categories),
+      encode_keywords(&paper.keywords),
+      &paper.raw_abstract,
+    ],
+  )?;
+
+  conn.execute("DELETE FROM authors WHERE paper_id = ?1", [paper_id])?;
+  insert_authors(conn, paper_id, &paper.authors)?;
+
+  Ok(())
+}
+
+/// Whether `new` is a later version than the stored version `old`, e.g. `"v2"` over `"v1"`.
+///
+/// A versioned paper is newer than an unversioned one; unversioned papers are never newer.
+fn is_newer_version(new: Option<&str>, old: Option<&str>) -> bool {
+  let number = |version: &str| version.trim_start_matches('v').parse::<u32>().ok();
+  match (new.and_then(number), old.and_then(number)) {
+    (Some(new), Some(old)) => new > old,
+    (Some(_), None) => true,
+    (None, _) => false,
+  }
+}
+
+/// Drops the search index of databases created before author names, notes or abstracts were
+/// indexed, so that `init.sql` creates the current one. Returns whether the index was dropped
+/// and needs to be filled again.
+fn drop_outdated_search_index(conn: &rusqlite::Connection) -> Result<bool, rusqlite::Error> {
+  let (exists, is_current): (bool, bool) = conn.query_row(
+    "SELECT
+       EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'papers_fts'),
+       EXISTS (SELECT 1 FROM pragma_table_info('papers_fts') WHERE name = 'abstract_text')",
+    [],
+    |row| Ok((row.get(0)?, row.get(1)?)),
+  )?;
+  if !exists || is_current {
+    return Ok(false);
+  }
+
+  conn.execute_batch(
+    "DROP TRIGGER IF EXISTS papers_ai;
+     DROP TRIGGER IF EXISTS papers_ad;
+     DROP TRIGGER IF EXISTS papers_au;
+     DROP TABLE papers_fts;",
+  )?;
+  Ok(true)
+}
+
+/// Rebuilds the notes table of databases created when papers had at most one note, keeping the
+/// notes, so that `003_notes.sql` finds the current table and triggers.
+fn migrate_single_notes(conn: &rusqlite::Connection) -> Result<(), rusqlite::Error> {
+  let single: bool = conn.query_row(
+    "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'notes')
+        AND NOT EXISTS (SELECT 1 FROM pragma_table_info('notes') WHERE name = 'id')",
+    [],
+    |row| row.get(0),
+  )?;
+  if !single {
+    return Ok(());
+  }
+
+  conn.execute_batch(
+    "DROP TRIGGER IF EXISTS notes_ai;
+     DROP TRIGGER IF EXISTS notes_ad;
+     DROP TRIGGER IF EXISTS notes_au;
+     ALTER TABLE notes RENAME TO single_notes;",
+  )?;
+  conn.execute_batch(include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/migrations/003_notes.sql"
+  )))?;
+  conn.execute_batch(
+    "INSERT INTO notes (paper_id, content, created_at)
+       SELECT paper_id, content, updated_at FROM single_notes;
+     DROP TABLE single_notes;",
+  )
+}
+
+/// Upgrades the schema of databases created by older versions of the library.
+///
+/// `init.sql` only creates missing tables, so columns added to existing tables since are added
+/// here, along with the normalized names of authors saved before names were normalized and the
+/// lowercase names of sources saved before [`Source`] was displayed in lowercase, and the index
+/// papers are looked up by DOI with.
+fn migrate(conn: &rusqlite::Connection) -> Result<(), rusqlite::Error> {
+  for (table, column, definition) in [
+    ("papers", "version", "TEXT"),
+    ("papers", "categories", "TEXT"),
+    ("papers", "keywords", "TEXT"),
+    ("papers", "reading_status", "TEXT NOT NULL DEFAULT 'unread'"),
+    ("papers", "started_reading_at", "TEXT"),
+    ("papers", "finished_reading_at", "TEXT"),
+    ("papers", "raw_abstract", "TEXT"),
+    ("files", "size", "INTEGER"),
+    ("authors", "normalized_name", "TEXT"),
+  ] {
+    let exists: bool = conn.query_row(
+      "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = ?2",
+      [table, column],
+      |row| row.get(0),
+    )?;
+    if !exists {
+      conn.execute(&format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"), [])?;
+    }
+  }
+
+  // Papers saved when sources were stored as e.g. `Arxiv` rather than `arxiv`
+  conn.execute("UPDATE papers SET source = lower(source) WHERE source <> lower(source)", [])?;
+
+  // Authors saved before names were normalized
+  let unnormalized = conn
+    .prepare("SELECT id, name FROM authors WHERE normalized_name IS NULL")?
+    .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+    .collect::<Result<Vec<_>, _>>()?;
+  let mut stmt = conn.prepare("UPDATE authors SET normalized_name = ?2 WHERE id = ?1")?;
+  for (id, name) in unnormalized {
+    stmt.execute(params![id, format::normalize_name(&name)])?;
+  }
+  conn.execute(
+    "CREATE INDEX IF NOT EXISTS idx_authors_normalized_name ON authors(normalized_name)",
+    [],
+  )?;
+  // Separate from init.sql's `idx_papers_doi`, which compares DOIs case-sensitively
+  conn
+    .execute("CREATE INDEX IF NOT EXISTS idx_papers_doi_nocase ON papers(doi COLLATE NOCASE)", [
+    ])?;
+  Ok(())
+}
+
+/// Joins categories for storage in the `categories` column, storing no categories as `NULL`.
+fn join_categories(categories: &[String]) -> Option<String> {
+  (!categories.is_empty()).then(|| categories.join(","))
+}
+
+/// Splits the `categories` column back into a list of categories.
+fn split_categories(categories: Option<String>) -> Vec<String> {
+  categories
+    .map(|categories| categories.split(',').map(str::to_string).collect())
+    .unwrap_or_default()
+}
+
+/// Encodes keywords as a JSON array for the `keywords` column, storing no keywords as `NULL`.
+///
+/// Unlike categories, keywords such as Crossref subject areas may contain commas.
+fn encode_keywords(keywords: &[String]) -> Option<String> {
+  (!keywords.is_empty()).then(|| serde_json::Value::from(keywords).to_string())
+}
+
+/// Decodes the `keywords` column back into a list of keywords.
+fn decode_keywords(keywords: Option<String>) -> Result<Vec<String>, rusqlite::Error> {
+  keywords.map_or(Ok(Vec::new()), |keywords| {
+    serde_json::from_str(&keywords).map_err(|e| {
+      rusqlite::Error::FromSqlConversionFailure(9, rusqlite::types::Type::Text, Box::new(e))
+    })
+  })
+}
+
+/// Loads a complete paper, including its authors, tags, and downloaded PDF, by database ID.
+fn load_paper(conn: &rusqlite::Connection, paper_id: i64) -> Result<Paper, rusqlite::Error> {
+  let mut paper_stmt = conn.prepare_cached(
+    "SELECT title, abstract_text, publication_date,
+                    source, source_identifier, pdf_url, doi, version, categories, keywords,
+                    reading_status, raw_abstract, created_at
+             FROM papers 
+             WHERE id = ?",
+  )?;
+
+  let mut paper = paper_stmt.query_row([paper_id], |row| {
+    Ok(Paper {
+      title:             row.get(0)?,
+      abstract_text:     row.get(1)?,
+      publication_date:  row.get(2)?,
+      source:            Source::from_str(&row.get::<_, String>(3)?).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+      })?,
+      source_identifier: row.get(4)?,
+      pdf_url:           row.get(5)?,
+      doi:               row.get(6)?,
+      version:           row.get(7)?,
+      categories:        split_categories(row.get(8)?),
+      keywords:          decode_keywords(row.get(9)?)?,
+      reading_status:    ReadingStatus::from_str(&row.get::<_, String>(10)?).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(10, rusqlite::types::Type::Text, Box::new(e))
+      })?,
+      raw_abstract:      row.get(11)?,
+      added_at:          Some(row.get(12)?),
+      authors:           Vec::new(),
+      tags:              Vec::new(),
+      pdf_path:          None,
+    })
+  })?;
+
+  let mut author_stmt = conn.prepare_cached(
+    "SELECT name, affiliation, email
+             FROM authors
+             WHERE paper_id = ?",
+  )?;
+
+  paper.authors = author_stmt
+    .query_map([paper_id], |row| {
+      Ok(Author { name: row.get(0)?, affiliation: row.get(1)?, email: row.get(2)? })
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+
+  paper.tags = conn
+    .prepare_cached("SELECT tag FROM tags WHERE paper_id = ? ORDER BY tag")?
+    .query_map([paper_id], |row| row.get(0))?
+    .collect::<Result<Vec<_>, _>>()?;
+
+  paper.pdf_path = conn
+    .prepare_cached("SELECT path FROM files WHERE paper_id = ? AND download_status = 'success'")?
+    .query_row([paper_id], |row| row.get::<_, String>(0))
+    .optional()?
+    .map(PathBuf::from);
+
+  Ok(paper)
+}
+
+#[cfg(test)]
+mod tests {
+
+  use chrono::Datelike;
+
+  use super::*;
+
+  /// Helper function to create a test paper
+  fn create_test_paper() -> Paper {
+    Paper {
+      title:             "Test Paper".to_string(),
+      abstract_text:     "This is a test abstract".to_string(),
+      raw_abstract:      None,
+      publication_date:  Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+      source:            Source::Arxiv,
+      source_identifier: "2401.00000".to_string(),
+      pdf_url:           Some("https://arxiv.org/pdf/2401.00000".to_string()),
+      doi:               Some("10.1000/test.123".to_string()),
+      version:           None,
+      categories:        Vec::new(),
+      keywords:          Vec::new(),
+      tags:              Vec::new(),
+      pdf_path:          None,
+      reading_status:    ReadingStatus::Unread,
+      added_at:          None,
+      authors:           vec![
+        Author {
+          name:        "John Doe".to_string(),
+          affiliation: Some("Test University".to_string()),
+          email:       Some("john@test.edu".to_string()),
+        },
+        Author { name: "Jane Smith".to_string(), affiliation: None, email: None },
+      ],
+    }
+  }
+
+  /// Helper function to set up a test database
+  async fn setup_test_db() -> (Database, tempfile::TempDir) {
+    let dir = tempdir().unwrap();
+    let db_path = dir.path().join("test.db");
+    let db = Database::open(&db_path).await.unwrap();
+    (db, dir)
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_database_creation() {
+    let dir = tempdir().unwrap();
+    let db_path = dir.path().join("test.db");
+
+    // Create database
+    let _db = Database::open(&db_path).await.unwrap();
+
+    // Check that file exists
+    assert!(db_path.exists());
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_save_and_retrieve_paper() {
+    let (db, _dir) = setup_test_db().await;
+    let paper = create_test_paper();
+
+    // Save paper
+    let paper_id = db.save_paper(&paper).await.unwrap();
+    assert!(paper_id > 0);
+
+    // Retrieve paper
+    let retrieved = db
+      .get_paper_by_source_id(&paper.source, &paper.source_identifier)
+      .await
+      .unwrap()
+      .expect("Paper should exist");
+
+    // Verify paper data
+    assert_eq!(retrieved.title, paper.title);
+    assert_eq!(retrieved.abstract_text, paper.abstract_text);
+    assert_eq!(retrieved.publication_date, paper.publication_date);
+    assert_eq!(retrieved.source, paper.source);
+    assert_eq!(retrieved.source_identifier, paper.source_identifier);
+    assert_eq!(retrieved.pdf_url, paper.pdf_url);
+    assert_eq!(retrieved.doi, paper.doi);
+
+    // Verify authors
+    assert_eq!(retrieved.authors.len(), paper.authors.len());
+    assert_eq!(retrieved.authors[0].name, paper.authors[0].name);
+    assert_eq!(retrieved.authors[0].affiliation, paper.authors[0].affiliation);
+    assert_eq!(retrieved.authors[0].email, paper.authors[0].email);
+    assert_eq!(retrieved.authors[1].name, paper.authors[1].name);
+    assert_eq!(retrieved.authors[1].affiliation, None);
+    assert_eq!(retrieved.authors[1].email, None);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_search_papers_in_date_range() {
+    let (db, _dir) = setup_test_db().await;
+    let mut papers = create_test_papers(3);
+    for (paper, year) in papers.iter_mut().zip(2020..) {
+      paper.publication_date = Utc.with_ymd_and_hms(year, 6, 1, 12, 0, 0).unwrap();
+      db.save_paper(paper).await.unwrap();
+    }
+    let date = |year, month, day| Some(Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap());
+    let years = |found: Vec<Paper>| {
+      let mut years: Vec<_> = found.iter().map(|paper| paper.publication_date.year()).collect();
+      years.sort();
+      years
+    };
+
+    let found = db.search_papers_in_date_range("synthetic", date(2021, 1, 1), date(2021, 12, 31));
+    assert_eq!(years(found.await.unwrap()), [2021]);
+    let found = db.search_papers_in_date_range("synthetic", date(2021, 1, 1), None);
+    assert_eq!(years(found.await.unwrap()), [2021, 2022]);
+    let found = db.search_papers_in_date_range("synthetic", None, date(2021, 6, 1));
+    assert_eq!(years(found.await.unwrap()), [2020]);
+    let found = db.search_papers_in_date_range("synthetic", None, None);
+    assert_eq!(years(found.await.unwrap()), [2020, 2021, 2022]);
+
+    // Bounds are inclusive
+    let noon = Some(Utc.with_ymd_and_hms(2022, 6, 1, 12, 0, 0).unwrap());
+    let found = db.search_papers_in_date_range("synthetic", noon, noon);
+    assert_eq!(years(found.await.unwrap()), [2022]);
+
+    // Counts reflect the date filter too
+    let query = SearchQuery::new("synthetic").published_between(date(2021, 1, 1), None);
+    assert_eq!(db.search(&query).await.unwrap().total_matches, 2);
+
+    // and the reading status filter
+    db.set_reading_status(&papers[1].source, &papers[1].source_identifier, ReadingStatus::Read)
+      .await
+      .unwrap();
+    let outcome = db.search(&SearchQuery::new("synthetic").status(ReadingStatus::Read)).await;
+    let outcome = outcome.unwrap();
+    assert_eq!(outcome.total_matches, 1);
+    assert_eq!(years(outcome.papers), [2021]);
+    let query = SearchQuery::new("synthetic")
+      .published_between(date(2021, 1, 1), None)
+      .status(ReadingStatus::Unread);
+    assert_eq!(years(db.search(&query).await.unwrap().papers), [2022]);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_get_nonexistent_paper() {
+    let (db, _dir) = setup_test_db().await;
+
+    let result = db.get_paper_by_source_id(&Source::Arxiv, "nonexistent").await.unwrap();
+
+    assert!(result.is_none());
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_full_text_search() {
+    let (db, _dir) = setup_test_db().await;
+
+    // Save a few papers
+    let mut paper1 = create_test_paper();
+    paper1.title = "Neural Networks in Machine Learning".to_string();
+    paper1.abstract_text = "This paper discusses deep learning".to_string();
+    paper1.source_identifier = "2401.00001".to_string();
+
+    let mut paper2 = create_test_paper();
+    paper2.title = "Advanced Algorithms".to_string();
+    paper2.abstract_text = "Classical computer science topics".to_string();
+    paper2.source_identifier = "2401.00002".to_string();
+    paper2.doi = None;
+
+    db.save_paper(&paper1).await.unwrap();
+    db.save_paper(&paper2).await.unwrap();
+
+    // Search for papers
+    let results = db.search_papers("neural").await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].title, paper1.title);
+
+    let results = db.search_papers("learning").await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].source_identifier, paper1.source_identifier);
+
+    let results = db.search_papers("algorithms").await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].title, paper2.title);
+
+    // Abstracts are searched too, ranked below titles
+    let results = db.search_papers("classical").await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].title, paper2.title);
+    paper2.title = "Deep Learning Algorithms".to_string();
+    paper2.abstract_text = "Learning, learning, learning".to_string();
+    db.update_paper(&paper2).await.unwrap();
+    let results = db.search_papers("learning").await.unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(db.search_papers("classical").await.unwrap().is_empty());
+    assert_search_index_consistent(&db).await;
+  }
+
+  #[tokio::test]
+  async fn test_search_results_carry_authors() {
+    let (db, _dir) = setup_test_db().await;
+    let mut abstract_match = create_test_paper();
+    abstract_match.title = "Sorting Networks".to_string();
+    abstract_match.abstract_text = "Lattices appear here".to_string();
+    abstract_match.source_identifier = "2401.00001".to_string();
+    abstract_match.authors.truncate(1);
+    let mut title_match = create_test_paper();
+    title_match.title = "Lattice Cryptography".to_string();
+    title_match.source_identifier = "2401.00002".to_string();
+    title_match.doi = None;
+    db.save_paper(&abstract_match).await.unwrap();
+    db.save_paper(&title_match).await.unwrap();
+
+    // Ranked by relevance, each with all of its authors
+    let results = db.search_papers("lattice*").await.unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].title, "Lattice Cryptography");
+    let names: Vec<_> = results[0].authors.iter().map(|author| author.name.as_str()).collect();
+    assert_eq!(names, ["John Doe", "Jane Smith"]);
+    assert_eq!(results[0].authors[0].affiliation.as_deref(), Some("Test University"));
+    assert_eq!(results[1].authors.len(), 1);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_search_reports_total_matches() {
+    let (db, _dir) = setup_test_db().await;
+
+    // Seed a library where more papers match than fit on one page
+    for i in 0..25 {
+      let mut paper = create_test_paper();
+      paper.title = format!("Neural Network Study {i}");
+      paper.source_identifier = format!("2401.{i:05}");
+      paper.doi = None;
+      db.save_paper(&paper).await.unwrap();
+    }
+    let mut other = create_test_paper();
+    other.title = "Advanced Algorithms".to_string();
+    other.source_identifier = "2401.99999".to_string();
+    db.save_paper(&other).await.unwrap();
+
+    let outcome = db.search(&SearchQuery::new("neural").limit(10)).await.unwrap();
+    assert_eq!(outcome.papers.len(), 10);
+    assert_eq!(outcome.total_matches, 25);
+
+    // The last page is short but the total is unchanged
+    let outcome = db.search(&SearchQuery::new("neural").limit(10).offset(20)).await.unwrap();
+    assert_eq!(outcome.papers.len(), 5);
+    assert_eq!(outcome.total_matches, 25);
+
+    // Without a limit every match is returned
+    let outcome = db.search(&SearchQuery::new("neural")).await.unwrap();
+    assert_eq!(outcome.papers.len(), 25);
+    assert_eq!(outcome.total_matches, 25);
+    assert_eq!(db.search_papers("neural").await.unwrap().len(), 25);
+
+    let outcome = db.search(&SearchQuery::new("nonexistent")).await.unwrap();
+    assert!(outcome.papers.is_empty());
+    assert_eq!(outcome.total_matches, 0);
+  }
+
+  /// Helper function to create `count` distinct test papers
+  fn create_test_papers(count: usize) -> Vec<Paper> {
+    (0..count)
+      .map(|i| {
+        let mut paper = create_test_paper();
+        paper.title = format!("Synthetic Paper {i}");
+        paper.source_identifier = format!("2401.{i:05}");
+        paper.doi = Some(format!("10.1000/test.{i}"));
+        paper
+      })
+      .collect()
+  }
+
+  /// A progress sink recording every update it receives
+  #[derive(Default)]
+  struct RecordingSink(std::sync::Mutex<Vec<(usize, usize, String)>>);
+
+  impl ProgressSink for RecordingSink {
+    fn on_progress(&self, done: usize, total: usize, stage: &str) {
+      self.0.lock().unwrap().push((done, total, stage.to_string()));
+    }
+  }
+
+  impl RecordingSink {
+    /// Asserts updates arrive in the given stages with monotonically increasing progress
+    fn assert_progress(&self, stages: &[&str]) {
+      let updates = self.0.lock().unwrap();
+      let seen: Vec<&str> = updates.iter().fold(Vec::new(), |mut seen, (_, _, stage)| {
+        if seen.last() != Some(&stage.as_str()) {
+          seen.push(stage);
+        }
+        seen
+      });
+      assert_eq!(seen, stages);
+
+      for stage in stages {
+        let updates: Vec<_> = updates.iter().filter(|(_, _, s)| s == stage).collect();
+        assert!(updates.windows(2).all(|w| w[0].0 <= w[1].0), "{stage} progress decreased");
+        let (done, total, _) = updates.last().unwrap();
+        assert_eq!(done, total, "{stage} did not finish");
+      }
+    }
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_import_reports_progress() {
+    let (db, _dir) = setup_test_db().await;
+    let papers = create_test_papers(350);
+    let sink = RecordingSink::default();
+
+    let summary = db.import_papers(&papers, Some(&sink)).await.unwrap();
+    assert_eq!(summary, ImportSummary { imported: 350, skipped: 0 });
+    sink.assert_progress(&["importing papers"]);
+    assert_eq!(sink.0.lock().unwrap().last().unwrap().0, 350);
+
+    // Re-importing skips everything already present
+    let summary = db.import_papers(&papers[..120], None).await.unwrap();
+    assert_eq!(summary, ImportSummary { imported: 0, skipped: 120 });
+
+    assert_eq!(db.search_papers("synthetic").await.unwrap().len(), 350);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_import_skips_saved_dois() {
+    let (db, _dir) = setup_test_db().await;
+    let preprint = create_test_paper();
+    let mut published = create_test_paper();
+    published.source = Source::DOI;
+    published.source_identifier = "10.1000/TEST.123".to_string();
+    published.doi = Some("10.1000/TEST.123".to_string());
+
+    // The second paper shares the first one's DOI, in the same batch
+    let summary = db.import_papers(&[preprint, published.clone()], None).await.unwrap();
+    assert_eq!(summary, ImportSummary { imported: 1, skipped: 1 });
+    assert!(db.get_paper_by_source_id(&Source::DOI, "10.1000/TEST.123").await.unwrap().is_none());
+
+    // And in a later import
+    let summary = db.import_papers(&[published], None).await.unwrap();
+    assert_eq!(summary, ImportSummary { imported: 0, skipped: 1 });
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_export_and_reindex_report_progress() {
+    let (db, _dir) = setup_test_db().await;
+    db.import_papers(&create_test_papers(250), None).await.unwrap();
+
+    let sink = RecordingSink::default();
+    let papers = db.export_papers(Some(&sink)).await.unwrap();
+    assert_eq!(papers.len(), 250);
+    assert_eq!(papers[0].title, "Synthetic Paper 0");
+    assert_eq!(papers[0].authors.len(), 2);
+    sink.assert_progress(&["exporting papers"]);
+
+    let sink = RecordingSink::default();
+    db.rebuild_search_index(Some(&sink)).await.unwrap();
+    sink.assert_progress(&["rebuilding search index"]);
+    assert_eq!(db.search_papers("synthetic").await.unwrap().len(), 250);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_export_bibtex() {
+    let (db, _dir) = setup_test_db().await;
+    db.import_papers(&create_test_papers(3), None).await.unwrap();
+
+    let bibtex = db.export_bibtex().await.unwrap();
+    assert_eq!(bibtex.matches("@misc{doe2024synthetic").count(), 3);
+    assert!(bibtex.contains("@misc{doe2024syntheticc,"));
+    assert!(bibtex.contains("title = {Synthetic Paper 2}"));
+    assert!(bibtex.contains("}\n\n@misc{"));
+
+    let (db, _dir) = setup_test_db().await;
+    assert!(db.export_bibtex().await.unwrap().is_empty());
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_export_ris() {
+    let (db, _dir) = setup_test_db().await;
+    assert!(db.export_ris().await.unwrap().is_empty());
+
+    db.import_papers(&create_test_papers(2), None).await.unwrap();
+    let ris = db.export_ris().await.unwrap();
+    assert_eq!(ris.matches("TY  - ").count(), 2);
+    assert_eq!(ris.matches("ER  - \n").count(), 2);
+    assert!(ris.contains("TI  - Synthetic Paper 1\n"));
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_export_markdown_and_html() {
+    let (db, _dir) = setup_test_db().await;
+    let mut papers = create_test_papers(3);
+    for (paper, year) in papers.iter_mut().zip([2021, 2023, 2022]) {
+      paper.publication_date = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap();
+    }
+    db.import_papers(&papers, None).await.unwrap();
+    db.add_tag(&papers[0].source, &papers[0].source_identifier, "crypto").await.unwrap();
+
+    let titles = |document: &str, prefix: &str| {
+      document
+        .lines()
+        .filter_map(|line| line.strip_prefix(prefix)?.split(['<', ']']).next())
+        .map(str::to_string)
+        .collect::<Vec<_>>()
+    };
+    let markdown = db.export_markdown(PaperGrouping::Source, PaperSort::Date, false).await.unwrap();
+    assert!(markdown.contains("\n## arXiv\n"));
+    assert_eq!(titles(&markdown, "### ["), [
+      "Synthetic Paper 1",
+      "Synthetic Paper 2",
+      "Synthetic Paper 0"
+    ]);
+    let markdown = db.export_markdown(PaperGrouping::Tag, PaperSort::Title, true).await.unwrap();
+    assert!(markdown.contains("## crypto\n\n### [Synthetic Paper 0]"));
+    assert!(markdown.contains("## Untagged\n\n### [Synthetic Paper 1]"));
+
+    let html = db.export_html(PaperGrouping::Year, PaperSort::Title, false).await.unwrap();
+    assert_eq!(html.matches("<article>").count(), 3);
+    assert!(html.find("<h2>2023</h2>").unwrap() < html.find("<h2>2021</h2>").unwrap());
+  }
+
+  #[test]
+  fn test_sort_papers() {
+    let mut papers = create_test_papers(3);
+    papers[0].title = "beta".to_string();
+    papers[1].title = "Alpha".to_string();
+    papers[2].title = "gamma".to_string();
+    PaperSort::Title.sort_papers(&mut papers, true);
+    assert_eq!(papers.iter().map(|p| p.title.as_str()).collect::<Vec<_>>(), [
+      "Alpha", "beta", "gamma"
+    ]);
+    PaperSort::Title.sort_papers(&mut papers, false);
+    assert_eq!(papers[0].title, "gamma");
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_search_by_keyword() {
+    let (db, _dir) = setup_test_db().await;
+    let mut papers = create_test_papers(3);
+    papers[0].keywords = vec!["cs.CR".to_string(), "cs.LG".to_string()];
+    papers[1].keywords = vec!["Computer Networks and Communications".to_string()];
+    papers[2].keywords = vec!["cs.CR".to_string()];
+    for paper in &papers {
+      db.save_paper(paper).await.unwrap();
+    }
+
+    let stored = db
+      .get_paper_by_source_id(&papers[1].source, &papers[1].source_identifier)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(stored.keywords, ["Computer Networks and Communications"]);
+
+    let found = db.search_by_keyword("cs.cr").await.unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].source_identifier, papers[0].source_identifier);
+    assert_eq!(found[0].keywords, ["cs.CR", "cs.LG"]);
+    assert_eq!(found[1].source_identifier, papers[2].source_identifier);
+
+    // Keywords containing commas are kept whole, and must match in full
+    assert_eq!(
+      db.search_by_keyword(" computer networks and communications").await.unwrap().len(),
+      1
+    );
+    assert!(db.search_by_keyword("Computer Networks").await.unwrap().is_empty());
+    assert!(db.search_by_keyword("cs").await.unwrap().is_empty());
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_get_statistics() {
+    let (db, _dir) = setup_test_db().await;
+    let empty = db.get_statistics().await.unwrap();
+    assert_eq!(empty, DatabaseStats { database_size: empty.database_size, ..Default::default() });
+    assert!(empty.database_size > 0);
+
+    let mut papers = create_test_papers(5);
+    for (paper, (source, year)) in papers.iter_mut().zip([
+      (Source::Arxiv, 2021),
+      (Source::Arxiv, 2023),
+      (Source::DOI, 2023),
+      (Source::IACR, 2023),
+      (Source::Arxiv, 2021),
+    ]) {
+      paper.source = source;
+      paper.publication_date = Utc.with_ymd_and_hms(year, 6, 1, 0, 0, 0).unwrap();
+    }
+    let mut ids = Vec::new();
+    for paper in &papers {
+      ids.push(db.save_paper(paper).await.unwrap());
+    }
+    db.record_pdf(ids[0], PathBuf::from("/a.pdf"), "a.pdf".into(), "success", None).await.unwrap();
+    db.record_pdf(ids[2], PathBuf::from("/c.pdf"), "c.pdf".into(), "success", None).await.unwrap();
+    db.record_pdf(ids[3], PathBuf::from("/d.pdf"), "d.pdf".into(), "failed", None).await.unwrap();
+
+    let stats = db.get_statistics().await.unwrap();
+    assert_eq!(stats.total_papers, 5);
+    assert_eq!(
+      stats.by_source,
+      HashMap::from([(Source::Arxiv, 3), (Source::DOI, 1), (Source::IACR, 1)])
+    );
+    assert_eq!(stats.by_year, BTreeMap::from([(2021, 2), (2023, 3)]));
+    assert_eq!(stats.papers_with_pdf, 2);
+    // Every test paper has the same authors
+    assert_eq!(stats.total_authors, papers[0].authors.len());
+    assert_eq!(stats.added_by_month, BTreeMap::from([(Utc::now().format("%Y-%m").to_string(), 5)]));
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_paper_categories_round_trip() {
+    let (db, _dir) = setup_test_db().await;
+    let mut paper = create_test_paper();
+    paper.categories = vec!["cs.CR".to_string(), "cs.LG".to_string()];
+    db.save_paper(&paper).await.unwrap();
+
+    let stored =
+      db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await.unwrap().unwrap();
+    assert_eq!(stored.categories, ["cs.CR", "cs.LG"]);
+    let found = db.search_papers("Test").await.unwrap();
+    assert_eq!(found[0].categories, ["cs.CR", "cs.LG"]);
+
+    // No categories are stored as none, not as a single empty category
+    let mut uncategorized = create_test_papers(2).remove(1);
+    uncategorized.categories.clear();
+    db.save_paper(&uncategorized).await.unwrap();
+    let stored = db
+      .get_paper_by_source_id(&uncategorized.source, &uncategorized.source_identifier)
+      .await
+      .unwrap()
+      .unwrap();
+    assert!(stored.categories.is_empty());
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_paper_version_round_trip() {
+    let (db, _dir) = setup_test_db().await;
+    let mut paper = create_test_paper();
+    paper.version = Some("v2".to_string());
+    db.save_paper(&paper).await.unwrap();
+
+    let stored = db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await.unwrap();
+    assert_eq!(stored.unwrap().version.as_deref(), Some("v2"));
+    let listed = db.list_papers(PaperSort::Title, true, 1, 0).await.unwrap();
+    assert_eq!(listed[0].version.as_deref(), Some("v2"));
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_save_newer_version_updates() {
+    let (db, _dir) = setup_test_db().await;
+    let mut paper = create_test_paper();
+    paper.version = Some("v1".to_string());
+    let id = db.save_paper(&paper).await.unwrap();
+    db.add_tag(&paper.source, &paper.source_identifier, "to-read").await.unwrap();
+
+    paper.version = Some("v2".to_string());
+    paper.title = "Revised Paper".to_string();
+    paper.authors.truncate(1);
+    assert_eq!(db.save_paper(&paper).await.unwrap(), id);
+
+    let stored =
+      db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await.unwrap().unwrap();
+    assert_eq!(stored.version.as_deref(), Some("v2"));
+    assert_eq!(stored.title, "Revised Paper");
+    assert_eq!(stored.authors.len(), 1);
+    assert_eq!(db.get_tags(&paper.source, &paper.source_identifier).await.unwrap(), ["to-read"]);
+    assert_eq!(db.search_papers("Revised").await.unwrap().len(), 1);
+    // The old title is gone from the index, though the abstract still mentions a test
+    assert!(db.search_papers("title:Test").await.unwrap().is_empty());
+    assert_search_index_consistent(&db).await;
+
+    // The same or an older version is a duplicate
+    assert!(db.save_paper(&paper).await.unwrap_err().is_duplicate_error());
+    paper.version = Some("v1".to_string());
+    assert!(db.save_paper(&paper).await.unwrap_err().is_duplicate_error());
+
+    // A newer version can't take a DOI saved for another paper
+    let mut other = create_test_paper();
+    other.source_identifier = "2401.00001".to_string();
+    other.doi = Some("10.1000/other".to_string());
+    db.save_paper(&other).await.unwrap();
+    paper.version = Some("v3".to_string());
+    paper.doi = Some("10.1000/OTHER".to_string());
+    let err = db.save_paper(&paper).await.unwrap_err();
+    assert!(matches!(&err, LearnerError::Duplicate(saved_as) if saved_as == "arxiv 2401.00001"));
+    let stored =
+      db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await.unwrap().unwrap();
+    assert_eq!(stored.version.as_deref(), Some("v2"));
+    assert_eq!(stored.doi.as_deref(), Some("10.1000/test.123"));
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_get_paper_by_doi_across_sources() {
+    let (db, _dir) = setup_test_db().await;
+    let preprint = create_test_paper();
+    db.save_paper(&preprint).await.unwrap();
+
+    // Found by DOI, regardless of case, though saved from arXiv
+    let found = db.get_paper_by_doi("10.1000/TEST.123").await.unwrap().unwrap();
+    assert_eq!((found.source, found.source_identifier.as_str()), (Source::Arxiv, "2401.00000"));
+    assert!(db.get_paper_by_doi("10.1000/other").await.unwrap().is_none());
+
+    // The published version of the same paper isn't saved a second time
+    let mut published = create_test_paper();
+    published.source = Source::DOI;
+    published.source_identifier = "10.1000/test.123".to_string();
+    let err = db.save_paper(&published).await.unwrap_err();
+    assert!(matches!(&err, LearnerError::Duplicate(saved_as) if saved_as == "arxiv 2401.00000"));
+    assert!(err.is_duplicate_error());
+    assert!(db.get_paper_by_source_id(&Source::DOI, "10.1000/test.123").await.unwrap().is_none());
+
+    // Papers without a DOI, or with another one, are saved as usual
+    published.doi = None;
+    db.save_paper(&published).await.unwrap();
+    let mut other = create_test_paper();
+    other.source_identifier = "2401.00001".to_string();
+    other.doi = Some("10.1000/other".to_string());
+    db.save_paper(&other).await.unwrap();
+
+    // Case-insensitive lookups by DOI are indexed
+    let plan = db
+      .conn
+      .call(|conn| {
+        let plan = conn
+          .prepare(
+            "EXPLAIN QUERY PLAN SELECT source, source_identifier FROM papers WHERE doi = ?1 \
+             COLLATE NOCASE AND id IS NOT ?2",
+          )?
+          .query_map(params!["10.1000/TEST.123", 1], |row| row.get::<_, String>(3))?
+          .collect::<Result<Vec<_>, _>>()?;
+        Ok(plan.join("\n"))
+      })
+      .await
+      .unwrap();
+    assert!(plan.contains("INDEX idx_papers_doi_nocase (doi=?)"), "{plan}");
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_update_paper() {
+    let (db, _dir) = setup_test_db().await;
+    let mut paper = create_test_paper();
+    assert!(matches!(db.update_paper(&paper).await, Err(LearnerError::NotFound)));
+
+    let id = db.save_paper(&paper).await.unwrap();
+    paper.title = "Corrected Title".to_string();
+    paper.doi = Some("10.1234/corrected".to_string());
+    paper.authors.push(Author {
+      name:        "New Author".to_string(),
+      affiliation: None,
+      email:       None,
+    });
+    assert_eq!(db.update_paper(&paper).await.unwrap(), id);
+
+    let stored =
+      db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await.unwrap().unwrap();
+    assert_eq!(stored.title, "Corrected Title");
+    assert_eq!(stored.doi.as_deref(), Some("10.1234/corrected"));
+    assert_eq!(stored.authors.len(), paper.authors.len());
+    assert_eq!(db.search_papers("Corrected").await.unwrap().len(), 1);
+    assert_search_index_consistent(&db).await;
+
+    // Nor can an update take a DOI saved for another paper
+    let mut other = create_test_paper();
+    other.source_identifier = "2401.00001".to_string();
+    other.doi = Some("10.1000/other".to_string());
+    db.save_paper(&other).await.unwrap();
+    paper.doi = Some("10.1000/Other".to_string());
+    assert!(matches!(db.update_paper(&paper).await, Err(LearnerError::Duplicate(_))));
+    assert!(matches!(db.save_or_update_paper(&paper).await, Err(LearnerError::Duplicate(_))));
+    let stored =
+      db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await.unwrap().unwrap();
+    assert_eq!(stored.doi.as_deref(), Some("10.1234/corrected"));
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_update_paper_metadata() {
+    let (db, _dir) = setup_test_db().await;
+    let paper = create_test_paper();
+
+    // Missing papers are reported without fetching anything
+    assert!(matches!(
+      db.update_paper_metadata(&paper.source, &paper.source_identifier).await,
+      Err(LearnerError::NotFound)
+    ));
+    assert!(db.get_updated_at(&paper.source, &paper.source_identifier).await.unwrap().is_none());
+
+    db.save_paper(&paper).await.unwrap();
+    db.conn
+      .call(|conn| {
+        conn.execute("UPDATE papers SET updated_at = '2024-01-01 00:00:00'", [])?;
+        Ok(())
+      })
+      .await
+      .unwrap();
+    let saved_at =
+      db.get_updated_at(&paper.source, &paper.source_identifier).await.unwrap().unwrap();
+    assert_eq!(saved_at, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+
+    db.update_paper(&paper).await.unwrap();
+    let updated_at =
+      db.get_updated_at(&paper.source, &paper.source_identifier).await.unwrap().unwrap();
+    assert!(updated_at > saved_at);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_save_or_update_paper() {
+    let (db, _dir) = setup_test_db().await;
+    let mut paper = create_test_paper();
+
+    let (id, inserted) = db.save_or_update_paper(&paper).await.unwrap();
+    assert!(inserted);
+
+    paper.abstract_text = "A new abstract".to_string();
+    assert_eq!(db.save_or_update_paper(&paper).await.unwrap(), (id, false));
+    let stored =
+      db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await.unwrap().unwrap();
+    assert_eq!(stored.abstract_text, "A new abstract");
+    assert_eq!(db.list_papers(PaperSort::Date, false, 10, 0).await.unwrap().len(), 1);
+
+    // A new paper with a saved DOI isn't inserted
+    paper.source_identifier = "2401.99999".to_string();
+    let err = db.save_or_update_paper(&paper).await.unwrap_err();
+    assert!(matches!(err, LearnerError::Duplicate(_)));
+  }
+
+  #[test]
+  fn test_is_newer_version() {
+    assert!(is_newer_version(Some("v2"), Some("v1")));
+    assert!(is_newer_version(Some("v10"), Some("v9")));
+    assert!(is_newer_version(Some("v1"), None));
+    assert!(!is_newer_version(Some("v1"), Some("v1")));
+    assert!(!is_newer_version(Some("v1"), Some("v2")));
+    assert!(!is_newer_version(None, Some("v1")));
+    assert!(!is_newer_version(None, None));
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_migrate_adds_columns() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("old.db");
+
+    // A papers table as created before versions and categories were stored
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn
+      .execute_batch(
+        "CREATE TABLE papers (
+          id INTEGER PRIMARY KEY,
+          title TEXT NOT NULL,
+          abstract_text TEXT NOT NULL,
+          publication_date TEXT NOT NULL,
+          source TEXT NOT NULL,
+          source_identifier TEXT NOT NULL,
+          pdf_url TEXT,
+          doi TEXT,
+          metadata TEXT,
+          created_at TEXT NOT NULL DEFAULT (datetime('now')),
+          updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+          UNIQUE(source, source_identifier)
+        );",
+      )
+      .unwrap();
+    drop(conn);
+
+    let db = Database::open(&path).await.unwrap();
+    // Authors saved before names were normalized are found by their normalized name
+    db.conn
+      .call(|conn| {
+        conn.execute(
+          "INSERT INTO papers (title, abstract_text, publication_date, source, source_identifier)
+           VALUES ('Old', '', '2020-01-01T00:00:00Z', 'Arxiv', '2001.00001')",
+          [],
+        )?;
+        conn.execute(
+          "INSERT INTO authors (paper_id, name) VALUES (last_insert_rowid(), 'Ivan Damgård')",
+          [],
+        )?;
+        Ok(())
+      })
+      .await
+      .unwrap();
+    drop(db);
+    let db = Database::open(&path).await.unwrap();
+    assert_eq!(db.get_papers_by_author("ivan damgard").await.unwrap().len(), 1);
+    // and papers saved with sources like `Arxiv` by their lowercase source
+    assert!(db.get_paper_by_source_id(&Source::Arxiv, "2001.00001").await.unwrap().is_some());
+
+    let mut paper = create_test_paper();
+    paper.version = Some("v3".to_string());
+    paper.categories = vec!["cs.CR".to_string()];
+    paper.keywords = vec!["cs.CR".to_string()];
+    db.save_paper(&paper).await.unwrap();
+    let stored =
+      db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await.unwrap().unwrap();
+    assert_eq!(stored.version.as_deref(), Some("v3"));
+    assert_eq!(stored.categories, ["cs.CR"]);
+    assert_eq!(stored.keywords, ["cs.CR"]);
+    assert_eq!(stored.reading_status, ReadingStatus::Unread);
+
+    // Opening again leaves the migrated schema alone
+    drop(db);
+    Database::open(&path).await.unwrap();
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_collect_garbage() {
+    let (db, dir) = setup_test_db().await;
+    let papers = create_test_papers(2);
+    let kept_id = db.save_paper(&papers[0]).await.unwrap();
+    let stale_id = db.save_paper(&papers[1]).await.unwrap();
+
+    let kept_path = dir.path().join("kept.pdf");
+    std::fs::write(&kept_path, b"%PDF").unwrap();
+    db.record_pdf(kept_id, kept_path, "kept.pdf".into(), "success", None).await.unwrap();
+    db.record_pdf(stale_id, dir.path().join("gone.pdf"), "gone.pdf".into(), "success", None)
+      .await
+      .unwrap();
+
+    let sink = RecordingSink::default();
+    assert_eq!(db.collect_garbage(Some(&sink)).await.unwrap(), 1);
+    sink.assert_progress(&["checking files", "compacting database"]);
+
+    assert!(db.get_pdf_status(kept_id).await.unwrap().is_some());
+    assert!(db.get_pdf_status(stale_id).await.unwrap().is_none());
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_list_papers_sorting_and_paging() {
+    let (db, _dir) = setup_test_db().await;
+
+    let entries = [
+      ("Gamma Rays", Source::DOI, "10.1000/c", 2021),
+      ("alpha Particles", Source::Arxiv, "2401.00002", 2019),
+      ("Beta Decay", Source::IACR, "2020/001", 2023),
+      ("Delta Functions", Source::Arxiv, "2401.00001", 2020),
+    ];
+    for (title, source, id, year) in entries {
+      let mut paper = create_test_paper();
+      paper.title = title.to_string();
+      paper.source = source;
+      paper.source_identifier = id.to_string();
+      paper.doi = None;
+      paper.publication_date = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap();
+      db.save_paper(&paper).await.unwrap();
+    }
+
+    let titles = |papers: Vec<Paper>| papers.into_iter().map(|p| p.title).collect::<Vec<_>>();
+
+    let papers = db.list_papers(PaperSort::Title, true, 10, 0).await.unwrap();
+    assert_eq!(papers[0].authors.len(), 2);
+    assert_eq!(titles(papers), ["alpha Particles", "Beta Decay", "Delta Functions", "Gamma Rays"]);
+
+    let papers = db.list_papers(PaperSort::Title, false, 10, 0).await.unwrap();
+    assert_eq!(titles(papers), ["Gamma Rays", "Delta Functions", "Beta Decay", "alpha Particles"]);
+
+    let papers = db.list_papers(PaperSort::Date, false, 10, 0).await.unwrap();
+    assert_eq!(titles(papers), ["Beta Decay", "Gamma Rays", "Delta Functions", "alpha Particles"]);
+
+    let papers = db.list_papers(PaperSort::Date, true, 10, 0).await.unwrap();
+    assert_eq!(titles(papers), ["alpha Particles", "Delta Functions", "Gamma Rays", "Beta Decay"]);
+
+    let papers = db.list_papers(PaperSort::Source, true, 10, 0).await.unwrap();
+    assert_eq!(titles(papers), ["Delta Functions", "alpha Particles", "Gamma Rays", "Beta Decay"]);
+
+    // Slice boundaries
+    let papers = db.list_papers(PaperSort::Title, true, 2, 0).await.unwrap();
+    assert_eq!(titles(papers), ["alpha Particles", "Beta Decay"]);
+    let papers = db.list_papers(PaperSort::Title, true, 2, 2).await.unwrap();
+    assert_eq!(titles(papers), ["Delta Functions", "Gamma Rays"]);
+    let papers = db.list_papers(PaperSort::Title, true, 2, 3).await.unwrap();
+    assert_eq!(titles(papers), ["Gamma Rays"]);
+    let papers = db.list_papers(PaperSort::Title, false, 2, 3).await.unwrap();
+    assert_eq!(titles(papers), ["alpha Particles"]);
+    assert!(db.list_papers(PaperSort::Title, true, 2, 4).await.unwrap().is_empty());
+    assert!(db.list_papers(PaperSort::Title, true, 0, 0).await.unwrap().is_empty());
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_recent_papers() {
+    let (db, _dir) = setup_test_db().await;
+    let papers = create_test_papers(3);
+    for paper in &papers {
+      db.save_paper(paper).await.unwrap();
+    }
+    assert!(papers[0].added_at.is_none());
+
+    // The second paper was saved last but added a while ago, e.g. by an older version
+    let identifier = papers[1].source_identifier.clone();
+    db.conn
+      .call(move |conn| {
+        conn.execute(
+          "UPDATE papers SET created_at = '2024-01-01 00:00:00' WHERE source_identifier = ?1",
+          [identifier],
+        )?;
+        Ok(())
+      })
+      .await
+      .unwrap();
+
+    let identifiers =
+      |papers: Vec<Paper>| papers.into_iter().map(|p| p.source_identifier).collect::<Vec<_>>();
+    let recent = db.recent_papers(10).await.unwrap();
+    assert_eq!(recent[2].added_at, Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()));
+    assert!(recent[0].added_at.unwrap() > recent[2].added_at.unwrap());
+    assert_eq!(identifiers(recent), [
+      papers[2].source_identifier.as_str(),
+      papers[0].source_identifier.as_str(),
+      papers[1].source_identifier.as_str()
+    ]);
+    assert_eq!(identifiers(db.recent_papers(1).await.unwrap()), [papers[2]
+      .source_identifier
+      .as_str()]);
+
+    // Updating a paper doesn't make it recent
+    db.update_paper(&papers[1]).await.unwrap();
+    let recent = db.recent_papers(10).await.unwrap();
+    assert_eq!(recent[2].source_identifier, papers[1].source_identifier);
+  }
+
+  #[test]
+  fn test_paper_sort_from_str() {
+    assert_eq!(PaperSort::from_str("title").unwrap(), PaperSort::Title);
+    assert_eq!(PaperSort::from_str("Date").unwrap(), PaperSort::Date);
+    assert_eq!(PaperSort::from_str("source").unwrap(), PaperSort::Source);
+    assert!(matches!(PaperSort::from_str("size"), Err(LearnerError::InvalidSort(_))));
+    assert!(!PaperSort::Date.default_ascending());
+    assert!(PaperSort::Title.default_ascending());
+  }
+
+  /// Helper function to check the full-text index matches the papers, authors and notes tables
+  async fn assert_search_index_consistent(db: &Database) {
+    let mismatches: i64 = db
+      .conn
+      .call(|conn| {
+        conn.execute("INSERT INTO papers_fts(papers_fts) VALUES('integrity-check')", [])?;
+        Ok(conn.query_row(
+          "SELECT (SELECT COUNT(*) FROM papers) != (SELECT COUNT(*) FROM papers_fts)
+                  OR EXISTS (
+                    SELECT 1 FROM papers p LEFT JOIN papers_fts f ON f.rowid = p.id
+                    WHERE f.title IS NOT p.title
+                       OR f.authors IS NOT coalesce(
+                         (SELECT group_concat(name, ' ') FROM authors WHERE paper_id = p.id), '')
+                       OR f.notes IS NOT coalesce(
+                         (SELECT group_concat(content, ' ') FROM notes WHERE paper_id = p.id), '')
+                       OR f.abstract_text IS NOT p.abstract_text)",
+          [],
+          |row| row.get(0),
+        )?)
+      })
+      .await
+      .unwrap();
+    assert_eq!(mismatches, 0, "search index should be consistent with papers");
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_search_author_names() {
+    let (db, _dir) = setup_test_db().await;
+    let mut papers = create_test_papers(3);
+    papers[0].title = "On the Size of Pairing-based Non-interactive Arguments".to_string();
+    papers[0].authors =
+      vec![Author { name: "Jens Groth".to_string(), affiliation: None, email: None }];
+    papers[1].title = "Revisiting Groth's Argument".to_string();
+    papers[2].authors =
+      vec![Author { name: "Jörg Müller".to_string(), affiliation: None, email: None }];
+    for paper in &papers {
+      db.save_paper(paper).await.unwrap();
+    }
+    assert_search_index_consistent(&db).await;
+
+    let found = db.search_papers("groth").await.unwrap();
+    assert_eq!(found.len(), 2);
+    // Title matches rank above author matches
+    assert_eq!(found[0].source_identifier, papers[1].source_identifier);
+    assert_eq!(found[1].source_identifier, papers[0].source_identifier);
+
+    // Terms may match in different fields, and diacritics are ignored
+    assert_eq!(db.search_papers("groth pairing").await.unwrap().len(), 1);
+    assert_eq!(db.search_papers("muller").await.unwrap().len(), 1);
+    assert_eq!(db.search_papers("authors:groth").await.unwrap().len(), 1);
+
+    // Replacing a paper's authors updates the index
+    let mut updated = papers[0].clone();
+    updated.authors =
+      vec![Author { name: "Alan Turing".to_string(), affiliation: None, email: None }];
+    db.update_paper(&updated).await.unwrap();
+    assert_search_index_consistent(&db).await;
+    assert_eq!(db.search_papers("groth").await.unwrap().len(), 1);
+    assert_eq!(db.search_papers("turing").await.unwrap().len(), 1);
+
+    db.remove_paper(&papers[0].source, &papers[0].source_identifier).await.unwrap();
+    assert_search_index_consistent(&db).await;
+    assert!(db.search_papers("turing").await.unwrap().is_empty());
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_migrate_title_only_search_index() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("old.db");
+
+    // A database as created before author names were indexed
+    let db = Database::open(&path).await.unwrap();
+    db.save_paper(&create_test_paper()).await.unwrap();
+    db.conn
+      .call(|conn| {
+        conn.execute_batch(
+          "DROP TRIGGER papers_ai; DROP TRIGGER papers_ad; DROP TRIGGER papers_au;
+           DROP TRIGGER authors_ai; DROP TRIGGER authors_ad; DROP TRIGGER authors_au;
+           DROP TABLE papers_fts;
+           CREATE VIRTUAL TABLE papers_fts USING fts5(
+             title, content=papers, content_rowid=id, tokenize='unicode61 remove_diacritics 1'
+           );
+           CREATE TRIGGER papers_ai AFTER INSERT ON papers BEGIN
+             INSERT INTO papers_fts(rowid, title) VALUES (new.id, new.title);
+           END;
+           INSERT INTO papers_fts(papers_fts) VALUES('rebuild');",
+        )?;
+        Ok(())
+      })
+      .await
+      .unwrap();
+    drop(db);
+
+    let db = Database::open(&path).await.unwrap();
+    assert_search_index_consistent(&db).await;
+    assert_eq!(db.search_papers("doe").await.unwrap().len(), 1);
+    assert_eq!(db.search_papers("test").await.unwrap().len(), 1);
+
+    db.save_paper(&create_test_papers(2).remove(1)).await.unwrap();
+    assert_search_index_consistent(&db).await;
+    assert_eq!(db.search_papers("doe").await.unwrap().len(), 2);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_remove_paper() {
+    let (db, _dir) = setup_test_db().await;
+    let papers = create_test_papers(2);
+    let paper_id = db.save_paper(&papers[0]).await.unwrap();
+    db.save_paper(&papers[1]).await.unwrap();
+    db.record_pdf(paper_id, PathBuf::from("/test/paper.pdf"), "paper.pdf".into(), "success", None)
+      .await
+      .unwrap();
+
+    assert!(db.remove_paper(&papers[0].source, &papers[0].source_identifier).await.unwrap());
+
+    assert!(db
+      .get_paper_by_source_id(&papers[0].source, &papers[0].source_identifier)
+      .await
+      .unwrap()
+      .is_none());
+    assert!(db.get_pdf_status(paper_id).await.unwrap().is_none());
 
-  /// Gets the PDF status for a paper.
-  ///
-  /// # Arguments
-  ///
-  /// * `paper_id` - The database ID of the paper
-  ///
-  /// # Returns
-  ///
-  /// Returns a [`Result`] containing either:
-  /// - Some((PathBuf, String, String, Option<String>)) with the path, filename, status, and error
-  /// - None if no PDF entry exists
-  pub async fn get_pdf_status(
-    &self,
-    paper_id: i64,
-  ) -> Result<Option<(PathBuf, String, String, Option<String>)>, LearnerError> {
-    self
+    // Authors are removed along with the paper
+    let orphaned: i64 = db
       .conn
       .call(move |conn| {
-        let mut stmt = conn.prepare_cached(
-          "SELECT path, filename, download_status, error_message FROM files 
-                   WHERE paper_id = ?1",
-        )?;
+        Ok(conn.query_row(
+          "SELECT COUNT(*) FROM authors WHERE paper_id = ?1",
+          [paper_id],
+          |row| row.get(0),
+        )?)
+      })
+      .await
+      .unwrap();
+    assert_eq!(orphaned, 0);
 
-        let result = stmt.query_row([paper_id], |row| {
-          Ok((
-            PathBuf::from(row.get::<_, String>(0)?),
-            row.get::<_, String>(1)?,
-            row.get::<_, String>(2)?,
-            row.get::<_, Option<String>>(3)?,
-          ))
-        });
+    // The other paper is untouched and still searchable
+    let results = db.search_papers("synthetic").await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].source_identifier, papers[1].source_identifier);
+    assert_search_index_consistent(&db).await;
 
-        match result {
-          Ok(info) => Ok(Some(info)),
-          Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-          Err(e) => Err(e.into()),
-        }
-      })
+    // Removing the last paper leaves nothing to find
+    assert!(papers[1].remove(&db).await.unwrap());
+    assert!(!papers[1].remove(&db).await.unwrap());
+    assert!(db
+      .get_paper_by_source_id(&papers[1].source, &papers[1].source_identifier)
       .await
-      .map_err(LearnerError::from)
+      .unwrap()
+      .is_none());
+    assert!(db.search_papers("synthetic").await.unwrap().is_empty());
+    assert_search_index_consistent(&db).await;
   }
-}
 
-#[cfg(test)]
-mod tests {
+  #[traced_test]
+  #[tokio::test]
+  async fn test_search_by_author() {
+    let (db, _dir) = setup_test_db().await;
+    let mut papers = create_test_papers(3);
+    papers[0].authors =
+      vec![Author { name: "Alan Turing".to_string(), affiliation: None, email: None }];
+    papers[1].authors =
+      vec![Author { name: "Alonzo Church".to_string(), affiliation: None, email: None }, Author {
+        name:        "Alan Turing".to_string(),
+        affiliation: None,
+        email:       None,
+      }];
+    papers[2].authors =
+      vec![Author { name: "Kurt Gödel".to_string(), affiliation: None, email: None }];
+    for paper in &papers {
+      db.save_paper(paper).await.unwrap();
+    }
 
-  use super::*;
+    // Partial, case-insensitive matches across all of an author's papers
+    let found = db.search_by_author("tur").await.unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].source_identifier, papers[0].source_identifier);
+    assert_eq!(found[1].source_identifier, papers[1].source_identifier);
+    // Papers come with all their authors, not just the matching one
+    assert_eq!(found[1].authors.len(), 2);
 
-  /// Helper function to create a test paper
-  fn create_test_paper() -> Paper {
-    Paper {
-      title:             "Test Paper".to_string(),
-      abstract_text:     "This is a test abstract".to_string(),
-      publication_date:  Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
-      source:            Source::Arxiv,
-      source_identifier: "2401.00000".to_string(),
-      pdf_url:           Some("https://arxiv.org/pdf/2401.00000".to_string()),
-      doi:               Some("10.1000/test.123".to_string()),
-      authors:           vec![
-        Author {
-          name:        "John Doe".to_string(),
-          affiliation: Some("Test University".to_string()),
-          email:       Some("john@test.edu".to_string()),
-        },
-        Author { name: "Jane Smith".to_string(), affiliation: None, email: None },
-      ],
-    }
+    assert_eq!(db.search_by_author("CHURCH").await.unwrap().len(), 1);
+    assert_eq!(db.search_by_author("Gödel").await.unwrap().len(), 1);
+    assert!(db.search_by_author("Hopper").await.unwrap().is_empty());
+    // Wildcards are matched literally
+    assert!(db.search_by_author("%").await.unwrap().is_empty());
+    assert!(db.search_by_author("A_an").await.unwrap().is_empty());
   }
 
-  /// Helper function to set up a test database
-  async fn setup_test_db() -> (Database, tempfile::TempDir) {
-    let dir = tempdir().unwrap();
-    let db_path = dir.path().join("test.db");
-    let db = Database::open(&db_path).await.unwrap();
-    (db, dir)
+  #[traced_test]
+  #[tokio::test]
+  async fn test_get_papers_by_author_and_list_authors() {
+    let (db, _dir) = setup_test_db().await;
+    let author =
+      |name: &str| Author { name: name.to_string(), affiliation: None, email: None };
+    let mut papers = create_test_papers(3);
+    papers[0].authors = vec![author("Ivan Damgård"), author("Peter  Schwabe")];
+    papers[1].authors = vec![author("Peter Schwabe")];
+    papers[2].authors = vec![author("IVAN DAMGARD"), author("Peter Schwabe"), author("Jens Groth")];
+    for paper in &papers {
+      db.save_paper(paper).await.unwrap();
+    }
+
+    // Whole names match regardless of case, accents, and whitespace
+    let found = db.get_papers_by_author("peter schwabe").await.unwrap();
+    assert_eq!(found.len(), 3);
+    assert_eq!(found[0].source_identifier, papers[0].source_identifier);
+    assert_eq!(found[2].authors.len(), 3);
+    assert_eq!(db.get_papers_by_author(" ivan   damgard ").await.unwrap().len(), 2);
+    assert_eq!(db.get_papers_by_author("Ivan Damgård").await.unwrap().len(), 2);
+    // Partial names don't
+    assert!(db.get_papers_by_author("Schwabe").await.unwrap().is_empty());
+    assert!(db.get_papers_by_author("Grace Hopper").await.unwrap().is_empty());
+
+    let authors = db.list_authors().await.unwrap();
+    assert_eq!(authors, [
+      AuthorCount { name: "Peter  Schwabe".to_string(), paper_count: 3 },
+      AuthorCount { name: "Ivan Damgård".to_string(), paper_count: 2 },
+      AuthorCount { name: "Jens Groth".to_string(), paper_count: 1 },
+    ]);
+    assert_eq!(db.get_statistics().await.unwrap().total_authors, 3);
   }
 
   #[traced_test]
   #[tokio::test]
-  async fn test_database_creation() {
-    let dir = tempdir().unwrap();
-    let db_path = dir.path().join("test.db");
+  async fn test_tags() {
+    let (db, _dir) = setup_test_db().await;
+    let papers = create_test_papers(2);
+    for paper in &papers {
+      db.save_paper(paper).await.unwrap();
+    }
+    let (source, id) = (&papers[0].source, papers[0].source_identifier.as_str());
 
-    // Create database
-    let _db = Database::open(&db_path).await.unwrap();
+    assert!(db.add_tag(source, id, "to-read").await.unwrap());
+    assert!(db.add_tag(source, id, " ML-theory ").await.unwrap());
+    // Tags are stored lowercased, and adding one twice is silently ignored
+    assert!(!db.add_tag(source, id, "TO-READ").await.unwrap());
+    assert_eq!(db.get_tags(source, id).await.unwrap(), vec!["ml-theory", "to-read"]);
 
-    // Check that file exists
-    assert!(db_path.exists());
+    db.add_tag(&papers[1].source, &papers[1].source_identifier, "to-read").await.unwrap();
+    let tagged = db.search_by_tag("to-read").await.unwrap();
+    assert_eq!(tagged.len(), 2);
+    assert_eq!(tagged[0].source_identifier, papers[0].source_identifier);
+    assert_eq!(db.search_by_tag("ml-theory").await.unwrap().len(), 1);
+    assert!(db.search_by_tag("unused").await.unwrap().is_empty());
+
+    assert!(db.remove_tag(source, id, "to-read").await.unwrap());
+    assert!(!db.remove_tag(source, id, "to-read").await.unwrap());
+    assert_eq!(db.get_tags(source, id).await.unwrap(), vec!["ml-theory"]);
+
+    assert!(matches!(db.add_tag(source, id, "  ").await, Err(LearnerError::InvalidTag(_))));
+    assert!(matches!(
+      db.add_tag(&Source::Arxiv, "0000.00000", "to-read").await,
+      Err(LearnerError::NotFound)
+    ));
+
+    // Tags are removed along with their paper
+    db.remove_paper(source, id).await.unwrap();
+    assert!(db.get_tags(source, id).await.unwrap().is_empty());
+    assert_eq!(db.search_by_tag("to-read").await.unwrap().len(), 1);
   }
 
   #[traced_test]
   #[tokio::test]
-  async fn test_save_and_retrieve_paper() {
+  async fn test_reading_status() {
     let (db, _dir) = setup_test_db().await;
-    let paper = create_test_paper();
+    let papers = create_test_papers(3);
+    for paper in &papers {
+      db.save_paper(paper).await.unwrap();
+    }
+    let (source, id) = (&papers[0].source, papers[0].source_identifier.as_str());
+    let progress = || async { db.get_reading_status(source, id).await.unwrap().unwrap() };
 
-    // Save paper
-    let paper_id = db.save_paper(&paper).await.unwrap();
-    assert!(paper_id > 0);
+    assert_eq!(progress().await, ReadingProgress {
+      status:      ReadingStatus::Unread,
+      started_at:  None,
+      finished_at: None,
+    });
 
-    // Retrieve paper
-    let retrieved = db
-      .get_paper_by_source_id(&paper.source, &paper.source_identifier)
+    db.set_reading_status(source, id, ReadingStatus::Reading).await.unwrap();
+    let reading = progress().await;
+    assert_eq!(reading.status, ReadingStatus::Reading);
+    assert!(reading.started_at.is_some());
+    assert_eq!(reading.finished_at, None);
+
+    // Finishing keeps the start time, and marking as read again keeps the finish time
+    db.set_reading_status(source, id, ReadingStatus::Read).await.unwrap();
+    let read = progress().await;
+    assert_eq!(read.started_at, reading.started_at);
+    assert!(read.finished_at.is_some());
+    db.set_reading_status(source, id, ReadingStatus::Read).await.unwrap();
+    assert_eq!(progress().await, read);
+
+    db.set_reading_status(&papers[1].source, &papers[1].source_identifier, ReadingStatus::Reading)
       .await
-      .unwrap()
-      .expect("Paper should exist");
+      .unwrap();
+    let listed =
+      db.list_papers_by_status(ReadingStatus::Read, PaperSort::Title, true, 10, 0).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].source_identifier, id);
+    assert_eq!(listed[0].reading_status, ReadingStatus::Read);
+    let unread =
+      db.list_papers_by_status(ReadingStatus::Unread, PaperSort::Title, true, 10, 0).await.unwrap();
+    assert_eq!(unread.len(), 1);
+    assert_eq!(unread[0].source_identifier, papers[2].source_identifier);
 
-    // Verify paper data
-    assert_eq!(retrieved.title, paper.title);
-    assert_eq!(retrieved.abstract_text, paper.abstract_text);
-    assert_eq!(retrieved.publication_date, paper.publication_date);
-    assert_eq!(retrieved.source, paper.source);
-    assert_eq!(retrieved.source_identifier, paper.source_identifier);
-    assert_eq!(retrieved.pdf_url, paper.pdf_url);
-    assert_eq!(retrieved.doi, paper.doi);
+    db.set_reading_status(source, id, ReadingStatus::Unread).await.unwrap();
+    assert_eq!(progress().await, ReadingProgress {
+      status:      ReadingStatus::Unread,
+      started_at:  None,
+      finished_at: None,
+    });
 
-    // Verify authors
-    assert_eq!(retrieved.authors.len(), paper.authors.len());
-    assert_eq!(retrieved.authors[0].name, paper.authors[0].name);
-    assert_eq!(retrieved.authors[0].affiliation, paper.authors[0].affiliation);
-    assert_eq!(retrieved.authors[0].email, paper.authors[0].email);
-    assert_eq!(retrieved.authors[1].name, paper.authors[1].name);
-    assert_eq!(retrieved.authors[1].affiliation, None);
-    assert_eq!(retrieved.authors[1].email, None);
+    assert!(matches!(
+      db.set_reading_status(&Source::Arxiv, "0000.00000", ReadingStatus::Read).await,
+      Err(LearnerError::NotFound)
+    ));
+    assert_eq!(db.get_reading_status(&Source::Arxiv, "0000.00000").await.unwrap(), None);
+  }
+
+  #[test]
+  fn test_reading_status_from_str() {
+    assert_eq!("Reading".parse::<ReadingStatus>().unwrap(), ReadingStatus::Reading);
+    assert_eq!(
+      ReadingStatus::Read.to_string().parse::<ReadingStatus>().unwrap(),
+      ReadingStatus::Read
+    );
+    assert!(matches!(
+      "done".parse::<ReadingStatus>(),
+      Err(LearnerError::InvalidReadingStatus(s)) if s == "done"
+    ));
   }
 
   #[traced_test]
   #[tokio::test]
-  async fn test_get_nonexistent_paper() {
+  async fn test_notes() {
     let (db, _dir) = setup_test_db().await;
+    let papers = create_test_papers(2);
+    for paper in &papers {
+      db.save_paper(paper).await.unwrap();
+    }
+    let (source, id) = (&papers[0].source, papers[0].source_identifier.as_str());
 
-    let result = db.get_paper_by_source_id(&Source::Arxiv, "nonexistent").await.unwrap();
+    assert!(db.get_notes(source, id).await.unwrap().is_empty());
+    let first = db.add_note(source, id, "Read for journal club").await.unwrap();
+    let second = db.add_note(source, id, "Nice construction in §4").await.unwrap();
+    assert_eq!(first.content, "Read for journal club");
+    assert_eq!(db.get_notes(source, id).await.unwrap(), vec![first.clone(), second.clone()]);
+    assert_search_index_consistent(&db).await;
 
-    assert!(result.is_none());
+    // Every note is searchable
+    let found = db.search_papers("journal").await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].source_identifier, id);
+    assert_eq!(db.search_papers("notes:construction").await.unwrap().len(), 1);
+
+    assert!(matches!(
+      db.add_note(&Source::Arxiv, "0000.00000", "missing").await,
+      Err(LearnerError::NotFound)
+    ));
+
+    assert!(db.delete_note(first.id).await.unwrap());
+    assert!(!db.delete_note(first.id).await.unwrap());
+    assert_eq!(db.get_notes(source, id).await.unwrap(), vec![second]);
+    assert!(db.search_papers("journal").await.unwrap().is_empty());
+    assert_search_index_consistent(&db).await;
+
+    // Notes survive a metadata update and a reindex, and are removed along with their paper
+    db.add_note(source, id, "Worth rereading").await.unwrap();
+    let mut updated = papers[0].clone();
+    updated.title = "Corrected Title".to_string();
+    db.update_paper(&updated).await.unwrap();
+    assert_eq!(db.get_notes(source, id).await.unwrap().len(), 2);
+    db.rebuild_search_index(None).await.unwrap();
+    assert_eq!(db.search_papers("rereading").await.unwrap().len(), 1);
+    db.remove_paper(source, id).await.unwrap();
+    assert!(db.get_notes(source, id).await.unwrap().is_empty());
+    assert!(db.search_papers("rereading").await.unwrap().is_empty());
+    assert_search_index_consistent(&db).await;
   }
 
   #[traced_test]
   #[tokio::test]
-  async fn test_full_text_search() {
+  async fn test_watch_targets() {
     let (db, _dir) = setup_test_db().await;
+    assert!(db.list_watch_targets().await.unwrap().is_empty());
 
-    // Save a few papers
-    let mut paper1 = create_test_paper();
-    paper1.title = "Neural Networks in Machine Learning".to_string();
-    paper1.abstract_text = "This paper discusses deep learning".to_string();
-    paper1.source_identifier = "2401.00001".to_string();
+    assert!(db.add_watch_target("math.AG", &Source::Arxiv).await.unwrap());
+    assert!(db.add_watch_target(" cs.CR ", &Source::Arxiv).await.unwrap());
+    assert!(!db.add_watch_target("cs.CR", &Source::Arxiv).await.unwrap());
 
-    let mut paper2 = create_test_paper();
-    paper2.title = "Advanced Algorithms".to_string();
-    paper2.abstract_text = "Classical computer science topics".to_string();
-    paper2.source_identifier = "2401.00002".to_string();
+    let targets = db.list_watch_targets().await.unwrap();
+    let categories = targets.iter().map(|t| t.category.as_str()).collect::<Vec<_>>();
+    assert_eq!(categories, ["cs.CR", "math.AG"]);
+    assert!(targets.iter().all(|t| t.source == Source::Arxiv && t.last_checked.is_none()));
 
-    db.save_paper(&paper1).await.unwrap();
-    db.save_paper(&paper2).await.unwrap();
+    let checked_at = Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+    db.set_watch_target_checked("cs.CR", &Source::Arxiv, checked_at).await.unwrap();
+    let targets = db.list_watch_targets().await.unwrap();
+    assert_eq!(targets[0].last_checked, Some(checked_at));
+    assert_eq!(targets[1].last_checked, None);
 
-    // Search for papers
-    let results = db.search_papers("neural").await.unwrap();
-    assert_eq!(results.len(), 1);
-    assert_eq!(results[0].title, paper1.title);
+    assert!(db.remove_watch_target("cs.CR", &Source::Arxiv).await.unwrap());
+    assert!(!db.remove_watch_target("cs.CR", &Source::Arxiv).await.unwrap());
+    assert_eq!(db.list_watch_targets().await.unwrap().len(), 1);
+  }
 
-    let results = db.search_papers("learning").await.unwrap();
-    assert_eq!(results.len(), 1);
-    assert_eq!(results[0].source_identifier, paper1.source_identifier);
+  #[traced_test]
+  #[tokio::test]
+  async fn test_collections() {
+    let (db, _dir) = setup_test_db().await;
+    let papers = create_test_papers(3);
+    for paper in &papers {
+      db.save_paper(paper).await.unwrap();
+    }
+    let ids: Vec<_> = papers.iter().map(|paper| paper.source_identifier.as_str()).collect();
 
-    let results = db.search_papers("algorithms").await.unwrap();
-    assert_eq!(results.len(), 1);
-    assert_eq!(results[0].title, paper2.title);
+    let crypto = db.create_collection(" Cryptography ", Some("For the survey")).await.unwrap();
+    assert_eq!(crypto.name, "Cryptography");
+    db.create_collection("Systems", None).await.unwrap();
+    assert!(matches!(
+      db.create_collection("cryptography", None).await,
+      Err(LearnerError::CollectionExists(_))
+    ));
+    assert!(matches!(
+      db.create_collection("  ", None).await,
+      Err(LearnerError::InvalidCollection(_))
+    ));
+
+    // Papers are listed in the order they were added, and names match regardless of case
+    assert!(db.add_paper_to_collection(&Source::Arxiv, ids[2], "Cryptography").await.unwrap());
+    assert!(db.add_paper_to_collection(&Source::Arxiv, ids[0], "cryptography").await.unwrap());
+    assert!(!db.add_paper_to_collection(&Source::Arxiv, ids[0], "Cryptography").await.unwrap());
+    let titles =
+      |papers: Vec<Paper>| papers.into_iter().map(|paper| paper.title).collect::<Vec<_>>();
+    assert_eq!(titles(db.get_collection_papers("Cryptography").await.unwrap()), vec![
+      papers[2].title.clone(),
+      papers[0].title.clone()
+    ]);
+    assert!(db.get_collection_papers("Systems").await.unwrap().is_empty());
+
+    assert!(matches!(
+      db.add_paper_to_collection(&Source::Arxiv, "0000.00000", "Cryptography").await,
+      Err(LearnerError::NotFound)
+    ));
+    assert!(matches!(
+      db.add_paper_to_collection(&Source::Arxiv, ids[0], "Biology").await,
+      Err(LearnerError::CollectionNotFound(_))
+    ));
+    assert!(matches!(
+      db.get_collection_papers("Biology").await,
+      Err(LearnerError::CollectionNotFound(_))
+    ));
+
+    let collections = db.list_collections().await.unwrap();
+    assert_eq!(collections, vec![
+      Collection {
+        id:          crypto.id,
+        name:        "Cryptography".to_string(),
+        description: Some("For the survey".to_string()),
+        paper_count: 2,
+      },
+      Collection {
+        id:          collections[1].id,
+        name:        "Systems".to_string(),
+        description: None,
+        paper_count: 0,
+      },
+    ]);
+
+    // Removing a paper from a collection keeps it in the database; removing it from the
+    // database takes it out of its collections
+    assert!(db.remove_paper_from_collection(&Source::Arxiv, ids[2], "Cryptography").await.unwrap());
+    assert!(!db
+      .remove_paper_from_collection(&Source::Arxiv, ids[2], "Cryptography")
+      .await
+      .unwrap());
+    assert!(db.get_paper_by_source_id(&Source::Arxiv, ids[2]).await.unwrap().is_some());
+    db.remove_paper(&Source::Arxiv, ids[0]).await.unwrap();
+    assert!(db.get_collection_papers("Cryptography").await.unwrap().is_empty());
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_migrate_single_notes() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("old.db");
+
+    // A database as created when papers had at most one note
+    let db = Database::open(&path).await.unwrap();
+    let paper = create_test_paper();
+    db.save_paper(&paper).await.unwrap();
+    db.conn
+      .call(|conn| {
+        conn.execute_batch(
+          "DROP TABLE notes;
+           CREATE TABLE notes (
+             paper_id INTEGER PRIMARY KEY,
+             content TEXT NOT NULL,
+             updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+             FOREIGN KEY(paper_id) REFERENCES papers(id) ON DELETE CASCADE
+           ) STRICT;
+           CREATE TRIGGER notes_ai AFTER INSERT ON notes BEGIN
+             UPDATE papers_fts SET notes = new.content WHERE rowid = new.paper_id;
+           END;
+           INSERT INTO notes (paper_id, content) SELECT id, 'Read for journal club' FROM papers;",
+        )?;
+        Ok(())
+      })
+      .await
+      .unwrap();
+    drop(db);
+
+    let db = Database::open(&path).await.unwrap();
+    let notes = db.get_notes(&paper.source, &paper.source_identifier).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].content, "Read for journal club");
+    db.add_note(&paper.source, &paper.source_identifier, "Nice construction").await.unwrap();
+    assert_eq!(db.search_papers("journal construction").await.unwrap().len(), 1);
+    assert_search_index_consistent(&db).await;
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_papers_carry_tags() {
+    let (db, _dir) = setup_test_db().await;
+    let papers = create_test_papers(3);
+    for paper in &papers {
+      db.save_paper(paper).await.unwrap();
+    }
+    for paper in &papers[1..] {
+      db.add_tag(&paper.source, &paper.source_identifier, "Crypto").await.unwrap();
+    }
+    db.add_tag(&papers[1].source, &papers[1].source_identifier, "zk").await.unwrap();
+
+    let paper =
+      db.get_paper_by_source_id(&papers[1].source, &papers[1].source_identifier).await.unwrap();
+    assert_eq!(paper.unwrap().tags, vec!["crypto", "zk"]);
+
+    let listed = db.list_papers(PaperSort::Title, true, 10, 0).await.unwrap();
+    assert!(listed[0].tags.is_empty());
+
+    let tagged = db.list_papers_by_tag(" CRYPTO", PaperSort::Title, false, 10, 0).await.unwrap();
+    let ids: Vec<_> = tagged.iter().map(|p| p.source_identifier.as_str()).collect();
+    assert_eq!(ids, vec![papers[2].source_identifier.as_str(), &papers[1].source_identifier]);
+    assert_eq!(
+      db.list_papers_by_tag("crypto", PaperSort::Title, true, 1, 1).await.unwrap().len(),
+      1
+    );
+    assert!(db
+      .list_papers_by_tag("unused", PaperSort::Title, true, 10, 0)
+      .await
+      .unwrap()
+      .is_empty());
+
+    // Tags survive an export and re-import
+    let mut exported = reload(&db, &papers[1]).await;
+    db.remove_paper(&exported.source, &exported.source_identifier).await.unwrap();
+    exported.tags.push("  ".to_string());
+    db.save_paper(&exported).await.unwrap();
+    assert_eq!(reload(&db, &papers[1]).await.tags, vec!["crypto", "zk"]);
+  }
+
+  /// Reloads `paper` from the database.
+  async fn reload(db: &Database, paper: &Paper) -> Paper {
+    db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await.unwrap().unwrap()
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_remove_missing_paper() {
+    let (db, _dir) = setup_test_db().await;
+    db.save_paper(&create_test_paper()).await.unwrap();
+
+    assert!(!db.remove_paper(&Source::IACR, "2016/260").await.unwrap());
+
+    assert_eq!(db.search_papers("test").await.unwrap().len(), 1);
+    assert_search_index_consistent(&db).await;
   }
 
   #[traced_test]
@@ -744,6 +5069,103 @@ mod tests {
     assert_eq!(updated, Some("new_value".to_string()));
   }
 
+  #[traced_test]
+  #[tokio::test]
+  async fn test_verify_pdfs() {
+    let (db, dir) = setup_test_db().await;
+    let papers = create_test_papers(4);
+    let mut ids = Vec::new();
+    for paper in &papers {
+      ids.push(db.save_paper(paper).await.unwrap());
+    }
+
+    // A complete PDF under its usual name, without a recorded download
+    let usual_path =
+      dir.path().join(format!("{}.pdf", format::format_title(&papers[0].title, Some(50))));
+    std::fs::write(&usual_path, b"%PDF-1.7 test\n%%EOF\n").unwrap();
+
+    // A recorded download that turned out to be an error page
+    let invalid_path = dir.path().join("invalid.pdf");
+    std::fs::write(&invalid_path, b"<html>Access denied</html>").unwrap();
+    db.record_pdf(ids[1], invalid_path, "invalid.pdf".to_string(), "success", None).await.unwrap();
+
+    // A recorded download whose file has since been deleted
+    let missing_path = dir.path().join("missing.pdf");
+    db.record_pdf(ids[2], missing_path, "missing.pdf".to_string(), "success", None).await.unwrap();
+
+    // The fourth paper has never been downloaded and is left out
+    let statuses: Vec<_> = db
+      .verify_pdfs(dir.path())
+      .await
+      .unwrap()
+      .into_iter()
+      .map(|(paper, status)| (paper.title, status))
+      .collect();
+    assert_eq!(statuses, vec![
+      (papers[0].title.clone(), PdfStatus::Valid),
+      (papers[1].title.clone(), PdfStatus::Invalid),
+      (papers[2].title.clone(), PdfStatus::Missing),
+    ]);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_papers_missing_pdf() {
+    let (db, dir) = setup_test_db().await;
+    let mut papers = create_test_papers(4);
+    papers[3].pdf_url = None;
+    let mut ids = Vec::new();
+    for paper in &papers {
+      ids.push(db.save_paper(paper).await.unwrap());
+    }
+
+    // Failed downloads are still missing
+    db.record_pdf(ids[0], dir.path().join("0.pdf"), "0.pdf".to_string(), "success", None)
+      .await
+      .unwrap();
+    db.record_pdf(ids[1], dir.path().join("1.pdf"), "1.pdf".to_string(), "failed", None)
+      .await
+      .unwrap();
+
+    let missing: Vec<_> =
+      db.papers_missing_pdf().await.unwrap().into_iter().map(|paper| paper.title).collect();
+    assert_eq!(missing, [papers[1].title.clone(), papers[2].title.clone()]);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_find_duplicates() {
+    let (db, _dir) = setup_test_db().await;
+    let titles = [
+      "Verifiable Fully Homomorphic Encryption",
+      "Attention Is All You Need",
+      "Verifiable fully-homomorphic encryption.",
+      "Deep Residual Learning for Image Recognition",
+      "Attention is all you need (extended version)",
+    ];
+    for (i, title) in titles.iter().enumerate() {
+      let mut paper = create_test_paper();
+      paper.title = title.to_string();
+      paper.source_identifier = format!("2401.{i:05}");
+      paper.doi = None;
+      db.save_paper(&paper).await.unwrap();
+    }
+
+    let duplicates = db.find_duplicates(DEFAULT_DUPLICATE_THRESHOLD, None).await.unwrap();
+    let pairs: Vec<_> = duplicates
+      .iter()
+      .map(|(first, second, _)| (first.title.as_str(), second.title.as_str()))
+      .collect();
+    assert_eq!(pairs, [(titles[0], titles[2]), (titles[1], titles[4])]);
+    assert_eq!(duplicates[0].2, 1.0);
+    assert!(duplicates[1].2 < 1.0);
+
+    // Only exact matches, up to case and punctuation
+    let sink = RecordingSink::default();
+    assert_eq!(db.find_duplicates(1.0, Some(&sink)).await.unwrap().len(), 1);
+    sink.assert_progress(&["exporting papers", "comparing titles"]);
+  }
+
   #[traced_test]
   #[tokio::test]
   async fn test_pdf_recording() {
@@ -773,6 +5195,57 @@ mod tests {
     assert_eq!(error, None);
   }
 
+  #[traced_test]
+  #[tokio::test]
+  async fn test_set_pdf_path() {
+    let (db, dir) = setup_test_db().await;
+    let paper = create_test_paper();
+    let path = dir.path().join("paper.pdf");
+    let result = db.set_pdf_path(&paper.source, &paper.source_identifier, path.clone()).await;
+    assert!(matches!(result, Err(LearnerError::NotFound)));
+
+    db.save_paper(&paper).await.unwrap();
+    std::fs::write(&path, b"%PDF-1.7 test\n%%EOF\n").unwrap();
+    db.set_pdf_path(&paper.source, &paper.source_identifier, path.clone()).await.unwrap();
+
+    let stored =
+      db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await.unwrap().unwrap();
+    assert_eq!(stored.pdf_path, Some(path.clone()));
+    let found = db.search_papers("Test Paper").await.unwrap();
+    assert_eq!(found[0].pdf_path, Some(path));
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_recorded_pdf_on_paper() {
+    let (db, dir) = setup_test_db().await;
+    let paper = create_test_paper();
+    let paper_id = db.save_paper(&paper).await.unwrap();
+    let reload = || db.get_paper_by_source_id(&paper.source, &paper.source_identifier);
+    assert_eq!(reload().await.unwrap().unwrap().pdf_path, None);
+
+    // Failed downloads don't count as a PDF
+    let path = dir.path().join("paper.pdf");
+    db.record_pdf(paper_id, path.clone(), "paper.pdf".into(), "failed", Some("403".into()))
+      .await
+      .unwrap();
+    assert_eq!(reload().await.unwrap().unwrap().pdf_path, None);
+
+    std::fs::write(&path, b"%PDF-1.7 test").unwrap();
+    db.record_pdf(paper_id, path.clone(), "paper.pdf".into(), "success", None).await.unwrap();
+    assert_eq!(reload().await.unwrap().unwrap().pdf_path, Some(path));
+    let size: Option<i64> =
+      db.conn
+        .call(move |conn| {
+          Ok(conn.query_row("SELECT size FROM files WHERE paper_id = ?1", [paper_id], |row| {
+            row.get(0)
+          })?)
+        })
+        .await
+        .unwrap();
+    assert_eq!(size, Some(13));
+  }
+
   #[traced_test]
   #[tokio::test]
   async fn test_pdf_failure_recording() {
@@ -859,4 +5332,16 @@ mod tests {
       assert_eq!(value, Some("/test/path".to_string()));
     }
   }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_get_pdf_dir() {
+    let (db, _dir) = setup_test_db().await;
+
+    // Without a configured directory, PDFs go to the default location
+    assert_eq!(db.get_pdf_dir().await.unwrap(), Database::default_pdf_path());
+
+    db.set_config("pdf_dir", "/test/path").await.unwrap();
+    assert_eq!(db.get_pdf_dir().await.unwrap(), PathBuf::from("/test/path"));
+  }
 }
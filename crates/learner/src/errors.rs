@@ -57,15 +57,60 @@ pub enum LearnerError {
   #[error("Invalid source type, see `learner::paper::Source`")]
   InvalidSource(String),
 
+  /// The provided sort order string couldn't be parsed.
+  ///
+  /// The string parameter contains the invalid value, see
+  /// `learner::database::PaperSort` for the accepted values.
+  #[error("Invalid sort order: {0}")]
+  InvalidSort(String),
+
+  /// The provided grouping string couldn't be parsed.
+  ///
+  /// The string parameter contains the invalid value, see
+  /// `learner::paper::PaperGrouping` for the accepted values.
+  #[error("Invalid grouping: {0}")]
+  InvalidGrouping(String),
+
+  /// The provided reading status string couldn't be parsed.
+  ///
+  /// The string parameter contains the invalid value, see
+  /// `learner::paper::ReadingStatus` for the accepted values.
+  #[error("Invalid reading status: {0}")]
+  InvalidReadingStatus(String),
+
+  /// The tag is empty or only whitespace.
+  #[error("Invalid tag: {0:?}")]
+  InvalidTag(String),
+
+  /// The collection name is empty or only whitespace.
+  #[error("Invalid collection name: {0:?}")]
+  InvalidCollection(String),
+
+  /// A collection with this name already exists, compared regardless of case.
+  #[error("Collection already exists: {0}")]
+  CollectionExists(String),
+
+  /// No collection has this name.
+  #[error("Collection not found: {0}")]
+  CollectionNotFound(String),
+
   /// A network request failed.
   ///
   /// This can occur when:
   /// - The network is unavailable
   /// - The server is unreachable
-  /// - The request times out
   /// - TLS/SSL errors occur
+  ///
+  /// Requests that time out are reported as [`LearnerError::Timeout`] instead.
   #[error(transparent)]
-  Network(#[from] reqwest::Error),
+  Network(reqwest::Error),
+
+  /// A network request took longer than the client's timeout.
+  ///
+  /// The timeout defaults to 30 seconds and can be changed per client with `with_timeout`,
+  /// or for every client with the `LEARNER_HTTP_TIMEOUT_SECS` environment variable.
+  #[error("Request timed out: {0}")]
+  Timeout(reqwest::Error),
 
   /// The requested paper couldn't be found.
   ///
@@ -76,6 +121,18 @@ pub enum LearnerError {
   #[error("Paper not found")]
   NotFound,
 
+  /// The paper has the same DOI as a paper already saved under another source or identifier,
+  /// named in the message (e.g. "arxiv 2301.07041").
+  #[error("A paper with this DOI is already saved as {0}")]
+  Duplicate(String),
+
+  /// An API rejected the request because too many requests were made.
+  ///
+  /// This occurs when an external API responds with HTTP 429. The string
+  /// parameter names the service; callers should wait before retrying.
+  #[error("Rate limited by {0}, try again later")]
+  RateLimited(String),
+
   /// An API returned an error response.
   ///
   /// This occurs when the external API (arXiv, IACR, DOI) returns
@@ -84,6 +141,30 @@ pub enum LearnerError {
   #[error("API error: {0}")]
   ApiError(String),
 
+  /// A BibTeX file couldn't be parsed.
+  ///
+  /// This occurs when importing a `.bib` file with a malformed entry, or an entry that
+  /// lacks the information needed to fetch the paper. The string parameter describes the
+  /// problem and where it is.
+  #[error("Invalid BibTeX: {0}")]
+  InvalidBibtex(String),
+
+  /// A PDF download returned something other than a complete PDF.
+  ///
+  /// This occurs when a publisher answers a PDF link with an HTML page, such as a login
+  /// wall or a bot check, instead of the document, or when the download is cut short. The
+  /// string parameter contains the URL that was requested, the HTTP status, and the start of
+  /// what was returned, for diagnosis.
+  #[error("Invalid PDF: {0}")]
+  InvalidPdf(String),
+
+  /// A PDF file name template uses a placeholder that doesn't exist.
+  ///
+  /// This occurs when configuring `pdf_filename_template` with a misspelled placeholder. The
+  /// string parameter names the placeholder and lists the valid ones.
+  #[error("Invalid file name template: {0}")]
+  InvalidFilenameTemplate(String),
+
   /// Failed to parse a URL.
   ///
   /// This occurs when attempting to parse paper URLs from various
@@ -132,12 +213,23 @@ pub enum LearnerError {
   ColumnOverflow(#[from] std::num::TryFromIntError),
 }
 
+impl From<reqwest::Error> for LearnerError {
+  fn from(error: reqwest::Error) -> Self {
+    if error.is_timeout() {
+      LearnerError::Timeout(error)
+    } else {
+      LearnerError::Network(error)
+    }
+  }
+}
+
 impl LearnerError {
   /// Checks if this error represents a duplicate entry in the database.
   ///
   /// This helper method checks for SQLite's unique constraint violation, which
   /// occurs when trying to insert a paper that already exists in the database
-  /// (matching source and source_identifier).
+  /// (matching source and source_identifier), and for [`LearnerError::Duplicate`], returned
+  /// when a paper with the same DOI is saved under another source.
   ///
   /// # Examples
   ///
@@ -165,6 +257,6 @@ impl LearnerError {
         LearnerError::AsyncSqlite(tokio_rusqlite::Error::Rusqlite(
             rusqlite::Error::SqliteFailure(error, _)
         )) if error.code == rusqlite::ErrorCode::ConstraintViolation
-    )
+    ) || matches!(self, LearnerError::Duplicate(_))
   }
 }
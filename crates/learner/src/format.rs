@@ -1,10 +1,15 @@
-//! Text formatting utilities for standardizing document titles and filenames.
+//! Text formatting utilities for standardizing document titles, filenames, author names, and
+//! abstracts.
 //!
 //! This module provides functionality for cleaning and standardizing text strings,
 //! particularly focused on converting document titles into filesystem-friendly
 //! filenames. It handles common transformations like converting to lowercase,
-//! replacing spaces with underscores, and enforcing length limits while preserving
-//! word boundaries.
+//! replacing spaces with underscores (or another separator, with [`format_title_with`]), and
+//! enforcing length limits while preserving word boundaries. Author names are normalized for
+//! matching with [`normalize_name`], and abstracts fetched from sources are tidied into plain text
+//! with [`clean_abstract`], or [`clean_latex`] for text that only has LaTeX markup, like arXiv
+//! titles. Near-identical titles, such as those of a preprint and its published version, are found
+//! with [`title_similarity`]. Downloaded PDFs are named after a template with [`render_filename`].
 //!
 //! # Examples
 //!
@@ -22,19 +27,29 @@
 //! assert_eq!(formatted, "this_is_a_very_long");
 //! ```
 
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+use crate::paper::Paper;
+
 /// Formats a title string for use as a filename or identifier.
 ///
 /// This function performs several transformations to make titles more suitable for
 /// use as filenames or identifiers:
 /// - Converts the text to lowercase
 /// - Replaces whitespace (including multiple spaces) with single underscores
-/// - Truncates to a maximum length while preserving word boundaries
+/// - Replaces characters that aren't allowed in file names on some platforms (`< > : " / \\ | ? *`
+///   and control characters) with underscores, so the result is always a single path segment
+/// - Truncates to a maximum length in characters while preserving word boundaries, only cutting
+///   within a word when the first is longer than the limit
+/// - Drops leading and trailing dots, and appends an underscore to names Windows reserves for
+///   devices, such as `con` or `nul`
 ///
 /// # Arguments
 ///
 /// * `title` - The input title string to format
-/// * `max_length` - Optional maximum length limit. If `None`, defaults to 50 characters. The
-///   function will truncate at word boundaries to stay within this limit.
+/// * `max_length` - Optional maximum length in characters. If `None`, defaults to 50. The function
+///   will truncate at word boundaries to stay within this limit.
 ///
 /// # Returns
 ///
@@ -51,6 +66,9 @@
 /// // Handling multiple spaces
 /// assert_eq!(format::format_title("No    Extra    Spaces", None), "no_extra_spaces");
 ///
+/// // Characters that can't appear in file names
+/// assert_eq!(format::format_title("TCP/IP: A Survey?", None), "tcp_ip_a_survey");
+///
 /// // Length limiting
 /// assert_eq!(
 ///   format::format_title("This Is A Very Long Title Indeed", Some(20)),
@@ -58,47 +76,477 @@
 /// );
 /// ```
 pub fn format_title(title: &str, max_length: Option<usize>) -> String {
-  // Convert to lowercase and collapse multiple spaces into one, then replace with underscore
+  format_title_with(title, max_length, '_')
+}
+
+/// Formats a title like [`format_title`], joining words with `separator` instead of `_`.
+///
+/// Words are split at `separator` too when the title is truncated, so a hyphenated word may be
+/// cut at its hyphen when `separator` is `'-'`. A separator that isn't allowed in file names,
+/// such as `/` or a space, is replaced by `_` so the result stays a single file name.
+///
+/// # Examples
+///
+/// ```
+/// use learner::format;
+///
+/// assert_eq!(format::format_title_with("No    Extra    Spaces", None, '-'), "no-extra-spaces");
+/// assert_eq!(format::format_title_with("Hello World", None, '.'), "hello.world");
+/// assert_eq!(
+///   format::format_title_with("This Is A Very Long Title Indeed", Some(20), '-'),
+///   "this-is-a-very-long"
+/// );
+/// ```
+pub fn format_title_with(title: &str, max_length: Option<usize>, separator: char) -> String {
+  let separator = if is_reserved(separator) || separator.is_whitespace() { '_' } else { separator };
+
+  // Convert to lowercase and collapse runs of whitespace and reserved characters into one
+  // separator, dropping words that are only dots like `..` or an ellipsis, or only separators
   let formatted = title
-        .to_lowercase()
-        .split_whitespace() // This splits on any whitespace and removes empty strings
-        .collect::<Vec<&str>>()
-        .join("_");
+    .to_lowercase()
+    .split(|c: char| c.is_whitespace() || is_reserved(c))
+    .filter(|word| !word.chars().all(|c| c == '.' || c == separator))
+    .collect::<Vec<&str>>()
+    .join(&separator.to_string());
 
   let max_length = max_length.unwrap_or(50);
 
-  // If the string is already within length limit, return it
-  if formatted.len() <= max_length {
-    return formatted;
+  // If the string is already within length limit, return it. Lengths are counted in
+  // characters, not bytes, so accented and CJK titles get as much room as any other
+  if formatted.chars().count() <= max_length {
+    return safe_file_name(formatted);
   }
 
   // Split into words
-  let words: Vec<&str> = formatted.split('_').collect();
+  let words: Vec<&str> = formatted.split(separator).collect();
   let mut result = String::new();
+  let mut length = 0;
 
   // Build string word by word until we hit the limit
   for (i, word) in words.iter().enumerate() {
+    let word_length = word.chars().count();
     if i > 0 {
-      // Check if adding underscore + word would exceed limit
-      if result.len() + word.len() + 1 > max_length {
+      // Check if adding separator + word would exceed limit
+      if length + word_length + 1 > max_length {
         break;
       }
-      result.push('_');
+      result.push(separator);
+      length += 1;
     }
 
-    // Check if adding just the word would exceed limit
-    if result.len() + word.len() > max_length {
+    // Check if adding just the word would exceed limit. A first word that's longer than the
+    // limit on its own, as in titles without spaces, is cut between characters instead
+    if length + word_length > max_length {
+      if i == 0 {
+        result.extend(word.chars().take(max_length));
+      }
       break;
     }
     result.push_str(word);
+    length += word_length;
+  }
+
+  safe_file_name(result)
+}
+
+/// Characters that aren't allowed in file names on Windows, or separate path segments.
+const RESERVED_CHARACTERS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Whether `c` can't appear in a file name on some platform.
+fn is_reserved(c: char) -> bool { c.is_control() || RESERVED_CHARACTERS.contains(&c) }
+
+/// Names Windows reserves for devices, with or without an extension.
+const RESERVED_NAMES: [&str; 22] = [
+  "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+  "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// Makes a formatted title safe to use as a file name: without leading dots, which hide files
+/// on Unix, or trailing ones, which Windows ignores, and not named after a Windows device.
+fn safe_file_name(name: String) -> String {
+  let mut name = name.trim_matches('.').to_string();
+  let stem = name.split('.').next().unwrap_or_default();
+  if RESERVED_NAMES.contains(&stem) {
+    name.insert(stem.len(), '_');
+  }
+  name
+}
+
+/// Names a file after `paper` following `template`, such as `"{source}_{id}_{title}"`.
+///
+/// This is [`Paper::format_filename`] with the title shortened to the default length of
+/// [`format_title`]; see it for the placeholders. The result is always safe to use as a single
+/// file name: characters such as `/`, `:` and `?` are replaced by `_`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use learner::{format::render_filename, paper::Paper};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let paper = Paper::new("2301.07041").await?;
+/// assert_eq!(
+///   render_filename("{source}_{id}_{title}", &paper),
+///   "arxiv_2301.07041_verifiable_fully_homomorphic_encryption"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn render_filename(template: &str, paper: &Paper) -> String {
+  paper.format_filename(template, None)
+}
+
+/// Normalizes a person's name for comparison, so that spellings of the same name match.
+///
+/// Case, accents and other diacritics, and the amount of whitespace are ignored. Letters that
+/// don't decompose into a base letter and an accent, such as `ø` or `ł`, are spelled with their
+/// base letters.
+///
+/// # Examples
+///
+/// ```
+/// use learner::format::normalize_name;
+///
+/// assert_eq!(normalize_name("Peter  Schwabe"), "peter schwabe");
+/// assert_eq!(normalize_name("Ivan Damgård"), normalize_name("ivan damgard"));
+/// assert_eq!(normalize_name(" Léo Ducas "), "leo ducas");
+/// ```
+pub fn normalize_name(name: &str) -> String {
+  let decomposed = icu_normalizer::DecomposingNormalizer::new_nfd().normalize(name);
+  let mut normalized = String::with_capacity(decomposed.len());
+  for c in decomposed.chars() {
+    match c {
+      // Combining marks, left over from decomposing accented letters
+      '\u{0300}'..='\u{036f}'
+      | '\u{1ab0}'..='\u{1aff}'
+      | '\u{1dc0}'..='\u{1dff}'
+      | '\u{20d0}'..='\u{20ff}'
+      | '\u{fe20}'..='\u{fe2f}' => {},
+      'ø' | 'Ø' => normalized.push('o'),
+      'ł' | 'Ł' => normalized.push('l'),
+      'đ' | 'Đ' => normalized.push('d'),
+      'ß' => normalized.push_str("ss"),
+      'æ' | 'Æ' => normalized.push_str("ae"),
+      'œ' | 'Œ' => normalized.push_str("oe"),
+      c => normalized.extend(c.to_lowercase()),
+    }
+  }
+  normalized.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Scores how alike two titles are, from `0.0` for nothing in common to `1.0` for the same title.
+///
+/// Titles are compared with the Jaro-Winkler similarity after normalizing them like
+/// [`normalize_name`] and treating punctuation as whitespace, so differences in case, accents,
+/// punctuation and spacing don't count. Small edits, like a changed word or a missing subtitle,
+/// score above `0.85`, while different titles on the same topic usually score well below.
+///
+/// # Examples
+///
+/// ```
+/// use learner::format::title_similarity;
+///
+/// assert_eq!(title_similarity("Attention Is All You Need", "attention is all you need."), 1.0);
+/// assert!(
+///   title_similarity("Verifiable Fully Homomorphic Encryption", "Verifiable FHE Encryption") > 0.85
+/// );
+/// assert!(title_similarity("Attention Is All You Need", "Deep Residual Learning") < 0.7);
+/// ```
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+  let normalize = |title: &str| -> Vec<char> {
+    let title = normalize_name(title).replace(|c: char| !c.is_alphanumeric(), " ");
+    title.split_whitespace().collect::<Vec<_>>().join(" ").chars().collect()
+  };
+  jaro_winkler(&normalize(a), &normalize(b))
+}
+
+/// The Jaro-Winkler similarity of two strings, given as characters.
+fn jaro_winkler(a: &[char], b: &[char]) -> f64 {
+  if a.is_empty() && b.is_empty() {
+    return 1.0;
+  }
+  if a.is_empty() || b.is_empty() {
+    return 0.0;
+  }
+
+  // Characters match if they are the same and not too far apart
+  let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+  let mut b_matched = vec![false; b.len()];
+  let mut a_matches = Vec::new();
+  for (i, &c) in a.iter().enumerate() {
+    let start = i.saturating_sub(window);
+    let end = (i + window + 1).min(b.len());
+    if let Some(j) = (start..end).find(|&j| !b_matched[j] && b[j] == c) {
+      b_matched[j] = true;
+      a_matches.push(c);
+    }
+  }
+  if a_matches.is_empty() {
+    return 0.0;
+  }
+
+  // Half the matched characters that appear in a different order
+  let b_matches = b.iter().zip(&b_matched).filter(|(_, &matched)| matched).map(|(&c, _)| c);
+  let transpositions = a_matches.iter().zip(b_matches).filter(|(&a, b)| a != *b).count() / 2;
+
+  let m = a_matches.len() as f64;
+  let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0;
+
+  // Favor strings that start the same, for up to four characters
+  let prefix = a.iter().zip(b).take(4).take_while(|(a, b)| a == b).count() as f64;
+  jaro + prefix * 0.1 * (1.0 - jaro)
+}
+
+lazy_static! {
+  /// An HTML or XML tag, such as `<em>`, `</jats:p>` or `<a href="...">`. Anything else between
+  /// angle brackets, like `a < b and c > d` in a formula, is left alone.
+  static ref TAG: Regex = Regex::new(
+    r#"</?([A-Za-z][\w:.-]*)(?:\s+[\w:.-]+\s*=\s*(?:"[^"]*"|'[^']*'))*\s*/?>"#
+  )
+  .unwrap();
+  /// A named or numeric character reference, such as `&lt;`, `&#39;` or `&#x2014;`.
+  static ref ENTITY: Regex = Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[A-Za-z]+);").unwrap();
+  /// Display math between `$$` delimiters.
+  static ref DISPLAY_MATH: Regex = Regex::new(r"(?s)\$\$(.*?)\$\$").unwrap();
+  /// Inline math between `$` delimiters.
+  static ref INLINE_MATH: Regex = Regex::new(r"(?s)\$(.*?)\$").unwrap();
+  /// A blank line between paragraphs.
+  static ref PARAGRAPH_BREAK: Regex = Regex::new(r"\n\s*\n").unwrap();
+  /// A LaTeX text formatting command around a group without nested braces, such as
+  /// `\emph{new}` or `\textbf{not}`.
+  static ref TEXT_COMMAND: Regex = Regex::new(
+    r"\\(?:emph|textbf|textit|textsl|textsc|texttt|textrm|textsf|textup|textmd|underline)\s*\{([^{}]*)\}"
+  )
+  .unwrap();
+  /// An old-style LaTeX font switch around a group without nested braces, such as `{\em new}`.
+  static ref FONT_GROUP: Regex = Regex::new(r"\{\\(?:em|bf|it|sl|sc|tt|rm|sf)\s+([^{}]*)\}").unwrap();
+  /// A LaTeX escaped special character other than `\$`, such as `\%` or `\&`.
+  static ref LATEX_ESCAPE: Regex = Regex::new(r"\\([%&_#])").unwrap();
+}
+
+/// Tags that separate paragraphs, rather than marking up text within one.
+const PARAGRAPH_TAGS: &[&str] = &["p", "div", "br", "li", "sec", "title"];
+
+/// Turns an abstract as returned by a paper source into plain text.
+///
+/// Sources return abstracts with markup meant for rendering: arXiv keeps the LaTeX of formulas,
+/// and Crossref, IACR, and Semantic Scholar may include HTML or JATS XML tags and character
+/// references. This:
+/// - Decodes character references such as `&lt;` and `&#233;`, including doubly escaped ones like
+///   `&amp;lt;`
+/// - Strips HTML and XML tags, turning paragraph tags such as `<p>` into paragraph breaks
+/// - Cleans up LaTeX markup and whitespace as [`clean_latex`] does
+///
+/// # Examples
+///
+/// ```
+/// use learner::format::clean_abstract;
+///
+/// assert_eq!(
+///   clean_abstract("Sorting in $\\mathcal{O}(n \\log n)$\n  time"),
+///   "Sorting in \\mathcal{O}(n \\log n) time"
+/// );
+/// assert_eq!(clean_abstract("<p>We show <em>x</em> &lt; y.</p>"), "We show x < y.");
+/// ```
+pub fn clean_abstract(text: &str) -> String {
+  let text = decode_entities(text);
+  let text = TAG.replace_all(&text, |captures: &Captures| {
+    let name = captures[1].rsplit(':').next().unwrap_or_default().to_lowercase();
+    if PARAGRAPH_TAGS.contains(&name.as_str()) {
+      "\n\n"
+    } else {
+      ""
+    }
+  });
+
+  clean_latex(&text)
+}
+
+/// Turns text with LaTeX markup, as arXiv returns titles and abstracts, into plain text.
+///
+/// This:
+/// - Unwraps text formatting commands such as `\emph{...}`, `\textbf{...}` and `{\em ...}`, keeping
+///   their text
+/// - Removes the `$...$` and `$$...$$` delimiters around formulas, keeping the formula itself,
+///   while `\$` stays a literal dollar sign
+/// - Turns escaped special characters such as `\%` and `\&` into the characters themselves
+/// - Collapses whitespace within paragraphs, such as the line breaks and indentation of a wrapped
+///   abstract, keeping blank lines between paragraphs
+///
+/// Other commands, such as `\mathcal` within a formula, are kept as they are.
+///
+/// # Examples
+///
+/// ```
+/// use learner::format::clean_latex;
+///
+/// assert_eq!(
+///   clean_latex("An \\emph{optimal}\n  $\\mathcal{O}(n)$ algorithm for 50\\% of\n  big data"),
+///   "An optimal \\mathcal{O}(n) algorithm for 50% of big data"
+/// );
+/// ```
+pub fn clean_latex(text: &str) -> String {
+  // Unwrap the innermost commands first, until none are left, so nested ones come out too
+  let mut text = text.to_string();
+  loop {
+    let unwrapped = TEXT_COMMAND.replace_all(&text, "$1");
+    let unwrapped = FONT_GROUP.replace_all(&unwrapped, "$1");
+    if unwrapped == text {
+      break;
+    }
+    text = unwrapped.into_owned();
+  }
+
+  // Set escaped dollar signs aside so they aren't taken for math delimiters
+  const ESCAPED_DOLLAR: &str = "\u{e000}";
+  let text = text.replace("\\$", ESCAPED_DOLLAR);
+  let text = DISPLAY_MATH.replace_all(&text, "$1");
+  let text = INLINE_MATH.replace_all(&text, "$1");
+  let text = LATEX_ESCAPE.replace_all(&text, "$1").replace(ESCAPED_DOLLAR, "$");
+
+  PARAGRAPH_BREAK
+    .split(&text)
+    .map(|paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+    .filter(|paragraph| !paragraph.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n\n")
+}
+
+/// Decodes the character references in `text`, such as `&lt;` and `&#233;`, leaving unknown
+/// ones as they are.
+///
+/// Decoding is repeated until nothing changes, as some sources escape their text twice.
+pub(crate) fn decode_entities(text: &str) -> String {
+  let mut text = text.to_string();
+  loop {
+    let decoded =
+      ENTITY.replace_all(&text, |captures: &Captures| decode_entity(&captures[0], &captures[1]));
+    if decoded == text {
+      return text;
+    }
+    text = decoded.into_owned();
   }
+}
 
-  result
+/// Decodes one character reference, or returns it unchanged if it isn't a known one.
+fn decode_entity(reference: &str, name: &str) -> String {
+  let decoded = match name {
+    "amp" => Some('&'),
+    "lt" => Some('<'),
+    "gt" => Some('>'),
+    "quot" => Some('"'),
+    "apos" => Some('\''),
+    "nbsp" => Some(' '),
+    "ndash" => Some('–'),
+    "mdash" => Some('—'),
+    "hellip" => Some('…'),
+    "lsquo" => Some('‘'),
+    "rsquo" => Some('’'),
+    "ldquo" => Some('“'),
+    "rdquo" => Some('”'),
+    _ => name
+      .strip_prefix("#x")
+      .or_else(|| name.strip_prefix("#X"))
+      .map(|hex| u32::from_str_radix(hex, 16).ok())
+      .unwrap_or_else(|| name.strip_prefix('#').and_then(|decimal| decimal.parse().ok()))
+      .and_then(char::from_u32),
+  };
+  decoded.map_or_else(|| reference.to_string(), String::from)
 }
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_normalize_name() {
+    assert_eq!(normalize_name("Jens Groth"), "jens groth");
+    assert_eq!(normalize_name("jens\tGROTH"), "jens groth");
+    assert_eq!(normalize_name("Peter  Schwabe"), normalize_name("Peter Schwabe"));
+    assert_eq!(normalize_name("Ivan Damgård"), "ivan damgard");
+    assert_eq!(normalize_name("Søren Kierkegaard"), "soren kierkegaard");
+    assert_eq!(normalize_name("Paweł Łukasz"), "pawel lukasz");
+    assert_eq!(normalize_name("Gauß"), "gauss");
+    // Precomposed and decomposed accents are the same
+    assert_eq!(normalize_name("Andr\u{e9}"), normalize_name("Andre\u{301}"));
+    assert_eq!(normalize_name("   "), "");
+  }
+
+  #[test]
+  fn test_clean_abstract_strips_math_delimiters() {
+    assert_eq!(
+      clean_abstract("We prove $\\mathcal{O}(n \\log n)$ bounds for $k$-SAT."),
+      "We prove \\mathcal{O}(n \\log n) bounds for k-SAT."
+    );
+    assert_eq!(
+      clean_abstract("Given $$\\sum_{i=1}^n x_i = 0,$$ we show"),
+      "Given \\sum_{i=1}^n x_i = 0, we show"
+    );
+    // A formula may span lines, and escaped dollars are kept
+    assert_eq!(clean_abstract("for $a\n  + b$ at \\$5"), "for a + b at $5");
+    // Comparisons in formulas aren't mistaken for tags
+    assert_eq!(clean_abstract("when $a<b$ and $c>d$"), "when a<b and c>d");
+  }
+
+  #[test]
+  fn test_title_similarity() {
+    // Textbook Jaro-Winkler values
+    let chars = |s: &str| s.chars().collect::<Vec<_>>();
+    assert!((jaro_winkler(&chars("martha"), &chars("marhta")) - 0.961).abs() < 0.001);
+    assert!((jaro_winkler(&chars("dixon"), &chars("dicksonx")) - 0.813).abs() < 0.001);
+    assert_eq!(jaro_winkler(&chars("abc"), &chars("xyz")), 0.0);
+    assert_eq!(jaro_winkler(&[], &[]), 1.0);
+
+    // A preprint and its published version
+    assert!(
+      title_similarity(
+        "SNARKs for C: Verifying Program Executions Succinctly and in Zero Knowledge",
+        "SNARKs for C: verifying program executions succinctly and in zero knowledge (extended \
+         version)"
+      ) > 0.85
+    );
+    assert!(title_similarity("Lattice-Based Cryptography", "Lattice Based  Cryptography") == 1.0);
+    assert!(title_similarity("Théorie des Nombres", "Theorie des nombres") == 1.0);
+    assert!(
+      title_similarity("Zero-Knowledge Proofs for Set Membership", "A Survey of Post-Quantum KEMs")
+        < 0.7
+    );
+    assert_eq!(title_similarity("", "Something"), 0.0);
+  }
+
+  #[test]
+  fn test_clean_latex() {
+    assert_eq!(
+      clean_latex("We \\emph{prove} a \\textbf{tight} bound, \\textit{i.e.} {\\em optimal}"),
+      "We prove a tight bound, i.e. optimal"
+    );
+    // Nested commands, and commands within formulas
+    assert_eq!(clean_latex("\\emph{very \\textbf{bold}} claims"), "very bold claims");
+    assert_eq!(clean_latex("$\\textbf{x} \\in \\mathbb{R}^n$"), "x \\in \\mathbb{R}^n");
+    // Escaped characters, and braces that aren't a command's are kept
+    assert_eq!(clean_latex("R\\&D at 5\\% for \\$10 in \\#1_{2}"), "R&D at 5% for $10 in #1_{2}");
+    // arXiv wraps long titles and abstracts, indenting the continuation lines
+    assert_eq!(
+      clean_latex("Scaling Laws for\n  Big\n  Data\n\n  Second paragraph"),
+      "Scaling Laws for Big Data\n\nSecond paragraph"
+    );
+  }
+
+  #[test]
+  fn test_clean_abstract_decodes_html() {
+    assert_eq!(clean_abstract("A &lt;&lt; B &amp; C &#8212; D&#x2019;s"), "A << B & C — D’s");
+    // Doubly escaped references, and tags hidden behind them
+    assert_eq!(clean_abstract("x &amp;lt; y, &amp;amp;"), "x < y, &");
+    assert_eq!(clean_abstract("H&lt;sub&gt;2&lt;/sub&gt;O"), "H2O");
+    assert_eq!(clean_abstract("&bogus; &#xZZ;"), "&bogus; &#xZZ;");
+    assert_eq!(
+      clean_abstract(
+        "<jats:title>Abstract</jats:title><jats:p>We <jats:italic>study</jats:italic>\n  \
+         graphs.</jats:p><jats:p>They <a href=\"https://example.org\">help</a>.</jats:p>"
+      ),
+      "Abstract\n\nWe study graphs.\n\nThey help."
+    );
+  }
+
   #[test]
   fn test_format_title() {
     assert_eq!(format_title("Hello World", None), "hello_world");
@@ -115,4 +563,78 @@ mod tests {
     assert_eq!(format_title("UPPERCASE TEXT", None), "uppercase_text");
     assert_eq!(format_title("No    Extra    Spaces", None), "no_extra_spaces");
   }
+
+  #[test]
+  fn test_format_title_with_separator() {
+    assert_eq!(format_title_with("Hello World", None, '-'), "hello-world");
+    assert_eq!(format_title_with("No    Extra    Spaces", None, '-'), "no-extra-spaces");
+    assert_eq!(format_title_with("No  \t  Extra   Spaces", None, '.'), "no.extra.spaces");
+    assert_eq!(format_title_with("TCP/IP: A Survey?", None, '-'), "tcp-ip-a-survey");
+    assert_eq!(format_title_with("Et al.", None, '.'), "et.al");
+    assert_eq!(format_title_with("Before - After", None, '-'), "before-after");
+
+    // Truncation splits words at the separator
+    let title = "This Is A Very Long Title Indeed";
+    assert_eq!(format_title_with(title, Some(20), '-'), "this-is-a-very-long");
+    assert_eq!(format_title_with(title, Some(30), '.'), "this.is.a.very.long.title");
+    assert_eq!(format_title_with("Zero-Knowledge Proofs", Some(10), '-'), "zero");
+    assert_eq!(format_title_with("Zero-Knowledge Proofs", Some(10), '_'), "zero-knowl");
+
+    // The default separator is an underscore
+    assert_eq!(format_title_with(title, Some(20), '_'), format_title(title, Some(20)));
+
+    // Separators that can't appear in file names fall back to an underscore
+    assert_eq!(format_title_with("Hello World", None, '/'), "hello_world");
+    assert_eq!(format_title_with("Hello World", None, ' '), "hello_world");
+  }
+
+  #[test]
+  fn test_format_title_is_a_safe_file_name() {
+    for (title, expected) in [
+      ("Input/Output Complexity", "input_output_complexity"),
+      ("TCP/IP: A Survey", "tcp_ip_a_survey"),
+      ("What Is a Monad?", "what_is_a_monad"),
+      (r#"A "Quoted" <Title> | C:\\Windows\\*"#, "a_quoted_title_c_windows"),
+      ("Tabs\tand\u{7}bells", "tabs_and_bells"),
+      ("../../etc/passwd", "etc_passwd"),
+      ("Et al.", "et_al"),
+      (".NET Internals", "net_internals"),
+      ("Wait ... What?", "wait_what"),
+      ("CON", "con_"),
+      ("Nul", "nul_"),
+      ("aux.pdf", "aux_.pdf"),
+      ("Con Artists", "con_artists"),
+      ("lpt1", "lpt1_"),
+    ] {
+      let formatted = format_title(title, None);
+      assert_eq!(formatted, expected, "{title}");
+      assert_eq!(std::path::Path::new(&formatted).components().count(), 1, "{title}");
+    }
+
+    // Truncation still happens at word boundaries
+    assert_eq!(format_title("Cryptography: Theory/Practice", Some(19)), "cryptography_theory");
+    assert_eq!(format_title("Con Artists", Some(3)), "con_");
+  }
+
+  #[test]
+  fn test_format_title_counts_characters() {
+    // 21 characters, but 25 bytes
+    let title = "Über die Größe von Möbeln";
+    assert_eq!(format_title(title, Some(21)), "über_die_größe_von");
+    assert_eq!(format_title(title, Some(25)), "über_die_größe_von_möbeln");
+
+    // Without spaces, the title is one word cut at the limit
+    let title = "深層学習による画像認識の研究";
+    let formatted = format_title(title, Some(6));
+    assert_eq!(formatted, "深層学習によ");
+    assert_eq!(formatted.chars().count(), 6);
+    assert!(std::str::from_utf8(formatted.as_bytes()).is_ok());
+
+    let formatted = format_title("機械学習 入門 概論", Some(8));
+    assert_eq!(formatted, "機械学習_入門");
+    for max_length in 1..20 {
+      let formatted = format_title("Ça marche très bien, n’est-ce pas", Some(max_length));
+      assert!(formatted.chars().count() <= max_length, "{formatted}");
+    }
+  }
 }
@@ -1,5 +1,6 @@
 //! A library for fetching academic papers and their metadata from various sources
-//! including arXiv, IACR, and DOI-based repositories.
+//! including arXiv, IACR, Semantic Scholar, PubMed, bioRxiv/medRxiv, and DOI-based
+//! repositories.
 //!
 //! # Example
 //! ```no_run
@@ -25,13 +26,19 @@ use tracing::{debug, trace};
 #[cfg(test)]
 use {tempfile::tempdir, tracing_test::traced_test};
 
+pub mod bibtex;
+pub mod cache;
 pub mod clients;
 pub mod database;
 pub mod errors;
 pub mod format;
 pub mod paper;
+pub mod progress;
 
-use clients::{ArxivClient, DOIClient, IACRClient};
+use clients::{
+  ArxivClient, BioRxivClient, DOIClient, IACRClient, OpenAlexClient, PaperSource, PubMedClient,
+  SSRNClient, SemanticScholarClient, SourceRegistry,
+};
 use database::Database;
 use errors::LearnerError;
-use paper::{Author, Paper, Source};
+use paper::{Author, Paper, ReadingStatus, Source};
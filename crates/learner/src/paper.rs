@@ -24,17 +24,31 @@
 //! # }
 //! ```
 
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
+
+use chrono::Datelike;
 use lazy_static::lazy_static;
 use regex::Regex;
+use tokio::{
+  io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+  sync::Semaphore,
+  task::JoinSet,
+};
 use url::Url;
 
 use super::*;
+use crate::clients::{semantic_scholar, RateLimiter};
 
 /// The source repository or system from which a paper originates.
 ///
 /// This enum represents the supported academic paper sources, each with its own
 /// identifier format and access patterns.
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+///
+/// Sources are displayed, and stored in the database, by the lowercase names
+/// [`Source::from_str`] accepts, such as `arxiv` or `semanticscholar`. Parsing ignores case and
+/// also accepts `s2`, `pmid` and `medrxiv`. With the `clap` feature, [`clap::ValueEnum`] is
+/// implemented with the same names and aliases.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum Source {
   /// Papers from arxiv.org, using either new-style (2301.07041) or
   /// old-style (math.AG/0601001) identifiers
@@ -43,27 +57,139 @@ pub enum Source {
   IACR,
   /// Papers identified by a Digital Object Identifier (DOI)
   DOI,
+  /// Papers from Semantic Scholar (semanticscholar.org), identified by their 40 character
+  /// hexadecimal paper ID
+  SemanticScholar,
+  /// Papers from PubMed (pubmed.ncbi.nlm.nih.gov), identified by their PubMed ID (PMID)
+  PubMed,
+  /// Preprints from bioRxiv (biorxiv.org) or medRxiv (medrxiv.org), identified by their
+  /// `10.1101` DOI
+  BioRxiv,
+  /// Works from OpenAlex (openalex.org), identified by their OpenAlex Work ID (e.g.
+  /// "W2741809807")
+  OpenAlex,
+  /// Papers from SSRN (ssrn.com), identified by their abstract ID (e.g. "4123456")
+  SSRN,
 }
 
-impl std::fmt::Display for Source {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Source {
+  /// Every source, in declaration order.
+  pub const ALL: [Source; 8] = [
+    Source::Arxiv,
+    Source::IACR,
+    Source::DOI,
+    Source::SemanticScholar,
+    Source::PubMed,
+    Source::BioRxiv,
+    Source::OpenAlex,
+    Source::SSRN,
+  ];
+
+  /// The source's canonical name, as stored in the database and accepted by
+  /// [`Source::from_str`].
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Source::Arxiv => "arxiv",
+      Source::IACR => "iacr",
+      Source::DOI => "doi",
+      Source::SemanticScholar => "semanticscholar",
+      Source::PubMed => "pubmed",
+      Source::BioRxiv => "biorxiv",
+      Source::OpenAlex => "openalex",
+      Source::SSRN => "ssrn",
+    }
+  }
+
+  /// The source's name as written in prose, e.g. "arXiv" or "Semantic Scholar".
+  pub fn name(&self) -> &'static str {
     match self {
-      Source::Arxiv => write!(f, "Arxiv"),
-      Source::IACR => write!(f, "IACR"),
-      Source::DOI => write!(f, "DOI"),
+      Source::Arxiv => "arXiv",
+      Source::IACR => "IACR ePrint",
+      Source::DOI => "DOI",
+      Source::SemanticScholar => "Semantic Scholar",
+      Source::PubMed => "PubMed",
+      Source::BioRxiv => "bioRxiv",
+      Source::OpenAlex => "OpenAlex",
+      Source::SSRN => "SSRN",
+    }
+  }
+
+  /// Other names [`Source::from_str`] accepts for the source.
+  fn aliases(&self) -> &'static [&'static str] {
+    match self {
+      Source::SemanticScholar => &["s2"],
+      Source::PubMed => &["pmid"],
+      Source::BioRxiv => &["medrxiv"],
+      _ => &[],
     }
   }
 }
 
+impl std::fmt::Display for Source {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.pad(self.as_str()) }
+}
+
 impl FromStr for Source {
   type Err = LearnerError;
 
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let s = s.to_lowercase();
+    Source::ALL
+      .into_iter()
+      .find(|source| source.as_str() == s || source.aliases().contains(&s.as_str()))
+      .ok_or(LearnerError::InvalidSource(s))
+  }
+}
+
+#[cfg(feature = "clap")]
+impl clap::ValueEnum for Source {
+  fn value_variants<'a>() -> &'a [Self] { &Source::ALL }
+
+  fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+    Some(clap::builder::PossibleValue::new(self.as_str()).aliases(self.aliases().iter().copied()))
+  }
+}
+
+/// How far along reading a paper is, as tracked in the database (see
+/// [`Database::set_reading_status`]).
+///
+/// Parsing from a string accepts `unread`, `reading`, and `read`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadingStatus {
+  /// Not started yet, the status of every newly added paper
+  #[default]
+  Unread,
+  /// Currently being read
+  Reading,
+  /// Finished
+  Read,
+}
+
+impl ReadingStatus {
+  /// The status as stored in the database and accepted by [`ReadingStatus::from_str`].
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      ReadingStatus::Unread => "unread",
+      ReadingStatus::Reading => "reading",
+      ReadingStatus::Read => "read",
+    }
+  }
+}
+
+impl std::fmt::Display for ReadingStatus {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str(self.as_str()) }
+}
+
+impl FromStr for ReadingStatus {
+  type Err = LearnerError;
+
   fn from_str(s: &str) -> Result<Self, Self::Err> {
     match &s.to_lowercase() as &str {
-      "arxiv" => Ok(Source::Arxiv),
-      "iacr" => Ok(Source::IACR),
-      "doi" => Ok(Source::DOI),
-      s => Err(LearnerError::InvalidSource(s.to_owned())),
+      "unread" => Ok(ReadingStatus::Unread),
+      "reading" => Ok(ReadingStatus::Reading),
+      "read" => Ok(ReadingStatus::Read),
+      s => Err(LearnerError::InvalidReadingStatus(s.to_owned())),
     }
   }
 }
@@ -111,8 +237,12 @@ pub struct Paper {
   pub title:             String,
   /// List of the paper's authors
   pub authors:           Vec<Author>,
-  /// The paper's abstract text
+  /// The paper's abstract text, as plain text (see [`format::clean_abstract`])
   pub abstract_text:     String,
+  /// The abstract as returned by the source, if cleaning it up for
+  /// [`Paper::abstract_text`] removed markup such as LaTeX math delimiters or HTML tags
+  #[serde(default)]
+  pub raw_abstract:      Option<String>,
   /// When the paper was published or last updated
   pub publication_date:  DateTime<Utc>,
   /// The source system (arXiv, IACR, DOI)
@@ -123,6 +253,35 @@ pub struct Paper {
   pub pdf_url:           Option<String>,
   /// The paper's DOI, if available
   pub doi:               Option<String>,
+  /// The version of the paper that was fetched (e.g. "v2" for arXiv), for sources that
+  /// version their papers
+  #[serde(default)]
+  pub version:           Option<String>,
+  /// Subject categories (e.g. "cs.CR" for arXiv, or OpenAlex concepts such as "Computer
+  /// science"), with the primary category first. Empty for sources without categories
+  #[serde(default)]
+  pub categories:        Vec<String>,
+  /// Subject keywords describing the paper: arXiv categories, Crossref subject areas (e.g.
+  /// "Computer Networks and Communications"), or OpenAlex keywords. Empty for sources without
+  /// them
+  #[serde(default)]
+  pub keywords:          Vec<String>,
+  /// Tags attached to the paper in the database (see [`crate::database::Database::add_tag`]), in
+  /// alphabetical order. Always empty for freshly fetched papers
+  #[serde(default)]
+  pub tags:              Vec<String>,
+  /// Where the paper's PDF was downloaded to, as recorded in the database (see
+  /// [`Paper::download_and_record_pdf`]). Always `None` for freshly fetched papers
+  #[serde(default)]
+  pub pdf_path:          Option<PathBuf>,
+  /// How far along reading the paper is, as tracked in the database. Always
+  /// [`ReadingStatus::Unread`] for freshly fetched papers
+  #[serde(default)]
+  pub reading_status:    ReadingStatus,
+  /// When the paper was saved to the database (see
+  /// [`crate::database::Database::recent_papers`]). Always `None` for freshly fetched papers
+  #[serde(default)]
+  pub added_at:          Option<DateTime<Utc>>,
 }
 
 impl Paper {
@@ -140,6 +299,29 @@ impl Paper {
   ///   - An IACR ID (e.g., "2023/123")
   ///   - A DOI URL (e.g., "https://doi.org/10.1145/1327452.1327492")
   ///   - A DOI (e.g., "10.1145/1327452.1327492")
+  ///   - An ACM Digital Library URL (e.g., "https://dl.acm.org/doi/10.1145/1327452.1327492"), which
+  ///     is fetched by its DOI
+  ///   - An IEEE Xplore URL (e.g., "https://ieeexplore.ieee.org/document/771073"), which is fetched
+  ///     by the DOI its page gives (see [`IEEEClient`](crate::clients::IEEEClient))
+  ///   - A Semantic Scholar URL (e.g.,
+  ///     "https://www.semanticscholar.org/paper/204e3073870fae3d05bcbc2f6a8e263d9b72e776")
+  ///   - A Semantic Scholar paper ID (e.g., "204e3073870fae3d05bcbc2f6a8e263d9b72e776")
+  ///   - A PubMed URL (e.g., "https://pubmed.ncbi.nlm.nih.gov/31452104/" or
+  ///     "https://www.ncbi.nlm.nih.gov/pubmed/31452104")
+  ///   - A PubMed ID, optionally prefixed with `pmid:` or `pubmed:` (e.g., "31452104" or
+  ///     "pmid:31452104")
+  ///   - A bioRxiv or medRxiv URL (e.g.,
+  ///     "https://www.biorxiv.org/content/10.1101/2020.03.20.000001v2")
+  ///   - A bioRxiv or medRxiv DOI (e.g., "10.1101/2020.03.20.000001"), which is fetched from
+  ///     bioRxiv rather than through DOI resolution
+  ///   - An OpenAlex URL (e.g., "https://openalex.org/W2741809807")
+  ///   - An OpenAlex Work ID (e.g., "W2741809807")
+  ///   - An SSRN URL (e.g., "https://ssrn.com/abstract=4123456" or
+  ///     "https://papers.ssrn.com/sol3/papers.cfm?abstract_id=4123456")
+  ///   - An SSRN abstract ID prefixed with `ssrn:` (e.g., "ssrn:4123456"), as bare numbers are
+  ///     taken to be PubMed IDs
+  ///   - Anything recognized by a source added with [`SourceRegistry::register`], which is
+  ///     consulted after the built-in sources above
   ///
   /// # Returns
   ///
@@ -164,83 +346,499 @@ impl Paper {
   /// # }
   /// ```
   pub async fn new(input: &str) -> Result<Self, LearnerError> {
-    lazy_static! {
-        // arXiv patterns
-        static ref ARXIV_NEW: Regex = Regex::new(r"^(\d{4}\.\d{4,5})$").unwrap();
-        static ref ARXIV_OLD: Regex = Regex::new(r"^([a-zA-Z-]+/\d{7})$").unwrap();
+    SourceRegistry::global().fetch_paper(input).await
+  }
 
-        // IACR pattern
-        static ref IACR: Regex = Regex::new(r"^(\d{4}/\d+)$").unwrap();
+  /// Adds a source for [`Paper::new`] to fetch papers from, consulted after the built-in
+  /// sources and any added before it.
+  ///
+  /// This is [`SourceRegistry::register`]; see [`clients::source`] for an example.
+  pub fn register_client(source: impl PaperSource + 'static) { SourceRegistry::register(source) }
 
-        // DOI pattern
-        static ref DOI: Regex = Regex::new(r"^10\.\d{4,9}/[-._;()/:\w]+$").unwrap();
-    }
+  /// Create papers from many URLs, identifiers, or DOIs at once.
+  ///
+  /// This is [`Paper::new_many_with`] using the default [`BatchOptions`], which fetch up to
+  /// five papers at a time.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::paper::Paper;
+  /// # async fn example() {
+  /// let results = Paper::new_many(&["2301.07041", "10.1145/1327452.1327492", "2016/260"]).await;
+  /// for result in results {
+  ///   match result {
+  ///     Ok(paper) => println!("Fetched: {}", paper.title),
+  ///     Err(e) => println!("Failed: {}", e),
+  ///   }
+  /// }
+  /// # }
+  /// ```
+  pub async fn new_many(inputs: &[&str]) -> Vec<Result<Self, LearnerError>> {
+    Self::new_many_with(inputs, BatchOptions::default()).await
+  }
 
-    // First try to parse as URL
-    if let Ok(url) = Url::parse(input) {
-      return match url.host_str() {
-        Some("arxiv.org") => {
-          let id = extract_arxiv_id(&url)?;
-          ArxivClient::new().fetch_paper(&id).await
-        },
-        Some("eprint.iacr.org") => {
-          let id = extract_iacr_id(&url)?;
-          IACRClient::new().fetch_paper(&id).await
-        },
-        Some("doi.org") => {
-          let doi = extract_doi(&url)?;
-          DOIClient::new().fetch_paper(&doi).await
-        },
-        _ => Err(LearnerError::InvalidIdentifier),
-      };
-    }
+  /// Create papers from many URLs, identifiers, or DOIs, fetching them concurrently.
+  ///
+  /// Every input is resolved independently as in [`Paper::new`], so an invalid identifier or a
+  /// failed request only affects its own result. At most `options.concurrency` requests are in
+  /// flight at once, and requests to the same source are spaced out by
+  /// [`Source::min_request_interval`] (e.g. three seconds for arXiv, following its API
+  /// guidance).
+  ///
+  /// # Arguments
+  ///
+  /// * `inputs` - Paper URLs, identifiers, or DOIs, in any format [`Paper::new`] accepts, including
+  ///   those recognized by sources added with [`SourceRegistry::register`]
+  /// * `options` - Controls how many papers are fetched at once
+  ///
+  /// # Returns
+  ///
+  /// Returns one result per input, in the same order as `inputs`.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::paper::{BatchOptions, Paper};
+  /// # async fn example() {
+  /// let options = BatchOptions { concurrency: 2 };
+  /// let results = Paper::new_many_with(&["2301.07041", "2016/260"], options).await;
+  /// assert_eq!(results.len(), 2);
+  /// # }
+  /// ```
+  pub async fn new_many_with(
+    inputs: &[&str],
+    options: BatchOptions,
+  ) -> Vec<Result<Self, LearnerError>> {
+    let permits = Arc::new(Semaphore::new(options.concurrency.max(1)));
+    // One limiter per source, so requests to different sources don't wait on each other
+    let limiters = Arc::new(
+      Source::ALL
+        .map(|source| {
+          let limiter = RateLimiter::with_interval(source.min_request_interval());
+          (source, limiter)
+        })
+        .into_iter()
+        .collect::<HashMap<_, _>>(),
+    );
 
-    // If not a URL, try to match against known patterns
-    match input {
-      // arXiv patterns
-      id if ARXIV_NEW.is_match(id) || ARXIV_OLD.is_match(id) =>
-        ArxivClient::new().fetch_paper(id).await,
+    let registry = SourceRegistry::global();
+    let mut tasks = JoinSet::new();
+    for (index, input) in inputs.iter().enumerate() {
+      let resolved = registry.resolve(input);
+      let permits = Arc::clone(&permits);
+      let limiters = Arc::clone(&limiters);
+      tasks.spawn(async move {
+        let result = async {
+          let (source, identifier) = resolved.ok_or(LearnerError::InvalidIdentifier)?;
+          // Waiting for a source's turn doesn't hold a permit, so other sources can go meanwhile.
+          // Custom sources without a `Source` of their own aren't paced
+          if let Some(kind) = source.source() {
+            limiters[&kind].wait().await;
+          }
+          let _permit = permits.acquire().await.expect("semaphore is never closed");
+          debug!("Fetching paper {identifier} ({index})");
+          source.fetch_paper(&identifier).await
+        }
+        .await;
+        (index, result)
+      });
+    }
 
-      // IACR pattern
-      id if IACR.is_match(id) => IACRClient::new().fetch_paper(id).await,
+    let mut results: Vec<Option<Result<Self, LearnerError>>> =
+      std::iter::repeat_with(|| None).take(inputs.len()).collect();
+    while let Some(joined) = tasks.join_next().await {
+      match joined {
+        Ok((index, result)) => results[index] = Some(result),
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+      }
+    }
+    results.into_iter().map(|result| result.expect("every input produces a result")).collect()
+  }
 
-      // DOI pattern
-      id if DOI.is_match(id) => DOIClient::new().fetch_paper(id).await,
+  /// Finds papers related to this one, as recommended by Semantic Scholar.
+  ///
+  /// The paper is looked up on Semantic Scholar by its identifier (see
+  /// [`semantic_scholar::lookup_id`]), or by its DOI for sources Semantic Scholar doesn't index
+  /// by their own identifiers.
+  ///
+  /// # Arguments
+  ///
+  /// * `client` - The client to ask for recommendations
+  /// * `limit` - The most papers to return
+  ///
+  /// # Returns
+  ///
+  /// The recommended papers, most related first, as [`Source::SemanticScholar`] papers.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::ApiError`] if the paper can't be looked up on Semantic Scholar,
+  /// having neither an identifier it knows nor a DOI, and otherwise whatever error
+  /// [`SemanticScholarClient::recommendations`] fails with.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # use learner::{clients::SemanticScholarClient, paper::Paper};
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let paper = Paper::new("2301.07041").await?;
+  /// for related in paper.find_related(&SemanticScholarClient::new(), 5).await? {
+  ///   println!("{}", related.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn find_related(
+    &self,
+    client: &SemanticScholarClient,
+    limit: usize,
+  ) -> Result<Vec<Paper>, LearnerError> {
+    let paper_id = semantic_scholar::lookup_id(&self.source, &self.source_identifier)
+      .or_else(|| self.doi.as_ref().map(|doi| format!("DOI:{doi}")))
+      .ok_or_else(|| {
+        LearnerError::ApiError(format!(
+          "{} {} can't be looked up on Semantic Scholar without a DOI",
+          self.source, self.source_identifier
+        ))
+      })?;
+    client.recommendations(&paper_id, limit).await
+  }
 
-      // No pattern matched
-      _ => Err(LearnerError::InvalidIdentifier),
-    }
+  /// Names a file after the paper, following a template such as `"{year}_{first_author}_{title}"`.
+  ///
+  /// The template's placeholders are replaced by the paper's details, all safe to use in a
+  /// file name:
+  /// - `{title}`: the title, lowercased with words joined by underscores (see
+  ///   [`format::format_title`])
+  /// - `{first_author}` (or `{author}`): the first author's lowercased last name, or `unknown`
+  /// - `{year}`: the year of publication
+  /// - `{source_id}` (or `{id}`): the identifier within the source, e.g. `2301.07041`
+  /// - `{source}`: the source, lowercased, e.g. `arxiv`
+  ///
+  /// Placeholders are checked by [`validate_filename_template`]; unknown ones are left as they
+  /// are. Characters that can't appear in file names, such as `/`, are replaced by `_`, both in
+  /// the substituted details and in the template itself, so the name never reaches into another
+  /// directory. [`format::render_filename`] is shorthand for the default title length.
+  ///
+  /// # Arguments
+  ///
+  /// * `template` - The file name template, without extension
+  /// * `max_length` - The maximum length of `{title}`, as for [`format::format_title`]
+  ///
+  /// # Returns
+  ///
+  /// The file name, without extension.
+  pub fn format_filename(&self, template: &str, max_length: Option<usize>) -> String {
+    let filename =
+      FILENAME_PLACEHOLDER.replace_all(template, |captures: &regex::Captures| match &captures[1] {
+        "title" => format::format_title(&self.title, max_length),
+        "first_author" | "author" => self
+          .authors
+          .first()
+          .map(|author| last_name_first(&author.name))
+          .and_then(|name| name.split(',').next().map(|last| format::format_title(last, None)))
+          .filter(|last| !last.is_empty())
+          .unwrap_or_else(|| "unknown".to_string()),
+        "year" => self.publication_date.year().to_string(),
+        "source_id" | "id" => self.source_identifier.clone(),
+        "source" => self.source.to_string(),
+        _ => captures[0].to_string(),
+      });
+    filename
+      .chars()
+      .map(|c| match c {
+        '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+        c if c.is_control() => '_',
+        c => c,
+      })
+      .collect()
   }
 
   /// Download the paper's PDF to a specified path.
   ///
+  /// The PDF is streamed to disk as it arrives rather than held in memory, and only moved into
+  /// place once it has been checked to be complete. If the connection drops, what arrived is
+  /// kept in a `.pdf.part` file next to the PDF, and the next download asks the server for just
+  /// the rest with an HTTP `Range` request, starting over if the server doesn't support them.
+  /// Downloads go through a shared client (see [`crate::clients::http::pdf_client`]) with a 30
+  /// second timeout, learner's user agent, and a limit on redirects.
+  ///
   /// # Arguments
   ///
-  /// * `path` - The filesystem path where the PDF should be saved
+  /// * `dir` - The directory the PDF is created in, named by the default filename template (see
+  ///   [`DEFAULT_FILENAME_TEMPLATE`])
   ///
   /// # Errors
   ///
   /// Returns `LearnerError` if:
   /// - The paper has no PDF URL available
   /// - The download fails
+  /// - The server returns something other than a complete PDF, such as an HTML login page or a
+  ///   truncated file, as checked by [`validate_pdf`] ([`LearnerError::InvalidPdf`])
   /// - Writing to the specified path fails
-  pub async fn download_pdf(&self, dir: PathBuf) -> Result<(), LearnerError> {
-    // unimplemented!("Work in progress -- needs integrated with `Database`");
+  ///
+  /// # Returns
+  ///
+  /// Returns the path of the written PDF, named after the paper's formatted title (see
+  /// [`DEFAULT_FILENAME_TEMPLATE`]).
+  pub async fn download_pdf(&self, dir: PathBuf) -> Result<PathBuf, LearnerError> {
+    self.download_pdf_with(clients::http::pdf_client(), dir).await
+  }
+
+  /// Download the paper's PDF as [`Paper::download_pdf`] does, reporting progress as it arrives.
+  ///
+  /// `progress` is called after each chunk is written with the number of bytes downloaded so
+  /// far and the total size of the PDF, if the server gave a `Content-Length`. A resumed
+  /// download counts the bytes kept from the earlier attempt in both.
+  ///
+  /// # Errors
+  ///
+  /// See [`Paper::download_pdf`].
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let paper = learner::paper::Paper::new("2301.07041").await?;
+  /// paper
+  ///   .download_pdf_with_progress("pdfs".into(), |downloaded, total| match total {
+  ///     Some(total) => println!("{downloaded} of {total} bytes"),
+  ///     None => println!("{downloaded} bytes"),
+  ///   })
+  ///   .await?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn download_pdf_with_progress(
+    &self,
+    dir: PathBuf,
+    progress: impl Fn(u64, Option<u64>) + Send + Sync,
+  ) -> Result<PathBuf, LearnerError> {
+    self.download(clients::http::pdf_client(), dir, DEFAULT_FILENAME_TEMPLATE, &progress).await
+  }
+
+  /// Download the paper's PDF to a specified path using the given HTTP client.
+  ///
+  /// This is [`Paper::download_pdf`] with control over the client, e.g. one built by
+  /// [`ClientConfig::pdf_client`](crate::clients::ClientConfig::pdf_client) with a longer
+  /// timeout.
+  ///
+  /// # Arguments
+  ///
+  /// * `client` - The client to download with
+  /// * `dir` - The directory the PDF is created in, named by the default filename template (see
+  ///   [`DEFAULT_FILENAME_TEMPLATE`])
+  ///
+  /// # Errors
+  ///
+  /// See [`Paper::download_pdf`]. Nothing is written unless the response is a PDF.
+  pub async fn download_pdf_with(
+    &self,
+    client: &reqwest::Client,
+    dir: PathBuf,
+  ) -> Result<PathBuf, LearnerError> {
+    self.download_pdf_as(client, dir, DEFAULT_FILENAME_TEMPLATE).await
+  }
+
+  /// Download the paper's PDF using the given HTTP client, naming the file after a template.
+  ///
+  /// This is [`Paper::download_pdf_with`] with the file named by [`Paper::format_filename`],
+  /// with titles limited to 50 characters.
+  ///
+  /// # Arguments
+  ///
+  /// * `client` - The client to download with
+  /// * `dir` - The directory where the PDF should be saved
+  /// * `template` - The file name template, e.g. `"{year}_{first_author}_{title}"`
+  ///
+  /// # Errors
+  ///
+  /// See [`Paper::download_pdf`].
+  pub async fn download_pdf_as(
+    &self,
+    client: &reqwest::Client,
+    dir: PathBuf,
+    template: &str,
+  ) -> Result<PathBuf, LearnerError> {
+    self.download(client, dir, template, &|_, _| {}).await
+  }
+
+  /// Downloads the PDF as [`Paper::download_pdf_as`] does, calling `progress` with the bytes
+  /// downloaded and the total size, if known, after each chunk is written.
+  async fn download(
+    &self,
+    client: &reqwest::Client,
+    dir: PathBuf,
+    template: &str,
+    progress: &(dyn Fn(u64, Option<u64>) + Send + Sync),
+  ) -> Result<PathBuf, LearnerError> {
     let Some(pdf_url) = &self.pdf_url else {
       return Err(LearnerError::ApiError("No PDF URL available".into()));
     };
 
-    let response = reqwest::get(pdf_url).await?;
+    // Stream into a partial file next to the final one, so an interrupted or rejected download
+    // never leaves a broken PDF under the paper's name. A partial file left by an interrupted
+    // download is resumed where it stopped, if the server supports ranges.
+    let path = dir.join(format!("{}.pdf", self.format_filename(template, Some(50))));
+    let partial = path.with_extension("pdf.part");
+    let resume_from = tokio::fs::metadata(&partial).await.map_or(0, |metadata| metadata.len());
+
+    let mut request = client.get(pdf_url);
+    if resume_from > 0 {
+      request = request.header(reqwest::header::RANGE, format!("bytes={resume_from}-"));
+    }
+    let mut response = request.send().await?;
     trace!("{} pdf_url response: {response:?}", self.source);
-    let bytes = response.bytes().await?;
+    let resuming = resume_from > 0
+      && response.status() == reqwest::StatusCode::PARTIAL_CONTENT
+      && response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)
+        .and_then(|range| range.to_str().ok())
+        .is_some_and(|range| range.starts_with(&format!("bytes {resume_from}-")));
+    if resuming {
+      debug!("Resuming download of {pdf_url} from byte {resume_from}");
+    } else if resume_from > 0 {
+      debug!(
+        "Can't resume download of {pdf_url} from byte {resume_from} ({}), restarting",
+        response.status()
+      );
+      // A full response replaces the partial file, anything else is requested again in full
+      if response.status() != reqwest::StatusCode::OK {
+        response = client.get(pdf_url).send().await?;
+      }
+    }
+    let status = response.status();
+    if !status.is_success() {
+      return Err(LearnerError::ApiError(format!("PDF download returned {status}")));
+    }
+    let invalid = |head: &[u8]| {
+      let preview = String::from_utf8_lossy(&head[..head.len().min(INVALID_PDF_PREVIEW)]);
+      LearnerError::InvalidPdf(format!("{pdf_url} returned {status}: {preview:?}"))
+    };
+
+    // A login or bot check page is labelled as HTML, so there's no need to download the rest of
+    // it. The start of the body still decides, as some servers label PDFs as HTML too.
+    let is_html = response
+      .headers()
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|content_type| content_type.to_str().ok())
+      .is_some_and(|content_type| content_type.starts_with("text/html"));
+    // The size hint shrinks as the body is read, so take it first
+    let kept = if resuming { resume_from } else { 0 };
+    let total = response.content_length().map(|length| kept + length);
+    let first = response.chunk().await?;
+    if !resuming && is_html && !first.as_deref().unwrap_or_default().starts_with(b"%PDF-") {
+      return Err(invalid(first.as_deref().unwrap_or_default()));
+    }
 
-    // TODO (autoparallel): uses a fixed max output filename length, should make this configurable
-    // in the future.
-    let formatted_title = format::format_title(&self.title, Some(50));
-    let path = dir.join(format!("{}.pdf", formatted_title));
     debug!("Writing PDF to path: {path:?}");
-    std::fs::write(path, bytes)?;
-    Ok(())
+    let written = async {
+      let (mut file, mut head, mut tail) = if resuming {
+        let (head, tail) = read_ends(&partial).await?;
+        (tokio::fs::OpenOptions::new().append(true).open(&partial).await?, head, tail)
+      } else {
+        (tokio::fs::File::create(&partial).await?, Vec::new(), Vec::new())
+      };
+      let mut next = first;
+      let mut downloaded = kept;
+      while let Some(chunk) = next {
+        let wanted = INVALID_PDF_PREVIEW.saturating_sub(head.len()).min(chunk.len());
+        head.extend_from_slice(&chunk[..wanted]);
+        tail.extend_from_slice(&chunk);
+        tail.drain(..tail.len().saturating_sub(PDF_EOF_WINDOW));
+        file.write_all(&chunk).await?;
+        downloaded += chunk.len() as u64;
+        progress(downloaded, total);
+        next = match response.chunk().await {
+          Ok(next) => next,
+          Err(e) => {
+            // Make sure what arrived is on disk, to resume from
+            file.flush().await?;
+            return Err(e.into());
+          },
+        };
+      }
+      file.flush().await?;
+      Ok::<_, LearnerError>((head, tail))
+    }
+    .await;
+
+    // Check the contents, as the `Content-Type` of a PDF is often wrong or generic
+    let result = match written {
+      Ok((head, tail)) if has_pdf_markers(&head, &tail) =>
+        tokio::fs::rename(&partial, &path).await.map_err(LearnerError::from),
+      Ok((head, _)) => Err(invalid(&head)),
+      // Keep what arrived before the connection dropped, to resume from next time
+      Err(e @ (LearnerError::Network(_) | LearnerError::Timeout(_))) => {
+        debug!("Download of {pdf_url} interrupted, keeping {partial:?} to resume later");
+        return Err(e);
+      },
+      Err(e) => Err(e),
+    };
+    if result.is_err() {
+      let _ = tokio::fs::remove_file(&partial).await;
+    }
+    result.map(|()| path)
+  }
+
+  /// Download the paper's PDF and record where it was saved in the database.
+  ///
+  /// The paper must already be saved to `db`. The recorded location is returned as
+  /// [`Paper::pdf_path`] by papers loaded from the database, so it stays accurate even if the
+  /// way PDFs are named changes later.
+  ///
+  /// # Arguments
+  ///
+  /// * `db` - The database the paper is saved in
+  /// * `dir` - The directory where the PDF should be saved
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LearnerError::NotFound`] if the paper is not in the database, and otherwise fails
+  /// like [`Paper::download_pdf`]. Nothing is recorded if the download fails.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = learner::database::Database::open("papers.db").await?;
+  /// let paper = learner::paper::Paper::new("2301.07041").await?;
+  /// paper.save(&db).await?;
+  /// let path = paper.download_and_record_pdf(&db, "pdfs".into()).await?;
+  /// println!("Saved PDF to {}", path.display());
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn download_and_record_pdf(
+    &self,
+    db: &Database,
+    dir: PathBuf,
+  ) -> Result<PathBuf, LearnerError> {
+    self.download_and_record_pdf_with_progress(db, dir, |_, _| {}).await
+  }
+
+  /// Download the paper's PDF and record where it was saved, as
+  /// [`Paper::download_and_record_pdf`] does, reporting progress as
+  /// [`Paper::download_pdf_with_progress`] does.
+  ///
+  /// # Errors
+  ///
+  /// See [`Paper::download_and_record_pdf`].
+  pub async fn download_and_record_pdf_with_progress(
+    &self,
+    db: &Database,
+    dir: PathBuf,
+    progress: impl Fn(u64, Option<u64>) + Send + Sync,
+  ) -> Result<PathBuf, LearnerError> {
+    if db.get_paper_id(&self.source, &self.source_identifier).await?.is_none() {
+      return Err(LearnerError::NotFound);
+    }
+    let template = db.get_pdf_filename_template().await?;
+    let path = self.download(clients::http::pdf_client(), dir, &template, &progress).await?;
+    db.set_pdf_path(&self.source, &self.source_identifier, path.clone()).await?;
+    Ok(path)
   }
 
   /// Save the paper to a database.
@@ -265,55 +863,1515 @@ impl Paper {
   /// # }
   /// ```
   pub async fn save(&self, db: &Database) -> Result<i64, LearnerError> { db.save_paper(self).await }
-}
-
-/// Extracts the arXiv identifier from a URL.
-///
-/// Parses URLs like "https://arxiv.org/abs/2301.07041" to extract "2301.07041".
-fn extract_arxiv_id(url: &Url) -> Result<String, LearnerError> {
-  let path = url.path();
-  let re = regex::Regex::new(r"abs/([^/]+)$").unwrap();
-  re.captures(path)
-    .and_then(|cap| cap.get(1))
-    .map(|m| m.as_str().to_string())
-    .ok_or(LearnerError::InvalidIdentifier)
-}
 
-/// Extracts the IACR identifier from a URL.
-///
-/// Parses URLs like "https://eprint.iacr.org/2016/260" to extract "2016/260".
-fn extract_iacr_id(url: &Url) -> Result<String, LearnerError> {
-  let path = url.path();
-  let re = regex::Regex::new(r"(\d{4}/\d+)$").unwrap();
-  re.captures(path)
-    .and_then(|cap| cap.get(1))
-    .map(|m| m.as_str().to_string())
-    .ok_or(LearnerError::InvalidIdentifier)
-}
+  /// Remove the paper from a database.
+  ///
+  /// The paper is matched by its source and source identifier, so this works for any copy of
+  /// the paper, not only the one that was saved.
+  ///
+  /// # Arguments
+  ///
+  /// * `db` - Reference to an open database connection
+  ///
+  /// # Returns
+  ///
+  /// Returns `true` if the paper was removed, or `false` if it wasn't in the database.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let db = learner::database::Database::open("papers.db").await?;
+  /// let paper = learner::paper::Paper::new("2301.07041").await?;
+  /// if paper.remove(&db).await? {
+  ///   println!("Removed {}", paper.title);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub async fn remove(&self, db: &Database) -> Result<bool, LearnerError> {
+    db.remove_paper(&self.source, &self.source_identifier).await
+  }
 
-/// Extracts the DOI from a URL.
-///
-/// Parses URLs like "https://doi.org/10.1145/1327452.1327492" to extract the DOI.
-fn extract_doi(url: &Url) -> Result<String, LearnerError> {
-  url.path().strip_prefix('/').map(|s| s.to_string()).ok_or(LearnerError::InvalidIdentifier)
-}
+  /// Formats the paper as a BibTeX entry.
+  ///
+  /// The entry type follows the paper's source: arXiv and IACR preprints become `@misc`
+  /// entries with `eprint` and `archivePrefix` fields, DOI papers become `@article` entries,
+  /// and anything else is a plain `@misc` with the source recorded in `note`. Authors are
+  /// written in the BibTeX "Last, First" convention and joined with `and`. LaTeX special
+  /// characters (`&`, `%`, `_`, `#`, `$`) in the title and author names are escaped. Fields
+  /// without a value (e.g. a missing DOI) are left out.
+  ///
+  /// The citation key combines the first author's last name, the publication year and the
+  /// first significant word of the title, e.g. `vaswani2017attention`.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let paper = learner::paper::Paper::new("2301.07041").await?;
+  /// std::fs::write("refs.bib", paper.to_bibtex())?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn to_bibtex(&self) -> String { self.to_bibtex_with_key(&self.citation_key()) }
 
-#[cfg(test)]
-mod tests {
+  /// Formats the paper as a BibTeX entry under the given citation key.
+  fn to_bibtex_with_key(&self, key: &str) -> String {
+    let (entry_type, archive) = match self.source {
+      Source::Arxiv => ("misc", Some("arXiv")),
+      Source::IACR => ("misc", Some("IACR")),
+      Source::DOI => ("article", None),
+      Source::SemanticScholar
+      | Source::PubMed
+      | Source::BioRxiv
+      | Source::OpenAlex
+      | Source::SSRN => ("misc", None),
+    };
 
-  use super::*;
+    let mut fields = Vec::new();
+    if !self.authors.is_empty() {
+      let authors = self
+        .authors
+        .iter()
+        .map(|a| escape_latex(&last_name_first(&a.name)))
+        .collect::<Vec<_>>()
+        .join(" and ");
+      fields.push(("author", authors));
+    }
+    fields.push(("title", escape_latex(&self.title)));
+    fields.push(("year", self.publication_date.year().to_string()));
+    if let Some(archive) = archive {
+      fields.push(("eprint", self.source_identifier.clone()));
+      fields.push(("archivePrefix", archive.to_string()));
+    }
+    if let Some(doi) = &self.doi {
+      fields.push(("doi", doi.clone()));
+    }
+    if let Some(url) = &self.pdf_url {
+      fields.push(("url", url.clone()));
+    }
+    if archive.is_none() && self.source != Source::DOI {
+      fields.push(("note", format!("{}: {}", self.source, self.source_identifier)));
+    }
 
-  #[traced_test]
-  #[tokio::test]
-  async fn test_arxiv_paper_from_id() {
-    let paper = Paper::new("2301.07041").await.unwrap();
-    assert!(!paper.title.is_empty());
-    assert!(!paper.authors.is_empty());
-    assert_eq!(paper.source, Source::Arxiv);
-    dbg!(paper);
+    let mut entry = format!("@{}{{{},\n", entry_type, key);
+    for (name, value) in fields {
+      entry.push_str(&format!("  {} = {{{}}},\n", name, value));
+    }
+    entry.push_str("}\n");
+    entry
+  }
+
+  /// Returns the web page describing the paper: its arXiv or IACR abstract page, its DOI link,
+  /// or failing those its page in the source it was fetched from.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let paper = learner::paper::Paper::new("2301.07041").await?;
+  /// assert_eq!(paper.url(), "https://arxiv.org/abs/2301.07041");
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn url(&self) -> String {
+    let id = &self.source_identifier;
+    match (&self.source, &self.doi) {
+      (Source::Arxiv, _) => format!("https://arxiv.org/abs/{id}"),
+      (Source::IACR, _) => format!("https://eprint.iacr.org/{id}"),
+      (_, Some(doi)) => format!("https://doi.org/{doi}"),
+      (Source::DOI | Source::BioRxiv, None) => format!("https://doi.org/{id}"),
+      (Source::SemanticScholar, None) => format!("https://www.semanticscholar.org/paper/{id}"),
+      (Source::PubMed, None) => format!("https://pubmed.ncbi.nlm.nih.gov/{id}/"),
+      (Source::OpenAlex, None) => format!("https://openalex.org/{id}"),
+      (Source::SSRN, None) => format!("https://papers.ssrn.com/sol3/papers.cfm?abstract_id={id}"),
+    }
+  }
+
+  /// Formats the paper as an RIS record, for import into reference managers such as Zotero or
+  /// EndNote.
+  ///
+  /// The record type follows the source: DOI and PubMed papers are journal articles
+  /// (`TY  - JOUR`), arXiv and bioRxiv preprints are unpublished works (`TY  - UNPB`), IACR ePrints
+  /// are reports (`TY  - RPRT`), and anything else is a generic work (`TY  - GEN`). For arXiv and
+  /// IACR the archive is recorded in `DB` and the identifier in `AN`. Each author gets their own
+  /// `AU` line in "Last, First" form.
+  ///
+  /// # Examples
+  ///
+  /// ```no_run
+  /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+  /// let paper = learner::paper::Paper::new("2301.07041").await?;
+  /// std::fs::write("refs.ris", paper.to_ris())?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn to_ris(&self) -> String {
+    let (entry_type, archive) = match self.source {
+      Source::Arxiv => ("UNPB", Some("arXiv")),
+      Source::IACR => ("RPRT", Some("Cryptology ePrint Archive")),
+      Source::DOI => ("JOUR", None),
+      Source::SemanticScholar => ("GEN", None),
+      Source::PubMed => ("JOUR", None),
+      Source::BioRxiv => ("UNPB", None),
+      Source::OpenAlex => ("GEN", None),
+      Source::SSRN => ("UNPB", Some("SSRN")),
+    };
+
+    let mut fields = vec![("TY", entry_type.to_string()), ("TI", self.title.clone())];
+    for author in &self.authors {
+      fields.push(("AU", last_name_first(&author.name)));
+    }
+    if !self.abstract_text.is_empty() {
+      fields.push(("AB", self.abstract_text.clone()));
+    }
+    fields.push(("PY", self.publication_date.year().to_string()));
+    if let Some(archive) = archive {
+      fields.push(("DB", archive.to_string()));
+      fields.push(("AN", self.source_identifier.clone()));
+    }
+    if let Some(doi) = &self.doi {
+      fields.push(("DO", doi.clone()));
+    }
+    if let Some(url) = &self.pdf_url {
+      fields.push(("UR", url.clone()));
+    }
+    fields.push(("ER", String::new()));
+
+    // RIS values can't span lines
+    fields
+      .into_iter()
+      .map(|(tag, value)| {
+        format!("{}  - {}\n", tag, value.split_whitespace().collect::<Vec<_>>().join(" "))
+      })
+      .collect()
+  }
+
+  /// Builds a BibTeX citation key from the first author's last name, the publication year and
+  /// the first significant word of the title, e.g. `vaswani2017attention`.
+  fn citation_key(&self) -> String {
+    /// Words skipped when picking the title word
+    const STOP_WORDS: &[&str] =
+      &["a", "an", "the", "on", "of", "in", "for", "and", "to", "with", "at", "by", "from"];
+
+    let key_part = |text: &str| {
+      text.chars().filter(char::is_ascii_alphanumeric).collect::<String>().to_lowercase()
+    };
+
+    let last_name = self
+      .authors
+      .first()
+      .map(|author| last_name_first(&author.name))
+      .and_then(|name| name.split(',').next().map(key_part))
+      .unwrap_or_else(|| "anonymous".to_string());
+    let title_word = self
+      .title
+      .split_whitespace()
+      .map(key_part)
+      .find(|word| !word.is_empty() && !STOP_WORDS.contains(&word.as_str()))
+      .unwrap_or_default();
+
+    format!("{}{}{}", last_name, self.publication_date.year(), title_word)
+  }
+}
+
+/// Formats papers as RIS records, separated by blank lines.
+///
+/// Each record is formatted as by [`Paper::to_ris`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use learner::paper::{to_ris_records, Paper};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let papers = vec![Paper::new("2301.07041").await?, Paper::new("2016/260").await?];
+/// std::fs::write("refs.ris", to_ris_records(&papers))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_ris_records(papers: &[Paper]) -> String {
+  papers.iter().map(Paper::to_ris).collect::<Vec<_>>().join("\n")
+}
+
+/// Formats papers as a BibTeX bibliography, with entries separated by blank lines.
+///
+/// Each entry is formatted as by [`Paper::to_bibtex`]. Papers whose citation keys collide
+/// (e.g. two 2017 papers by the same author starting with the same word) get `a`, `b`, ...
+/// suffixes in order, so every key in the bibliography is unique.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use learner::paper::{to_bibtex_bibliography, Paper};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let papers = vec![Paper::new("2301.07041").await?, Paper::new("2016/260").await?];
+/// std::fs::write("refs.bib", to_bibtex_bibliography(&papers))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_bibtex_bibliography(papers: &[Paper]) -> String {
+  let keys = papers.iter().map(Paper::citation_key).collect::<Vec<_>>();
+
+  let mut seen = Vec::<(&str, usize)>::new();
+  let mut entries = Vec::with_capacity(papers.len());
+  for (paper, key) in papers.iter().zip(&keys) {
+    let duplicates = keys.iter().filter(|k| *k == key).count();
+    let entry = if duplicates > 1 {
+      let index = match seen.iter_mut().find(|(k, _)| k == key) {
+        Some((_, count)) => {
+          *count += 1;
+          *count
+        },
+        None => {
+          seen.push((key, 0));
+          0
+        },
+      };
+      paper.to_bibtex_with_key(&format!("{}{}", key, key_suffix(index)))
+    } else {
+      paper.to_bibtex()
+    };
+    entries.push(entry);
+  }
+  entries.join("\n")
+}
+
+/// How [`to_markdown_document`] and [`to_html_document`] group papers under headings.
+///
+/// Parsing from a string accepts `source`, `tag`, and `year`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PaperGrouping {
+  /// By source, in the order of [`Source::ALL`]
+  #[default]
+  Source,
+  /// By tag, alphabetically, with untagged papers last. Papers with several tags appear under
+  /// each of them
+  Tag,
+  /// By publication year, newest first
+  Year,
+}
+
+impl FromStr for PaperGrouping {
+  type Err = LearnerError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match &s.to_lowercase() as &str {
+      "source" => Ok(PaperGrouping::Source),
+      "tag" => Ok(PaperGrouping::Tag),
+      "year" => Ok(PaperGrouping::Year),
+      s => Err(LearnerError::InvalidGrouping(s.to_owned())),
+    }
+  }
+}
+
+/// Maximum number of characters of an abstract shown in Markdown and HTML documents.
+const SNIPPET_LENGTH: usize = 300;
+
+/// Formats papers as a Markdown document, e.g. for publishing a reading list.
+///
+/// Papers are listed under a heading per group, keeping their order within each group. Each
+/// paper's title links to its [`Paper::url`], followed by its authors, publication date and the
+/// start of its abstract.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use learner::paper::{to_markdown_document, Paper, PaperGrouping};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let papers = vec![Paper::new("2301.07041").await?, Paper::new("2016/260").await?];
+/// std::fs::write("papers.md", to_markdown_document(&papers, PaperGrouping::Year))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_markdown_document(papers: &[Paper], group_by: PaperGrouping) -> String {
+  let mut document = String::from("# Papers\n");
+  for (heading, papers) in group_papers(papers, group_by) {
+    document += &format!("\n## {}\n", escape_markdown(&heading));
+    for paper in papers {
+      document += &format!("\n### [{}](<{}>)\n\n", escape_markdown(&paper.title), paper.url());
+      document += &format!(
+        "*{}*, {}\n",
+        escape_markdown(&author_list(paper)),
+        paper.publication_date.format("%Y-%m-%d")
+      );
+      let snippet = abstract_snippet(&paper.abstract_text);
+      if !snippet.is_empty() {
+        document += &format!("\n> {}\n", escape_markdown(&snippet));
+      }
+    }
+  }
+  document
+}
+
+/// Formats papers as a self-contained HTML page, e.g. for publishing a reading list.
+///
+/// The page has the same contents as [`to_markdown_document`], styled with a little inline
+/// CSS and no external resources.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use learner::paper::{to_html_document, Paper, PaperGrouping};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let papers = vec![Paper::new("2301.07041").await?, Paper::new("2016/260").await?];
+/// std::fs::write("papers.html", to_html_document(&papers, PaperGrouping::Source))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_html_document(papers: &[Paper], group_by: PaperGrouping) -> String {
+  let mut document = String::from(HTML_HEADER);
+  for (heading, papers) in group_papers(papers, group_by) {
+    document += &format!("<section>\n<h2>{}</h2>\n", escape_html(&heading));
+    for paper in papers {
+      let date = paper.publication_date.format("%Y-%m-%d");
+      document += &format!(
+        "<article>\n<h3><a href=\"{}\">{}</a></h3>\n<p class=\"meta\">{} · <time \
+         datetime=\"{date}\">{date}</time></p>\n",
+        escape_html(&paper.url()),
+        escape_html(&paper.title),
+        escape_html(&author_list(paper)),
+      );
+      let snippet = abstract_snippet(&paper.abstract_text);
+      if !snippet.is_empty() {
+        document += &format!("<p>{}</p>\n", escape_html(&snippet));
+      }
+      document += "</article>\n";
+    }
+    document += "</section>\n";
+  }
+  document + "</body>\n</html>\n"
+}
+
+/// The start of the page written by [`to_html_document`], up to the opening of its body.
+const HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Papers</title>
+<style>
+body { font-family: system-ui, sans-serif; line-height: 1.5; color: #222; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; }
+h2 { border-bottom: 1px solid #ddd; margin-top: 2.5rem; }
+h3 { font-size: 1.1rem; margin: 1.5rem 0 0.25rem; }
+a { color: #1a5fb4; text-decoration: none; }
+a:hover { text-decoration: underline; }
+.meta { color: #666; font-size: 0.9rem; margin: 0; }
+</style>
+</head>
+<body>
+<h1>Papers</h1>
+"#;
+
+/// Splits papers into headed groups, keeping their order within each group.
+fn group_papers(papers: &[Paper], group_by: PaperGrouping) -> Vec<(String, Vec<&Paper>)> {
+  match group_by {
+    PaperGrouping::Source => Source::ALL
+      .iter()
+      .map(|source| {
+        (source.name().to_string(), papers.iter().filter(|p| p.source == *source).collect())
+      })
+      .filter(|(_, papers): &(String, Vec<&Paper>)| !papers.is_empty())
+      .collect(),
+    PaperGrouping::Tag => {
+      let mut groups = std::collections::BTreeMap::<&str, Vec<&Paper>>::new();
+      let mut untagged = Vec::new();
+      for paper in papers {
+        for tag in &paper.tags {
+          groups.entry(tag).or_default().push(paper);
+        }
+        if paper.tags.is_empty() {
+          untagged.push(paper);
+        }
+      }
+      let mut groups: Vec<_> =
+        groups.into_iter().map(|(tag, papers)| (tag.to_string(), papers)).collect();
+      if !untagged.is_empty() {
+        groups.push(("Untagged".to_string(), untagged));
+      }
+      groups
+    },
+    PaperGrouping::Year => {
+      let mut groups = std::collections::BTreeMap::<i32, Vec<&Paper>>::new();
+      for paper in papers {
+        groups.entry(paper.publication_date.year()).or_default().push(paper);
+      }
+      groups.into_iter().rev().map(|(year, papers)| (year.to_string(), papers)).collect()
+    },
+  }
+}
+
+/// Joins a paper's author names, for the byline of Markdown and HTML documents.
+fn author_list(paper: &Paper) -> String {
+  if paper.authors.is_empty() {
+    return "Unknown authors".to_string();
+  }
+  paper.authors.iter().map(|author| author.name.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+/// Shortens an abstract to at most [`SNIPPET_LENGTH`] characters, on a single line, cutting at
+/// a word boundary and marking the cut with an ellipsis.
+fn abstract_snippet(text: &str) -> String {
+  let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  if text.chars().count() <= SNIPPET_LENGTH {
+    return text;
+  }
+  let cut = text.char_indices().nth(SNIPPET_LENGTH).map_or(text.len(), |(i, _)| i);
+  let end = text[..cut].rfind(' ').unwrap_or(cut);
+  format!("{}…", text[..end].trim_end_matches([',', '.', ';', ':']))
+}
+
+/// Escapes the characters Markdown would otherwise read as formatting.
+fn escape_markdown(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|') {
+      escaped.push('\\');
+    }
+    escaped.push(c);
+  }
+  escaped
+}
+
+/// Escapes the characters with special meaning in HTML text and attribute values.
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+    .replace('\'', "&#39;")
+}
+
+/// Returns the disambiguation suffix for the `index`th paper sharing a citation key: `a`, `b`,
+/// ..., `z`, `aa`, `ab`, ...
+fn key_suffix(index: usize) -> String {
+  let letter = (b'a' + (index % 26) as u8) as char;
+  match index / 26 {
+    0 => letter.to_string(),
+    prefix => format!("{}{}", key_suffix(prefix - 1), letter),
+  }
+}
+
+/// Rewrites an author name into the "Last, First" form used by BibTeX and RIS.
+///
+/// Names that already contain a comma, or consist of a single word, are returned unchanged.
+fn last_name_first(name: &str) -> String {
+  let name = name.trim();
+  if name.contains(',') {
+    return name.to_string();
+  }
+  match name.rsplit_once(char::is_whitespace) {
+    Some((first, last)) => format!("{}, {}", last, first.trim()),
+    None => name.to_string(),
+  }
+}
+
+/// Escapes characters with a special meaning in LaTeX so they are typeset literally.
+fn escape_latex(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    if matches!(c, '&' | '%' | '_' | '#' | '$') {
+      escaped.push('\\');
+    }
+    escaped.push(c);
+  }
+  escaped
+}
+
+/// Options for fetching many papers with [`Paper::new_many_with`].
+#[derive(Debug, Clone, Copy)]
+pub struct BatchOptions {
+  /// Maximum number of requests in flight at once
+  pub concurrency: usize,
+}
+
+impl Default for BatchOptions {
+  fn default() -> Self { Self { concurrency: 5 } }
+}
+
+impl Source {
+  /// The minimum time to leave between two requests to this source's API.
+  ///
+  /// arXiv asks clients to wait three seconds between requests. The other APIs document no
+  /// fixed interval, so a shorter courtesy delay is used.
+  pub fn min_request_interval(&self) -> Duration {
+    match self {
+      Source::Arxiv => Duration::from_secs(3),
+      Source::IACR | Source::SemanticScholar | Source::BioRxiv | Source::SSRN =>
+        Duration::from_secs(1),
+      // OpenAlex allows ten requests per second
+      Source::DOI | Source::OpenAlex => Duration::from_millis(100),
+      // NCBI allows three requests per second without an API key
+      Source::PubMed => Duration::from_millis(350),
+    }
+  }
+}
+
+/// The template PDFs are named after unless configured otherwise, see
+/// [`Paper::format_filename`].
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{title}";
+
+/// The placeholders [`Paper::format_filename`] replaces.
+pub const FILENAME_PLACEHOLDERS: [&str; 7] =
+  ["title", "first_author", "author", "year", "source_id", "id", "source"];
+
+lazy_static! {
+  /// A `{name}` placeholder in a file name template.
+  static ref FILENAME_PLACEHOLDER: Regex = Regex::new(r"\{([^{}]*)\}").unwrap();
+}
+
+/// Checks that a file name template only uses the placeholders [`Paper::format_filename`]
+/// knows, listed in [`FILENAME_PLACEHOLDERS`].
+///
+/// # Errors
+///
+/// Returns [`LearnerError::InvalidFilenameTemplate`] naming the first unknown placeholder.
+///
+/// # Examples
+///
+/// ```
+/// use learner::paper::validate_filename_template;
+///
+/// assert!(validate_filename_template("{year}_{first_author}_{title}").is_ok());
+/// assert!(validate_filename_template("{journal}_{title}").is_err());
+/// ```
+pub fn validate_filename_template(template: &str) -> Result<(), LearnerError> {
+  match FILENAME_PLACEHOLDER
+    .captures_iter(template)
+    .find(|captures| !FILENAME_PLACEHOLDERS.contains(&&captures[1]))
+  {
+    Some(captures) => Err(LearnerError::InvalidFilenameTemplate(format!(
+      "unknown placeholder {}, expected one of {{{}}}",
+      &captures[0],
+      FILENAME_PLACEHOLDERS.join("}, {")
+    ))),
+    None => Ok(()),
+  }
+}
+
+/// How many bytes from the end of a PDF the `%%EOF` marker may appear, as allowed by the PDF
+/// specification.
+const PDF_EOF_WINDOW: usize = 1024;
+
+/// How much of a response that isn't a valid PDF is included in [`LearnerError::InvalidPdf`].
+const INVALID_PDF_PREVIEW: usize = 200;
+
+/// Reads the start and end of a partly downloaded PDF, as kept while streaming the rest of it, so
+/// the whole file can be checked with [`has_pdf_markers`] once complete.
+async fn read_ends(path: &Path) -> std::io::Result<(Vec<u8>, Vec<u8>)> {
+  let mut file = tokio::fs::File::open(path).await?;
+  let len = file.metadata().await?.len();
+
+  let mut head = Vec::new();
+  (&mut file).take(INVALID_PDF_PREVIEW as u64).read_to_end(&mut head).await?;
+  file.seek(std::io::SeekFrom::Start(len.saturating_sub(PDF_EOF_WINDOW as u64))).await?;
+  let mut tail = Vec::new();
+  file.read_to_end(&mut tail).await?;
+  Ok((head, tail))
+}
+
+/// Checks that `bytes` look like a complete PDF document.
+///
+/// A PDF starts with a `%PDF-` header and ends with a `%%EOF` marker, which may be followed by a
+/// little trailing whitespace. An HTML error page fails the first check, and a download that was
+/// cut short typically fails the second.
+///
+/// # Examples
+///
+/// ```
+/// use learner::paper::validate_pdf;
+///
+/// assert!(validate_pdf(b"%PDF-1.7\n...\n%%EOF\n"));
+/// assert!(!validate_pdf(b"<html>Access denied</html>"));
+/// assert!(!validate_pdf(b"%PDF-1.7\n... truncated"));
+/// ```
+pub fn validate_pdf(bytes: &[u8]) -> bool {
+  has_pdf_markers(bytes, &bytes[bytes.len().saturating_sub(PDF_EOF_WINDOW)..])
+}
+
+/// Checks a PDF's header in `head`, its start, and its `%%EOF` marker in `tail`, its last
+/// [`PDF_EOF_WINDOW`] bytes, as for [`validate_pdf`] on a file too large to hold in memory.
+fn has_pdf_markers(head: &[u8], tail: &[u8]) -> bool {
+  head.starts_with(b"%PDF-") && tail.windows(5).any(|window| window == b"%%EOF")
+}
+
+/// Determines the source and identifier of a paper URL, identifier, or DOI, without fetching it.
+///
+/// This is how [`Paper::new`] decides which built-in source to fetch a paper from, so it accepts
+/// the same formats. The identifier is normalized for its source, e.g. a PMID loses its `pmid:`
+/// prefix and a DOI URL becomes a bare DOI. Sources added with [`SourceRegistry::register`] are
+/// not consulted.
+///
+/// # Arguments
+///
+/// * `input` - A paper URL, identifier, or DOI, in any format listed for [`Paper::new`]
+///
+/// # Returns
+///
+/// The paper's source and identifier, or `None` if the input isn't recognized.
+///
+/// # Examples
+///
+/// ```
+/// # use learner::paper::{detect_source, Source};
+/// assert_eq!(
+///   detect_source("https://arxiv.org/abs/2301.07041"),
+///   Some((Source::Arxiv, "2301.07041".to_string()))
+/// );
+/// assert_eq!(detect_source("pmid:31452104"), Some((Source::PubMed, "31452104".to_string())));
+/// assert_eq!(detect_source("not a paper"), None);
+/// ```
+pub fn detect_source(input: &str) -> Option<(Source, String)> { parse_input(input).ok() }
+
+/// Determines the source and source-specific identifier of a paper URL, identifier, or DOI.
+///
+/// See [`Paper::new`] for the accepted formats.
+fn parse_input(input: &str) -> Result<(Source, String), LearnerError> {
+  lazy_static! {
+      // arXiv patterns
+      static ref ARXIV_NEW: Regex = Regex::new(r"^(\d{4}\.\d{4,5})(v\d+)?$").unwrap();
+      static ref ARXIV_OLD: Regex = Regex::new(r"^([a-zA-Z-]+/\d{7})(v\d+)?$").unwrap();
+
+      // IACR pattern
+      static ref IACR: Regex = Regex::new(r"^(\d{4}/\d+)$").unwrap();
+
+      // DOI pattern
+      static ref DOI: Regex = Regex::new(r"^10\.\d{4,9}/[-._;()/:\w]+$").unwrap();
+
+      // Semantic Scholar pattern
+      static ref SEMANTIC_SCHOLAR: Regex = Regex::new(r"^[0-9a-f]{40}$").unwrap();
+
+      // bioRxiv/medRxiv DOI pattern, either date-based (10.1101/2020.03.20.000001) or the older
+      // numeric form (10.1101/339747). Cold Spring Harbor journals share the prefix but not
+      // this format (e.g. 10.1101/gr.277335.122), so they still resolve as DOIs
+      static ref BIORXIV: Regex =
+        Regex::new(r"^10\.1101/(?:\d{4}\.\d{2}\.\d{2}\.)?\d{6,}$").unwrap();
+
+      // PubMed pattern
+      static ref PUBMED: Regex = Regex::new(r"^(?i:(?:pmid|pubmed):\s*)?(\d{1,8})$").unwrap();
+
+      // OpenAlex Work ID pattern
+      static ref OPENALEX: Regex = Regex::new(r"^W\d+$").unwrap();
+
+      // SSRN pattern. Bare abstract IDs are indistinguishable from PMIDs, so they need a prefix
+      static ref SSRN: Regex = Regex::new(r"^(?i:ssrn):\s*(\d+)$").unwrap();
+  }
+
+  // First try to parse as a web URL, as prefixed identifiers like `pmid:123` parse as URLs too
+  if let Some(url) = Url::parse(input).ok().filter(|url| matches!(url.scheme(), "http" | "https")) {
+    return match url.host_str() {
+      Some("arxiv.org") => Ok((Source::Arxiv, extract_arxiv_id(&url)?)),
+      Some("eprint.iacr.org") => Ok((Source::IACR, extract_iacr_id(&url)?)),
+      Some("doi.org") => {
+        let doi = extract_doi(&url)?;
+        let source = if BIORXIV.is_match(&doi) { Source::BioRxiv } else { Source::DOI };
+        Ok((source, doi))
+      },
+      Some("dl.acm.org") => Ok((Source::DOI, extract_acm_doi(&url)?)),
+      Some("www.semanticscholar.org" | "semanticscholar.org") =>
+        Ok((Source::SemanticScholar, extract_semantic_scholar_id(&url)?)),
+      Some("pubmed.ncbi.nlm.nih.gov" | "www.ncbi.nlm.nih.gov" | "ncbi.nlm.nih.gov") =>
+        Ok((Source::PubMed, extract_pubmed_id(&url)?)),
+      Some("www.biorxiv.org" | "biorxiv.org" | "www.medrxiv.org" | "medrxiv.org") =>
+        Ok((Source::BioRxiv, extract_biorxiv_doi(&url)?)),
+      Some("openalex.org" | "www.openalex.org" | "api.openalex.org") =>
+        Ok((Source::OpenAlex, extract_openalex_id(&url)?)),
+      Some("ssrn.com" | "www.ssrn.com" | "papers.ssrn.com") =>
+        Ok((Source::SSRN, extract_ssrn_id(&url)?)),
+      _ => Err(LearnerError::InvalidIdentifier),
+    };
+  }
+
+  // If not a URL, try to match against known patterns
+  match input {
+    // arXiv patterns
+    id if ARXIV_NEW.is_match(id) || ARXIV_OLD.is_match(id) => Ok((Source::Arxiv, id.to_string())),
+
+    // IACR pattern
+    id if IACR.is_match(id) => Ok((Source::IACR, id.to_string())),
+
+    // bioRxiv/medRxiv DOIs are also DOIs, so they're checked first
+    id if BIORXIV.is_match(id) => Ok((Source::BioRxiv, id.to_string())),
+
+    // DOI pattern
+    id if DOI.is_match(id) => Ok((Source::DOI, id.to_string())),
+
+    // Semantic Scholar pattern
+    id if SEMANTIC_SCHOLAR.is_match(id) => Ok((Source::SemanticScholar, id.to_string())),
+
+    // PubMed pattern, either a bare PMID or one with a `pmid:` or `pubmed:` prefix. arXiv IDs
+    // always contain a dot or a slash, so they can't be mistaken for PMIDs
+    id if PUBMED.is_match(id) => Ok((Source::PubMed, PUBMED.captures(id).unwrap()[1].to_string())),
+
+    // OpenAlex pattern
+    id if OPENALEX.is_match(id) => Ok((Source::OpenAlex, id.to_string())),
+
+    // SSRN pattern
+    id if SSRN.is_match(id) => Ok((Source::SSRN, SSRN.captures(id).unwrap()[1].to_string())),
+
+    // No pattern matched
+    _ => Err(LearnerError::InvalidIdentifier),
+  }
+}
+
+/// Fetches a paper from the client for its source.
+pub(crate) async fn fetch_from_source(
+  source: &Source,
+  identifier: &str,
+) -> Result<Paper, LearnerError> {
+  match source {
+    Source::Arxiv => ArxivClient::new().fetch_paper(identifier).await,
+    Source::IACR => IACRClient::new().fetch_paper(identifier).await,
+    Source::DOI => DOIClient::new().fetch_paper(identifier).await,
+    Source::SemanticScholar => SemanticScholarClient::new().fetch_paper(identifier).await,
+    Source::PubMed => PubMedClient::new().fetch_paper(identifier).await,
+    Source::BioRxiv => BioRxivClient::new().fetch_paper(identifier).await,
+    Source::OpenAlex => OpenAlexClient::new().fetch_paper(identifier).await,
+    Source::SSRN => SSRNClient::new().fetch_paper(identifier).await,
+  }
+}
+
+/// Extracts the arXiv identifier from a URL.
+///
+/// Parses URLs like "https://arxiv.org/abs/2301.07041" to extract "2301.07041".
+fn extract_arxiv_id(url: &Url) -> Result<String, LearnerError> {
+  let path = url.path();
+  let re = regex::Regex::new(r"abs/([^/]+)$").unwrap();
+  re.captures(path)
+    .and_then(|cap| cap.get(1))
+    .map(|m| m.as_str().to_string())
+    .ok_or(LearnerError::InvalidIdentifier)
+}
+
+/// Extracts the IACR identifier from a URL.
+///
+/// Parses URLs like "https://eprint.iacr.org/2016/260" to extract "2016/260".
+fn extract_iacr_id(url: &Url) -> Result<String, LearnerError> {
+  let path = url.path();
+  let re = regex::Regex::new(r"(\d{4}/\d+)$").unwrap();
+  re.captures(path)
+    .and_then(|cap| cap.get(1))
+    .map(|m| m.as_str().to_string())
+    .ok_or(LearnerError::InvalidIdentifier)
+}
+
+/// Extracts the DOI from a URL.
+///
+/// Parses URLs like "https://doi.org/10.1145/1327452.1327492" to extract the DOI.
+fn extract_doi(url: &Url) -> Result<String, LearnerError> {
+  url.path().strip_prefix('/').map(|s| s.to_string()).ok_or(LearnerError::InvalidIdentifier)
+}
+
+/// Extracts the DOI from an ACM Digital Library URL.
+///
+/// Parses URLs like "https://dl.acm.org/doi/10.1145/1327452.1327492", or the same with
+/// `/doi/abs/`, `/doi/pdf/` or `/doi/fullHtml/`, to extract "10.1145/1327452.1327492".
+fn extract_acm_doi(url: &Url) -> Result<String, LearnerError> {
+  let re = regex::Regex::new(r"^/doi/(?:[a-zA-Z]+/)?(10\.\d{4,9}/[^?#]+?)/?$").unwrap();
+  re.captures(url.path())
+    .and_then(|cap| cap.get(1))
+    .map(|m| m.as_str().to_string())
+    .ok_or(LearnerError::InvalidIdentifier)
+}
+
+/// Extracts the Semantic Scholar paper ID from a URL.
+///
+/// Parses URLs like "https://www.semanticscholar.org/paper/Attention-is-All-you-Need/204e3073870fae3d05bcbc2f6a8e263d9b72e776"
+/// or "https://www.semanticscholar.org/paper/204e3073870fae3d05bcbc2f6a8e263d9b72e776" to
+/// extract "204e3073870fae3d05bcbc2f6a8e263d9b72e776".
+fn extract_semantic_scholar_id(url: &Url) -> Result<String, LearnerError> {
+  let path = url.path();
+  let re = regex::Regex::new(r"^/paper/(?:[^/]+/)?([0-9a-f]{40})/?$").unwrap();
+  re.captures(path)
+    .and_then(|cap| cap.get(1))
+    .map(|m| m.as_str().to_string())
+    .ok_or(LearnerError::InvalidIdentifier)
+}
+
+/// Extracts the PubMed ID from a URL.
+///
+/// Parses URLs like "https://pubmed.ncbi.nlm.nih.gov/31452104/", or the older
+/// "https://www.ncbi.nlm.nih.gov/pubmed/31452104", to extract "31452104".
+fn extract_pubmed_id(url: &Url) -> Result<String, LearnerError> {
+  let path = url.path();
+  let re = if url.host_str() == Some("pubmed.ncbi.nlm.nih.gov") {
+    regex::Regex::new(r"^/(\d{1,8})/?$").unwrap()
+  } else {
+    regex::Regex::new(r"^/pubmed/(\d{1,8})/?$").unwrap()
+  };
+  re.captures(path)
+    .and_then(|cap| cap.get(1))
+    .map(|m| m.as_str().to_string())
+    .ok_or(LearnerError::InvalidIdentifier)
+}
+
+/// Extracts the DOI from a bioRxiv or medRxiv URL.
+///
+/// Parses URLs like "https://www.biorxiv.org/content/10.1101/2020.03.20.000001v2.full.pdf" to
+/// extract "10.1101/2020.03.20.000001", dropping the version and any page suffix.
+fn extract_biorxiv_doi(url: &Url) -> Result<String, LearnerError> {
+  let path = url.path();
+  let re = regex::Regex::new(r"^/content/(10\.1101/[\d.]*\d)(?:v\d+)?(?:[./].*)?$").unwrap();
+  re.captures(path)
+    .and_then(|cap| cap.get(1))
+    .map(|m| m.as_str().to_string())
+    .ok_or(LearnerError::InvalidIdentifier)
+}
+
+/// Extracts the Work ID from an OpenAlex URL.
+///
+/// Parses URLs like "https://openalex.org/W2741809807" or
+/// "https://api.openalex.org/works/W2741809807" to extract "W2741809807".
+fn extract_openalex_id(url: &Url) -> Result<String, LearnerError> {
+  let path = url.path();
+  let re = regex::Regex::new(r"^/(?:works/)?(W\d+)/?$").unwrap();
+  re.captures(path)
+    .and_then(|cap| cap.get(1))
+    .map(|m| m.as_str().to_string())
+    .ok_or(LearnerError::InvalidIdentifier)
+}
+
+/// Extracts the abstract ID from an SSRN URL.
+///
+/// Parses URLs like "https://ssrn.com/abstract=4123456" or
+/// "https://papers.ssrn.com/sol3/papers.cfm?abstract_id=4123456" to extract "4123456".
+fn extract_ssrn_id(url: &Url) -> Result<String, LearnerError> {
+  let re = regex::Regex::new(r"^/abstract=(\d+)/?$").unwrap();
+  re.captures(url.path())
+    .and_then(|cap| cap.get(1))
+    .map(|m| m.as_str().to_string())
+    .or_else(|| {
+      url
+        .query_pairs()
+        .find(|(key, _)| {
+          key.eq_ignore_ascii_case("abstract_id") || key.eq_ignore_ascii_case("abstractid")
+        })
+        .map(|(_, id)| id.into_owned())
+    })
+    .filter(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+    .ok_or(LearnerError::InvalidIdentifier)
+}
+
+#[cfg(test)]
+mod tests {
+
+  use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Mutex,
+  };
+
+  use super::*;
+  use crate::clients::PaperFuture;
+
+  #[test]
+  fn test_extract_semantic_scholar_id() {
+    let id = "204e3073870fae3d05bcbc2f6a8e263d9b72e776";
+    let url = Url::parse(&format!("https://www.semanticscholar.org/paper/{id}")).unwrap();
+    assert_eq!(extract_semantic_scholar_id(&url).unwrap(), id);
+
+    let url = Url::parse(&format!(
+      "https://www.semanticscholar.org/paper/Attention-is-All-you-Need-Vaswani-Shazeer/{id}"
+    ))
+    .unwrap();
+    assert_eq!(extract_semantic_scholar_id(&url).unwrap(), id);
+
+    let url = Url::parse("https://www.semanticscholar.org/author/123").unwrap();
+    assert!(extract_semantic_scholar_id(&url).is_err());
+  }
+
+  #[test]
+  fn test_extract_biorxiv_doi() {
+    let doi = "10.1101/2020.03.20.000001";
+    for url in [
+      "https://www.biorxiv.org/content/10.1101/2020.03.20.000001v2",
+      "https://www.biorxiv.org/content/10.1101/2020.03.20.000001v1.full.pdf",
+      "https://www.medrxiv.org/content/10.1101/2020.03.20.000001v3.full",
+      "https://biorxiv.org/content/10.1101/2020.03.20.000001",
+    ] {
+      assert_eq!(parse_input(url).unwrap(), (Source::BioRxiv, doi.to_string()), "{url}");
+    }
+
+    let url = Url::parse("https://www.biorxiv.org/content/10.1101/339747v1.abstract").unwrap();
+    assert_eq!(extract_biorxiv_doi(&url).unwrap(), "10.1101/339747");
+
+    let url = Url::parse("https://www.biorxiv.org/collection/neuroscience").unwrap();
+    assert!(extract_biorxiv_doi(&url).is_err());
+  }
+
+  /// Builds a paper by hand for testing output formats.
+  fn create_bibtex_paper() -> Paper {
+    Paper {
+      title:             "Attention Is All You Need".to_string(),
+      authors:           vec![
+        Author { name: "Ashish Vaswani".to_string(), affiliation: None, email: None },
+        Author { name: "Noam M. Shazeer".to_string(), affiliation: None, email: None },
+        Author { name: "Parmar, Niki".to_string(), affiliation: None, email: None },
+      ],
+      abstract_text:     "The dominant sequence transduction models...".to_string(),
+      raw_abstract:      None,
+      publication_date:  Utc.with_ymd_and_hms(2017, 6, 12, 0, 0, 0).unwrap(),
+      source:            Source::DOI,
+      source_identifier: "10.48550/arXiv.1706.03762".to_string(),
+      pdf_url:           Some("https://arxiv.org/pdf/1706.03762".to_string()),
+      doi:               Some("10.48550/arXiv.1706.03762".to_string()),
+      version:           None,
+      categories:        Vec::new(),
+      keywords:          Vec::new(),
+      tags:              Vec::new(),
+      pdf_path:          None,
+      reading_status:    ReadingStatus::Unread,
+      added_at:          None,
+    }
+  }
+
+  #[test]
+  fn test_format_filename() {
+    let mut paper = create_bibtex_paper();
+    assert_eq!(
+      paper.format_filename(DEFAULT_FILENAME_TEMPLATE, Some(50)),
+      "attention_is_all_you_need"
+    );
+    assert_eq!(paper.format_filename("{title}", Some(10)), "attention");
+    assert_eq!(paper.format_filename("{first_author}", None), "vaswani");
+    assert_eq!(paper.format_filename("{author}", None), "vaswani");
+    assert_eq!(paper.format_filename("{year}", None), "2017");
+    assert_eq!(paper.format_filename("{source}", None), "doi");
+    // Path separators in identifiers don't turn into directories
+    assert_eq!(paper.format_filename("{source_id}", None), "10.48550_arXiv.1706.03762");
+    assert_eq!(
+      paper.format_filename("{year}_{first_author}_{title}", Some(50)),
+      "2017_vaswani_attention_is_all_you_need"
+    );
+    assert_eq!(paper.format_filename("{journal}-{year}", None), "{journal}-2017");
+    assert_eq!(
+      format::render_filename("{source}_{id}_{title}", &paper),
+      "doi_10.48550_arXiv.1706.03762_attention_is_all_you_need"
+    );
+
+    // Characters reserved on some platforms are replaced, wherever they come from
+    paper.title = "Attention: Is <All> You \"Need\"?".to_string();
+    paper.source_identifier = "a\\b|c*".to_string();
+    assert_eq!(format::render_filename("{id}/{title}", &paper), "a_b_c__attention_is_all_you_need");
+
+    paper.authors.swap(0, 2);
+    assert_eq!(paper.format_filename("{first_author}", None), "parmar");
+    paper.authors.clear();
+    assert_eq!(paper.format_filename("{first_author}", None), "unknown");
+  }
+
+  #[test]
+  fn test_validate_filename_template() {
+    for placeholder in FILENAME_PLACEHOLDERS {
+      validate_filename_template(&format!("x_{{{placeholder}}}")).unwrap();
+    }
+    validate_filename_template("plain name").unwrap();
+    let err = validate_filename_template("{year}_{journal}").unwrap_err();
+    assert!(
+      matches!(&err, LearnerError::InvalidFilenameTemplate(message) if message.contains("{journal}"))
+    );
+  }
+
+  #[tokio::test]
+  async fn test_download_uses_configured_template() {
+    let (url, _) = clients::http::serve_responses(&[(200, "%PDF-1.7 test\n%%EOF\n")]).await;
+    let mut paper = create_bibtex_paper();
+    paper.pdf_url = Some(url);
+    let dir = tempdir().unwrap();
+    let db = Database::open(dir.path().join("test.db")).await.unwrap();
+    db.set_config("pdf_filename_template", "{year}_{first_author}_{title}").await.unwrap();
+    paper.save(&db).await.unwrap();
+
+    let path = paper.download_and_record_pdf(&db, dir.path().into()).await.unwrap();
+    assert_eq!(path, dir.path().join("2017_vaswani_attention_is_all_you_need.pdf"));
+  }
+
+  #[test]
+  fn test_to_bibtex_doi() {
+    let expected = [
+      "@article{vaswani2017attention,",
+      "  author = {Vaswani, Ashish and Shazeer, Noam M. and Parmar, Niki},",
+      "  title = {Attention Is All You Need},",
+      "  year = {2017},",
+      "  doi = {10.48550/arXiv.1706.03762},",
+      "  url = {https://arxiv.org/pdf/1706.03762},",
+      "}",
+      "",
+    ];
+    assert_eq!(create_bibtex_paper().to_bibtex(), expected.join("\n"));
+  }
+
+  #[test]
+  fn test_to_bibtex_arxiv() {
+    let mut paper = create_bibtex_paper();
+    paper.source = Source::Arxiv;
+    paper.source_identifier = "1706.03762".to_string();
+    paper.doi = None;
+
+    let expected = [
+      "@misc{vaswani2017attention,",
+      "  author = {Vaswani, Ashish and Shazeer, Noam M. and Parmar, Niki},",
+      "  title = {Attention Is All You Need},",
+      "  year = {2017},",
+      "  eprint = {1706.03762},",
+      "  archivePrefix = {arXiv},",
+      "  url = {https://arxiv.org/pdf/1706.03762},",
+      "}",
+      "",
+    ];
+    assert_eq!(paper.to_bibtex(), expected.join("\n"));
+  }
+
+  #[test]
+  fn test_to_bibtex_escapes_special_characters() {
+    let mut paper = create_bibtex_paper();
+    paper.title = "The 100% Guide to R&D: #1 in snake_case $ales".to_string();
+    paper.authors =
+      vec![Author { name: "A_B Smith".to_string(), affiliation: None, email: None }];
+    paper.source = Source::SemanticScholar;
+    paper.source_identifier = "204e3073870fae3d05bcbc2f6a8e263d9b72e776".to_string();
+    paper.doi = None;
+    paper.pdf_url = None;
+
+    let expected = [
+      "@misc{smith2017100,",
+      "  author = {Smith, A\\_B},",
+      "  title = {The 100\\% Guide to R\\&D: \\#1 in snake\\_case \\$ales},",
+      "  year = {2017},",
+      "  note = {semanticscholar: 204e3073870fae3d05bcbc2f6a8e263d9b72e776},",
+      "}",
+      "",
+    ];
+    assert_eq!(paper.to_bibtex(), expected.join("\n"));
+  }
+
+  #[test]
+  fn test_to_bibtex_missing_fields() {
+    let mut paper = create_bibtex_paper();
+    paper.authors.clear();
+    paper.doi = None;
+    paper.pdf_url = None;
+    paper.source = Source::IACR;
+    paper.source_identifier = "2016/260".to_string();
+
+    let expected = [
+      "@misc{anonymous2017attention,",
+      "  title = {Attention Is All You Need},",
+      "  year = {2017},",
+      "  eprint = {2016/260},",
+      "  archivePrefix = {IACR},",
+      "}",
+      "",
+    ];
+    assert_eq!(paper.to_bibtex(), expected.join("\n"));
+  }
+
+  #[test]
+  fn test_bibliography_disambiguates_keys() {
+    let first = create_bibtex_paper();
+    let mut second = create_bibtex_paper();
+    second.title = "Attention Is Not All You Need".to_string();
+    let mut other = create_bibtex_paper();
+    other.title = "Transformers Revisited".to_string();
+
+    let bibliography = to_bibtex_bibliography(&[first, other, second]);
+    let keys = bibliography
+      .lines()
+      .filter_map(|line| line.strip_prefix("@article{"))
+      .map(|line| line.trim_end_matches(','))
+      .collect::<Vec<_>>();
+    assert_eq!(keys, ["vaswani2017attentiona", "vaswani2017transformers", "vaswani2017attentionb"]);
+    assert_eq!(bibliography.matches("}\n\n@article{").count(), 2);
+
+    assert!(to_bibtex_bibliography(&[]).is_empty());
+    assert_eq!(key_suffix(0), "a");
+    assert_eq!(key_suffix(25), "z");
+    assert_eq!(key_suffix(26), "aa");
+  }
+
+  /// Splits an RIS record into its `(tag, value)` pairs, checking each line is well formed.
+  fn parse_ris(ris: &str) -> Vec<(String, String)> {
+    ris
+      .lines()
+      .map(|line| {
+        let (tag, value) = line.split_once("  - ").expect("RIS lines are `TAG  - value`");
+        assert_eq!(tag.len(), 2);
+        (tag.to_string(), value.to_string())
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_to_ris_journal_article() {
+    let fields = parse_ris(&create_bibtex_paper().to_ris());
+    let values = |tag: &str| {
+      fields.iter().filter(|(t, _)| t == tag).map(|(_, v)| v.as_str()).collect::<Vec<_>>()
+    };
+
+    assert_eq!(fields.first().unwrap(), &("TY".to_string(), "JOUR".to_string()));
+    assert_eq!(fields.last().unwrap(), &("ER".to_string(), String::new()));
+    assert_eq!(values("TI"), ["Attention Is All You Need"]);
+    assert_eq!(values("AU"), ["Vaswani, Ashish", "Shazeer, Noam M.", "Parmar, Niki"]);
+    assert_eq!(values("AB"), ["The dominant sequence transduction models..."]);
+    assert_eq!(values("PY"), ["2017"]);
+    assert_eq!(values("DO"), ["10.48550/arXiv.1706.03762"]);
+    assert_eq!(values("UR"), ["https://arxiv.org/pdf/1706.03762"]);
+    assert!(values("DB").is_empty());
+  }
+
+  #[test]
+  fn test_to_ris_preprint() {
+    let mut paper = create_bibtex_paper();
+    paper.source = Source::Arxiv;
+    paper.source_identifier = "1706.03762".to_string();
+    paper.title = "Attention Is\n  All You Need".to_string();
+    paper.abstract_text = "A multi-line\nabstract.".to_string();
+    paper.doi = None;
+    paper.pdf_url = None;
+    paper.authors.truncate(1);
+    paper.authors[0].name = "Ashish\nVaswani".to_string();
+
+    let expected = [
+      "TY  - UNPB",
+      "TI  - Attention Is All You Need",
+      "AU  - Vaswani, Ashish",
+      "AB  - A multi-line abstract.",
+      "PY  - 2017",
+      "DB  - arXiv",
+      "AN  - 1706.03762",
+      "ER  - ",
+      "",
+    ];
+    assert_eq!(paper.to_ris(), expected.join("\n"));
+
+    paper.source = Source::IACR;
+    paper.source_identifier = "2016/260".to_string();
+    let fields = parse_ris(&paper.to_ris());
+    assert!(fields.contains(&("TY".to_string(), "RPRT".to_string())));
+    assert!(fields.contains(&("DB".to_string(), "Cryptology ePrint Archive".to_string())));
+    assert!(fields.contains(&("AN".to_string(), "2016/260".to_string())));
+  }
+
+  #[test]
+  fn test_to_ris_records() {
+    let mut preprint = create_bibtex_paper();
+    preprint.source = Source::Arxiv;
+    let papers = [create_bibtex_paper(), preprint];
+
+    let ris = to_ris_records(&papers);
+    let records = ris.split("\n\n").collect::<Vec<_>>();
+    assert_eq!(records.len(), 2);
+    assert_eq!(parse_ris(records[0])[0].1, "JOUR");
+    assert_eq!(parse_ris(records[1])[0].1, "UNPB");
+    assert!(to_ris_records(&[]).is_empty());
+  }
+
+  #[test]
+  fn test_paper_url() {
+    let mut paper = create_bibtex_paper();
+    assert_eq!(paper.url(), "https://doi.org/10.48550/arXiv.1706.03762");
+
+    // Preprint servers' own pages are preferred to DOIs
+    paper.source = Source::Arxiv;
+    paper.source_identifier = "1706.03762".to_string();
+    assert_eq!(paper.url(), "https://arxiv.org/abs/1706.03762");
+
+    paper.source = Source::PubMed;
+    paper.source_identifier = "12345678".to_string();
+    paper.doi = None;
+    assert_eq!(paper.url(), "https://pubmed.ncbi.nlm.nih.gov/12345678/");
+  }
+
+  #[test]
+  fn test_markdown_document() {
+    let mut older = create_bibtex_paper();
+    older.title = "Notes on [Brackets] and *Stars*".to_string();
+    older.source = Source::IACR;
+    older.source_identifier = "2016/260".to_string();
+    older.publication_date = Utc.with_ymd_and_hms(2016, 3, 1, 0, 0, 0).unwrap();
+    older.tags = vec!["crypto".to_string(), "to-read".to_string()];
+    let papers = [create_bibtex_paper(), older];
+
+    let markdown = to_markdown_document(&papers, PaperGrouping::Source);
+    let expected = [
+      "# Papers",
+      "",
+      "## IACR ePrint",
+      "",
+      "### [Notes on \\[Brackets\\] and \\*Stars\\*](<https://eprint.iacr.org/2016/260>)",
+      "",
+      "*Ashish Vaswani, Noam M. Shazeer, Parmar, Niki*, 2016-03-01",
+      "",
+      "> The dominant sequence transduction models...",
+      "",
+      "## DOI",
+      "",
+      "### [Attention Is All You Need](<https://doi.org/10.48550/arXiv.1706.03762>)",
+      "",
+      "*Ashish Vaswani, Noam M. Shazeer, Parmar, Niki*, 2017-06-12",
+      "",
+      "> The dominant sequence transduction models...",
+      "",
+    ];
+    assert_eq!(markdown, expected.join("\n"));
+
+    let headings = |markdown: String| {
+      markdown
+        .lines()
+        .filter(|line| line.starts_with("## "))
+        .map(str::to_string)
+        .collect::<Vec<_>>()
+    };
+    let markdown = to_markdown_document(&papers, PaperGrouping::Tag);
+    assert_eq!(headings(markdown), ["## crypto", "## to-read", "## Untagged"]);
+    let markdown = to_markdown_document(&papers, PaperGrouping::Year);
+    assert_eq!(headings(markdown), ["## 2017", "## 2016"]);
+    assert_eq!(to_markdown_document(&[], PaperGrouping::Source), "# Papers\n");
+
+    assert_eq!(PaperGrouping::from_str("Year").unwrap(), PaperGrouping::Year);
+    assert!(matches!(PaperGrouping::from_str("author"), Err(LearnerError::InvalidGrouping(_))));
+  }
+
+  #[test]
+  fn test_html_document() {
+    let mut paper = create_bibtex_paper();
+    paper.title = "Less <than> & \"more\"".to_string();
+    paper.abstract_text = "word ".repeat(100);
+
+    let html = to_html_document(&[paper], PaperGrouping::Source);
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.ends_with("</html>\n"));
+    assert!(html.contains("<h2>DOI</h2>"));
+    assert!(html.contains(
+      "<h3><a href=\"https://doi.org/10.48550/arXiv.1706.03762\">Less &lt;than&gt; &amp; \
+       &quot;more&quot;</a></h3>"
+    ));
+    assert!(html.contains("<time datetime=\"2017-06-12\">2017-06-12</time>"));
+    // Long abstracts are cut at a word
+    assert!(html.contains(&format!("<p>{}word…</p>", "word ".repeat(59))));
+    // Nothing is loaded from elsewhere
+    assert!(!html.contains("<link") && !html.contains("<script"));
+  }
+
+  #[test]
+  fn test_parse_arxiv_versions() {
+    assert_eq!(parse_input("2301.07041v2").unwrap(), (Source::Arxiv, "2301.07041v2".to_string()));
+    assert_eq!(
+      parse_input("https://arxiv.org/abs/2301.07041v2").unwrap(),
+      (Source::Arxiv, "2301.07041v2".to_string())
+    );
+    assert_eq!(
+      parse_input("math/0601001v1").unwrap(),
+      (Source::Arxiv, "math/0601001v1".to_string())
+    );
+    assert!(matches!(parse_input("2301.07041v"), Err(LearnerError::InvalidIdentifier)));
+  }
+
+  #[tokio::test]
+  async fn test_arxiv_paper_version() -> anyhow::Result<()> {
+    let paper = Paper::new("2301.07041v2").await?;
+    assert_eq!(paper.source_identifier, "2301.07041");
+    assert_eq!(paper.version.as_deref(), Some("v2"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_detect_source() {
+    let detected = |source: Source, id: &str| Some((source, id.to_string()));
+    let cases = [
+      ("https://arxiv.org/abs/2301.07041", detected(Source::Arxiv, "2301.07041")),
+      ("https://arxiv.org/abs/2301.07041v2", detected(Source::Arxiv, "2301.07041v2")),
+      ("2301.07041", detected(Source::Arxiv, "2301.07041")),
+      ("math/0601001", detected(Source::Arxiv, "math/0601001")),
+      ("https://eprint.iacr.org/2016/260", detected(Source::IACR, "2016/260")),
+      ("2016/260", detected(Source::IACR, "2016/260")),
+      ("https://doi.org/10.1145/1327452.1327492", detected(Source::DOI, "10.1145/1327452.1327492")),
+      (
+        "https://dl.acm.org/doi/10.1145/1327452.1327492",
+        detected(Source::DOI, "10.1145/1327452.1327492"),
+      ),
+      (
+        "https://dl.acm.org/doi/pdf/10.1145/3297858.3304013",
+        detected(Source::DOI, "10.1145/3297858.3304013"),
+      ),
+      ("https://dl.acm.org/doi/", None),
+      // IEEE Xplore document numbers only become DOIs once looked up
+      ("https://ieeexplore.ieee.org/document/771073", None),
+      ("10.1145/1327452.1327492", detected(Source::DOI, "10.1145/1327452.1327492")),
+      (
+        "https://www.semanticscholar.org/paper/204e3073870fae3d05bcbc2f6a8e263d9b72e776",
+        detected(Source::SemanticScholar, "204e3073870fae3d05bcbc2f6a8e263d9b72e776"),
+      ),
+      (
+        "204e3073870fae3d05bcbc2f6a8e263d9b72e776",
+        detected(Source::SemanticScholar, "204e3073870fae3d05bcbc2f6a8e263d9b72e776"),
+      ),
+      ("https://pubmed.ncbi.nlm.nih.gov/31452104/", detected(Source::PubMed, "31452104")),
+      ("31452104", detected(Source::PubMed, "31452104")),
+      (
+        "https://www.biorxiv.org/content/10.1101/2020.03.20.000001v2",
+        detected(Source::BioRxiv, "10.1101/2020.03.20.000001"),
+      ),
+      ("10.1101/2020.03.20.000001", detected(Source::BioRxiv, "10.1101/2020.03.20.000001")),
+      ("https://openalex.org/W2741809807", detected(Source::OpenAlex, "W2741809807")),
+      ("https://api.openalex.org/works/W2741809807", detected(Source::OpenAlex, "W2741809807")),
+      ("W2741809807", detected(Source::OpenAlex, "W2741809807")),
+      ("https://openalex.org/A5023888391", None),
+      ("https://ssrn.com/abstract=4123456", detected(Source::SSRN, "4123456")),
+      (
+        "https://papers.ssrn.com/sol3/papers.cfm?abstract_id=4123456",
+        detected(Source::SSRN, "4123456"),
+      ),
+      ("ssrn:4123456", detected(Source::SSRN, "4123456")),
+      ("https://papers.ssrn.com/sol3/papers.cfm", None),
+      ("not a paper", None),
+      ("https://example.com/2301.07041", None),
+      ("", None),
+    ];
+    for (input, expected) in cases {
+      assert_eq!(detect_source(input), expected, "{input}");
+    }
+  }
+
+  #[test]
+  fn test_parse_input() {
+    assert_eq!(parse_input("2301.07041").unwrap(), (Source::Arxiv, "2301.07041".to_string()));
+    assert_eq!(
+      parse_input("https://arxiv.org/abs/2301.07041").unwrap(),
+      (Source::Arxiv, "2301.07041".to_string())
+    );
+    assert_eq!(parse_input("2016/260").unwrap(), (Source::IACR, "2016/260".to_string()));
+    assert_eq!(
+      parse_input("https://doi.org/10.1145/1327452.1327492").unwrap(),
+      (Source::DOI, "10.1145/1327452.1327492".to_string())
+    );
+    assert_eq!(parse_input("31452104").unwrap(), (Source::PubMed, "31452104".to_string()));
+    assert_eq!(parse_input("PMID: 31452104").unwrap(), (Source::PubMed, "31452104".to_string()));
+    assert_eq!(
+      parse_input("https://pubmed.ncbi.nlm.nih.gov/31452104/").unwrap(),
+      (Source::PubMed, "31452104".to_string())
+    );
+    assert_eq!(
+      parse_input("https://www.ncbi.nlm.nih.gov/pubmed/31452104").unwrap(),
+      (Source::PubMed, "31452104".to_string())
+    );
+    assert_eq!(parse_input("pubmed:31452104").unwrap(), (Source::PubMed, "31452104".to_string()));
+    assert_eq!(
+      parse_input("10.1101/2020.03.20.000001").unwrap(),
+      (Source::BioRxiv, "10.1101/2020.03.20.000001".to_string())
+    );
+    assert_eq!(
+      parse_input("10.1101/339747").unwrap(),
+      (Source::BioRxiv, "10.1101/339747".to_string())
+    );
+    assert_eq!(
+      parse_input("https://doi.org/10.1101/2020.03.20.000001").unwrap(),
+      (Source::BioRxiv, "10.1101/2020.03.20.000001".to_string())
+    );
+    // Cold Spring Harbor journal DOIs share the prefix but aren't preprints
+    assert_eq!(
+      parse_input("10.1101/gr.277335.122").unwrap(),
+      (Source::DOI, "10.1101/gr.277335.122".to_string())
+    );
+    assert!(matches!(
+      parse_input("https://www.ncbi.nlm.nih.gov/pmc/articles/PMC6711428/"),
+      Err(LearnerError::InvalidIdentifier)
+    ));
+    // Numeric arXiv IDs are not mistaken for PMIDs
+    assert_eq!(parse_input("2301.07041").unwrap().0, Source::Arxiv);
+    assert!(matches!(parse_input("123456789"), Err(LearnerError::InvalidIdentifier)));
+    assert!(matches!(parse_input("not a paper"), Err(LearnerError::InvalidIdentifier)));
+    assert!(matches!(
+      parse_input("https://example.com/2301.07041"),
+      Err(LearnerError::InvalidIdentifier)
+    ));
   }
 
-  #[traced_test]
+  #[tokio::test]
+  async fn test_new_many_resolves_inputs_independently() {
+    let results = Paper::new_many(&["not a paper", "https://example.com/paper", ""]).await;
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|r| matches!(r, Err(LearnerError::InvalidIdentifier))));
+
+    assert!(Paper::new_many(&[]).await.is_empty());
+  }
+
+  /// A source for inputs like `batch:30`, which takes that many milliseconds to return a paper
+  /// and records how many fetches are in flight at once. Non-numeric delays fail to fetch.
+  struct DelayedSource {
+    in_flight:      Arc<AtomicUsize>,
+    most_in_flight: Arc<AtomicUsize>,
+  }
+
+  impl PaperSource for DelayedSource {
+    fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+      Box::pin(async move {
+        let delay = id.parse().map_err(|_| LearnerError::InvalidIdentifier)?;
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.most_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        let mut paper = create_bibtex_paper();
+        paper.source_identifier = id.to_string();
+        Ok(paper)
+      })
+    }
+
+    fn matches(&self, input: &str) -> Option<String> {
+      input.strip_prefix("batch:").map(str::to_string)
+    }
+  }
+
+  #[tokio::test]
+  async fn test_new_many_keeps_input_order_within_concurrency() {
+    let most_in_flight = Arc::new(AtomicUsize::new(0));
+    SourceRegistry::register(DelayedSource {
+      in_flight:      Arc::new(AtomicUsize::new(0)),
+      most_in_flight: Arc::clone(&most_in_flight),
+    });
+
+    // Later inputs finish first, so results arrive out of order
+    let inputs = ["batch:60", "not a paper", "batch:40", "batch:slow", "batch:20", "batch:0"];
+    let results = Paper::new_many_with(&inputs, BatchOptions { concurrency: 2 }).await;
+
+    assert_eq!(results.len(), inputs.len());
+    let ids = results
+      .iter()
+      .map(|result| result.as_ref().ok().map(|paper| paper.source_identifier.as_str()))
+      .collect::<Vec<_>>();
+    assert_eq!(ids, [Some("60"), None, Some("40"), None, Some("20"), Some("0")]);
+    assert!(matches!(results[1], Err(LearnerError::InvalidIdentifier)));
+    assert!(matches!(results[3], Err(LearnerError::InvalidIdentifier)));
+    assert_eq!(most_in_flight.load(Ordering::SeqCst), 2);
+  }
+
+  #[test]
+  fn test_source_round_trip() {
+    for source in Source::ALL {
+      assert_eq!(Source::from_str(&source.to_string()).unwrap(), source);
+      assert_eq!(source.to_string(), source.to_string().to_lowercase());
+    }
+    assert_eq!(Source::Arxiv.to_string(), "arxiv");
+    assert_eq!(Source::from_str("Arxiv").unwrap(), Source::Arxiv);
+    assert_eq!(Source::from_str("SemanticScholar").unwrap(), Source::SemanticScholar);
+    assert!(matches!(Source::from_str("jstor"), Err(LearnerError::InvalidSource(_))));
+    assert_eq!(Source::from_str("s2").unwrap(), Source::SemanticScholar);
+    assert_eq!(Source::from_str("pmid").unwrap(), Source::PubMed);
+    assert_eq!(Source::from_str("medrxiv").unwrap(), Source::BioRxiv);
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_arxiv_paper_from_id() {
+    let paper = Paper::new("2301.07041").await.unwrap();
+    assert!(!paper.title.is_empty());
+    assert!(!paper.authors.is_empty());
+    assert_eq!(paper.source, Source::Arxiv);
+    dbg!(paper);
+  }
+
+  #[traced_test]
   #[tokio::test]
   async fn test_arxiv_paper_from_url() {
     let paper = Paper::new("https://arxiv.org/abs/2301.07041").await.unwrap();
@@ -361,6 +2419,26 @@ mod tests {
     Ok(())
   }
 
+  #[traced_test]
+  #[tokio::test]
+  async fn test_acm_paper_from_url() -> anyhow::Result<()> {
+    let paper = Paper::new("https://dl.acm.org/doi/10.1145/1327452.1327492").await?;
+    assert!(!paper.title.is_empty());
+    assert_eq!(paper.source, Source::DOI);
+    assert_eq!(paper.source_identifier, "10.1145/1327452.1327492");
+    Ok(())
+  }
+
+  #[traced_test]
+  #[tokio::test]
+  async fn test_ieee_paper_from_url() -> anyhow::Result<()> {
+    let paper = Paper::new("https://ieeexplore.ieee.org/document/771073").await?;
+    assert!(!paper.title.is_empty());
+    assert_eq!(paper.source, Source::DOI);
+    assert!(paper.source_identifier.starts_with("10.1109/"));
+    Ok(())
+  }
+
   #[traced_test]
   #[tokio::test]
   async fn test_arxiv_pdf_from_paper() -> anyhow::Result<()> {
@@ -403,6 +2481,250 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  async fn test_download_pdf_with_client() {
+    let (url, _) = clients::http::serve_responses(&[(200, "%PDF-1.7 test\n%%EOF\n")]).await;
+    let mut paper = create_bibtex_paper();
+    paper.pdf_url = Some(url);
+    let dir = tempdir().unwrap();
+
+    paper
+      .download_pdf_with(&clients::ClientConfig::default().pdf_client(), dir.path().into())
+      .await
+      .unwrap();
+    let path = dir.path().join(format!("{}.pdf", format::format_title(&paper.title, Some(50))));
+    assert_eq!(std::fs::read(path).unwrap(), b"%PDF-1.7 test\n%%EOF\n");
+  }
+
+  #[tokio::test]
+  async fn test_download_and_record_pdf() {
+    let (url, _) = clients::http::serve_responses(&[(200, "%PDF-1.7 test\n%%EOF\n")]).await;
+    let mut paper = create_bibtex_paper();
+    paper.pdf_url = Some(url);
+    let dir = tempdir().unwrap();
+    let db = Database::open(dir.path().join("test.db")).await.unwrap();
+
+    // The paper has to be saved first
+    let result = paper.download_and_record_pdf(&db, dir.path().into()).await;
+    assert!(matches!(result, Err(LearnerError::NotFound)));
+
+    paper.save(&db).await.unwrap();
+    let path = paper.download_and_record_pdf(&db, dir.path().into()).await.unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"%PDF-1.7 test\n%%EOF\n");
+
+    let stored =
+      db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await.unwrap().unwrap();
+    assert_eq!(stored.pdf_path, Some(path));
+  }
+
+  #[tokio::test]
+  async fn test_download_streams_large_pdf() {
+    // Large enough to arrive in many chunks, with the `%%EOF` only in the last one
+    let body = format!("%PDF-1.7\n{}\n%%EOF\n", "0123456789".repeat(100_000));
+    let body: &'static str = Box::leak(body.into_boxed_str());
+    let responses: &'static [(u16, &'static str)] = Box::leak(Box::new([(200, body)]));
+    let (url, _) = clients::http::serve_responses(responses).await;
+    let mut paper = create_bibtex_paper();
+    paper.pdf_url = Some(url);
+    let dir = tempdir().unwrap();
+
+    let path = paper.download_pdf(dir.path().into()).await.unwrap();
+    assert_eq!(path, dir.path().join("attention_is_all_you_need.pdf"));
+    assert_eq!(std::fs::read(&path).unwrap(), body.as_bytes());
+    // The partial file was moved into place
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+  }
+
+  #[tokio::test]
+  async fn test_download_reports_progress() {
+    let body = format!("%PDF-1.7\n{}\n%%EOF\n", "0123456789".repeat(100_000));
+    let body: &'static str = Box::leak(body.into_boxed_str());
+    let responses: &'static [(u16, &'static str)] = Box::leak(Box::new([(200, body)]));
+    let (url, _) = clients::http::serve_responses(responses).await;
+    let mut paper = create_bibtex_paper();
+    paper.pdf_url = Some(url);
+    let dir = tempdir().unwrap();
+
+    let reports = Mutex::new(Vec::new());
+    let path = paper
+      .download_pdf_with_progress(dir.path().into(), |downloaded, total| {
+        reports.lock().unwrap().push((downloaded, total));
+      })
+      .await
+      .unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), body.as_bytes());
+
+    // One report per chunk, counting up to the Content-Length
+    let reports = reports.into_inner().unwrap();
+    let len = body.len() as u64;
+    assert!(reports.len() > 1);
+    assert!(reports.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert!(reports.iter().all(|&(_, total)| total == Some(len)));
+    assert_eq!(reports.last(), Some(&(len, Some(len))));
+
+    // A resumed download counts the bytes kept from before
+    let body = b"%PDF-1.7\n0123456789012345678901234567890123456789\n%%EOF\n";
+    let (url, _) = serve_interrupted(body, 20, true).await;
+    paper.pdf_url = Some(url);
+    let dir = tempdir().unwrap();
+    assert!(paper.download_pdf(dir.path().into()).await.is_err());
+    let reports = Mutex::new(Vec::new());
+    paper
+      .download_pdf_with_progress(dir.path().into(), |downloaded, total| {
+        reports.lock().unwrap().push((downloaded, total));
+      })
+      .await
+      .unwrap();
+    let len = body.len() as u64;
+    assert_eq!(reports.into_inner().unwrap().last(), Some(&(len, Some(len))));
+  }
+
+  #[tokio::test]
+  async fn test_download_rejects_non_pdf() {
+    let (url, _) = clients::http::serve_responses(&[
+      (200, "<html>Please sign in</html>"),
+      (200, "%PDF-1.7 cut short"),
+      (403, ""),
+    ])
+    .await;
+    let mut paper = create_bibtex_paper();
+    paper.pdf_url = Some(url.clone());
+    let dir = tempdir().unwrap();
+
+    // The error shows what was served instead, to tell a login page from a broken file
+    let result = paper.download_pdf(dir.path().into()).await;
+    assert!(matches!(result, Err(LearnerError::InvalidPdf(ref msg))
+      if msg.contains(&url) && msg.contains("200") && msg.contains("Please sign in")));
+    let result = paper.download_pdf(dir.path().into()).await;
+    assert!(matches!(result, Err(LearnerError::InvalidPdf(ref msg)) if msg.contains("cut short")));
+    let result = paper.download_pdf(dir.path().into()).await;
+    assert!(matches!(result, Err(LearnerError::ApiError(ref msg)) if msg.contains("403")));
+    // Nothing was written
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+  }
+
+  #[tokio::test]
+  async fn test_download_checks_html_content_type() {
+    let (url, requests) = clients::http::serve_responses_with_headers(vec![
+      (200, "Content-Type: text/html; charset=utf-8\r\n", "<!DOCTYPE html><p>Access denied</p>"),
+      (200, "Content-Type: text/html\r\n", "%PDF-1.7 test\n%%EOF\n"),
+    ])
+    .await;
+    let mut paper = create_bibtex_paper();
+    paper.pdf_url = Some(url);
+    let dir = tempdir().unwrap();
+
+    let result = paper.download_pdf(dir.path().into()).await;
+    assert!(
+      matches!(result, Err(LearnerError::InvalidPdf(ref msg)) if msg.contains("Access denied"))
+    );
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+    // A PDF labelled as HTML is still saved
+    let path = paper.download_pdf(dir.path().into()).await.unwrap();
+    assert_eq!(std::fs::read(path).unwrap(), b"%PDF-1.7 test\n%%EOF\n");
+    assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+  }
+
+  /// Serves `body`, dropping the connection after `cut_at` bytes of the first response, and
+  /// answering `Range` requests with the rest if `ranges` is set. Returns the server's URL and
+  /// the `Range` header of each request received.
+  async fn serve_interrupted(
+    body: &'static [u8],
+    cut_at: usize,
+    ranges: bool,
+  ) -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+    use tokio::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let requests = Arc::clone(&received);
+    tokio::spawn(async move {
+      while let Ok((mut socket, _)) = listener.accept().await {
+        let mut buffer = [0; 4096];
+        let read = socket.read(&mut buffer).await.unwrap_or_default();
+        let request = String::from_utf8_lossy(&buffer[..read]).to_lowercase();
+        let range = request
+          .lines()
+          .find_map(|line| line.strip_prefix("range: bytes="))
+          .map(|range| range.trim_end_matches('-').parse::<usize>().unwrap());
+        let first = {
+          let mut requests = requests.lock().unwrap();
+          requests.push(range.map(|start| format!("bytes={start}-")));
+          requests.len() == 1
+        };
+
+        let len = body.len();
+        let response = match range.filter(|_| ranges) {
+          _ if first => [
+            format!("HTTP/1.1 200 OK\r\nContent-Length: {len}\r\n\r\n").as_bytes(),
+            &body[..cut_at],
+          ]
+          .concat(),
+          Some(start) => [
+            format!(
+              "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes \
+               {start}-{}/{len}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+              len - 1,
+              len - start
+            )
+            .as_bytes(),
+            &body[start..],
+          ]
+          .concat(),
+          None => [
+            format!("HTTP/1.1 200 OK\r\nContent-Length: {len}\r\nConnection: close\r\n\r\n")
+              .as_bytes(),
+            body,
+          ]
+          .concat(),
+        };
+        let _ = socket.write_all(&response).await;
+      }
+    });
+
+    (url, received)
+  }
+
+  #[tokio::test]
+  async fn test_download_resumes_with_range() {
+    let body = b"%PDF-1.7\n0123456789012345678901234567890123456789\n%%EOF\n";
+    let (url, requests) = serve_interrupted(body, 20, true).await;
+    let mut paper = create_bibtex_paper();
+    paper.pdf_url = Some(url);
+    let dir = tempdir().unwrap();
+    let partial = dir.path().join("attention_is_all_you_need.pdf.part");
+
+    // What arrived before the connection dropped is kept
+    let result = paper.download_pdf(dir.path().into()).await;
+    assert!(matches!(result, Err(LearnerError::Network(_))));
+    assert_eq!(std::fs::read(&partial).unwrap(), &body[..20]);
+
+    // And only the rest is fetched next time
+    let path = paper.download_pdf(dir.path().into()).await.unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), body);
+    assert!(!partial.exists());
+    assert_eq!(*requests.lock().unwrap(), [None, Some("bytes=20-".to_string())]);
+  }
+
+  #[tokio::test]
+  async fn test_download_restarts_without_range_support() {
+    let body = b"%PDF-1.7\n0123456789012345678901234567890123456789\n%%EOF\n";
+    let (url, requests) = serve_interrupted(body, 20, false).await;
+    let mut paper = create_bibtex_paper();
+    paper.pdf_url = Some(url);
+    let dir = tempdir().unwrap();
+
+    assert!(paper.download_pdf(dir.path().into()).await.is_err());
+    // The full response replaces the partial file rather than being appended to it
+    let path = paper.download_pdf(dir.path().into()).await.unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), body);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    assert_eq!(requests.lock().unwrap().len(), 2);
+  }
+
   //  TODO (autoparallel): Convenient entrypoint to try seeing if the PDF comes out correct. What I
   // have tried now is using a `reqwest` client with ```
   // let _ = client.get("https://dl.acm.org/").send().await?;
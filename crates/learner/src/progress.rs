@@ -0,0 +1,98 @@
+//! Progress reporting for long-running operations.
+//!
+//! Some operations, such as importing many papers or rebuilding the search index, can take tens
+//! of seconds on a large database. These operations accept an optional [`ProgressSink`] which is
+//! told how far along they are, so that callers can surface progress in whatever way suits them:
+//! a progress bar in a terminal, periodic log lines in a daemon, or nothing at all.
+//!
+//! Progress is reported as a `(done, total, stage)` triple. Within a stage, `done` never
+//! decreases and the final report of a stage always has `done == total`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use learner::{database::Database, progress::LogProgress};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let db = Database::open("papers.db").await?;
+//!
+//! // Any closure taking `(done, total, stage)` is a sink
+//! let print = |done: usize, total: usize, stage: &str| println!("{stage}: {done}/{total}");
+//! db.rebuild_search_index(Some(&print)).await?;
+//!
+//! // Log a line every few seconds instead
+//! db.rebuild_search_index(Some(&LogProgress::default())).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+  sync::Mutex,
+  time::{Duration, Instant},
+};
+
+use tracing::info;
+
+/// Receives progress updates from long-running operations.
+///
+/// Implementations must be cheap to call, as operations may report progress frequently.
+pub trait ProgressSink: Send + Sync {
+  /// Called as an operation makes progress.
+  ///
+  /// # Arguments
+  ///
+  /// * `done` - Number of units of work completed so far in this stage
+  /// * `total` - Total number of units of work in this stage
+  /// * `stage` - Short human-readable name of the current stage (e.g. "importing papers")
+  fn on_progress(&self, done: usize, total: usize, stage: &str);
+}
+
+impl<F> ProgressSink for F
+where F: Fn(usize, usize, &str) + Send + Sync
+{
+  fn on_progress(&self, done: usize, total: usize, stage: &str) { self(done, total, stage) }
+}
+
+/// A [`ProgressSink`] that logs progress at `info` level at most once per interval.
+///
+/// The first and last report of each stage are always logged, so short operations still
+/// produce a start and finish line. This is intended for background processes such as the
+/// daemon, where a progress bar is not available.
+pub struct LogProgress {
+  /// Minimum time between log lines within a stage
+  interval: Duration,
+  /// When the last line was logged, and for which stage
+  last:     Mutex<Option<(Instant, String)>>,
+}
+
+impl LogProgress {
+  /// Creates a sink that logs at most once per `interval`.
+  pub fn new(interval: Duration) -> Self { Self { interval, last: Mutex::new(None) } }
+}
+
+impl Default for LogProgress {
+  fn default() -> Self { Self::new(Duration::from_secs(5)) }
+}
+
+impl ProgressSink for LogProgress {
+  fn on_progress(&self, done: usize, total: usize, stage: &str) {
+    let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+    let should_log = match last.as_ref() {
+      Some((at, last_stage)) =>
+        last_stage != stage || done >= total || at.elapsed() >= self.interval,
+      None => true,
+    };
+
+    if should_log {
+      info!("{stage}: {done}/{total}");
+      *last = Some((Instant::now(), stage.to_string()));
+    }
+  }
+}
+
+/// Forwards a progress report to `sink`, if there is one.
+pub(crate) fn report(sink: Option<&dyn ProgressSink>, done: usize, total: usize, stage: &str) {
+  if let Some(sink) = sink {
+    sink.on_progress(done, total, stage);
+  }
+}
@@ -0,0 +1,331 @@
+//! Local HTTP API for querying the daemon's database.
+//!
+//! When [`WatchConfig::api_port`] is set, the daemon listens on that port on 127.0.0.1 only,
+//! so other tools on the machine can use the library without going through the CLI:
+//!
+//! - `GET /papers?query=...` searches papers as [`Database::search_papers`] does
+//! - `GET /papers/{source}/{id}` returns one paper, e.g. `/papers/arxiv/2301.07041`
+//! - `POST /papers` with a body like `{"identifier": "2301.07041"}` fetches a paper and saves it,
+//!   as `learnerd add` does
+//!
+//! Papers are returned as JSON [`Paper`] objects, with `201 Created` for a newly saved paper.
+//! Failures are returned with a matching status code and a body like `{"error": "..."}`. Each
+//! connection serves a single request.
+
+use std::sync::Arc;
+
+use percent_encoding::percent_decode_str;
+use tokio::{
+  io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+  net::{TcpListener, TcpStream},
+};
+use url::Url;
+
+use super::*;
+
+/// Largest request body accepted, in bytes.
+pub const MAX_BODY_LEN: usize = 64 * 1024;
+
+/// The body of a `POST /papers` request.
+#[derive(Deserialize)]
+struct AddRequest {
+  /// Anything [`Paper::new`] accepts, such as an arXiv ID, DOI or URL
+  identifier: String,
+}
+
+/// A response ready to be written out.
+struct Response {
+  /// HTTP status code
+  status: u16,
+  /// JSON body
+  body:   String,
+}
+
+impl Response {
+  /// A response with `value` as its body.
+  fn json(status: u16, value: &impl Serialize) -> Self {
+    match serde_json::to_string(value) {
+      Ok(body) => Self { status, body },
+      Err(e) => Self::error(500, &e.to_string()),
+    }
+  }
+
+  /// A failure response with `message` as its error.
+  fn error(status: u16, message: &str) -> Self {
+    Self { status, body: serde_json::json!({ "error": message }).to_string() }
+  }
+
+  /// The reason phrase sent after the status code.
+  fn reason(&self) -> &'static str {
+    match self.status {
+      200 => "OK",
+      201 => "Created",
+      400 => "Bad Request",
+      404 => "Not Found",
+      405 => "Method Not Allowed",
+      409 => "Conflict",
+      413 => "Payload Too Large",
+      502 => "Bad Gateway",
+      _ => "Internal Server Error",
+    }
+  }
+}
+
+/// What requests are served from.
+struct Api {
+  /// Database papers are read from and saved to
+  db:      Database,
+  /// Sources `POST /papers` fetches papers from
+  sources: SourceRegistry,
+}
+
+/// Serves the API on `listener` until the task running it is aborted.
+///
+/// Papers are read from and saved to `db`, and `POST /papers` fetches them from the first of
+/// `sources` that recognizes the identifier, as [`Paper::new`] does with
+/// [`SourceRegistry::global`].
+pub async fn serve(listener: TcpListener, db: Database, sources: SourceRegistry) {
+  if let Ok(addr) = listener.local_addr() {
+    info!("Serving the API on http://{addr}");
+  }
+
+  let api = Arc::new(Api { db, sources });
+  loop {
+    match listener.accept().await {
+      Ok((stream, peer)) => {
+        let api = Arc::clone(&api);
+        tokio::spawn(async move {
+          if let Err(e) = api.handle(stream).await {
+            debug!("API connection from {peer} failed: {e}");
+          }
+        });
+      },
+      Err(e) => {
+        // Usually out of file descriptors, so give connections in flight a moment to close
+        warn!("Failed to accept API connection: {e}");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+      },
+    }
+  }
+}
+
+impl Api {
+  /// Reads a request from `stream`, answers it and closes the connection.
+  async fn handle(&self, stream: TcpStream) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+
+    let mut content_length = 0;
+    loop {
+      let mut header = String::new();
+      if reader.read_line(&mut header).await? == 0 || header.trim_end().is_empty() {
+        break;
+      }
+      if let Some((name, value)) = header.split_once(':') {
+        if name.trim().eq_ignore_ascii_case("content-length") {
+          content_length = value.trim().parse().unwrap_or(0);
+        }
+      }
+    }
+
+    let response = if content_length > MAX_BODY_LEN {
+      Response::error(413, "Request body too large")
+    } else {
+      let mut body = vec![0; content_length];
+      reader.read_exact(&mut body).await?;
+      let mut parts = request_line.split_whitespace();
+      match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => self.respond(method, target, &body).await,
+        _ => Response::error(400, "Malformed request line"),
+      }
+    };
+    debug!("API: {} -> {}", request_line.trim_end(), response.status);
+
+    let mut stream = reader.into_inner();
+    let head = format!(
+      "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: \
+       close\r\n\r\n",
+      response.status,
+      response.reason(),
+      response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await
+  }
+
+  /// Routes a request to its handler.
+  async fn respond(&self, method: &str, target: &str, body: &[u8]) -> Response {
+    let Ok(url) = Url::parse("http://localhost").and_then(|base| base.join(target)) else {
+      return Response::error(400, "Malformed request target");
+    };
+    let segments: Vec<_> = url.path_segments().map(Iterator::collect).unwrap_or_default();
+
+    match (method, segments.as_slice()) {
+      ("GET", ["papers"]) => match url.query_pairs().find(|(key, _)| key == "query") {
+        Some((_, query)) => self.search(&query).await,
+        None => Response::error(400, "Missing query parameter"),
+      },
+      // DOIs contain slashes, so everything after the source is the identifier
+      ("GET", ["papers", source, id @ ..]) if !id.is_empty() => {
+        let source = percent_decode_str(source).decode_utf8_lossy();
+        let id = id.join("/");
+        self.get(&source, &percent_decode_str(&id).decode_utf8_lossy()).await
+      },
+      ("POST", ["papers"]) => self.add(body).await,
+      (method, ["papers", ..]) if method != "GET" && method != "POST" =>
+        Response::error(405, &format!("{method} is not supported")),
+      _ => Response::error(404, &format!("No route for {}", url.path())),
+    }
+  }
+
+  /// `GET /papers?query=...`
+  async fn search(&self, query: &str) -> Response {
+    match self.db.search_papers(query).await {
+      Ok(papers) => Response::json(200, &papers),
+      Err(e) => Response::error(500, &e.to_string()),
+    }
+  }
+
+  /// `GET /papers/{source}/{id}`
+  async fn get(&self, source: &str, id: &str) -> Response {
+    let source = match source.parse::<Source>() {
+      Ok(source) => source,
+      Err(e) => return Response::error(400, &e.to_string()),
+    };
+    match self.db.get_paper_by_source_id(&source, id).await {
+      Ok(Some(paper)) => Response::json(200, &paper),
+      Ok(None) => Response::error(404, &format!("No paper {source} {id} in the database")),
+      Err(e) => Response::error(500, &e.to_string()),
+    }
+  }
+
+  /// `POST /papers`
+  async fn add(&self, body: &[u8]) -> Response {
+    let request: AddRequest = match serde_json::from_slice(body) {
+      Ok(request) => request,
+      Err(e) => return Response::error(400, &format!("Invalid request body: {e}")),
+    };
+
+    let paper = match self.sources.fetch_paper(&request.identifier).await {
+      Ok(paper) => paper,
+      Err(e @ LearnerError::InvalidIdentifier) => return Response::error(400, &e.to_string()),
+      Err(e @ LearnerError::NotFound) => return Response::error(404, &e.to_string()),
+      Err(e) => return Response::error(502, &e.to_string()),
+    };
+
+    match self.db.save_paper(&paper).await {
+      Ok(_) => Response::json(201, &paper),
+      Err(e) if e.is_duplicate_error() => Response::error(
+        409,
+        &format!("Paper {} {} is already in the database", paper.source, paper.source_identifier),
+      ),
+      Err(e) => Response::error(500, &e.to_string()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use learner::clients::{PaperFuture, PaperSource};
+  use tempfile::tempdir;
+
+  use super::*;
+
+  fn paper(id: &str, title: &str) -> Paper {
+    Paper {
+      title:             title.to_string(),
+      authors:           Vec::new(),
+      abstract_text:     format!("Abstract of {title}"),
+      raw_abstract:      None,
+      publication_date:  Utc::now(),
+      source:            Source::DOI,
+      source_identifier: id.to_string(),
+      pdf_url:           None,
+      doi:               Some(id.to_string()),
+      version:           None,
+      categories:        Vec::new(),
+      keywords:          Vec::new(),
+      tags:              Vec::new(),
+      pdf_path:          None,
+      reading_status:    ReadingStatus::Unread,
+      added_at:          None,
+    }
+  }
+
+  /// A source for inputs like `test:10.1000/xyz`, fetching without any network access.
+  struct TestSource;
+
+  impl PaperSource for TestSource {
+    fn fetch_paper<'a>(&'a self, id: &'a str) -> PaperFuture<'a> {
+      Box::pin(async move { Ok(paper(id, "Fetched Paper")) })
+    }
+
+    fn matches(&self, input: &str) -> Option<String> {
+      input.strip_prefix("test:").map(str::to_string)
+    }
+  }
+
+  #[tokio::test]
+  async fn test_api_routes() {
+    let dir = tempdir().unwrap();
+    let db = Database::open(dir.path().join("test.db")).await.unwrap();
+    db.save_paper(&paper("10.1000/seeded", "Homomorphic Encryption in Practice")).await.unwrap();
+
+    let mut sources = SourceRegistry::new();
+    sources.push(TestSource);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(serve(listener, db, sources));
+    let client = reqwest::Client::new();
+
+    // Search
+    let response =
+      client.get(format!("{url}/papers")).query(&[("query", "homomorphic")]).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let papers: Vec<Paper> = response.json().await.unwrap();
+    assert_eq!(papers.len(), 1);
+    assert_eq!(papers[0].title, "Homomorphic Encryption in Practice");
+    let response = client.get(format!("{url}/papers")).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+
+    // Get, with the slash in the DOI either as is or escaped
+    for path in ["/papers/doi/10.1000/seeded", "/papers/DOI/10.1000%2Fseeded"] {
+      let response = client.get(format!("{url}{path}")).send().await.unwrap();
+      assert_eq!(response.status(), 200, "{path}");
+      let paper: Paper = response.json().await.unwrap();
+      assert_eq!(paper.source_identifier, "10.1000/seeded");
+    }
+    let response = client.get(format!("{url}/papers/doi/10.1000/missing")).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+    let response = client.get(format!("{url}/papers/nowhere/1")).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+
+    // Add
+    let add = |identifier: &str| {
+      client.post(format!("{url}/papers")).json(&serde_json::json!({ "identifier": identifier }))
+    };
+    let response = add("test:10.1000/added").send().await.unwrap();
+    assert_eq!(response.status(), 201);
+    let paper: Paper = response.json().await.unwrap();
+    assert_eq!(paper.title, "Fetched Paper");
+    let response = client.get(format!("{url}/papers/doi/10.1000/added")).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(add("test:10.1000/added").send().await.unwrap().status(), 409);
+    assert_eq!(add("unrecognized").send().await.unwrap().status(), 400);
+    let response = client.post(format!("{url}/papers")).body("not json").send().await.unwrap();
+    assert_eq!(response.status(), 400);
+
+    // Anything else
+    let response = client.delete(format!("{url}/papers/doi/10.1000/added")).send().await.unwrap();
+    assert_eq!(response.status(), 405);
+    let response = client.get(format!("{url}/elsewhere")).send().await.unwrap();
+    assert_eq!(response.status(), 404);
+    let error: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(error["error"], "No route for /elsewhere");
+
+    server.abort();
+    dir.close().unwrap();
+  }
+}
@@ -1,8 +1,8 @@
 //! Daemon implementation for the learnerd service.
 //!
 //! This module provides functionality for running learnerd as a system service, with support
-//! for both systemd (Linux) and launchd (macOS) environments. The daemon handles background
-//! tasks such as paper monitoring, metadata updates, and system integration.
+//! for systemd (Linux), launchd (macOS) and Windows service environments. The daemon handles
+//! background tasks such as paper monitoring, metadata updates, and system integration.
 //!
 //! # Architecture
 //!
@@ -11,6 +11,7 @@
 //! - Platform-specific service management is handled in submodules:
 //!   - [`linux`] module for systemd integration
 //!   - [`macos`] module for launchd integration
+//!   - `windows` module for Windows service integration
 //!
 //! # Features
 //!
@@ -35,6 +36,34 @@
 //! learnerd daemon uninstall
 //! ```
 //!
+//! # Watching arXiv
+//!
+//! While running, the daemon saves newly published papers in the arXiv categories registered
+//! in the database with `learnerd daemon watch add` (see [`Database::add_watch_target`]):
+//!
+//! ```bash
+//! learnerd daemon watch add cs.CR arxiv
+//! learnerd config set daemon_poll_interval_secs 1800
+//! ```
+//!
+//! Each check only fetches the papers submitted since the category was last checked. The
+//! categories listed in [`WATCH_CONFIG_FILE`] in its working directory (see [`WatchConfig`])
+//! are synced too, by fetching their newest papers, e.g.:
+//!
+//! ```json
+//! { "categories": ["cs.CR", "math.AG"], "interval_secs": 3600 }
+//! ```
+//!
+//! The file, watch list and interval are read again before every sync, so changes apply
+//! without a restart. After each sync the time and the number of papers added are recorded in
+//! [`SYNC_STATE_FILE`], which `learnerd daemon status` shows.
+//!
+//! # Local API
+//!
+//! If `api_port` is set in [`WATCH_CONFIG_FILE`], the daemon also serves an HTTP API on that
+//! port on 127.0.0.1 for searching, getting and adding papers (see [`api`]). Unlike the rest of
+//! the file, the port is only read when the daemon starts.
+//!
 //! # Platform-Specific Details
 //!
 //! ## Linux (systemd)
@@ -66,6 +95,20 @@
 //! sudo launchctl list | grep learnerd
 //! ```
 //!
+//! ## Windows (Service Control Manager)
+//!
+//! The daemon is registered as the `learnerd` service with `sc.exe`, starting automatically at
+//! boot. Key paths, under the default `%ProgramData%`:
+//! - PID file: `C:\ProgramData\learnerd\learnerd.pid`
+//! - Working directory: `C:\ProgramData\learnerd`
+//! - Logs: `C:\ProgramData\learnerd\logs`
+//!
+//! Service management, from an administrator prompt:
+//! ```bash
+//! sc.exe start learnerd
+//! sc.exe query learnerd
+//! ```
+//!
 //! # Implementation Notes
 //!
 //! The daemon implementation follows several best practices:
@@ -76,7 +119,7 @@
 //!    - Contextual metadata (thread IDs, source location)
 //!
 //! 2. Graceful shutdown handling:
-//!    - SIGTERM signal handling on Unix systems
+//!    - SIGTERM signal handling on Unix systems, and service stop requests on Windows
 //!    - Proper cleanup of PID files and resources
 //!
 //! 3. Error handling:
@@ -86,8 +129,6 @@
 //!
 //! # Future Improvements
 //!
-//! - [ ] Implement Windows service support
-//! - [ ] Add configurable monitoring intervals
 //! - [ ] Support for plugins/extensions
 //! - [ ] Health check endpoint
 //! - [ ] Metrics collection
@@ -100,23 +141,192 @@
 //! - [systemd documentation](https://www.freedesktop.org/software/systemd/man/systemd.service.html)
 //! - [launchd documentation](https://developer.apple.com/library/archive/documentation/MacOSX/Conceptual/BPSystemStartup/Chapters/CreatingLaunchdJobs.html)
 
-use std::{fs, path::PathBuf};
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::{Duration, Instant},
+};
 
+use chrono::{DateTime, Utc};
+use learner::{clients::SourceRegistry, progress::LogProgress};
+#[cfg(unix)]
 use nix::{
   sys::signal::{self, Signal},
   unistd::Pid,
 };
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error, info};
+use tokio::sync::Notify;
+use tracing::{debug, error, info, warn};
 use tracing_appender::rolling;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use super::*;
 
+pub mod api;
 #[cfg(target_os = "linux")] pub mod linux;
 #[cfg(target_os = "linux")] pub use linux::*;
 #[cfg(target_os = "macos")] pub mod macos;
 #[cfg(target_os = "macos")] pub use macos::*;
+#[cfg(target_os = "windows")] pub mod windows;
+#[cfg(target_os = "windows")] pub use windows::*;
+
+/// Name of the file in the daemon's working directory that lists what it watches.
+pub const WATCH_CONFIG_FILE: &str = "watch.json";
+
+/// Name of the file in the daemon's working directory where it records its last sync.
+pub const SYNC_STATE_FILE: &str = "state.json";
+
+/// Setting in the database's config table holding the seconds between syncs. When set, it
+/// takes precedence over [`WatchConfig::interval_secs`].
+pub const POLL_INTERVAL_CONFIG_KEY: &str = "daemon_poll_interval_secs";
+
+/// How far back the first check of a newly watched category looks for papers.
+const FIRST_CHECK_WINDOW: chrono::TimeDelta = chrono::TimeDelta::days(7);
+
+/// How long [`Daemon::stop`] waits for the daemon to exit after asking it to.
+pub const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A request for the daemon loop to stop, made by SIGTERM or SIGINT, or on Windows by the
+/// Service Control Manager.
+///
+/// The loop checks [`Shutdown::is_requested`] before every sync, and [`Shutdown::wait`] cuts
+/// short the wait between syncs, so the daemon exits promptly without interrupting a sync.
+#[derive(Clone, Debug, Default)]
+pub struct Shutdown {
+  /// Whether shutdown has been requested
+  requested: Arc<AtomicBool>,
+  /// Wakes the loop if it is waiting for the next sync
+  notify:    Arc<Notify>,
+}
+
+impl Shutdown {
+  /// Asks the daemon loop to stop.
+  pub fn request(&self) {
+    self.requested.store(true, Ordering::SeqCst);
+    self.notify.notify_one();
+  }
+
+  /// Whether shutdown has been requested.
+  pub fn is_requested(&self) -> bool { self.requested.load(Ordering::SeqCst) }
+
+  /// Waits until shutdown is requested, returning at once if it already has been.
+  pub async fn wait(&self) {
+    if !self.is_requested() {
+      self.notify.notified().await;
+    }
+  }
+
+  /// Requests shutdown when the process receives SIGTERM (e.g. from `learnerd daemon stop` or
+  /// the service manager) or SIGINT.
+  fn listen_for_signals(&self) -> Result<(), LearnerdErrors> {
+    #[cfg(unix)]
+    let mut terminate = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+    let shutdown = self.clone();
+    tokio::spawn(async move {
+      #[cfg(unix)]
+      let signal = tokio::select! {
+        _ = terminate.recv() => "SIGTERM",
+        _ = tokio::signal::ctrl_c() => "SIGINT",
+      };
+      #[cfg(not(unix))]
+      let signal = {
+        let _ = tokio::signal::ctrl_c().await;
+        "Ctrl-C"
+      };
+      info!("Received {signal}, shutting down");
+      shutdown.request();
+    });
+    Ok(())
+  }
+}
+
+/// What the daemon watches, read from [`WATCH_CONFIG_FILE`] in its working directory.
+///
+/// Every field is optional in the file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchConfig {
+  /// arXiv categories whose new papers are saved, e.g. `cs.CR` or `math.AG`
+  pub categories:    Vec<String>,
+  /// Seconds between syncs. Defaults to an hour, and is overridden by the
+  /// [`POLL_INTERVAL_CONFIG_KEY`] setting in the database.
+  pub interval_secs: u64,
+  /// How many of the newest papers in each category are fetched per sync. Defaults to 50.
+  pub max_results:   usize,
+  /// Database that papers are saved to. Defaults to [`Database::default_path`].
+  pub database:      Option<PathBuf>,
+  /// Port on 127.0.0.1 to serve the [`api`] on. The API is not served unless this is set.
+  pub api_port:      Option<u16>,
+}
+
+impl Default for WatchConfig {
+  fn default() -> Self {
+    Self {
+      categories:    Vec::new(),
+      interval_secs: 3600,
+      max_results:   50,
+      database:      None,
+      api_port:      None,
+    }
+  }
+}
+
+impl WatchConfig {
+  /// Reads the watch config from `working_dir`, or returns `None` if there is none.
+  ///
+  /// # Errors
+  ///
+  /// Returns `LearnerdErrors` if the file exists but cannot be read or parsed.
+  pub fn load(working_dir: &Path) -> Result<Option<Self>, LearnerdErrors> {
+    match fs::read_to_string(working_dir.join(WATCH_CONFIG_FILE)) {
+      Ok(contents) => Ok(Some(serde_json::from_str(&contents)?)),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+      Err(e) => Err(e.into()),
+    }
+  }
+
+  /// Time to wait between syncs, unless the database's [`POLL_INTERVAL_CONFIG_KEY`] setting
+  /// says otherwise.
+  pub fn interval(&self) -> Duration { Duration::from_secs(self.interval_secs) }
+
+  /// The database that papers are saved to, and the watch list read from.
+  pub fn database_path(&self) -> PathBuf {
+    self.database.clone().unwrap_or_else(Database::default_path)
+  }
+}
+
+/// The outcome of the daemon's last sync, recorded in [`SYNC_STATE_FILE`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncState {
+  /// When the last sync finished
+  pub last_sync:    DateTime<Utc>,
+  /// How many papers the last sync added
+  pub papers_added: usize,
+}
+
+impl SyncState {
+  /// Reads the state recorded in `working_dir`, or returns `None` if the daemon hasn't synced.
+  ///
+  /// # Errors
+  ///
+  /// Returns `LearnerdErrors` if the file exists but cannot be read or parsed.
+  pub fn load(working_dir: &Path) -> Result<Option<Self>, LearnerdErrors> {
+    match fs::read_to_string(working_dir.join(SYNC_STATE_FILE)) {
+      Ok(contents) => Ok(Some(serde_json::from_str(&contents)?)),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+      Err(e) => Err(e.into()),
+    }
+  }
+
+  /// Records this state in `working_dir`.
+  fn save(&self, working_dir: &Path) -> Result<(), LearnerdErrors> {
+    Ok(fs::write(working_dir.join(SYNC_STATE_FILE), serde_json::to_string_pretty(self)?)?)
+  }
+}
 
 /// Commands available for daemon management through the CLI.
 #[derive(Subcommand)]
@@ -134,7 +344,8 @@ pub enum DaemonCommands {
   /// This command will:
   /// 1. Read the PID file
   /// 2. Send SIGTERM to the process
-  /// 3. Clean up the PID file
+  /// 3. Wait for it to finish any sync in progress and exit
+  /// 4. Clean up the PID file
   Stop,
   /// Restart the daemon process.
   ///
@@ -161,7 +372,39 @@ pub enum DaemonCommands {
   /// - Running status and PID
   /// - Log file locations
   /// - Service registration status
+  /// - Watched categories, and the time and outcome of the last sync
   Status,
+  /// Manage the categories whose new papers the daemon saves.
+  Watch {
+    /// The watch list operation to perform
+    #[command(subcommand)]
+    cmd: WatchCommands,
+  },
+}
+
+/// Commands for managing the daemon's watch list, stored in the database.
+#[derive(Subcommand)]
+pub enum WatchCommands {
+  /// Save new papers in a category, e.g. `watch add cs.CR arxiv`
+  Add {
+    /// The category, e.g. cs.CR or math.AG
+    category: String,
+
+    /// Source system the category belongs to. Only arxiv can be watched for now
+    #[arg(value_enum, ignore_case = true, default_value = "arxiv")]
+    source: Source,
+  },
+  /// Stop saving new papers in a category
+  Remove {
+    /// The category, e.g. cs.CR or math.AG
+    category: String,
+
+    /// Source system the category belongs to
+    #[arg(value_enum, ignore_case = true, default_value = "arxiv")]
+    source: Source,
+  },
+  /// List the watched categories and when each was last checked
+  List,
 }
 
 /// Configuration for the daemon service.
@@ -203,6 +446,7 @@ pub struct Daemon {
 }
 
 impl Default for Daemon {
+  #[cfg(not(target_os = "windows"))]
   fn default() -> Self {
     Self {
       pid_file:    PathBuf::from(DEFAULT_PID_FILE),
@@ -210,6 +454,17 @@ impl Default for Daemon {
       log_dir:     PathBuf::from(DEFAULT_LOG_DIR),
     }
   }
+
+  /// Everything goes under `%ProgramData%`, wherever it is (see [`program_data_dir`])
+  #[cfg(target_os = "windows")]
+  fn default() -> Self {
+    let working_dir = program_data_dir();
+    Self {
+      pid_file: working_dir.join("learnerd.pid"),
+      log_dir: working_dir.join("logs"),
+      working_dir,
+    }
+  }
 }
 
 impl Daemon {
@@ -227,7 +482,10 @@ impl Daemon {
   /// Starts the daemon process and initializes logging.
   ///
   /// Sets up daily log rotation and dual logging to both files and system journal.
-  /// Creates required directories if they don't exist.
+  /// Creates required directories if they don't exist, and records the process ID in the PID
+  /// file. Runs until the process receives SIGTERM or SIGINT, then removes the PID file and
+  /// returns. On Windows, when started by the Service Control Manager, it reports its state as
+  /// a service and a service stop request stops it the same way.
   ///
   /// # Errors
   ///
@@ -235,7 +493,22 @@ impl Daemon {
   /// - Directory creation fails
   /// - Log initialization fails
   /// - Daemon process fails to start
-  pub fn start(&self) -> Result<(), LearnerdErrors> {
+  pub async fn start(&self) -> Result<(), LearnerdErrors> {
+    let shutdown = Shutdown::default();
+
+    // The Service Control Manager gives up on a service that doesn't connect soon after it
+    // starts, and must hear that it stopped even if it fails to set up
+    #[cfg(target_os = "windows")]
+    let service = dispatch_service(&shutdown);
+    let result = self.serve(&shutdown).await;
+    #[cfg(target_os = "windows")]
+    service.finish(result.is_err());
+    result
+  }
+
+  /// Sets up logging and the PID file, then runs the daemon loop until `shutdown` is
+  /// requested. See [`Daemon::start`].
+  async fn serve(&self, shutdown: &Shutdown) -> Result<(), LearnerdErrors> {
     // Ensure directories exist
     fs::create_dir_all(&self.working_dir)?;
     fs::create_dir_all(&self.log_dir)?;
@@ -269,25 +542,69 @@ impl Daemon {
     info!("Starting learnerd daemon");
     debug!("Using config: {:?}", self);
 
+    if let Some(parent) = self.pid_file.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    fs::write(&self.pid_file, std::process::id().to_string())?;
+
+    shutdown.listen_for_signals()?;
+    let api = self.spawn_api().await;
     info!("Daemon started successfully");
-    self.run()
+    let result = self.run(shutdown).await;
+    if let Some(api) = api {
+      api.abort();
+    }
+
+    if let Err(e) = fs::remove_file(&self.pid_file) {
+      error!("Failed to remove PID file: {}", e);
+    }
+    info!("Daemon stopped");
+    result
+  }
+
+  /// Starts serving the [`api`] if the watch config sets a port for it.
+  ///
+  /// The API is optional, so failing to start it is logged rather than stopping the daemon.
+  async fn spawn_api(&self) -> Option<tokio::task::JoinHandle<()>> {
+    let config = match WatchConfig::load(&self.working_dir) {
+      Ok(config) => config.unwrap_or_default(),
+      Err(e) => {
+        error!("Failed to read {WATCH_CONFIG_FILE}: {e}");
+        return None;
+      },
+    };
+    let port = config.api_port?;
+
+    let listener = match tokio::net::TcpListener::bind(("127.0.0.1", port)).await {
+      Ok(listener) => listener,
+      Err(e) => {
+        error!("Failed to listen for API requests on port {port}: {e}");
+        return None;
+      },
+    };
+    let path = config.database.unwrap_or_else(Database::default_path);
+    match Database::open(&path).await {
+      Ok(db) => Some(tokio::spawn(api::serve(listener, db, SourceRegistry::global()))),
+      Err(e) => {
+        error!("Failed to open {} for the API: {e}", path.display());
+        None
+      },
+    }
   }
 
-  // TODO (autoparallel): this is actually never really able to be used at the moment.
   /// Attempts to stop a running daemon process.
   ///
-  /// Sends SIGTERM to the process identified by PID file and performs cleanup.
+  /// Sends SIGTERM to the process identified by PID file, then waits up to [`STOP_TIMEOUT`]
+  /// for it to finish its current sync and exit, so that a following start doesn't race it. On
+  /// Windows the Service Control Manager is asked to stop the service instead, and a daemon
+  /// started from a console has its process ended (see `windows::stop_process`).
   ///
   /// # Errors
   ///
   /// Returns `LearnerdErrors` if:
   /// - PID file is missing or invalid
   /// - Process termination fails
-  /// - Cleanup fails
-  ///
-  /// # Note
-  ///
-  /// Currently has limited functionality - see TODO in implementation.
+  /// - The process is still running after [`STOP_TIMEOUT`]
   pub fn stop(&self) -> Result<(), LearnerdErrors> {
     if let Ok(pid) = fs::read_to_string(&self.pid_file) {
       let pid: i32 = pid.trim().parse().map_err(|e: std::num::ParseIntError| {
@@ -301,10 +618,28 @@ impl Daemon {
           error!("Failed to send SIGTERM to process: {}", e);
           return Err(LearnerdErrors::Daemon(format!("Failed to stop daemon: {}", e)));
         }
+
+        // Signal 0 only checks that the process still exists
+        let deadline = Instant::now() + STOP_TIMEOUT;
+        while signal::kill(Pid::from_raw(pid), None).is_ok() {
+          if Instant::now() >= deadline {
+            return Err(LearnerdErrors::Daemon(format!(
+              "Daemon (PID {pid}) still running after {} seconds",
+              STOP_TIMEOUT.as_secs()
+            )));
+          }
+          std::thread::sleep(Duration::from_millis(100));
+        }
       }
 
+      #[cfg(target_os = "windows")]
+      stop_process(pid as u32)?;
+
+      // The daemon removes its PID file on the way out, unless it was killed outright
       if let Err(e) = fs::remove_file(&self.pid_file) {
-        error!("Failed to remove PID file: {}", e);
+        if e.kind() != std::io::ErrorKind::NotFound {
+          error!("Failed to remove PID file: {}", e);
+        }
       }
 
       Ok(())
@@ -314,16 +649,15 @@ impl Daemon {
     }
   }
 
-  // TODO (autoparallel): this is actually never really able to be used at the moment.
   /// Restarts the daemon process with a 1-second delay between stop and start.
   ///
   /// # Errors
   ///
   /// Returns `LearnerdErrors` if either stop or start operations fail.
-  pub fn restart(&self) -> Result<(), LearnerdErrors> {
+  pub async fn restart(&self) -> Result<(), LearnerdErrors> {
     self.stop()?;
-    std::thread::sleep(std::time::Duration::from_secs(1));
-    self.start()
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    self.start().await
   }
 
   /// Installs the daemon as a system service using platform-specific mechanisms.
@@ -332,6 +666,7 @@ impl Daemon {
   ///
   /// - Linux: Creates systemd unit file
   /// - macOS: Creates launchd plist
+  /// - Windows: Registers a service with the Service Control Manager
   ///
   /// # Errors
   ///
@@ -345,23 +680,175 @@ impl Daemon {
   /// Returns `LearnerdErrors` if service removal fails.
   pub fn uninstall(&self) -> Result<(), LearnerdErrors> { uninstall_system_daemon() }
 
-  /// Main daemon loop that handles background tasks.
+  /// Fetches the new papers in each watched category and saves those not yet in `db`.
+  ///
+  /// Categories in `config` have their newest papers fetched, while those watched in `db` have
+  /// the papers submitted since their last check fetched, and are then marked as checked.
+  /// Papers already in the database are skipped, and a category that cannot be fetched is
+  /// logged and skipped, so one failure doesn't hold up the rest. The outcome is recorded in
+  /// [`SYNC_STATE_FILE`].
+  ///
+  /// # Errors
   ///
-  /// Currently implements a basic heartbeat for monitoring.
-  fn run(&self) -> Result<(), LearnerdErrors> {
+  /// Returns `LearnerdErrors` if a paper cannot be saved for a reason other than being a
+  /// duplicate, or the state cannot be recorded.
+  pub async fn sync(
+    &self,
+    config: &WatchConfig,
+    db: &Database,
+    client: &ArxivClient,
+  ) -> Result<SyncState, LearnerdErrors> {
+    let options = ArxivSearchOptions {
+      max_results: config.max_results,
+      start:       0,
+      sort:        ArxivSort::Submitted,
+    };
+
+    // Logs how far along saving a long list of papers is, every few seconds
+    let progress = LogProgress::default();
+    let mut papers_added = 0;
+    for category in &config.categories {
+      let papers = match client.search_with(&format!("cat:{category}"), &options).await {
+        Ok(papers) => papers,
+        Err(e) => {
+          warn!("Failed to fetch new papers in {category}: {e}");
+          continue;
+        },
+      };
+
+      let added = save_new_papers(db, &papers, category, &progress).await?;
+      info!("Added {added} of the {} newest papers in {category}", papers.len());
+      papers_added += added;
+    }
+
+    for target in db.list_watch_targets().await? {
+      let category = &target.category;
+      if target.source != Source::Arxiv {
+        warn!("Skipping {category}: only arXiv categories can be watched, not {}", target.source);
+        continue;
+      }
+
+      // Papers submitted while this check runs are picked up by the next one
+      let checked_at = Utc::now();
+      let since = target.last_checked.unwrap_or(checked_at - FIRST_CHECK_WINDOW);
+      let papers = match client.list_new(category, since).await {
+        Ok(papers) => papers,
+        Err(e) => {
+          warn!("Failed to fetch new papers in {category}: {e}");
+          continue;
+        },
+      };
+
+      let added = save_new_papers(db, &papers, category, &progress).await?;
+      db.set_watch_target_checked(category, &target.source, checked_at).await?;
+      info!("Added {added} of the {} papers submitted to {category} since {since}", papers.len());
+      papers_added += added;
+    }
+
+    let state = SyncState { last_sync: Utc::now(), papers_added };
+    state.save(&self.working_dir)?;
+    Ok(state)
+  }
+
+  /// Main daemon loop, syncing the watched arXiv categories on the configured interval.
+  ///
+  /// The watch config, watch list and interval are read again before every sync. The database
+  /// is left alone, rather than created, while it doesn't exist and the config lists no
+  /// categories. Failures are logged and retried on the next sync rather than stopping the
+  /// loop. Returns once `shutdown` is requested, after any sync in progress.
+  async fn run(&self, shutdown: &Shutdown) -> Result<(), LearnerdErrors> {
     info!("Daemon running");
 
-    // TODO: Implement actual daemon functionality
-    loop {
-      std::thread::sleep(std::time::Duration::from_secs(5));
-      debug!("Daemon heartbeat");
+    let client = ArxivClient::new();
+    while !shutdown.is_requested() {
+      let config = match WatchConfig::load(&self.working_dir) {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+          debug!("No {WATCH_CONFIG_FILE} in {}, nothing to watch", self.working_dir.display());
+          WatchConfig::default()
+        },
+        Err(e) => {
+          error!("Failed to read {WATCH_CONFIG_FILE}: {e}");
+          WatchConfig::default()
+        },
+      };
+
+      let path = config.database_path();
+      let mut interval = config.interval();
+      if !config.categories.is_empty() || path.exists() {
+        match Database::open(&path).await {
+          Ok(db) => {
+            interval = poll_interval(&db).await.unwrap_or(interval);
+            match self.sync(&config, &db, &client).await {
+              Ok(state) => info!("Sync added {} papers", state.papers_added),
+              Err(e) => error!("Sync failed: {e}"),
+            }
+          },
+          Err(e) => error!("Sync failed: {e}"),
+        }
+      } else {
+        debug!("No database at {} and no categories to watch", path.display());
+      }
+
+      tokio::select! {
+        _ = tokio::time::sleep(interval) => {},
+        _ = shutdown.wait() => {},
+      }
+    }
+
+    info!("Daemon shutting down");
+    Ok(())
+  }
+}
+
+/// Saves the papers fetched from `category` that are not yet in `db`, returning how many were
+/// added. Progress is reported to `progress` after every paper under a stage naming the
+/// category.
+async fn save_new_papers(
+  db: &Database,
+  papers: &[Paper],
+  category: &str,
+  progress: &dyn ProgressSink,
+) -> Result<usize, LearnerdErrors> {
+  let stage = format!("saving papers in {category}");
+  progress.on_progress(0, papers.len(), &stage);
+
+  let mut added = 0;
+  for (i, paper) in papers.iter().enumerate() {
+    match db.save_paper(paper).await {
+      Ok(_) => added += 1,
+      Err(e) if e.is_duplicate_error() => {},
+      Err(e) => return Err(e.into()),
     }
+    progress.on_progress(i + 1, papers.len(), &stage);
+  }
+  Ok(added)
+}
+
+/// Reads the time between syncs from the [`POLL_INTERVAL_CONFIG_KEY`] setting, or returns
+/// `None` if it isn't set. A setting that isn't a positive number of seconds is logged and
+/// ignored.
+pub async fn poll_interval(db: &Database) -> Option<Duration> {
+  let secs = match db.get_config(POLL_INTERVAL_CONFIG_KEY).await {
+    Ok(secs) => secs?,
+    Err(e) => {
+      warn!("Failed to read {POLL_INTERVAL_CONFIG_KEY}: {e}");
+      return None;
+    },
+  };
+  match secs.trim().parse() {
+    Ok(secs) if secs > 0 => Some(Duration::from_secs(secs)),
+    _ => {
+      warn!("Ignoring {POLL_INTERVAL_CONFIG_KEY} = {secs}, which isn't a number of seconds");
+      None
+    },
   }
 }
 
 #[cfg(test)]
 mod tests {
 
+  use chrono::TimeZone;
   use tempfile::tempdir;
 
   use super::*;
@@ -376,15 +863,160 @@ mod tests {
     (daemon, test_dir)
   }
 
+  /// A trimmed arXiv API search response with two entries.
+  const SEARCH_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="html">ArXiv Query: search_query=cat:cs.CR</title>
+  <entry>
+    <id>http://arxiv.org/abs/2301.07041v2</id>
+    <published>2023-01-17T18:00:59Z</published>
+    <title>Verifiable Fully Homomorphic Encryption</title>
+    <summary>Fully Homomorphic Encryption (FHE) is seeing increasing real-world deployment.</summary>
+    <author>
+      <name>Alexander Viand</name>
+    </author>
+  </entry>
+  <entry>
+    <id>http://arxiv.org/abs/2301.07042v1</id>
+    <published>2023-01-17T17:00:00Z</published>
+    <title>Another Paper</title>
+    <summary>Another abstract.</summary>
+    <author>
+      <name>Someone Else</name>
+    </author>
+  </entry>
+</feed>"#;
+
+  /// Serves `body` in response to every request, returning the server's URL.
+  async fn serve(body: &'static str) -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+      while let Ok((mut socket, _)) = listener.accept().await {
+        let mut buffer = [0; 4096];
+        let _ = socket.read(&mut buffer).await;
+        let response = format!(
+          "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+          body.len()
+        );
+        let _ = socket.write_all(response.as_bytes()).await;
+      }
+    });
+    url
+  }
+
+  #[tokio::test]
+  async fn test_sync_saves_new_papers() {
+    let (daemon, temp) = setup_test_daemon();
+    fs::create_dir_all(&daemon.working_dir).unwrap();
+    fs::write(
+      daemon.working_dir.join(WATCH_CONFIG_FILE),
+      r#"{ "categories": ["cs.CR", "cs.LG"], "interval_secs": 60 }"#,
+    )
+    .unwrap();
+    let config = WatchConfig::load(&daemon.working_dir).unwrap().unwrap();
+    assert_eq!(config.interval(), Duration::from_secs(60));
+    assert_eq!(config.max_results, 50);
+    assert!(SyncState::load(&daemon.working_dir).unwrap().is_none());
+
+    let db = Database::open(temp.path().join("test.db")).await.unwrap();
+    let client = ArxivClient::new().with_base_url(serve(SEARCH_FEED).await);
+
+    // Both categories list the same papers, so they're only added once
+    let state = daemon.sync(&config, &db, &client).await.unwrap();
+    assert_eq!(state.papers_added, 2);
+    assert_eq!(SyncState::load(&daemon.working_dir).unwrap(), Some(state));
+    assert!(db.get_paper_by_source_id(&Source::Arxiv, "2301.07041").await.unwrap().is_some());
+
+    let state = daemon.sync(&config, &db, &client).await.unwrap();
+    assert_eq!(state.papers_added, 0);
+    assert_eq!(SyncState::load(&daemon.working_dir).unwrap(), Some(state));
+
+    // Saving reports progress after every paper
+    let papers = db.export_papers(None).await.unwrap();
+    let reports = std::sync::Mutex::new(Vec::new());
+    let record = |done: usize, total: usize, stage: &str| {
+      reports.lock().unwrap().push((done, total, stage.to_string()));
+    };
+    assert_eq!(save_new_papers(&db, &papers, "cs.CR", &record).await.unwrap(), 0);
+    let stage = "saving papers in cs.CR".to_string();
+    assert_eq!(reports.into_inner().unwrap(), [
+      (0, 2, stage.clone()),
+      (1, 2, stage.clone()),
+      (2, 2, stage)
+    ]);
+  }
+
+  #[tokio::test]
+  async fn test_sync_checks_watch_targets() {
+    let (daemon, temp) = setup_test_daemon();
+    fs::create_dir_all(&daemon.working_dir).unwrap();
+    let config = WatchConfig::default();
+    let db = Database::open(temp.path().join("test.db")).await.unwrap();
+    let client = ArxivClient::new().with_base_url(serve(SEARCH_FEED).await);
+
+    // A category checked last in 2020 gets the papers submitted since
+    db.add_watch_target("cs.CR", &Source::Arxiv).await.unwrap();
+    let since = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+    db.set_watch_target_checked("cs.CR", &Source::Arxiv, since).await.unwrap();
+    // Only arXiv categories can be watched
+    db.add_watch_target("crypto", &Source::IACR).await.unwrap();
+
+    let state = daemon.sync(&config, &db, &client).await.unwrap();
+    assert_eq!(state.papers_added, 2);
+    let targets = db.list_watch_targets().await.unwrap();
+    assert!(targets[0].last_checked.unwrap() > since);
+    assert_eq!(targets[1].last_checked, None);
+
+    // The next check only looks for papers submitted since this one
+    let state = daemon.sync(&config, &db, &client).await.unwrap();
+    assert_eq!(state.papers_added, 0);
+
+    assert_eq!(poll_interval(&db).await, None);
+    db.set_config(POLL_INTERVAL_CONFIG_KEY, "60").await.unwrap();
+    assert_eq!(poll_interval(&db).await, Some(Duration::from_secs(60)));
+    db.set_config(POLL_INTERVAL_CONFIG_KEY, "0").await.unwrap();
+    assert_eq!(poll_interval(&db).await, None);
+  }
+
+  #[tokio::test]
+  async fn test_run_stops_on_shutdown() {
+    let (daemon, _temp) = setup_test_daemon();
+    fs::create_dir_all(&daemon.working_dir).unwrap();
+    let shutdown = Shutdown::default();
+
+    // A shutdown requested while waiting for the next sync ends the wait early
+    let run = tokio::spawn({
+      let shutdown = shutdown.clone();
+      async move { daemon.run(&shutdown).await }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!run.is_finished());
+    shutdown.request();
+    tokio::time::timeout(Duration::from_secs(5), run).await.unwrap().unwrap().unwrap();
+
+    // A shutdown requested before the loop starts stops it at once
+    let (daemon, _temp) = setup_test_daemon();
+    tokio::time::timeout(Duration::from_secs(5), daemon.run(&shutdown)).await.unwrap().unwrap();
+  }
+
   #[test]
   fn test_daemon_directory_creation() {
     let (daemon, _temp) = setup_test_daemon();
     let daemon_clone = daemon.clone();
     // Start should create directories
-    let _handle = std::thread::spawn(move || daemon.start());
+    let _handle =
+      std::thread::spawn(move || tokio::runtime::Runtime::new().unwrap().block_on(daemon.start()));
     std::thread::sleep(std::time::Duration::from_secs(5));
 
     assert!(daemon_clone.working_dir.exists(), "Working directory should be created");
     assert!(daemon_clone.log_dir.exists(), "Log directory should be created");
+    assert_eq!(
+      fs::read_to_string(&daemon_clone.pid_file).unwrap(),
+      std::process::id().to_string(),
+      "PID file should hold the daemon's process ID"
+    );
   }
 }
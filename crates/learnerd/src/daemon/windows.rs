@@ -0,0 +1,325 @@
+//! Windows-specific daemon implementation using the Service Control Manager.
+//!
+//! Provides functions for installing and managing the daemon as a Windows service, through the
+//! [`windows_service`] crate. When the Service Control Manager starts the daemon, it reports its
+//! state as a service so that `sc.exe stop` and system shutdown stop it gracefully.
+//!
+//! # Service Configuration
+//!
+//! The daemon is installed as a Windows service with:
+//! - Automatic start at boot
+//! - Stop and shutdown requests handled like SIGTERM on Unix
+//! - Working directory and logs under `%ProgramData%\learnerd`
+
+use std::{
+  ffi::OsString,
+  sync::{Condvar, Mutex, OnceLock},
+  thread::JoinHandle,
+};
+
+use windows_service::{
+  define_windows_service,
+  service::{
+    Service, ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl,
+    ServiceExitCode, ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+  },
+  service_control_handler::{self, ServiceControlHandlerResult, ServiceStatusHandle},
+  service_dispatcher,
+  service_manager::{ServiceManager, ServiceManagerAccess},
+};
+
+use super::*;
+
+/// PID file location if `%ProgramData%` isn't set
+pub const DEFAULT_PID_FILE: &str = r"C:\ProgramData\learnerd\learnerd.pid";
+
+/// Working directory for daemon operations if `%ProgramData%` isn't set
+pub const DEFAULT_WORKING_DIR: &str = r"C:\ProgramData\learnerd";
+
+/// Log directory if `%ProgramData%` isn't set
+pub const DEFAULT_LOG_DIR: &str = r"C:\ProgramData\learnerd\logs";
+
+/// Name the service is registered under with the Service Control Manager
+pub const SERVICE_NAME: &str = "learnerd";
+
+/// Returns the daemon's working directory, `learnerd` under `%ProgramData%`.
+///
+/// `%ProgramData%` isn't always on `C:`, so [`DEFAULT_WORKING_DIR`] is only used if it isn't
+/// set. The PID file and log directory go in this directory.
+pub fn program_data_dir() -> PathBuf {
+  std::env::var_os("ProgramData")
+    .map_or_else(|| PathBuf::from(DEFAULT_WORKING_DIR), |dir| PathBuf::from(dir).join("learnerd"))
+}
+
+/// Installs the daemon as a Windows service.
+///
+/// Registers the current executable with the Service Control Manager, to run `daemon start`
+/// automatically at boot.
+///
+/// # Errors
+///
+/// Returns `LearnerdErrors` if:
+/// - Cannot determine current executable path
+/// - The service can't be created, e.g. without administrator rights
+pub fn install_system_daemon(_daemon: &Daemon) -> Result<(), LearnerdErrors> {
+  let manager = ServiceManager::local_computer(
+    None::<&str>,
+    ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+  )?;
+  let info = ServiceInfo {
+    name:             SERVICE_NAME.into(),
+    display_name:     SERVICE_NAME.into(),
+    service_type:     ServiceType::OWN_PROCESS,
+    start_type:       ServiceStartType::AutoStart,
+    error_control:    ServiceErrorControl::Normal,
+    executable_path:  std::env::current_exe()?,
+    launch_arguments: vec!["daemon".into(), "start".into()],
+    dependencies:     Vec::new(),
+    account_name:     None,
+    account_password: None,
+  };
+  let service = manager.create_service(&info, ServiceAccess::CHANGE_CONFIG)?;
+  service.set_description("Academic Paper Management Daemon")?;
+  Ok(())
+}
+
+/// Removes the daemon service registration.
+///
+/// # Errors
+///
+/// Returns `LearnerdErrors` if the service isn't installed or can't be deleted.
+pub fn uninstall_system_daemon() -> Result<(), LearnerdErrors> {
+  open_service(ServiceAccess::DELETE)?.delete()?;
+  Ok(())
+}
+
+/// Stops the daemon running as process `pid`.
+///
+/// If it is the service, the Service Control Manager is asked to stop it, which lets it finish
+/// a sync in progress. A daemon started from a console doesn't take service requests, so its
+/// process is ended instead. A PID that is no longer a learnerd process, left behind by a
+/// daemon that was killed, is logged and otherwise ignored, so the stale PID file is removed.
+///
+/// # Errors
+///
+/// Returns `LearnerdErrors` if the service can't be stopped, or `tasklist` or `taskkill` fails.
+pub fn stop_process(pid: u32) -> Result<(), LearnerdErrors> {
+  if service_pid() == Some(pid) {
+    debug!("Stopping the service running as PID {pid}");
+    open_service(ServiceAccess::STOP)?.stop()?;
+    return Ok(());
+  }
+
+  // PIDs are reused, so make sure this one is still a learnerd before ending it
+  let output = std::process::Command::new("tasklist")
+    .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+    .output()?;
+  let exe = std::env::current_exe()?;
+  let exe = exe.file_name().unwrap_or_default().to_string_lossy();
+  // The first column is the image name, e.g. "learnerd.exe","1234",...
+  let listed = String::from_utf8_lossy(&output.stdout);
+  let image = listed.trim_start().strip_prefix('"').and_then(|line| line.split('"').next());
+  if !image.is_some_and(|image| image.eq_ignore_ascii_case(&exe)) {
+    warn!("No learnerd process has PID {pid}, removing the stale PID file");
+    return Ok(());
+  }
+
+  debug!("Ending the daemon started from a console as PID {pid}");
+  let output =
+    std::process::Command::new("taskkill").args(["/PID", &pid.to_string(), "/F"]).output()?;
+  if output.status.success() {
+    Ok(())
+  } else {
+    Err(LearnerdErrors::Daemon(format!(
+      "taskkill failed: {}",
+      String::from_utf8_lossy(&output.stderr).trim()
+    )))
+  }
+}
+
+/// Finds the PID the service runs as, or `None` if it isn't installed or isn't running.
+fn service_pid() -> Option<u32> {
+  let status =
+    open_service(ServiceAccess::QUERY_STATUS).and_then(|service| Ok(service.query_status()?));
+  match status {
+    Ok(status) => status.process_id.filter(|&pid| pid != 0),
+    Err(e) => {
+      debug!("Not stopping the service: {e}");
+      None
+    },
+  }
+}
+
+/// Displays post-installation instructions and helpful commands.
+///
+/// Shows:
+/// - Service activation steps
+/// - Troubleshooting commands
+/// - Important file paths
+pub fn daemon_install_prompt(daemon: &Daemon) {
+  println!("{} Daemon service installed", style(SUCCESS).green());
+
+  println!(
+    "\n{} To activate the service, from an administrator prompt:",
+    style("Next steps").blue()
+  );
+  println!("   1. Start:    {}", style(format!("sc.exe start {SERVICE_NAME}")).yellow());
+  println!("   2. Verify:   {}", style(format!("sc.exe query {SERVICE_NAME}")).yellow());
+
+  println!("\n{} Troubleshooting commands:", style("Debug").blue());
+  println!(
+    "   View logs:     {}",
+    style(format!("Get-Content -Wait {}\\learnerd.*.log", daemon.log_dir.display())).yellow()
+  );
+  println!("   Check config:  {}", style(format!("sc.exe qc {SERVICE_NAME}")).yellow());
+
+  println!("\n{} Service paths:", style("Configuration").blue());
+  println!("   Working dir: {}", style(daemon.working_dir.display()).yellow());
+  println!("   PID file:    {}", style(daemon.pid_file.display()).yellow());
+  println!("   Log dir:     {}", style(daemon.log_dir.display()).yellow());
+}
+
+/// Opens the installed service with the `access` needed for what is done with it.
+fn open_service(access: ServiceAccess) -> Result<Service, LearnerdErrors> {
+  let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)?;
+  Ok(manager.open_service(SERVICE_NAME, access)?)
+}
+
+/// State shared with the callbacks the Service Control Manager calls, which can't capture any.
+struct ServiceContext {
+  /// Requested when the service is asked to stop
+  shutdown: Shutdown,
+  /// Handle the service reports its status through, once registered
+  handle:   OnceLock<ServiceStatusHandle>,
+  /// Whether the daemon loop has finished, and if so whether it failed
+  finished: Mutex<Option<bool>>,
+  /// Wakes the service main function when the daemon loop finishes
+  done:     Condvar,
+}
+
+/// The service state, set once by [`dispatch_service`].
+static SERVICE: OnceLock<ServiceContext> = OnceLock::new();
+
+define_windows_service!(ffi_service_main, service_main);
+
+/// The service dispatcher, running for as long as the daemon does.
+///
+/// Returned by [`dispatch_service`]; [`ServiceDispatcher::finish`] reports the daemon stopped.
+pub struct ServiceDispatcher {
+  /// Thread blocked in [`service_dispatcher::start`]
+  thread: JoinHandle<()>,
+}
+
+/// Connects to the Service Control Manager, if it started this process, so that service stop
+/// and system shutdown requests ask `shutdown` to stop the daemon loop.
+///
+/// The dispatcher runs on its own thread, as [`service_dispatcher::start`] blocks until the
+/// service stops. It should be started before anything else, as the Service Control Manager
+/// only waits so long for a service to connect. Started from a console instead, the connection
+/// fails at once and the daemon runs as usual, stopped with Ctrl-C or `learnerd daemon stop`.
+pub fn dispatch_service(shutdown: &Shutdown) -> ServiceDispatcher {
+  let context = ServiceContext {
+    shutdown: shutdown.clone(),
+    handle:   OnceLock::new(),
+    finished: Mutex::new(None),
+    done:     Condvar::new(),
+  };
+  if SERVICE.set(context).is_err() {
+    warn!("The service dispatcher was already started");
+  }
+
+  let thread = std::thread::spawn(|| {
+    if let Err(e) = service_dispatcher::start(SERVICE_NAME, ffi_service_main) {
+      debug!("Not running as a service: {e}");
+    }
+  });
+  ServiceDispatcher { thread }
+}
+
+impl ServiceDispatcher {
+  /// Reports the daemon stopped, failed or not, and waits for the dispatcher to return.
+  pub fn finish(self, failed: bool) {
+    if let Some(service) = SERVICE.get() {
+      *service.finished.lock().unwrap_or_else(|e| e.into_inner()) = Some(failed);
+      service.done.notify_all();
+    }
+    if self.thread.join().is_err() {
+      error!("The service dispatcher panicked");
+    }
+  }
+}
+
+/// Runs when the Service Control Manager starts the service.
+///
+/// Registers the control handler, reports the service running, then waits for the daemon loop
+/// to finish before reporting it stopped.
+fn service_main(_arguments: Vec<OsString>) {
+  let Some(service) = SERVICE.get() else { return };
+  let handle = match service_control_handler::register(SERVICE_NAME, control_handler) {
+    Ok(handle) => handle,
+    Err(e) => {
+      error!("Failed to register the service control handler: {e}");
+      return;
+    },
+  };
+  let _ = service.handle.set(handle);
+  set_status(
+    handle,
+    ServiceState::Running,
+    ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+    false,
+  );
+  info!("Running as the {SERVICE_NAME} service");
+
+  let mut finished = service.finished.lock().unwrap_or_else(|e| e.into_inner());
+  while finished.is_none() {
+    finished = service.done.wait(finished).unwrap_or_else(|e| e.into_inner());
+  }
+  set_status(
+    handle,
+    ServiceState::Stopped,
+    ServiceControlAccept::empty(),
+    finished.unwrap_or(false),
+  );
+}
+
+/// Handles requests from the Service Control Manager, asking the daemon loop to stop on a
+/// service stop or system shutdown.
+fn control_handler(control: ServiceControl) -> ServiceControlHandlerResult {
+  match control {
+    ServiceControl::Stop | ServiceControl::Shutdown => {
+      if let Some(service) = SERVICE.get() {
+        info!("Received a service stop request, shutting down");
+        if let Some(&handle) = service.handle.get() {
+          set_status(handle, ServiceState::StopPending, ServiceControlAccept::empty(), false);
+        }
+        service.shutdown.request();
+      }
+      ServiceControlHandlerResult::NoError
+    },
+    ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+    _ => ServiceControlHandlerResult::NotImplemented,
+  }
+}
+
+/// Reports the service's state to the Service Control Manager.
+fn set_status(
+  handle: ServiceStatusHandle,
+  state: ServiceState,
+  controls_accepted: ServiceControlAccept,
+  failed: bool,
+) {
+  let status = ServiceStatus {
+    service_type: ServiceType::OWN_PROCESS,
+    current_state: state,
+    controls_accepted,
+    exit_code: if failed { ServiceExitCode::ServiceSpecific(1) } else { ServiceExitCode::Win32(0) },
+    checkpoint: 0,
+    // Stopping waits for a sync in progress, as `learnerd daemon stop` does on Unix
+    wait_hint: if state == ServiceState::StopPending { STOP_TIMEOUT } else { Duration::ZERO },
+    process_id: None,
+  };
+  if let Err(e) = handle.set_service_status(status) {
+    error!("Failed to report the service status: {e}");
+  }
+}
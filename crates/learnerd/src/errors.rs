@@ -55,11 +55,40 @@ pub enum LearnerdErrors {
   #[error(transparent)]
   Glob(#[from] glob::PatternError),
 
+  /// JSON serialization errors, e.g. when exporting papers
+  #[error(transparent)]
+  Json(#[from] serde_json::Error),
+
   /// Tracing appender initialization error.
   #[error(transparent)]
   TracingInit(#[from] tracing_appender::rolling::InitError),
 
+  /// Errors from the Windows Service Control Manager, e.g. when installing the service
+  #[cfg(target_os = "windows")]
+  #[error(transparent)]
+  Service(#[from] windows_service::Error),
+
   /// Daemon-specific errors
   #[error("Daemon error: {0}")]
   Daemon(String),
+
+  /// A setting given to `config set`, or stored in the database, that can't be used
+  #[error("Invalid setting: {0}")]
+  InvalidConfig(String),
+}
+
+impl LearnerdErrors {
+  /// Returns the process exit code to report for this error.
+  ///
+  /// - `2`: the requested paper or collection could not be found
+  /// - `1`: any other failure
+  pub fn exit_code(&self) -> u8 {
+    match self {
+      LearnerdErrors::Learner(
+        learner::errors::LearnerError::NotFound
+        | learner::errors::LearnerError::CollectionNotFound(_),
+      ) => 2,
+      _ => 1,
+    }
+  }
 }
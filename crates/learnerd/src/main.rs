@@ -29,19 +29,47 @@
 //! The CLI provides colored output and interactive confirmations for destructive
 //! operations. It also supports various verbosity levels for debugging through
 //! the `-v` flag.
+//!
+//! Prompts can be skipped with `--yes` (`-y`, or `--accept-defaults`), which answers each one
+//! with its default. Destructive confirmations default to "no", so deleting or overwriting data
+//! without a prompt additionally requires `--force`.
 
 #![warn(missing_docs, clippy::missing_docs_in_private_items)]
 
-use std::{path::PathBuf, str::FromStr};
+use std::{
+  collections::HashSet,
+  path::{Path, PathBuf},
+  process::ExitCode,
+  str::FromStr,
+  sync::OnceLock,
+  time::{Duration, Instant},
+};
 
-use clap::{builder::ArgAction, Parser, Subcommand};
+use chrono::{NaiveDate, Utc};
+use clap::{builder::ArgAction, Parser, Subcommand, ValueEnum};
 use console::{style, Emoji};
 use errors::LearnerdErrors;
+use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
 use learner::{
-  database::Database,
+  bibtex::parse_bibtex,
+  cache::{Cache, CachingArxivClient, DEFAULT_TTL},
+  clients::{
+    arxiv::{ArxivSearchOptions, ArxivSort},
+    doi::{select_match, CROSSREF_EMAIL_ENV_VAR, CROSSREF_MAILTO_ENV_VAR, DEFAULT_MATCH_THRESHOLD},
+    http,
+    semantic_scholar::lookup_id,
+    ArxivClient, DOIClient, IACRClient, PaperSource, SemanticScholarClient,
+  },
+  database::{Database, Note, PaperSort, PdfStatus, SearchQuery, DEFAULT_DUPLICATE_THRESHOLD},
   errors::LearnerError,
-  paper::{Paper, Source},
+  paper::{
+    to_bibtex_bibliography, to_html_document, to_markdown_document, to_ris_records,
+    validate_filename_template, Paper, PaperGrouping, ReadingStatus, Source,
+  },
+  progress::ProgressSink,
 };
+use serde::Serialize;
+use tokio::task::JoinSet;
 use tracing::{debug, trace};
 use tracing_subscriber::EnvFilter;
 
@@ -81,7 +109,8 @@ struct Cli {
   verbose: u8,
 
   /// Path to the database file. This is where the database will be created or referenced from. If
-  /// not specified, uses the default platform-specific data directory.
+  /// not specified, uses the `LEARNER_DB_PATH` environment variable, or else the default
+  /// platform-specific data directory.
   #[arg(long, short, global = true)]
   path: Option<PathBuf>,
 
@@ -89,9 +118,25 @@ struct Cli {
   #[command(subcommand)]
   command: Commands,
 
-  /// Skip all prompts and accept defaults (mostly for testing)
-  #[arg(long, hide = true, global = true)]
+  /// Skip all prompts and accept the default answer of each one.
+  ///
+  /// Destructive confirmations default to "no", so they are declined unless `--force` is also
+  /// given.
+  #[arg(long, short = 'y', visible_alias = "yes", global = true)]
   accept_defaults: bool,
+
+  /// Proceed with destructive operations (deleting or overwriting data) without confirmation
+  #[arg(long, global = true)]
+  force: bool,
+
+  /// Format of the results printed to stdout, given before the subcommand (e.g. `learnerd
+  /// --output json list`).
+  ///
+  /// `json` applies to `add`, `get`, `search`, `fetch-search`, `list`, `remove` and `stats`: their
+  /// results are printed as JSON, progress messages are left out, and errors are printed to stderr
+  /// as an object with an `error` field.
+  #[arg(long, value_enum, default_value = "pretty")]
+  output: OutputFormat,
 }
 
 /// Available commands for the CLI
@@ -102,55 +147,393 @@ enum Commands {
 
   /// Add a paper to the database by its identifier
   Add {
-    /// Paper identifier (arXiv ID, DOI, or IACR ID)
+    /// Paper identifiers (arXiv ID, DOI, IACR ID, Semantic Scholar ID, or PMID). Each paper
+    /// is fetched and saved independently, so one failure doesn't stop the rest.
     /// Examples: "2301.07041", "10.1145/1327452.1327492"
-    identifier: String,
+    #[arg(required_unless_present_any = ["reference", "from_file"])]
+    identifiers: Vec<String>,
+
+    /// Full reference string to look up on Crossref instead of an identifier
+    /// Example: "Dean, J., Ghemawat, S.: MapReduce: simplified data processing on large
+    /// clusters. CACM 2008"
+    #[arg(long = "ref", conflicts_with = "identifiers")]
+    reference: Option<String>,
+
+    /// File listing papers to add, one identifier per line. Anything after a '#' is a comment.
+    /// Papers are fetched four at a time and summarised at the end.
+    #[arg(long, conflicts_with_all = ["identifiers", "reference"])]
+    from_file: Option<PathBuf>,
 
     /// Skip PDF download prompt
     #[arg(long)]
     no_pdf: bool,
+
+    /// Look up the DOI of the published version on Crossref for arXiv papers whose feed
+    /// doesn't give one
+    #[arg(long)]
+    resolve_doi: bool,
+
+    /// Fetch and show the papers without saving them or downloading their PDFs. Fails if any
+    /// paper can't be fetched, e.g. to check a list of identifiers
+    #[arg(long)]
+    dry_run: bool,
   },
 
-  /// Download the PDF for a given entry, replacing an existing PDF if desired.
+  /// Download the PDF for a given entry, replacing an existing PDF if desired, or the PDFs of
+  /// every paper still missing one
   Download {
-    /// Source system (arxiv, doi, iacr)
-    #[arg(value_enum)]
-    source: Source,
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true, required_unless_present = "all", conflicts_with = "all")]
+    source: Option<Source>,
 
     /// Paper identifier in the source system
     /// Example: "2301.07041" for arXiv
+    #[arg(required_unless_present = "all")]
+    identifier: Option<String>,
+
+    /// Download the PDF of every paper that has a PDF URL but no downloaded PDF
+    #[arg(long)]
+    all: bool,
+
+    /// How many PDFs to download at once with --all
+    #[arg(long, default_value_t = DOWNLOAD_CONCURRENCY, requires = "all")]
+    concurrency: usize,
+  },
+
+  /// Open a paper's PDF with the system's default viewer, offering to download it first if it
+  /// hasn't been
+  Open {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
+    identifier: String,
+
+    /// Print the PDF's path instead of opening it, e.g. to pass it to another viewer
+    #[arg(long)]
+    print_path: bool,
+  },
+
+  /// Find papers related to a paper, as recommended by Semantic Scholar
+  Recommend {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
     identifier: String,
+
+    /// The most papers to show
+    #[arg(long, default_value_t = 10)]
+    limit: usize,
+  },
+
+  /// Refresh papers' metadata from their sources, e.g. to pick up a new arXiv version
+  Update {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true, required_unless_present = "all", conflicts_with = "all")]
+    source: Option<Source>,
+
+    /// Paper identifier in the source system
+    #[arg(required_unless_present = "all")]
+    identifier: Option<String>,
+
+    /// Update every paper in the database
+    #[arg(long)]
+    all: bool,
   },
 
   /// Remove a paper from the database by its source and identifier
   Remove {
-    /// Source system (arxiv, doi, iacr)
-    #[arg(value_enum)]
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
     source: Source,
 
     /// Paper identifier in the source system
     identifier: String,
+
+    /// Also delete the paper's PDF from the PDF directory
+    #[arg(long)]
+    with_pdf: bool,
   },
 
   /// Retrieve and display a paper's details
   Get {
-    /// Source system (arxiv, doi, iacr)
-    #[arg(value_enum)]
-    source: Source,
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true, required_unless_present = "doi")]
+    source: Option<Source>,
 
     /// Paper identifier in the source system
-    identifier: String,
+    #[arg(required_unless_present = "doi")]
+    identifier: Option<String>,
+
+    /// Find the paper by its DOI instead, whichever source it was saved from (e.g. an arXiv
+    /// preprint with a journal DOI)
+    #[arg(long, conflicts_with_all = ["source", "identifier"])]
+    doi: Option<String>,
+
+    /// Show the abstract as the source returned it, before LaTeX and HTML markup was cleaned
+    /// up. JSON output always includes both, as `abstract_text` and `raw_abstract`
+    #[arg(long)]
+    raw: bool,
   },
 
   /// Search papers in the database
   Search {
     /// Search query - supports full text search
+    #[arg(required_unless_present_any = ["author", "tag", "keyword"])]
+    query: Option<String>,
+
+    /// Only show papers with an author whose name contains this text (case-insensitive)
+    #[arg(long)]
+    author: Option<String>,
+
+    /// Only show papers with this tag
+    #[arg(long)]
+    tag: Option<String>,
+
+    /// Only show papers with this keyword, such as an arXiv category (e.g. cs.CR)
+    #[arg(long)]
+    keyword: Option<String>,
+
+    /// Only show papers published on or after this date (YYYY-MM-DD)
+    #[arg(long, value_parser = parse_date)]
+    from: Option<NaiveDate>,
+
+    /// Only show papers published on or before this date (YYYY-MM-DD). Defaults to today when
+    /// only --from is given.
+    #[arg(long, value_parser = parse_date)]
+    to: Option<NaiveDate>,
+
+    /// Only show papers with this reading status: unread, reading, or read
+    #[arg(long)]
+    status: Option<ReadingStatus>,
+
+    /// Maximum number of papers to show, and with --live, to take from each service
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// Also search arXiv, the IACR ePrint Archive, and Crossref for the query, showing the
+    /// papers not already in the database as each service answers
+    #[arg(long, requires = "query")]
+    live: bool,
+  },
+
+  /// Show the papers by an author, or every author if none is named
+  Author {
+    /// The author's full name (e.g. "jens groth"), matched regardless of case, accents, and
+    /// spacing
+    name: Option<String>,
+  },
+
+  /// Search arXiv or Crossref for papers, optionally saving the matches to the database
+  FetchSearch {
+    /// Words to look for (e.g. "zero knowledge proofs"), or a query in arXiv's own syntax
+    /// (e.g. "ti:snark AND au:groth")
     query: String,
+
+    /// Where to search: arxiv, or doi to look up titles on Crossref
+    #[arg(long, value_enum, ignore_case = true, default_value = "arxiv")]
+    source: SearchSource,
+
+    /// Maximum number of papers to fetch
+    #[arg(long, default_value_t = 10)]
+    max: usize,
+
+    /// Number of matching papers to skip, for fetching later pages
+    #[arg(long, default_value_t = 0)]
+    start: usize,
+
+    /// Order of the arXiv results: relevance, submitted (newest first), or updated. Crossref
+    /// results are always ordered by relevance.
+    #[arg(long, default_value = "relevance")]
+    sort: ArxivSort,
+
+    /// Save the matching papers to the database
+    #[arg(long)]
+    add: bool,
+
+    /// Choose one of the matching papers to save to the database (with --accept-defaults, the
+    /// best match)
+    #[arg(long, conflicts_with = "add")]
+    pick: bool,
+  },
+
+  /// List the papers in the database, one page at a time
+  List {
+    /// Field to sort by: date (newest first), title, or source
+    #[arg(long, default_value = "date")]
+    sort: PaperSort,
+
+    /// Reverse the sort order (e.g. oldest first when sorting by date)
+    #[arg(long)]
+    reverse: bool,
+
+    /// Maximum number of papers to show
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+
+    /// Number of papers to skip before the first one shown
+    #[arg(long, default_value_t = 0)]
+    offset: usize,
+
+    /// Only list papers with this tag
+    #[arg(long)]
+    tag: Option<String>,
+
+    /// Only list papers with this reading status: unread, reading, or read
+    #[arg(long, visible_alias = "status", conflicts_with = "tag")]
+    filter: Option<ReadingStatus>,
+  },
+
+  /// List the papers most recently saved to the database, newest first
+  Recent {
+    /// Maximum number of papers to show
+    #[arg(long, default_value_t = 10)]
+    limit: usize,
+  },
+
+  /// Export papers for backup or sharing, to a file or stdout
+  Export {
+    /// Format of the export
+    #[arg(value_enum)]
+    format: ExportFormat,
+
+    /// File to write to. If not specified, writes to stdout
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+
+    /// Only export papers matching this full text search query
+    #[arg(long, short, conflicts_with = "source")]
+    query: Option<String>,
+
+    /// Only export the papers in this collection
+    #[arg(long, conflicts_with_all = ["query", "source"])]
+    collection: Option<String>,
+
+    /// Only export the paper from this source system (arxiv, doi, iacr, semanticscholar, pubmed,
+    /// biorxiv, openalex, ssrn)
+    #[arg(value_enum, ignore_case = true, requires = "identifier")]
+    source: Option<Source>,
+
+    /// Only export the paper with this identifier in the source system
+    identifier: Option<String>,
+
+    /// Order papers by this field: date (newest first), title, or source. Otherwise they are
+    /// exported in the order they were added, or by relevance with --query
+    #[arg(long)]
+    sort: Option<PaperSort>,
+
+    /// Reverse the sort order (e.g. oldest first when sorting by date)
+    #[arg(long, requires = "sort")]
+    reverse: bool,
+
+    /// How to group papers under headings in Markdown and HTML: source, tag, or year
+    #[arg(long, default_value = "source")]
+    group_by: PaperGrouping,
+  },
+
+  /// Import papers from a bibliography file, fetching each one from its source
+  Import {
+    /// Format of the file
+    #[arg(long, value_enum, default_value = "bibtex")]
+    format: ImportFormat,
+
+    /// File to import
+    file: PathBuf,
+  },
+
+  /// Group papers into named collections, e.g. for a reading list or a chapter
+  Collection {
+    /// The collection operation to perform
+    #[command(subcommand)]
+    cmd: CollectionCommands,
+  },
+
+  /// Attach, detach, or show tags on a paper
+  Tag {
+    /// The tag operation to perform
+    #[command(subcommand)]
+    cmd: TagCommands,
+  },
+
+  /// Set or show how far along reading a paper is
+  Status {
+    /// The reading status operation to perform
+    #[command(subcommand)]
+    cmd: StatusCommands,
+  },
+
+  /// Mark a paper as unread, reading, or read, e.g. `mark read arxiv 2301.07041`
+  Mark {
+    /// The new status: unread, reading, or read
+    status: ReadingStatus,
+
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
+    identifier: String,
+  },
+
+  /// Add, list, or delete notes on a paper
+  Note {
+    /// The note operation to perform
+    #[command(subcommand)]
+    cmd: NoteCommands,
   },
 
   /// Removes the entire database after confirmation
   Clean,
 
+  /// Rebuild the full-text search index from the stored papers
+  Reindex,
+
+  /// Forget downloaded PDFs that no longer exist on disk and compact the database
+  Gc,
+
+  /// Show how many papers and authors the database holds, by source, publication year, and
+  /// month added
+  Stats,
+
+  /// Check that every downloaded PDF still exists and is a complete PDF
+  VerifyPdfs,
+
+  /// Find papers with nearly the same title, such as a preprint and its published version, and
+  /// choose which to keep
+  Deduplicate {
+    /// How alike two titles must be to count as duplicates, from 0 to 1
+    #[arg(long, default_value_t = DEFAULT_DUPLICATE_THRESHOLD, value_parser = parse_similarity)]
+    threshold: f64,
+
+    /// List the likely duplicates without asking which to keep
+    #[arg(long)]
+    dry_run: bool,
+  },
+
+  /// Show or change settings, such as where PDFs are stored and how they are named
+  Config {
+    /// The config operation to perform
+    #[command(subcommand)]
+    cmd: ConfigCommands,
+  },
+
+  /// Manage the cache of recently fetched arXiv papers
+  Cache {
+    /// The cache operation to perform
+    #[command(subcommand)]
+    cmd: CacheCommands,
+  },
+
   /// Manage the learnerd daemon
   Daemon {
     /// The set of commands specifically for managing the [`Daemon`].
@@ -159,6 +542,270 @@ enum Commands {
   },
 }
 
+/// Operations of the `tag` command
+#[derive(Subcommand)]
+enum TagCommands {
+  /// Attach one or more tags to a paper
+  Add {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
+    identifier: String,
+
+    /// Tags to attach, e.g. "to-read" or "important"
+    #[arg(required = true)]
+    tags: Vec<String>,
+  },
+
+  /// Detach a tag from a paper
+  Remove {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
+    identifier: String,
+
+    /// Tag to detach
+    tag: String,
+  },
+
+  /// Show the tags attached to a paper
+  List {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
+    identifier: String,
+  },
+}
+
+/// Operations of the `status` command
+#[derive(Subcommand)]
+enum StatusCommands {
+  /// Mark a paper as unread, reading, or read
+  Set {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
+    identifier: String,
+
+    /// The new status: unread, reading, or read
+    status: ReadingStatus,
+  },
+
+  /// Show how far along reading a paper is, and since when
+  Get {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
+    identifier: String,
+  },
+}
+
+/// Operations of the `note` command
+#[derive(Subcommand)]
+enum NoteCommands {
+  /// Add a note to a paper
+  Add {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
+    identifier: String,
+
+    /// Text of the note, in Markdown, e.g. "Nice construction in §4"
+    content: String,
+  },
+
+  /// Show the notes on a paper, oldest first
+  List {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
+    identifier: String,
+  },
+
+  /// Delete a note
+  Delete {
+    /// Note ID, as shown by `note list`
+    id: i64,
+  },
+}
+
+/// Operations of the `collection` command
+#[derive(Subcommand)]
+enum CollectionCommands {
+  /// Create an empty collection
+  Create {
+    /// Name of the collection, e.g. "Cryptography"
+    name: String,
+
+    /// What the collection is for
+    #[arg(long, short)]
+    description: Option<String>,
+  },
+
+  /// Add a paper to a collection
+  Add {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
+    identifier: String,
+
+    /// Name of the collection
+    collection: String,
+  },
+
+  /// Remove a paper from a collection, keeping it in the database
+  Remove {
+    /// Source system (arxiv, doi, iacr, semanticscholar, pubmed, biorxiv,
+    /// openalex, ssrn)
+    #[arg(value_enum, ignore_case = true)]
+    source: Source,
+
+    /// Paper identifier in the source system
+    identifier: String,
+
+    /// Name of the collection
+    collection: String,
+  },
+
+  /// Show the papers in a collection, or every collection if none is named
+  List {
+    /// Name of the collection
+    collection: Option<String>,
+  },
+}
+
+/// Operations of the `config` command
+#[derive(Subcommand)]
+enum ConfigCommands {
+  /// Change a setting
+  Set {
+    /// The setting to change
+    #[arg(value_enum)]
+    key: ConfigKey,
+
+    /// The new value, e.g. "{year}_{first_author}_{title}" for pdf_filename_template
+    value: String,
+  },
+
+  /// Show the current value of a setting
+  Get {
+    /// The setting to show
+    #[arg(value_enum)]
+    key: ConfigKey,
+  },
+}
+
+/// Operations of the `cache` command
+#[derive(Subcommand)]
+enum CacheCommands {
+  /// Remove every cached paper, so the next fetch of each reaches its source
+  Clear,
+}
+
+/// Settings managed by the `config` command
+#[derive(Clone, Copy, ValueEnum)]
+#[value(rename_all = "snake_case")]
+enum ConfigKey {
+  /// The directory PDFs are downloaded to
+  PdfDir,
+  /// How downloaded PDFs are named, from the placeholders {title}, {first_author}, {year},
+  /// {source_id} (or {id}) and {source}
+  PdfFilenameTemplate,
+  /// The contact email sent with Crossref requests
+  CrossrefEmail,
+  /// How many seconds fetched arXiv papers are cached for, 0 to turn the cache off
+  CacheTtlSecs,
+  /// How many seconds the daemon waits between checks for new papers
+  DaemonPollIntervalSecs,
+}
+
+impl ConfigKey {
+  /// The key the setting is stored under in the database
+  fn as_str(self) -> &'static str {
+    match self {
+      ConfigKey::PdfDir => "pdf_dir",
+      ConfigKey::PdfFilenameTemplate => "pdf_filename_template",
+      ConfigKey::CrossrefEmail => "crossref_email",
+      ConfigKey::CacheTtlSecs => "cache_ttl_secs",
+      ConfigKey::DaemonPollIntervalSecs => POLL_INTERVAL_CONFIG_KEY,
+    }
+  }
+}
+
+/// Formats in which commands print their results
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+  /// Colored text for reading in a terminal
+  Pretty,
+  /// JSON, for scripts
+  Json,
+}
+
+/// Formats supported by the `export` command
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+  /// The papers as a JSON array, for backups and scripting
+  Json,
+  /// BibTeX entries, for LaTeX workflows
+  Bibtex,
+  /// RIS records, for reference managers such as Zotero and EndNote
+  Ris,
+  /// A Markdown document, e.g. for publishing a reading list
+  Markdown,
+  /// A self-contained HTML page, e.g. for publishing a reading list
+  Html,
+}
+
+/// Where the `fetch-search` command searches for papers
+#[derive(Clone, Copy, ValueEnum)]
+enum SearchSource {
+  /// arXiv's search API
+  Arxiv,
+  /// Crossref's bibliographic search, matching titles and references to DOIs
+  Doi,
+}
+
+impl SearchSource {
+  /// The name of the service searched, for messages.
+  fn service(&self) -> &'static str {
+    match self {
+      SearchSource::Arxiv => "arXiv",
+      SearchSource::Doi => "Crossref",
+    }
+  }
+}
+
+/// Formats supported by the `import` command
+#[derive(Clone, Copy, ValueEnum)]
+enum ImportFormat {
+  /// BibTeX entries; papers are fetched by each entry's DOI, arXiv eprint, or paper URL
+  Bibtex,
+}
+
 /// Configures the logging system based on the verbosity level
 ///
 /// # Arguments
@@ -180,7 +827,9 @@ fn setup_logging(verbosity: u8) {
 
   let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(filter));
 
+  // Log to stderr so that log lines never end up in JSON output
   tracing_subscriber::fmt()
+    .with_writer(std::io::stderr)
     .with_env_filter(filter)
     .with_file(true)
     .with_line_number(true)
@@ -189,143 +838,2052 @@ fn setup_logging(verbosity: u8) {
     .init();
 }
 
-/// Entry point for the learnerd CLI application
+/// Adapts progress reports from long-running library operations to a terminal progress bar.
+struct ProgressBarSink {
+  /// The bar being drawn to the terminal
+  bar: ProgressBar,
+}
+
+impl ProgressBarSink {
+  /// Creates a sink drawing a new progress bar.
+  fn new() -> Self {
+    let bar = ProgressBar::new(0);
+    bar.set_style(
+      ProgressStyle::with_template("{spinner:.cyan} {msg:24} [{bar:40.cyan/blue}] {pos}/{len}")
+        .unwrap_or_else(|_| ProgressStyle::default_bar())
+        .progress_chars("=> "),
+    );
+    bar.enable_steady_tick(std::time::Duration::from_millis(100));
+    Self { bar }
+  }
+
+  /// Removes the progress bar from the terminal.
+  fn finish(&self) { self.bar.finish_and_clear(); }
+}
+
+impl ProgressSink for ProgressBarSink {
+  fn on_progress(&self, done: usize, total: usize, stage: &str) {
+    self.bar.set_message(stage.to_string());
+    self.bar.set_length(total as u64);
+    self.bar.set_position(done as u64);
+  }
+}
+
+/// Downloads a saved paper's PDF into `dir` and records where, drawing a progress bar of the
+/// bytes received if `show_progress`.
 ///
-/// Handles command line argument parsing, sets up logging, and executes
-/// the requested command. All commands provide colored output and
-/// interactive confirmations for destructive operations.
+/// The bar is drawn to stderr, and not at all when it isn't a terminal.
+async fn download_pdf_with_bar(
+  paper: &Paper,
+  db: &Database,
+  dir: PathBuf,
+  show_progress: bool,
+) -> Result<PathBuf, LearnerError> {
+  if !show_progress {
+    return paper.download_and_record_pdf(db, dir).await;
+  }
+
+  // A spinner until the size is known, then a bar
+  let bar = ProgressBar::no_length();
+  bar.set_style(
+    ProgressStyle::with_template("{spinner:.cyan} {bytes} ({bytes_per_sec})")
+      .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+  );
+  bar.enable_steady_tick(Duration::from_millis(100));
+  let result = paper
+    .download_and_record_pdf_with_progress(db, dir, |downloaded, total| {
+      if let (Some(total), None) = (total, bar.length()) {
+        bar.set_length(total);
+        bar.set_style(
+          ProgressStyle::with_template(
+            "{spinner:.cyan} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+          )
+          .unwrap_or_else(|_| ProgressStyle::default_bar())
+          .progress_chars("=> "),
+        );
+      }
+      bar.set_position(downloaded);
+    })
+    .await;
+  bar.finish_and_clear();
+  result
+}
+
+/// Asks a yes/no question, answering with `default` when prompts are skipped.
 ///
-/// # Errors
+/// # Arguments
 ///
-/// Returns `LearnerdErrors` for various failure conditions including:
-/// - Database operations failures
-/// - Paper fetching failures
-/// - File system errors
-/// - User interaction errors
-#[tokio::main]
-async fn main() -> Result<(), LearnerdErrors> {
-  let cli = Cli::parse();
-  if let Commands::Daemon { .. } = cli.command {
+/// * `prompt` - The question to ask
+/// * `default` - The answer used when `accept_defaults` is set, and preselected otherwise
+/// * `accept_defaults` - Whether to skip the prompt
+fn confirm(prompt: &str, default: bool, accept_defaults: bool) -> Result<bool, LearnerdErrors> {
+  if accept_defaults {
+    return Ok(default);
+  }
+  Ok(dialoguer::Confirm::new().with_prompt(prompt).default(default).interact()?)
+}
+
+/// Environment variable giving the database path used when `--path` isn't.
+const DB_PATH_ENV_VAR: &str = "LEARNER_DB_PATH";
+
+/// Environment variable giving the directory PDFs are stored in, in place of the database's
+/// `pdf_dir` setting.
+const PDF_DIR_ENV_VAR: &str = "LEARNER_PDF_DIR";
+
+/// The database path given by `--path`, or else by the [`DB_PATH_ENV_VAR`] environment variable.
+fn configured_db_path(path: Option<PathBuf>) -> Option<PathBuf> {
+  path.or_else(|| std::env::var_os(DB_PATH_ENV_VAR).filter(|p| !p.is_empty()).map(PathBuf::from))
+}
+
+/// Resolves the database to use: `--path`, then the [`DB_PATH_ENV_VAR`] environment variable,
+/// then [`Database::default_path`]. When falling back to the default, says so if `announce`.
+fn resolve_db_path(path: Option<PathBuf>, announce: bool) -> PathBuf {
+  configured_db_path(path).unwrap_or_else(|| {
+    let default_path = Database::default_path();
+    if announce {
+      println!(
+        "{} Using default database path: {}",
+        style(BOOKS).cyan(),
+        style(default_path.display()).yellow()
+      );
+    }
+    default_path
+  })
+}
+
+/// The directory PDFs are stored in: the [`PDF_DIR_ENV_VAR`] environment variable if set, or
+/// else the database's `pdf_dir` setting.
+async fn pdf_dir(db: &Database) -> Result<PathBuf, LearnerdErrors> {
+  match std::env::var_os(PDF_DIR_ENV_VAR).filter(|dir| !dir.is_empty()) {
+    Some(dir) => Ok(PathBuf::from(dir)),
+    None => Ok(db.get_pdf_dir().await?),
+  }
+}
+
+/// Sends the contact email saved by `learnerd init` with Crossref requests, unless the
+/// [`CROSSREF_EMAIL_ENV_VAR`] or [`CROSSREF_MAILTO_ENV_VAR`] environment variable already gives
+/// one.
+async fn use_crossref_email(db: &Database) -> Result<(), LearnerdErrors> {
+  if std::env::var_os(CROSSREF_EMAIL_ENV_VAR).is_none()
+    && std::env::var_os(CROSSREF_MAILTO_ENV_VAR).is_none()
+  {
+    if let Some(email) = db.get_config("crossref_email").await? {
+      std::env::set_var(CROSSREF_EMAIL_ENV_VAR, email);
+    }
+  }
+  Ok(())
+}
+
+/// The arXiv client papers are fetched through, once [`use_cache`] has set it up.
+static ARXIV_CACHE: OnceLock<CachingArxivClient> = OnceLock::new();
+
+/// The directory fetched papers are cached in, `cache/` next to the database at `path`.
+fn cache_dir(path: &Path) -> PathBuf { path.parent().unwrap_or(Path::new(".")).join("cache") }
+
+/// Caches arXiv papers fetched by [`fetch_paper`] next to the database at `path`, for as long
+/// as the `cache_ttl_secs` setting says, or an hour.
+async fn use_cache(db: &Database, path: &Path) -> Result<(), LearnerdErrors> {
+  let ttl = match db.get_config(ConfigKey::CacheTtlSecs.as_str()).await? {
+    Some(secs) => Duration::from_secs(parse_ttl(&secs)?),
+    None => DEFAULT_TTL,
+  };
+  let _ = ARXIV_CACHE.set(CachingArxivClient::new(Cache::new(cache_dir(path)).with_ttl(ttl)));
+  Ok(())
+}
+
+/// Parses a `cache_ttl_secs` setting.
+fn parse_ttl(secs: &str) -> Result<u64, LearnerdErrors> {
+  secs.trim().parse().map_err(|_| {
+    LearnerdErrors::InvalidConfig(format!("cache_ttl_secs must be a number of seconds: {secs}"))
+  })
+}
+
+/// Fetches a paper as [`Paper::new`] does, answering arXiv papers from the cache set up by
+/// [`use_cache`] when they were fetched recently.
+async fn fetch_paper(identifier: &str) -> Result<Paper, LearnerError> {
+  match ARXIV_CACHE.get().and_then(|arxiv| Some((arxiv, arxiv.matches(identifier)?))) {
+    Some((arxiv, id)) => arxiv.fetch_paper(&id).await,
+    None => Paper::new(identifier).await,
+  }
+}
+
+/// Confirms an operation that deletes or overwrites data.
+///
+/// With `force` the operation proceeds without prompting. Otherwise the default answer is
+/// always "no", so `accept_defaults` declines. Interactively, the user must confirm and then
+/// type `keyword` (e.g. `DELETE`) to proceed.
+///
+/// # Arguments
+///
+/// * `prompt` - The yes/no question to ask first
+/// * `keyword` - The word that must be typed to confirm
+/// * `accept_defaults` - Whether to skip prompts
+/// * `force` - Whether destructive operations are pre-approved
+fn confirm_destructive(
+  prompt: &str,
+  keyword: &str,
+  accept_defaults: bool,
+  force: bool,
+) -> Result<bool, LearnerdErrors> {
+  if force {
+    return Ok(true);
+  }
+  if accept_defaults {
+    println!(
+      "{} Skipping destructive operation, pass {} to proceed without confirmation",
+      style("ℹ").blue(),
+      style("--force").yellow()
+    );
+    return Ok(false);
+  }
+
+  if !dialoguer::Confirm::new()
+    .with_prompt(prompt)
+    .default(false)
+    .wait_for_newline(true)
+    .interact()?
+  {
+    return Ok(false);
+  }
+
+  let input = dialoguer::Input::<String>::new()
+    .with_prompt(format!("{} Type {} to confirm", style("⚠️").red(), style(keyword).red().bold()))
+    .interact_text()?;
+  Ok(input == keyword)
+}
+
+/// Where a paper's PDF is, or would be once downloaded.
+///
+/// This is where the PDF was recorded as downloaded to, falling back to its name under the
+/// configured `pdf_dir` and `pdf_filename_template` for PDFs downloaded before their location was
+/// recorded, or not downloaded yet.
+async fn pdf_location(db: &Database, paper: &Paper) -> Result<PathBuf, LearnerdErrors> {
+  if let Some(pdf_path) = &paper.pdf_path {
+    return Ok(pdf_path.clone());
+  }
+  let filename = paper.format_filename(&db.get_pdf_filename_template().await?, Some(50));
+  Ok(pdf_dir(db).await?.join(format!("{filename}.pdf")))
+}
+
+/// Opens `path` with the system's default application for it, as `open` does on macOS,
+/// `start` on Windows and `xdg-open` elsewhere.
+fn open_with_system_viewer(path: &Path) -> std::io::Result<()> {
+  let mut command = if cfg!(target_os = "macos") {
+    std::process::Command::new("open")
+  } else if cfg!(windows) {
+    let mut command = std::process::Command::new("cmd");
+    // The empty argument is the window title `start` expects before a quoted path
+    command.args(["/C", "start", ""]);
+    command
   } else {
-    setup_logging(cli.verbose);
+    std::process::Command::new("xdg-open")
+  };
+
+  let status = command.arg(path).status()?;
+  if status.success() {
+    Ok(())
+  } else {
+    Err(std::io::Error::other(format!("the viewer exited with {status}")))
+  }
+}
+
+/// Prints `value` to stdout as pretty-printed JSON.
+fn print_json(value: &impl Serialize) -> Result<(), LearnerdErrors> {
+  println!("{}", serde_json::to_string_pretty(value)?);
+  Ok(())
+}
+
+/// Prints a paper's details, as `get` shows them.
+///
+/// # Arguments
+///
+/// * `paper` - The paper to show
+/// * `raw` - Whether to show the abstract as the source returned it
+fn print_paper_details(paper: &Paper, raw: bool) {
+  println!("   {} {}", style("Title:").green().bold(), style(&paper.title).white());
+  println!(
+    "   {} {}",
+    style("Authors:").green().bold(),
+    style(paper.authors.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", ")).white()
+  );
+  let abstract_text = match &paper.raw_abstract {
+    Some(raw_abstract) if raw => raw_abstract,
+    _ => &paper.abstract_text,
+  };
+  println!("   {} {}", style("Abstract:").green().bold(), style(abstract_text).white());
+  println!("   {} {}", style("Published:").green().bold(), style(&paper.publication_date).white());
+  if let Some(url) = &paper.pdf_url {
+    println!("   {} {}", style("PDF URL:").green().bold(), style(url).blue().underlined());
+  }
+  if let Some(doi) = &paper.doi {
+    println!("   {} {}", style("DOI:").green().bold(), style(doi).blue().underlined());
+  }
+  if !paper.categories.is_empty() {
+    println!(
+      "   {} {}",
+      style("Categories:").green().bold(),
+      style(paper.categories.join(", ")).white()
+    );
+  }
+  // arXiv keywords are its categories, so don't repeat them
+  if !paper.keywords.is_empty() && paper.keywords != paper.categories {
+    println!(
+      "   {} {}",
+      style("Keywords:").green().bold(),
+      style(paper.keywords.join(", ")).white()
+    );
+  }
+  if !paper.tags.is_empty() {
+    println!("   {} {}", style("Tags:").green().bold(), style(paper.tags.join(", ")).cyan());
+  }
+  println!("   {} {}", style("Status:").green().bold(), style_status(paper.reading_status));
+  match &paper.pdf_path {
+    Some(pdf_path) if pdf_path.exists() => println!(
+      "   {} downloaded at {}",
+      style("PDF:").green().bold(),
+      style(pdf_path.display()).yellow()
+    ),
+    Some(pdf_path) => println!(
+      "   {} missing, was downloaded to {}",
+      style("PDF:").green().bold(),
+      style(pdf_path.display()).yellow()
+    ),
+    None if paper.pdf_url.is_some() =>
+      println!("   {} not downloaded", style("PDF:").green().bold()),
+    None => {},
+  }
+}
+
+/// Prints a numbered one-paper summary, as used in search results and listings.
+///
+/// # Arguments
+///
+/// * `number` - The position shown before the title
+/// * `paper` - The paper to summarize
+fn print_paper_summary(number: usize, paper: &Paper) {
+  debug!("Paper details: {:?}", paper);
+  println!("\n{}. {}", style(number).yellow(), style(&paper.title).white().bold());
+
+  let authors = paper.authors.iter().map(|a| a.name.as_str()).collect::<Vec<_>>();
+
+  let author_display = if authors.is_empty() {
+    style("No authors listed").red().italic().to_string()
+  } else {
+    style(authors.join(", ")).white().to_string()
+  };
+
+  println!("   {} {}", style("Authors:").green(), author_display);
+
+  if let Some(doi) = &paper.doi {
+    println!("   {} {}", style("DOI:").green(), style(doi).blue().underlined());
+  }
+
+  println!(
+    "   {} {} {}",
+    style("Source:").green(),
+    style(&paper.source).cyan(),
+    style(&paper.source_identifier).yellow()
+  );
+
+  println!("   {} {}", style("Status:").green(), style_status(paper.reading_status));
+
+  if let Some(added_at) = paper.added_at {
+    println!("   {} {}", style("Added:").green(), style(added_at.format("%Y-%m-%d %H:%M")).white());
+  }
+
+  // Show a preview of the abstract
+  if !paper.abstract_text.is_empty() {
+    let preview = paper.abstract_text.chars().take(100).collect::<String>();
+    let preview = if paper.abstract_text.len() > 100 { format!("{}...", preview) } else { preview };
+    println!("   {} {}", style("Abstract:").green(), style(preview).white().italic());
+  }
+}
+
+/// Prints notes with their IDs and when they were added, indenting their text.
+fn print_notes(notes: &[Note]) {
+  for note in notes {
+    println!(
+      "   {} {}",
+      style(format!("#{}", note.id)).cyan(),
+      style(note.created_at.format("%Y-%m-%d %H:%M")).dim()
+    );
+    for line in note.content.lines() {
+      println!("      {}", style(line).white());
+    }
+  }
+}
+
+/// Formats a reading status with a marker, so unread papers stand out in listings.
+fn style_status(status: ReadingStatus) -> String {
+  match status {
+    ReadingStatus::Unread => style(format!("○ {status}")).yellow().to_string(),
+    ReadingStatus::Reading => style(format!("◐ {status}")).cyan().to_string(),
+    ReadingStatus::Read => style(format!("● {status}")).green().to_string(),
+  }
+}
+
+/// What happened to a paper passed to `learnerd add`.
+enum AddOutcome {
+  /// The paper was fetched and saved
+  Saved,
+  /// The paper was fetched but is already in the database
+  Duplicate,
+}
+
+impl AddOutcome {
+  /// The name of the outcome in JSON output.
+  fn as_str(&self) -> &'static str {
+    match self {
+      AddOutcome::Saved => "saved",
+      AddOutcome::Duplicate => "duplicate",
+    }
+  }
+}
+
+/// Fills in the DOI of an arXiv paper's published version from Crossref, for `--resolve-doi`,
+/// if the arXiv feed didn't give one. Not finding one, or failing to ask, leaves the paper as
+/// it is.
+async fn resolve_published_doi(paper: &mut Paper, pretty: bool) {
+  if paper.source != Source::Arxiv || paper.doi.is_some() {
+    return;
+  }
+  match DOIClient::new().find_published_doi(paper).await {
+    Ok(Some(doi)) => {
+      if pretty {
+        println!("   {} {}", style("Published DOI:").green().bold(), style(&doi).blue());
+      }
+      paper.doi = Some(doi);
+    },
+    Ok(None) if pretty =>
+      println!("   {} No published version found on Crossref", style("ℹ").blue()),
+    Err(e) if pretty => println!(
+      "   {} Failed to look up a published DOI: {}",
+      style(WARNING).yellow(),
+      style(e.to_string()).red()
+    ),
+    result => debug!("Published DOI lookup result: {:?}", result),
+  }
+}
+
+/// Fetches a single paper, saves it to `db`, and offers to download its PDF.
+///
+/// In [`OutputFormat::Pretty`] mode progress is printed as it goes, so that when adding several
+/// papers each one's result is visible even if a later one fails. In [`OutputFormat::Json`] mode
+/// nothing is printed and the caller reports the returned paper.
+///
+/// # Errors
+///
+/// Returns an error if the paper cannot be fetched or saved. Failing to download the PDF is
+/// reported but not treated as an error.
+async fn add_paper(
+  db: &Database,
+  identifier: &str,
+  no_pdf: bool,
+  resolve_doi: bool,
+  accept_defaults: bool,
+  force: bool,
+  output: OutputFormat,
+) -> Result<(AddOutcome, Paper), LearnerdErrors> {
+  let pretty = output == OutputFormat::Pretty;
+  if pretty {
+    println!("{} Fetching paper: {}", style(LOOKING_GLASS).cyan(), style(identifier).yellow());
+  }
+
+  let mut paper = fetch_paper(identifier).await?;
+  debug!("Paper details: {:?}", paper);
+
+  if pretty {
+    println!("\n{} Found paper:", style(SUCCESS).green());
+    println!("   {} {}", style("Title:").green().bold(), style(&paper.title).white());
+    println!(
+      "   {} {}",
+      style("Authors:").green().bold(),
+      style(paper.authors.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", ")).white()
+    );
+  }
+  if resolve_doi {
+    resolve_published_doi(&mut paper, pretty).await;
+  }
+
+  let outcome = match paper.save(db).await {
+    Ok(id) => {
+      if pretty {
+        println!("\n{} Saved paper with ID: {}", style(SAVE).green(), style(id).yellow());
+      }
+
+      // Handle PDF download for newly added paper
+      if paper.pdf_url.is_some() && !no_pdf {
+        if confirm("Download PDF?", true, accept_defaults)? {
+          if pretty {
+            println!("{} Downloading PDF...", style(LOOKING_GLASS).cyan());
+          }
+
+          let pdf_dir = pdf_dir(db).await?;
+          std::fs::create_dir_all(&pdf_dir)?;
+
+          match download_pdf_with_bar(&paper, db, pdf_dir, pretty).await {
+            Ok(_) if pretty => {
+              println!("{} PDF downloaded successfully!", style(SUCCESS).green());
+            },
+            Err(e) if pretty => {
+              println!(
+                "{} Failed to download PDF: {}",
+                style(WARNING).yellow(),
+                style(e.to_string()).red()
+              );
+              println!(
+                "   {} You can try downloading it later using: {} {} {}",
+                style("Tip:").blue(),
+                style("learnerd download").yellow(),
+                style(&paper.source.to_string()).cyan(),
+                style(&paper.source_identifier).yellow(),
+              );
+            },
+            result => debug!("PDF download result: {:?}", result),
+          }
+        }
+      } else if paper.pdf_url.is_none() && pretty {
+        println!("\n{} No PDF URL available for this paper", style(WARNING).yellow());
+      }
+      AddOutcome::Saved
+    },
+    Err(e) if e.is_duplicate_error() => {
+      if pretty {
+        match &e {
+          LearnerError::Duplicate(saved_as) => println!(
+            "\n{} This paper is already in your database as {}",
+            style("ℹ").blue(),
+            style(saved_as).yellow()
+          ),
+          _ => println!("\n{} This paper is already in your database", style("ℹ").blue()),
+        }
+      }
+      // The copy saved under another source is the one whose PDF to check
+      if let (LearnerError::Duplicate(_), Some(doi)) = (&e, &paper.doi) {
+        if let Some(saved) = db.get_paper_by_doi(doi).await? {
+          paper = saved;
+        }
+      }
+
+      // Check existing PDF status
+      if paper.pdf_url.is_some() && !no_pdf {
+        if let Ok(pdf_dir) = pdf_dir(db).await {
+          // Prefer where the PDF was recorded, which holds even if file naming has changed
+          let stored = db.get_paper_by_source_id(&paper.source, &paper.source_identifier).await?;
+          let template = db.get_pdf_filename_template().await?;
+          let pdf_path = stored.and_then(|stored| stored.pdf_path).unwrap_or_else(|| {
+            pdf_dir.join(format!("{}.pdf", paper.format_filename(&template, Some(50))))
+          });
+
+          let should_download = if pdf_path.exists() {
+            if pretty {
+              println!(
+                "   {} PDF exists at: {}",
+                style("📄").cyan(),
+                style(pdf_path.display()).yellow()
+              );
+            }
+
+            force
+              || confirm(
+                "Download fresh copy? (This will overwrite the existing file)",
+                false,
+                accept_defaults,
+              )?
+          } else {
+            confirm("PDF not found. Download it now?", true, accept_defaults)?
+          };
+
+          if should_download {
+            if pretty && pdf_path.exists() {
+              println!("{} Downloading fresh copy of PDF...", style(LOOKING_GLASS).cyan());
+            } else if pretty {
+              println!("{} Downloading PDF...", style(LOOKING_GLASS).cyan());
+            }
+            match download_pdf_with_bar(&paper, db, pdf_dir, pretty).await {
+              Ok(_) if pretty =>
+                println!("{} PDF downloaded successfully!", style(SUCCESS).green()),
+              Err(e) if pretty => println!(
+                "{} Failed to download PDF: {}",
+                style(WARNING).yellow(),
+                style(e.to_string()).red()
+              ),
+              result => debug!("PDF download result: {:?}", result),
+            }
+          }
+        }
+      }
+      AddOutcome::Duplicate
+    },
+    Err(e) => return Err(LearnerdErrors::Learner(e)),
+  };
+
+  Ok((outcome, paper))
+}
+
+/// Fetches papers and shows what `learnerd add` would store for them, without touching the
+/// database.
+///
+/// # Errors
+///
+/// Returns the last fetch error if any paper couldn't be fetched, after showing the rest.
+async fn preview_papers(
+  identifiers: &[String],
+  resolve_doi: bool,
+  output: OutputFormat,
+) -> Result<(), LearnerdErrors> {
+  let pretty = output == OutputFormat::Pretty;
+  let (mut results, mut last_error) = (Vec::new(), None);
+  for (i, identifier) in identifiers.iter().enumerate() {
+    if i > 0 && pretty {
+      println!();
+    }
+    if pretty {
+      println!("{} Fetching paper: {}", style(LOOKING_GLASS).cyan(), style(identifier).yellow());
+    }
+
+    match fetch_paper(identifier).await {
+      Ok(mut paper) => {
+        if resolve_doi {
+          resolve_published_doi(&mut paper, pretty).await;
+        }
+        if pretty {
+          println!("\n{} Paper details:", style(PAPER).green());
+          print_paper_details(&paper, false);
+        }
+        results.push(serde_json::json!({
+          "identifier": identifier,
+          "status": "fetched",
+          "paper": paper,
+        }));
+      },
+      Err(e) => {
+        if pretty {
+          println!(
+            "{} Failed to fetch {}: {}",
+            style(WARNING).yellow(),
+            style(identifier).yellow(),
+            style(e.to_string()).red()
+          );
+        }
+        results.push(serde_json::json!({
+          "identifier": identifier,
+          "status": "failed",
+          "error": e.to_string(),
+        }));
+        last_error = Some(e);
+      },
+    }
+  }
+
+  if !pretty {
+    print_json(&results)?;
+  } else {
+    println!("\n{} Dry run, nothing was saved", style("ℹ").blue());
+  }
+  match last_error {
+    Some(e) => Err(e.into()),
+    None => Ok(()),
+  }
+}
+
+/// How many papers `learnerd add --from-file` fetches at once.
+const FROM_FILE_CONCURRENCY: usize = 4;
+
+/// Reads paper identifiers from the contents of an identifier file.
+///
+/// Each line holds one identifier in any format accepted by [`Paper::new`]. Anything after a
+/// `#` is a comment, and blank lines are ignored.
+fn parse_identifier_file(contents: &str) -> Vec<String> {
+  contents
+    .lines()
+    .filter_map(|line| {
+      let identifier = line.split('#').next().unwrap_or_default().trim();
+      (!identifier.is_empty()).then(|| identifier.to_string())
+    })
+    .collect()
+}
+
+/// Parses a `YYYY-MM-DD` date given on the command line.
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+  NaiveDate::parse_from_str(date, "%Y-%m-%d")
+    .map_err(|e| format!("expected a date like 2020-01-31: {e}"))
+}
+
+/// Parses a similarity threshold for `learnerd deduplicate`, which must be between 0 and 1.
+fn parse_similarity(threshold: &str) -> Result<f64, String> {
+  match threshold.parse::<f64>() {
+    Ok(threshold) if (0.0..=1.0).contains(&threshold) => Ok(threshold),
+    Ok(_) => Err("expected a number between 0 and 1".to_string()),
+    Err(e) => Err(e.to_string()),
+  }
+}
+
+/// How many PDFs `learnerd download --all` downloads at once, unless told otherwise.
+const DOWNLOAD_CONCURRENCY: usize = 4;
+
+/// Downloads the PDF of every paper missing one, as for `learnerd download --all`.
+///
+/// PDFs are downloaded `concurrency` at a time into the configured PDF directory and named
+/// after the configured template, printing the outcome for each paper as it finishes and a
+/// summary of how many were downloaded or failed at the end. A failed download doesn't stop the
+/// rest.
+///
+/// # Errors
+///
+/// Returns an error if the PDF directory can't be created, or if there were PDFs to download and
+/// none of them could be.
+async fn download_all(db: &Database, concurrency: usize) -> Result<(), LearnerdErrors> {
+  let papers = db.papers_missing_pdf().await?;
+  if papers.is_empty() {
+    println!("{} Every paper with a PDF URL has its PDF", style(SUCCESS).green());
+    return Ok(());
+  }
+
+  let pdf_dir = pdf_dir(db).await?;
+  std::fs::create_dir_all(&pdf_dir)?;
+  let template = db.get_pdf_filename_template().await?;
+  println!(
+    "{} Downloading {} PDFs to {}",
+    style(LOOKING_GLASS).cyan(),
+    style(papers.len()).yellow(),
+    style(pdf_dir.display()).yellow()
+  );
+
+  let total = papers.len();
+  let mut pending = papers.into_iter();
+  let mut tasks = JoinSet::new();
+  let (mut done, mut downloaded, mut last_error) = (0, 0, None);
+  loop {
+    while tasks.len() < concurrency.max(1) {
+      let Some(paper) = pending.next() else { break };
+      let (pdf_dir, template) = (pdf_dir.clone(), template.clone());
+      tasks.spawn(async move {
+        let downloaded = paper.download_pdf_as(http::pdf_client(), pdf_dir, &template).await;
+        (paper, downloaded)
+      });
+    }
+    let Some(joined) = tasks.join_next().await else { break };
+    let (paper, result) = match joined {
+      Ok(joined) => joined,
+      Err(e) => std::panic::resume_unwind(e.into_panic()),
+    };
+
+    // Downloads run concurrently, but are recorded one at a time
+    let result = match result {
+      Ok(path) =>
+        db.set_pdf_path(&paper.source, &paper.source_identifier, path.clone()).await.map(|()| path),
+      Err(e) => Err(e),
+    };
+    done += 1;
+    let progress = style(format!("[{done}/{total}]")).dim();
+    match result {
+      Ok(path) => {
+        downloaded += 1;
+        println!(
+          "{progress} {} {} {}: {}",
+          style(SUCCESS).green(),
+          style(&paper.source).cyan(),
+          style(&paper.source_identifier).yellow(),
+          style(path.display()).white()
+        );
+      },
+      Err(e) => {
+        println!(
+          "{progress} {} {} {}: {}",
+          style(WARNING).yellow(),
+          style(&paper.source).cyan(),
+          style(&paper.source_identifier).yellow(),
+          style(e.to_string()).red()
+        );
+        last_error = Some(e);
+      },
+    }
+  }
+
+  let failed = total - downloaded;
+  println!(
+    "\n{} Downloaded: {}, Failed: {}",
+    style(BOOKS).cyan(),
+    style(downloaded).green(),
+    style(failed).red()
+  );
+
+  // Only fail the command as a whole if nothing could be downloaded
+  match last_error {
+    Some(e) if failed == total => Err(e.into()),
+    _ => Ok(()),
+  }
+}
+
+/// Fetches and saves every paper listed in `file`, as for `learnerd add --from-file`.
+///
+/// Papers are fetched [`FROM_FILE_CONCURRENCY`] at a time and saved as they arrive, printing a
+/// warning for each one that fails without stopping the rest. Once all are fetched, a summary
+/// of how many were added, already present, or failed is printed, followed by the failures.
+/// Unless `no_pdf` is set, the PDFs of the added papers are then offered for download in one go.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, or if it lists papers and none of them could be
+/// added.
+async fn add_from_file(
+  db: &Database,
+  file: &Path,
+  no_pdf: bool,
+  resolve_doi: bool,
+  accept_defaults: bool,
+  output: OutputFormat,
+) -> Result<(), LearnerdErrors> {
+  let pretty = output == OutputFormat::Pretty;
+  let identifiers = parse_identifier_file(&std::fs::read_to_string(file)?);
+  if pretty {
+    println!(
+      "{} Fetching {} papers from {}",
+      style(LOOKING_GLASS).cyan(),
+      style(identifiers.len()).yellow(),
+      style(file.display()).yellow()
+    );
+  }
+
+  let mut pending = identifiers.iter().cloned().enumerate();
+  let mut tasks = JoinSet::new();
+  let mut results = vec![serde_json::Value::Null; identifiers.len()];
+  let (mut added, mut duplicates, mut failures) = (Vec::new(), 0, Vec::new());
+  loop {
+    while tasks.len() < FROM_FILE_CONCURRENCY {
+      let Some((index, identifier)) = pending.next() else { break };
+      tasks.spawn(async move {
+        let mut fetched = fetch_paper(&identifier).await;
+        if let (true, Ok(paper)) = (resolve_doi, &mut fetched) {
+          resolve_published_doi(paper, false).await;
+        }
+        (index, fetched)
+      });
+    }
+    let Some(joined) = tasks.join_next().await else { break };
+    let (index, fetched) = match joined {
+      Ok(joined) => joined,
+      Err(e) => std::panic::resume_unwind(e.into_panic()),
+    };
+
+    let identifier = &identifiers[index];
+    let saved = match fetched {
+      Ok(paper) => db.save_paper(&paper).await.map(|_| paper),
+      Err(e) => Err(e),
+    };
+    match saved {
+      Ok(paper) => {
+        if pretty {
+          println!(
+            "{} {}: {}",
+            style(SUCCESS).green(),
+            style(identifier).yellow(),
+            style(&paper.title).white().bold()
+          );
+        }
+        results[index] =
+          serde_json::json!({ "identifier": identifier, "status": "saved", "paper": paper });
+        added.push(paper);
+      },
+      Err(e) if e.is_duplicate_error() => {
+        if pretty {
+          println!("{} {}: duplicate, skipping", style("ℹ").blue(), style(identifier).yellow());
+        }
+        results[index] = serde_json::json!({ "identifier": identifier, "status": "duplicate" });
+        duplicates += 1;
+      },
+      Err(e) => {
+        if pretty {
+          println!(
+            "{} Failed to add {}: {}",
+            style(WARNING).yellow(),
+            style(identifier).yellow(),
+            style(e.to_string()).red()
+          );
+        }
+        results[index] = serde_json::json!({
+          "identifier": identifier,
+          "status": "failed",
+          "error": e.to_string(),
+        });
+        failures.push((identifier, e));
+      },
+    }
+  }
+
+  if !pretty {
+    print_json(&results)?;
+  } else {
+    println!(
+      "\n{} Added: {}, Skipped (duplicate): {}, Failed: {}",
+      style(BOOKS).cyan(),
+      style(added.len()).green(),
+      style(duplicates).yellow(),
+      style(failures.len()).red()
+    );
+    for (identifier, e) in &failures {
+      println!("   {} {}: {}", style("✗").red(), style(identifier).yellow(), e);
+    }
+  }
+
+  let with_pdf = added.iter().filter(|paper| paper.pdf_url.is_some()).collect::<Vec<_>>();
+  if !no_pdf
+    && !with_pdf.is_empty()
+    && confirm(&format!("Download PDFs for {} papers?", with_pdf.len()), true, accept_defaults)?
+  {
+    let pdf_dir = pdf_dir(db).await?;
+    std::fs::create_dir_all(&pdf_dir)?;
+    for paper in with_pdf {
+      if let Err(e) = paper.download_and_record_pdf(db, pdf_dir.clone()).await {
+        if pretty {
+          println!(
+            "{} Failed to download PDF for {}: {}",
+            style(WARNING).yellow(),
+            style(&paper.source_identifier).yellow(),
+            style(e.to_string()).red()
+          );
+        }
+      }
+    }
+  }
+
+  // Only fail the command as a whole if no paper could be added
+  match failures.pop() {
+    Some((_, e)) if failures.len() + 1 == identifiers.len() => Err(e.into()),
+    _ => Ok(()),
+  }
+}
+
+/// Looks up a free-form reference string on Crossref and returns the DOI of the chosen work.
+///
+/// The top candidates are printed with their scores (in [`OutputFormat::Pretty`] mode).
+/// Interactively, the user picks one of them; with `accept_defaults` the top candidate is taken
+/// only if it clears [`DEFAULT_MATCH_THRESHOLD`].
+///
+/// # Errors
+///
+/// Returns [`LearnerError::NotFound`] if no candidate is chosen, after the best guesses have
+/// been printed.
+async fn resolve_reference(
+  reference: &str,
+  accept_defaults: bool,
+  output: OutputFormat,
+) -> Result<String, LearnerdErrors> {
+  let pretty = output == OutputFormat::Pretty;
+  if pretty {
+    println!(
+      "{} Searching Crossref for: {}",
+      style(LOOKING_GLASS).cyan(),
+      style(reference).yellow()
+    );
+  }
+
+  let matches = DOIClient::new().search_bibliographic(reference, 5).await?;
+  if matches.is_empty() {
+    if pretty {
+      println!("{} No matching works found on Crossref", style(WARNING).yellow());
+    }
+    return Err(LearnerError::NotFound.into());
+  }
+
+  if pretty {
+    println!("\n{} Best matches:", style(BOOKS).cyan());
+    for (i, candidate) in matches.iter().enumerate() {
+      println!(
+        "\n{}. {} {}",
+        style(i + 1).yellow(),
+        style(&candidate.title).white().bold(),
+        style(format!("(score {:.1})", candidate.score)).dim()
+      );
+      println!("   {} {}", style("Authors:").green(), style(candidate.authors.join(", ")).white());
+      if let Some(year) = candidate.year {
+        println!("   {} {}", style("Year:").green(), style(year).white());
+      }
+      println!("   {} {}", style("DOI:").green(), style(&candidate.doi).blue().underlined());
+    }
+  }
+
+  let confident = select_match(&matches, DEFAULT_MATCH_THRESHOLD);
+  let chosen = if accept_defaults {
+    confident
+  } else {
+    let items = matches
+      .iter()
+      .map(|m| format!("{} ({:.1})", m.title, m.score))
+      .chain(std::iter::once("None of these".to_string()))
+      .collect::<Vec<_>>();
+    // Only preselect a candidate when we are confident in it
+    let default = if confident.is_some() { 0 } else { matches.len() };
+    let selection = dialoguer::Select::new()
+      .with_prompt("Which work did you mean?")
+      .items(&items)
+      .default(default)
+      .interact()?;
+    matches.get(selection)
+  };
+
+  match chosen {
+    Some(candidate) => {
+      if pretty {
+        println!("\n{} Using DOI: {}", style(SUCCESS).green(), style(&candidate.doi).yellow());
+      }
+      Ok(candidate.doi.clone())
+    },
+    None => {
+      if pretty {
+        println!(
+          "\n{} No confident match found, see the best guesses above",
+          style(WARNING).yellow()
+        );
+      }
+      Err(LearnerError::NotFound.into())
+    },
   }
+}
+
+/// Identifies a paper across sources: by its DOI if it has one, or else by its source and
+/// identifier.
+fn paper_key(paper: &Paper) -> String {
+  match &paper.doi {
+    Some(doi) => format!("doi:{}", doi.to_lowercase()),
+    None => format!("{}:{}", paper.source, paper.source_identifier),
+  }
+}
+
+/// Searches arXiv, the IACR ePrint Archive, and Crossref for `query` at the same time, taking up
+/// to `max` papers from each and leaving out papers whose [`paper_key`] is in `seen`.
+///
+/// With pretty output, each service's papers are printed as soon as they arrive, numbered on
+/// from `numbered_from`. A service that fails is reported and skipped. Returns the new papers,
+/// newest first.
+async fn search_live(
+  query: &str,
+  max: usize,
+  mut seen: HashSet<String>,
+  pretty: bool,
+  numbered_from: usize,
+) -> Vec<Paper> {
+  let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+  let arxiv = {
+    let tx = tx.clone();
+    async move {
+      let _ = tx.send(("arXiv", ArxivClient::new().search(query, max).await));
+    }
+  };
+  let iacr = {
+    let tx = tx.clone();
+    async move {
+      let _ = tx.send(("IACR", IACRClient::new().search(query, max).await));
+    }
+  };
+  let crossref = async move {
+    let _ = tx.send(("Crossref", DOIClient::new().search(query, max).await));
+  };
+
+  let receive = async {
+    let mut found = Vec::new();
+    while let Some((service, result)) = rx.recv().await {
+      match result {
+        Ok(mut papers) => {
+          papers.retain(|paper| seen.insert(paper_key(paper)));
+          papers.sort_by_key(|paper| std::cmp::Reverse(paper.publication_date));
+          if pretty && !papers.is_empty() {
+            println!(
+              "\n{} {} new papers from {}:",
+              style(SUCCESS).green(),
+              style(papers.len()).yellow(),
+              style(service).cyan()
+            );
+            for (i, paper) in papers.iter().enumerate() {
+              print_paper_summary(numbered_from + found.len() + i, paper);
+            }
+          }
+          found.extend(papers);
+        },
+        Err(e) if pretty =>
+          println!("\n{} Searching {service} failed: {}", style(WARNING).yellow(), style(e).red()),
+        Err(e) => eprintln!("Searching {service} failed: {e}"),
+      }
+    }
+    found
+  };
+
+  let (.., mut found) = tokio::join!(arxiv, iacr, crossref, receive);
+  found.sort_by_key(|paper| std::cmp::Reverse(paper.publication_date));
+  found
+}
+
+/// Entry point for the learnerd CLI application
+///
+/// Parses the command line, runs the requested command, and maps any error to its exit code
+/// (see [`LearnerdErrors::exit_code`]).
+#[tokio::main]
+async fn main() -> ExitCode {
+  let cli = Cli::parse();
+  let output = cli.output;
+  match run(cli).await {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(e) => {
+      match output {
+        OutputFormat::Pretty => eprintln!("Error: {e:?}"),
+        OutputFormat::Json => eprintln!("{}", serde_json::json!({ "error": e.to_string() })),
+      }
+      ExitCode::from(e.exit_code())
+    },
+  }
+}
+
+/// Executes the parsed command line.
+///
+/// Sets up logging and executes the requested command. All commands provide colored output
+/// and interactive confirmations for destructive operations.
+///
+/// # Errors
+///
+/// Returns `LearnerdErrors` for various failure conditions including:
+/// - Database operations failures
+/// - Paper fetching failures
+/// - File system errors
+/// - User interaction errors
+async fn run(cli: Cli) -> Result<(), LearnerdErrors> {
+  if let Commands::Daemon { .. } = cli.command {
+  } else {
+    setup_logging(cli.verbose);
+  }
+
+  match cli.command {
+    Commands::Init => {
+      let db_path = resolve_db_path(cli.path, true);
+
+      if db_path.exists() {
+        println!(
+          "{} Database already exists at: {}",
+          style(WARNING).yellow(),
+          style(db_path.display()).yellow()
+        );
+
+        if !confirm_destructive(
+          "Do you want to reinitialize this database? This will erase all existing data",
+          "INIT",
+          cli.accept_defaults,
+          cli.force,
+        )? {
+          println!("{} Keeping existing database", style("ℹ").blue());
+          return Ok(());
+        }
+
+        // Remove existing database
+        println!("{} Removing existing database", style(WARNING).yellow());
+        std::fs::remove_file(&db_path)?;
+
+        // Also remove any FTS auxiliary files
+        let fts_files = glob::glob(&format!("{}*", db_path.display()))?;
+        for file in fts_files.flatten() {
+          std::fs::remove_file(file)?;
+        }
+      }
+
+      // Create parent directories if they don't exist
+      if let Some(parent) = db_path.parent() {
+        trace!("Creating parent directories: {}", parent.display());
+        std::fs::create_dir_all(parent)?;
+      }
+
+      println!(
+        "{} Initializing database at: {}",
+        style(ROCKET).cyan(),
+        style(db_path.display()).yellow()
+      );
+
+      let db = Database::open(&db_path).await?;
+
+      // Set up PDF directory
+      let pdf_dir = std::env::var_os(PDF_DIR_ENV_VAR)
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(Database::default_pdf_path, PathBuf::from);
+      println!(
+        "\n{} PDF files will be stored in: {}",
+        style(PAPER).cyan(),
+        style(pdf_dir.display()).yellow()
+      );
+
+      let pdf_dir = if confirm("Use this location for PDF storage?", true, cli.accept_defaults)? {
+        pdf_dir
+      } else {
+        let input: String =
+          dialoguer::Input::new().with_prompt("Enter path for PDF storage").interact_text()?;
+        PathBuf::from_str(&input).unwrap() // TODO (autoparallel): fix this unwrap
+      };
+
+      std::fs::create_dir_all(&pdf_dir)?;
+      db.set_config("pdf_dir", &pdf_dir.to_string_lossy()).await?;
+
+      // Crossref answers requests that come with a contact email from its more reliable pool
+      let email: String = if cli.accept_defaults {
+        String::new()
+      } else {
+        dialoguer::Input::new()
+          .with_prompt("Contact email to send with Crossref requests (optional)")
+          .allow_empty(true)
+          .interact_text()?
+      };
+      if !email.trim().is_empty() {
+        db.set_config("crossref_email", email.trim()).await?;
+      }
+
+      println!("{} Database initialized successfully!", style(SUCCESS).green());
+      Ok(())
+    },
+
+    Commands::Add { identifiers, reference, from_file, no_pdf, resolve_doi, dry_run } => {
+      let pretty = cli.output == OutputFormat::Pretty;
+      if dry_run {
+        let identifiers = match (from_file, reference) {
+          (Some(file), _) => parse_identifier_file(&std::fs::read_to_string(file)?),
+          (None, Some(reference)) =>
+            vec![resolve_reference(&reference, cli.accept_defaults, cli.output).await?],
+          (None, None) => identifiers,
+        };
+        return preview_papers(&identifiers, resolve_doi, cli.output).await;
+      }
+
+      let path = resolve_db_path(cli.path, pretty);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+      use_crossref_email(&db).await?;
+      use_cache(&db, &path).await?;
+
+      if let Some(file) = from_file {
+        return add_from_file(&db, &file, no_pdf, resolve_doi, cli.accept_defaults, cli.output)
+          .await;
+      }
+
+      let identifiers = match reference {
+        Some(reference) =>
+          vec![resolve_reference(&reference, cli.accept_defaults, cli.output).await?],
+        None => identifiers,
+      };
+
+      let (mut saved, mut duplicates, mut last_error) = (0, 0, None);
+      let mut results = Vec::new();
+      for (i, identifier) in identifiers.iter().enumerate() {
+        if i > 0 && pretty {
+          println!();
+        }
+        let added = add_paper(
+          &db,
+          identifier,
+          no_pdf,
+          resolve_doi,
+          cli.accept_defaults,
+          cli.force,
+          cli.output,
+        );
+        match added.await {
+          Ok((outcome, paper)) => {
+            match outcome {
+              AddOutcome::Saved => saved += 1,
+              AddOutcome::Duplicate => duplicates += 1,
+            }
+            results.push(serde_json::json!({
+              "identifier": identifier,
+              "status": outcome.as_str(),
+              "paper": paper,
+            }));
+          },
+          Err(e) => {
+            if pretty {
+              println!(
+                "{} Failed to add {}: {}",
+                style(WARNING).yellow(),
+                style(identifier).yellow(),
+                style(e.to_string()).red()
+              );
+            }
+            results.push(serde_json::json!({
+              "identifier": identifier,
+              "status": "failed",
+              "error": e.to_string(),
+            }));
+            last_error = Some(e);
+          },
+        }
+      }
+
+      let failed = identifiers.len() - saved - duplicates;
+      if !pretty {
+        print_json(&results)?;
+      } else if identifiers.len() > 1 {
+        println!(
+          "\n{} Added {} papers ({} already present, {} failed)",
+          style(BOOKS).cyan(),
+          style(saved).green(),
+          style(duplicates).yellow(),
+          style(failed).red()
+        );
+      }
+
+      // Only fail the command as a whole if no paper could be added
+      match last_error {
+        Some(e) if failed == identifiers.len() => Err(e),
+        _ => Ok(()),
+      }
+    },
+
+    Commands::Tag { cmd } => {
+      let path = resolve_db_path(cli.path, true);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      match cmd {
+        TagCommands::Add { source, identifier, tags } =>
+          for tag in &tags {
+            if db.add_tag(&source, &identifier, tag).await? {
+              println!(
+                "{} Tagged as {}",
+                style(SUCCESS).green(),
+                style(tag.trim().to_lowercase()).cyan()
+              );
+            } else {
+              println!(
+                "{} Already tagged as {}",
+                style("ℹ").blue(),
+                style(tag.trim().to_lowercase()).cyan()
+              );
+            }
+          },
+        TagCommands::Remove { source, identifier, tag } => {
+          if db.remove_tag(&source, &identifier, &tag).await? {
+            println!("{} Removed tag {}", style(SUCCESS).green(), style(tag.trim()).cyan());
+          } else {
+            println!(
+              "{} {} {} has no tag {}",
+              style(WARNING).yellow(),
+              style(&source).cyan(),
+              style(&identifier).yellow(),
+              style(tag.trim()).cyan()
+            );
+          }
+        },
+        TagCommands::List { source, identifier } => {
+          if db.get_paper_by_source_id(&source, &identifier).await?.is_none() {
+            println!("{} Paper not found", style(WARNING).yellow());
+            return Err(LearnerError::NotFound.into());
+          }
+          let tags = db.get_tags(&source, &identifier).await?;
+          if tags.is_empty() {
+            println!("{} No tags", style("ℹ").blue());
+          }
+          for tag in tags {
+            println!("   {}", style(tag).cyan());
+          }
+        },
+      }
+      Ok(())
+    },
+
+    Commands::Status { cmd } => {
+      let path = resolve_db_path(cli.path, true);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      match cmd {
+        StatusCommands::Set { source, identifier, status } => {
+          db.set_reading_status(&source, &identifier, status).await?;
+          println!("{} Marked as {}", style(SUCCESS).green(), style_status(status));
+        },
+        StatusCommands::Get { source, identifier } => {
+          let Some(progress) = db.get_reading_status(&source, &identifier).await? else {
+            println!("{} Paper not found", style(WARNING).yellow());
+            return Err(LearnerError::NotFound.into());
+          };
+          println!("   {} {}", style("Status:").green().bold(), style_status(progress.status));
+          if let Some(started_at) = progress.started_at {
+            println!(
+              "   {} {}",
+              style("Started:").green().bold(),
+              style(started_at.format("%Y-%m-%d %H:%M")).white()
+            );
+          }
+          if let Some(finished_at) = progress.finished_at {
+            println!(
+              "   {} {}",
+              style("Finished:").green().bold(),
+              style(finished_at.format("%Y-%m-%d %H:%M")).white()
+            );
+          }
+        },
+      }
+      Ok(())
+    },
+
+    Commands::Mark { status, source, identifier } => {
+      let path = resolve_db_path(cli.path, true);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      db.set_reading_status(&source, &identifier, status).await?;
+      println!("{} Marked as {}", style(SUCCESS).green(), style_status(status));
+      Ok(())
+    },
+
+    Commands::Collection { cmd } => {
+      let path = resolve_db_path(cli.path, true);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      match cmd {
+        CollectionCommands::Create { name, description } => {
+          let collection = db.create_collection(&name, description.as_deref()).await?;
+          println!(
+            "{} Created collection {}",
+            style(SUCCESS).green(),
+            style(&collection.name).cyan()
+          );
+        },
+        CollectionCommands::Add { source, identifier, collection } => {
+          if db.add_paper_to_collection(&source, &identifier, &collection).await? {
+            println!("{} Added to {}", style(SUCCESS).green(), style(&collection).cyan());
+          } else {
+            println!("{} Already in {}", style("ℹ").blue(), style(&collection).cyan());
+          }
+        },
+        CollectionCommands::Remove { source, identifier, collection } => {
+          if db.remove_paper_from_collection(&source, &identifier, &collection).await? {
+            println!("{} Removed from {}", style(SUCCESS).green(), style(&collection).cyan());
+          } else {
+            println!("{} Not in {}", style(WARNING).yellow(), style(&collection).cyan());
+          }
+        },
+        CollectionCommands::List { collection: Some(collection) } => {
+          let papers = db.get_collection_papers(&collection).await?;
+          if papers.is_empty() {
+            println!("{} {} is empty", style("ℹ").blue(), style(&collection).cyan());
+          }
+          for (i, paper) in papers.iter().enumerate() {
+            print_paper_summary(i + 1, paper);
+          }
+        },
+        CollectionCommands::List { collection: None } => {
+          let collections = db.list_collections().await?;
+          if collections.is_empty() {
+            println!("{} No collections", style("ℹ").blue());
+          }
+          for collection in collections {
+            println!(
+              "   {} ({} papers){}",
+              style(&collection.name).cyan().bold(),
+              style(collection.paper_count).yellow(),
+              collection.description.map(|d| format!(": {d}")).unwrap_or_default()
+            );
+          }
+        },
+      }
+      Ok(())
+    },
+
+    Commands::Note { cmd } => {
+      let path = resolve_db_path(cli.path, true);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      match cmd {
+        NoteCommands::Add { source, identifier, content } => {
+          let note = db.add_note(&source, &identifier, &content).await?;
+          println!(
+            "{} Added note {}",
+            style(SUCCESS).green(),
+            style(format!("#{}", note.id)).cyan()
+          );
+        },
+        NoteCommands::List { source, identifier } => {
+          if db.get_paper_by_source_id(&source, &identifier).await?.is_none() {
+            println!("{} Paper not found", style(WARNING).yellow());
+            return Err(LearnerError::NotFound.into());
+          }
+          let notes = db.get_notes(&source, &identifier).await?;
+          if notes.is_empty() {
+            println!("{} No notes", style("ℹ").blue());
+          }
+          print_notes(&notes);
+        },
+        NoteCommands::Delete { id } =>
+          if db.delete_note(id).await? {
+            println!("{} Note deleted", style(SUCCESS).green());
+          } else {
+            println!("{} No note {}", style(WARNING).yellow(), style(format!("#{id}")).cyan());
+          },
+      }
+      Ok(())
+    },
+
+    Commands::Remove { source, identifier, with_pdf } => {
+      let pretty = cli.output == OutputFormat::Pretty;
+      let path = resolve_db_path(cli.path, pretty);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      let Some(paper) = db.get_paper_by_source_id(&source, &identifier).await? else {
+        if pretty {
+          println!(
+            "{} Paper not found: {} {}",
+            style(WARNING).yellow(),
+            style(&source).cyan(),
+            style(&identifier).yellow()
+          );
+        }
+        return Err(LearnerError::NotFound.into());
+      };
+
+      if pretty {
+        println!("{} Found paper: {}", style(PAPER).green(), style(&paper.title).white().bold());
+      }
+      // Like other destructive operations, this defaults to "no", so -y alone declines
+      if !cli.force && !confirm("Remove this paper from the database?", false, cli.accept_defaults)?
+      {
+        if pretty && cli.accept_defaults {
+          println!(
+            "{} Removal cancelled, pass {} to remove without confirmation",
+            style(WARNING).yellow(),
+            style("--force").yellow()
+          );
+        } else if pretty {
+          println!("{} Removal cancelled", style(WARNING).yellow());
+        } else {
+          print_json(&serde_json::json!({ "removed": false, "paper": paper }))?;
+        }
+        return Ok(());
+      }
+
+      // Work out where the PDF lives before the record is gone, falling back to its usual
+      // name for PDFs downloaded before their location was recorded
+      let pdf_path = match &paper.pdf_path {
+        _ if !with_pdf => None,
+        Some(pdf_path) => Some(pdf_path.clone()),
+        None => {
+          let filename = paper.format_filename(&db.get_pdf_filename_template().await?, Some(50));
+          Some(pdf_dir(&db).await?.join(format!("{}.pdf", filename)))
+        },
+      };
+
+      if pretty {
+        println!("{} Removing paper: {}", style(WARNING).yellow(), style(&paper.title).white());
+      }
+      if !paper.remove(&db).await? {
+        if pretty {
+          println!("{} Paper not found", style(WARNING).yellow());
+        }
+        return Err(LearnerError::NotFound.into());
+      }
+      if pretty {
+        println!("{} Removed paper", style(SUCCESS).green());
+      }
+
+      let mut deleted_pdf = None;
+      if with_pdf {
+        match pdf_path {
+          Some(pdf_path) if pdf_path.exists() => {
+            std::fs::remove_file(&pdf_path)?;
+            if pretty {
+              println!(
+                "{} Deleted PDF: {}",
+                style(SUCCESS).green(),
+                style(pdf_path.display()).yellow()
+              );
+            }
+            deleted_pdf = Some(pdf_path);
+          },
+          Some(pdf_path) if pretty => println!(
+            "{} No PDF found at: {}",
+            style(WARNING).yellow(),
+            style(pdf_path.display()).yellow()
+          ),
+          _ => {},
+        }
+      }
+
+      if !pretty {
+        print_json(&serde_json::json!({
+          "removed": true,
+          "paper": paper,
+          "deleted_pdf": deleted_pdf,
+        }))?;
+      }
+      Ok(())
+    },
+
+    Commands::Get { source, identifier, doi, raw } => {
+      let pretty = cli.output == OutputFormat::Pretty;
+      let path = resolve_db_path(cli.path, pretty);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      let (source, identifier) = match (doi, source, identifier) {
+        (Some(doi), ..) => {
+          if pretty {
+            println!("{} Looking up DOI {}", style(LOOKING_GLASS).cyan(), style(&doi).yellow());
+          }
+          match db.get_paper_by_doi(&doi).await? {
+            Some(paper) => (paper.source, paper.source_identifier),
+            None => {
+              if pretty {
+                println!("{} Paper not found", style(WARNING).yellow());
+              }
+              return Err(LearnerError::NotFound.into());
+            },
+          }
+        },
+        (None, Some(source), Some(identifier)) => (source, identifier),
+        _ => unreachable!("clap requires a source and identifier unless --doi is given"),
+      };
+
+      if pretty {
+        println!(
+          "{} Fetching paper from {} with ID {}",
+          style(LOOKING_GLASS).cyan(),
+          style(&source).cyan(),
+          style(&identifier).yellow()
+        );
+      }
+
+      let notes = db.get_notes(&source, &identifier).await?;
+      let updated_at = db.get_updated_at(&source, &identifier).await?;
+      match db.get_paper_by_source_id(&source, &identifier).await? {
+        Some(paper) if !pretty => {
+          let mut value = serde_json::to_value(&paper)?;
+          value["notes"] = serde_json::to_value(&notes)?;
+          if let Some(updated_at) = updated_at {
+            value["updated_at"] = serde_json::to_value(updated_at)?;
+          }
+          print_json(&value)?;
+        },
+        Some(paper) => {
+          debug!("Found paper: {:?}", paper);
+          println!("\n{} Paper details:", style(PAPER).green());
+          print_paper_details(&paper, raw);
+          if !notes.is_empty() {
+            println!("   {}", style("Notes:").green().bold());
+            print_notes(&notes);
+          }
+          if let Some(updated_at) = updated_at {
+            println!(
+              "   {} {}",
+              style("Updated:").green().bold(),
+              style(updated_at.format("%Y-%m-%d %H:%M")).white()
+            );
+          }
+        },
+        None => {
+          if pretty {
+            println!("{} Paper not found", style(WARNING).yellow());
+          }
+          return Err(LearnerError::NotFound.into());
+        },
+      }
+      Ok(())
+    },
+
+    Commands::Search { query, author, tag, keyword, from, to, status, limit, live } => {
+      let pretty = cli.output == OutputFormat::Pretty;
+      let path = resolve_db_path(cli.path, pretty);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      let tag = tag.map(|tag| tag.trim().to_lowercase());
+      let filters: Vec<_> = author
+        .iter()
+        .map(|author| format!("author: {author}"))
+        .chain(tag.iter().map(|tag| format!("tag: {tag}")))
+        .chain(keyword.iter().map(|keyword| format!("keyword: {keyword}")))
+        .chain(from.iter().map(|from| format!("from: {from}")))
+        .chain(to.iter().map(|to| format!("to: {to}")))
+        .chain(status.iter().map(|status| format!("status: {status}")))
+        .collect();
+      let description = match (&query, filters.is_empty()) {
+        (Some(query), true) => query.clone(),
+        (Some(query), false) => format!("{query} ({})", filters.join(", ")),
+        (None, _) => filters.join(", "),
+      };
+      if pretty {
+        println!("{} Searching for: {}", style(LOOKING_GLASS).cyan(), style(&description).yellow());
+      }
+
+      // Modify query to use FTS5 syntax for better matching
+      let search_query =
+        query.as_deref().map(|query| query.split_whitespace().collect::<Vec<_>>().join(" OR "));
+      let live_query = query.filter(|_| live);
+      debug!("Modified search query: {:?}", search_query);
+
+      // Dates cover whole days in UTC
+      let from = from.map(|date| date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+      let to = to.map(|date| date.and_hms_opt(23, 59, 59).unwrap().and_utc());
+
+      let (papers, total_matches, elapsed) = match (search_query, author, tag, keyword) {
+        (Some(search_query), None, None, None) => {
+          let mut search = SearchQuery::new(&search_query).published_between(from, to);
+          if let Some(status) = status {
+            search = search.status(status);
+          }
+          if let Some(limit) = limit {
+            search = search.limit(limit);
+          }
+          let outcome = db.search(&search).await?;
+          (outcome.papers, outcome.total_matches, outcome.elapsed)
+        },
+        (search_query, author, tag, keyword) => {
+          let start = Instant::now();
+          let mut papers = match (&author, &tag, &keyword) {
+            (Some(author), ..) => db.search_by_author(author).await?,
+            (None, Some(tag), _) => db.search_by_tag(tag).await?,
+            (None, None, Some(keyword)) => db.search_by_keyword(keyword).await?,
+            (None, None, None) => unreachable!("handled by the full-text search"),
+          };
+          if let Some(tag) = &tag {
+            papers.retain(|paper| paper.tags.contains(tag));
+          }
+          if let Some(keyword) = &keyword {
+            let keyword = keyword.trim();
+            papers.retain(|paper| paper.keywords.iter().any(|k| k.eq_ignore_ascii_case(keyword)));
+          }
+          if let Some(status) = status {
+            papers.retain(|paper| paper.reading_status == status);
+          }
+          if from.is_some() || to.is_some() {
+            let to = to.unwrap_or_else(Utc::now);
+            papers.retain(|paper| {
+              from.is_none_or(|from| paper.publication_date >= from) && paper.publication_date <= to
+            });
+          }
+          if let Some(search_query) = search_query {
+            let matching: HashSet<_> = db
+              .search_papers(&search_query)
+              .await?
+              .into_iter()
+              .map(|paper| (paper.source, paper.source_identifier))
+              .collect();
+            papers.retain(|paper| {
+              matching.contains(&(paper.source.clone(), paper.source_identifier.clone()))
+            });
+          }
+          let total_matches = papers.len();
+          if let Some(limit) = limit {
+            papers.truncate(limit);
+          }
+          (papers, total_matches, start.elapsed())
+        },
+      };
+
+      if let Some(live_query) = live_query {
+        if pretty {
+          if papers.is_empty() {
+            println!("{} No saved papers match", style(WARNING).yellow());
+          } else {
+            println!("\n{} Saved papers:", style(BOOKS).cyan());
+            for (i, paper) in papers.iter().enumerate() {
+              print_paper_summary(i + 1, paper);
+            }
+          }
+          println!("\n{} Searching arXiv, IACR, and Crossref...", style(LOOKING_GLASS).cyan());
+        }
+        let seen = papers.iter().map(paper_key).collect();
+        let found =
+          search_live(&live_query, limit.unwrap_or(10), seen, pretty, papers.len() + 1).await;
+        if pretty {
+          println!(
+            "\n{} Found {} saved and {} new papers",
+            style(SUCCESS).green(),
+            style(papers.len()).yellow(),
+            style(found.len()).yellow()
+          );
+        } else {
+          let mut merged = papers;
+          merged.extend(found);
+          merged.sort_by_key(|paper| std::cmp::Reverse(paper.publication_date));
+          print_json(&merged)?;
+        }
+      } else if !pretty {
+        print_json(&papers)?;
+      } else if papers.is_empty() {
+        println!(
+          "{} No papers found matching: {}",
+          style(WARNING).yellow(),
+          style(&description).yellow()
+        );
+      } else {
+        let elapsed_ms = elapsed.as_millis();
+        if papers.len() < total_matches {
+          println!(
+            "\n{} Found {} papers, showing {} of {} ({} ms):",
+            style(SUCCESS).green(),
+            style(total_matches).yellow(),
+            style(papers.len()).yellow(),
+            style(total_matches).yellow(),
+            elapsed_ms
+          );
+        } else {
+          println!(
+            "\n{} Found {} papers ({} ms):",
+            style(SUCCESS).green(),
+            style(papers.len()).yellow(),
+            elapsed_ms
+          );
+        }
+
+        for (i, paper) in papers.iter().enumerate() {
+          print_paper_summary(i + 1, paper);
+        }
+
+        // If we have multiple results, show a tip about refining the search
+        if papers.len() > 1 {
+          println!(
+            "\n{} Tip: Use quotes for exact phrases, e.g. {}",
+            style("💡").yellow(),
+            style("\"exact phrase\"").yellow().italic()
+          );
+        }
+      }
+      Ok(())
+    },
+
+    Commands::Author { name } => {
+      let pretty = cli.output == OutputFormat::Pretty;
+      let path = resolve_db_path(cli.path, pretty);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      match name {
+        Some(name) => {
+          let papers = db.get_papers_by_author(&name).await?;
+          if !pretty {
+            print_json(&papers)?;
+          } else if papers.is_empty() {
+            println!("{} No papers by {}", style("ℹ").blue(), style(&name).yellow());
+          } else {
+            println!(
+              "{} {} papers by {}",
+              style(BOOKS).cyan(),
+              style(papers.len()).green(),
+              style(&name).yellow()
+            );
+            for (i, paper) in papers.iter().enumerate() {
+              print_paper_summary(i + 1, paper);
+            }
+          }
+        },
+        None => {
+          let authors = db.list_authors().await?;
+          if !pretty {
+            print_json(&authors)?;
+          } else if authors.is_empty() {
+            println!("{} No authors", style("ℹ").blue());
+          } else {
+            for author in authors {
+              println!(
+                "   {} ({} papers)",
+                style(&author.name).cyan().bold(),
+                style(author.paper_count).yellow()
+              );
+            }
+          }
+        },
+      }
+      Ok(())
+    },
 
-  match cli.command {
-    Commands::Init => {
-      let db_path = cli.path.unwrap_or_else(|| {
-        let default_path = Database::default_path();
+    Commands::FetchSearch { query, source, max, start, sort, add, pick } => {
+      let pretty = cli.output == OutputFormat::Pretty;
+      if pretty {
         println!(
-          "{} Using default database path: {}",
-          style(BOOKS).cyan(),
-          style(default_path.display()).yellow()
+          "{} Searching {} for: {}",
+          style(LOOKING_GLASS).cyan(),
+          source.service(),
+          style(&query).yellow()
         );
-        default_path
-      });
+      }
 
-      if db_path.exists() {
-        println!(
-          "{} Database already exists at: {}",
-          style(WARNING).yellow(),
-          style(db_path.display()).yellow()
-        );
+      let papers = match source {
+        SearchSource::Arxiv => {
+          let options = ArxivSearchOptions { max_results: max, start, sort };
+          ArxivClient::new().search_with(&query, &options).await?
+        },
+        // Crossref is asked for every result up to the last one wanted
+        SearchSource::Doi =>
+          DOIClient::new().search(&query, start + max).await?.into_iter().skip(start).collect(),
+      };
 
-        // Handle reinitialize confirmation
-        let should_reinit = if cli.accept_defaults {
-          false // Default to not reinitializing in automated mode
+      // Numbering of the papers shown, which goes on from --start
+      let mut first = start;
+      let papers = if pick && !papers.is_empty() {
+        if pretty {
+          println!("\n{} Found {} papers:", style(SUCCESS).green(), style(papers.len()).yellow());
+          for (i, paper) in papers.iter().enumerate() {
+            print_paper_summary(start + i + 1, paper);
+          }
+          println!();
+        }
+        let selection = if cli.accept_defaults {
+          Some(0)
         } else {
-          dialoguer::Confirm::new()
-            .with_prompt(
-              "Do you want to reinitialize this database? This will erase all existing data",
-            )
-            .default(false)
-            .interact()?
+          let items = papers
+            .iter()
+            .map(|paper| format!("{} ({})", paper.title, paper.source_identifier))
+            .chain(std::iter::once("None of these".to_string()))
+            .collect::<Vec<_>>();
+          let selection = dialoguer::Select::new()
+            .with_prompt("Which paper should be added?")
+            .items(&items)
+            .default(0)
+            .interact()?;
+          (selection < papers.len()).then_some(selection)
         };
-
-        if !should_reinit {
-          println!("{} Keeping existing database", style("ℹ").blue());
-          return Ok(());
+        match selection {
+          Some(i) => {
+            first += i;
+            vec![papers[i].clone()]
+          },
+          None => {
+            if pretty {
+              println!("{} Nothing added", style("ℹ").blue());
+            }
+            return Ok(());
+          },
         }
+      } else {
+        papers
+      };
 
-        // Handle INIT confirmation
-        let should_proceed = if cli.accept_defaults {
-          false // Default to not proceeding in automated mode
+      if !add && (!pick || papers.is_empty()) {
+        if !pretty {
+          print_json(&papers)?;
+        } else if papers.is_empty() {
+          println!("{} No papers found on {}", style(WARNING).yellow(), source.service());
         } else {
-          let input = dialoguer::Input::<String>::new()
-            .with_prompt(format!(
-              "{} Type {} to confirm reinitialization",
-              style("⚠️").red(),
-              style("INIT").red().bold()
-            ))
-            .interact_text()?;
-          input == "INIT"
-        };
-
-        if !should_proceed {
-          println!("{} Operation cancelled, keeping existing database", style("ℹ").blue());
-          return Ok(());
+          println!("\n{} Found {} papers:", style(SUCCESS).green(), style(papers.len()).yellow());
+          for (i, paper) in papers.iter().enumerate() {
+            print_paper_summary(start + i + 1, paper);
+          }
+          println!(
+            "\n{} Tip: Pass {} to save these papers to your database, or {} to choose one",
+            style("💡").yellow(),
+            style("--add").yellow(),
+            style("--pick").yellow()
+          );
         }
+        return Ok(());
+      }
 
-        // Remove existing database
-        println!("{} Removing existing database", style(WARNING).yellow());
-        std::fs::remove_file(&db_path)?;
+      let path = resolve_db_path(cli.path, pretty);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
 
-        // Also remove any FTS auxiliary files
-        let fts_files = glob::glob(&format!("{}*", db_path.display()))?;
-        for file in fts_files.flatten() {
-          std::fs::remove_file(file)?;
+      let (mut saved, mut duplicates) = (0, 0);
+      let mut results = Vec::new();
+      for (i, paper) in papers.iter().enumerate() {
+        let outcome = match paper.save(&db).await {
+          Ok(_) => {
+            saved += 1;
+            AddOutcome::Saved
+          },
+          Err(e) if e.is_duplicate_error() => {
+            duplicates += 1;
+            AddOutcome::Duplicate
+          },
+          Err(e) => return Err(e.into()),
+        };
+        if pretty {
+          print_paper_summary(first + i + 1, paper);
+          match outcome {
+            AddOutcome::Saved => println!("   {} Saved", style(SAVE).green()),
+            AddOutcome::Duplicate => println!("   {} Already in your database", style("ℹ").blue()),
+          }
         }
+        results.push(serde_json::json!({
+          "identifier": paper.source_identifier,
+          "status": outcome.as_str(),
+          "paper": paper,
+        }));
       }
 
-      // Create parent directories if they don't exist
-      if let Some(parent) = db_path.parent() {
-        trace!("Creating parent directories: {}", parent.display());
-        std::fs::create_dir_all(parent)?;
+      if pretty {
+        println!(
+          "\n{} Added {} papers ({} already present)",
+          style(BOOKS).cyan(),
+          style(saved).green(),
+          style(duplicates).yellow()
+        );
+      } else {
+        print_json(&results)?;
       }
+      Ok(())
+    },
 
-      println!(
-        "{} Initializing database at: {}",
-        style(ROCKET).cyan(),
-        style(db_path.display()).yellow()
-      );
-
-      let db = Database::open(&db_path).await?;
-
-      // Set up PDF directory
-      let pdf_dir = Database::default_pdf_path();
-      println!(
-        "\n{} PDF files will be stored in: {}",
-        style(PAPER).cyan(),
-        style(pdf_dir.display()).yellow()
-      );
+    Commands::List { sort, reverse, limit, offset, tag, filter } => {
+      let pretty = cli.output == OutputFormat::Pretty;
+      let path = resolve_db_path(cli.path, pretty);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
 
-      // TODO (autoparallel): I think we need this `allow` because though the returns are the same,
-      // the initial `if` bypasses interaction
-      #[allow(clippy::if_same_then_else)]
-      let pdf_dir = if cli.accept_defaults {
-        pdf_dir // Use default in automated mode
-      } else if dialoguer::Confirm::new()
-        .with_prompt("Use this location for PDF storage?")
-        .default(true)
-        .interact()?
-      {
-        pdf_dir
-      } else {
-        let input: String =
-          dialoguer::Input::new().with_prompt("Enter path for PDF storage").interact_text()?;
-        PathBuf::from_str(&input).unwrap() // TODO (autoparallel): fix this unwrap
+      let ascending = sort.default_ascending() != reverse;
+      let papers = match (tag, filter) {
+        (Some(tag), _) => db.list_papers_by_tag(&tag, sort, ascending, limit, offset).await?,
+        (None, Some(status)) =>
+          db.list_papers_by_status(status, sort, ascending, limit, offset).await?,
+        (None, None) => db.list_papers(sort, ascending, limit, offset).await?,
       };
+      if !pretty {
+        print_json(&papers)?;
+      } else if papers.is_empty() {
+        println!("{} No papers to show", style(WARNING).yellow());
+      } else {
+        println!(
+          "\n{} Showing papers {} to {}:",
+          style(SUCCESS).green(),
+          style(offset + 1).yellow(),
+          style(offset + papers.len()).yellow()
+        );
+        for (i, paper) in papers.iter().enumerate() {
+          print_paper_summary(offset + i + 1, paper);
+        }
+      }
+      Ok(())
+    },
 
-      std::fs::create_dir_all(&pdf_dir)?;
-      db.set_config("pdf_dir", &pdf_dir.to_string_lossy()).await?;
+    Commands::Recent { limit } => {
+      let pretty = cli.output == OutputFormat::Pretty;
+      let path = resolve_db_path(cli.path, pretty);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
 
-      println!("{} Database initialized successfully!", style(SUCCESS).green());
+      let papers = db.recent_papers(limit).await?;
+      if !pretty {
+        print_json(&papers)?;
+      } else if papers.is_empty() {
+        println!("{} No papers saved yet", style(WARNING).yellow());
+      } else {
+        println!("\n{} Most recently added papers:", style(SUCCESS).green());
+        for (i, paper) in papers.iter().enumerate() {
+          print_paper_summary(i + 1, paper);
+        }
+      }
       Ok(())
     },
 
-    Commands::Add { identifier, no_pdf } => {
-      let path = cli.path.unwrap_or_else(|| {
+    Commands::Export {
+      format,
+      output,
+      query,
+      collection,
+      source,
+      identifier,
+      sort,
+      reverse,
+      group_by,
+    } => {
+      // Status messages go to stderr so that stdout holds only the bibliography
+      let path = configured_db_path(cli.path).unwrap_or_else(|| {
         let default_path = Database::default_path();
-        println!(
+        eprintln!(
           "{} Using default database path: {}",
           style(BOOKS).cyan(),
           style(default_path.display()).yellow()
@@ -335,371 +2893,539 @@ async fn main() -> Result<(), LearnerdErrors> {
       trace!("Using database at: {}", path.display());
       let db = Database::open(&path).await?;
 
-      println!("{} Fetching paper: {}", style(LOOKING_GLASS).cyan(), style(&identifier).yellow());
+      let mut papers = match (query, collection, source, identifier) {
+        (Some(query), ..) => db.search_papers(&query).await?,
+        (None, Some(collection), ..) => db.get_collection_papers(&collection).await?,
+        (None, None, Some(source), Some(identifier)) =>
+          match db.get_paper_by_source_id(&source, &identifier).await? {
+            Some(paper) => vec![paper],
+            None => {
+              eprintln!(
+                "{} Paper not found: {} {}",
+                style(WARNING).yellow(),
+                style(&source).cyan(),
+                style(&identifier).yellow()
+              );
+              return Err(LearnerError::NotFound.into());
+            },
+          },
+        _ => {
+          let progress = ProgressBarSink::new();
+          let result = db.export_papers(Some(&progress)).await;
+          progress.finish();
+          result?
+        },
+      };
+
+      if let Some(sort) = sort {
+        sort.sort_papers(&mut papers, sort.default_ascending() != reverse);
+      }
+
+      let contents = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&papers)? + "\n",
+        ExportFormat::Bibtex => to_bibtex_bibliography(&papers),
+        ExportFormat::Ris => to_ris_records(&papers),
+        ExportFormat::Markdown => to_markdown_document(&papers, group_by),
+        ExportFormat::Html => to_html_document(&papers, group_by),
+      };
+
+      match output {
+        Some(output) => {
+          std::fs::write(&output, contents)?;
+          eprintln!(
+            "{} Exported {} papers to {}",
+            style(SUCCESS).green(),
+            style(papers.len()).yellow(),
+            style(output.display()).yellow()
+          );
+        },
+        None => print!("{}", contents),
+      }
+      Ok(())
+    },
 
-      let paper = Paper::new(&identifier).await?;
-      debug!("Paper details: {:?}", paper);
+    Commands::Import { format, file } => {
+      let path = resolve_db_path(cli.path, true);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+      use_crossref_email(&db).await?;
+      use_cache(&db, &path).await?;
 
-      println!("\n{} Found paper:", style(SUCCESS).green());
-      println!("   {} {}", style("Title:").green().bold(), style(&paper.title).white());
+      let entries = match format {
+        ImportFormat::Bibtex => parse_bibtex(&std::fs::read_to_string(&file)?)?,
+      };
       println!(
-        "   {} {}",
-        style("Authors:").green().bold(),
-        style(paper.authors.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", ")).white()
+        "{} Importing {} entries from {}",
+        style(BOOKS).cyan(),
+        style(entries.len()).yellow(),
+        style(file.display()).yellow()
       );
 
-      match paper.save(&db).await {
-        Ok(id) => {
-          println!("\n{} Saved paper with ID: {}", style(SAVE).green(), style(id).yellow());
+      let (mut imported, mut skipped, mut last_error) = (0, 0, None);
+      let mut failures = Vec::new();
+      let mut seen = HashSet::new();
+      for (i, entry) in entries.iter().enumerate() {
+        let progress = style(format!("[{}/{}]", i + 1, entries.len())).dim();
+        let Some((source, identifier)) = entry.identifier() else {
+          let reason = "no DOI, arXiv eprint, or paper URL";
+          println!(
+            "{progress} {} {}: {reason}, skipping",
+            style(WARNING).yellow(),
+            style(&entry.key).yellow()
+          );
+          failures.push((&entry.key, reason.to_string()));
+          last_error = Some(LearnerError::InvalidBibtex(format!(
+            "entry {:?} doesn't identify a paper",
+            entry.key
+          )));
+          continue;
+        };
 
-          // Handle PDF download for newly added paper
-          if paper.pdf_url.is_some() && !no_pdf {
-            let should_download = if cli.accept_defaults {
-              true // Default to downloading in automated mode
-            } else {
-              dialoguer::Confirm::new().with_prompt("Download PDF?").default(true).interact()?
-            };
+        // Check the file and the database first, to avoid fetching papers we already have
+        let known = !seen.insert((source.clone(), identifier.clone()))
+          || db.get_paper_by_source_id(&source, &identifier).await?.is_some();
+        let result = if known {
+          Err(None)
+        } else {
+          match fetch_paper(&identifier).await {
+            Ok(paper) => match db.save_paper(&paper).await {
+              Ok(_) => Ok(paper),
+              Err(e) if e.is_duplicate_error() => Err(None),
+              Err(e) => Err(Some(e)),
+            },
+            Err(e) => Err(Some(e)),
+          }
+        };
 
-            if should_download {
-              println!("{} Downloading PDF...", style(LOOKING_GLASS).cyan());
+        match result {
+          Ok(paper) => {
+            imported += 1;
+            println!(
+              "{progress} {} {}: {}",
+              style(SUCCESS).green(),
+              style(&entry.key).yellow(),
+              style(&paper.title).white().bold()
+            );
+          },
+          Err(None) => {
+            skipped += 1;
+            println!(
+              "{progress} {} {}: duplicate, skipping",
+              style("ℹ").blue(),
+              style(&entry.key).yellow()
+            );
+          },
+          Err(Some(e)) => {
+            println!(
+              "{progress} {} {}: {}",
+              style(WARNING).yellow(),
+              style(&entry.key).yellow(),
+              style(e.to_string()).red()
+            );
+            failures.push((&entry.key, e.to_string()));
+            last_error = Some(e);
+          },
+        }
+      }
 
-              let pdf_dir = match db.get_config("pdf_dir").await? {
-                Some(dir) => PathBuf::from(dir),
-                None => {
-                  println!(
-                    "{} PDF directory not configured. Run {} first",
-                    style(WARNING).yellow(),
-                    style("learnerd init").cyan()
-                  );
-                  return Ok(());
-                },
-              };
-
-              match paper.download_pdf(pdf_dir).await {
-                Ok(_) => {
-                  println!("{} PDF downloaded successfully!", style(SUCCESS).green());
-                },
-                Err(e) => {
-                  println!(
-                    "{} Failed to download PDF: {}",
-                    style(WARNING).yellow(),
-                    style(e.to_string()).red()
-                  );
-                  println!(
-                    "   {} You can try downloading it later using: {} {} {}",
-                    style("Tip:").blue(),
-                    style("learnerd download").yellow(),
-                    style(&paper.source.to_string()).cyan(),
-                    style(&paper.source_identifier).yellow(),
-                  );
-                },
-              }
-            }
-          } else if paper.pdf_url.is_none() {
-            println!("\n{} No PDF URL available for this paper", style(WARNING).yellow());
-          }
-        },
-        Err(e) if e.is_duplicate_error() => {
-          println!("\n{} This paper is already in your database", style("ℹ").blue());
+      let failed = failures.len();
+      println!(
+        "\n{} Imported: {}, Skipped (duplicate): {}, Failed: {}",
+        style(BOOKS).cyan(),
+        style(imported).green(),
+        style(skipped).yellow(),
+        style(failed).red()
+      );
+      if !failures.is_empty() {
+        println!("\n{} Not imported:", style(WARNING).yellow());
+        for (key, reason) in &failures {
+          println!("   {} {}: {}", style("✗").red(), style(key).yellow(), reason);
+        }
+      }
 
-          // Check existing PDF status
-          if paper.pdf_url.is_some() && !no_pdf {
-            if let Ok(Some(dir)) = db.get_config("pdf_dir").await {
-              let pdf_dir = PathBuf::from(dir);
-              let formatted_title = learner::format::format_title(&paper.title, Some(50));
-              let pdf_path = pdf_dir.join(format!("{}.pdf", formatted_title));
+      // Only fail the command as a whole if nothing could be imported
+      match last_error {
+        Some(e) if failed == entries.len() => Err(e.into()),
+        _ => Ok(()),
+      }
+    },
 
-              if pdf_path.exists() {
-                println!(
-                  "   {} PDF exists at: {}",
-                  style("📄").cyan(),
-                  style(pdf_path.display()).yellow()
-                );
+    Commands::Clean => {
+      let path = resolve_db_path(cli.path, true);
+      if path.exists() {
+        println!(
+          "{} Database found at: {}",
+          style(WARNING).yellow(),
+          style(path.display()).yellow()
+        );
 
-                let should_redownload = if cli.accept_defaults {
-                  false // Default to not redownloading in automated mode
-                } else {
-                  dialoguer::Confirm::new()
-                    .with_prompt("Download fresh copy? (This will overwrite the existing file)")
-                    .default(false)
-                    .interact()?
-                };
-
-                if should_redownload {
-                  println!("{} Downloading fresh copy of PDF...", style(LOOKING_GLASS).cyan());
-                  match paper.download_pdf(pdf_dir).await {
-                    Ok(_) => println!("{} PDF downloaded successfully!", style(SUCCESS).green()),
-                    Err(e) => println!(
-                      "{} Failed to download PDF: {}",
-                      style(WARNING).yellow(),
-                      style(e.to_string()).red()
-                    ),
-                  }
-                }
-              } else {
-                let should_download = if cli.accept_defaults {
-                  true // Default to downloading in automated mode
-                } else {
-                  dialoguer::Confirm::new()
-                    .with_prompt("PDF not found. Download it now?")
-                    .default(true)
-                    .interact()?
-                };
-
-                if should_download {
-                  println!("{} Downloading PDF...", style(LOOKING_GLASS).cyan());
-                  match paper.download_pdf(pdf_dir).await {
-                    Ok(_) => println!("{} PDF downloaded successfully!", style(SUCCESS).green()),
-                    Err(e) => println!(
-                      "{} Failed to download PDF: {}",
-                      style(WARNING).yellow(),
-                      style(e.to_string()).red()
-                    ),
-                  }
-                }
-              }
-            }
-          }
-        },
-        Err(e) => return Err(LearnerdErrors::Learner(e)),
+        if !confirm_destructive(
+          "Are you sure you want to delete this database?",
+          "DELETE",
+          cli.accept_defaults,
+          cli.force,
+        )? {
+          println!("{} Operation cancelled", style("✖").red());
+          return Ok(());
+        }
+
+        // Proceed with deletion
+        println!(
+          "{} Removing database: {}",
+          style(WARNING).yellow(),
+          style(path.display()).yellow()
+        );
+        std::fs::remove_file(&path)?;
+
+        // Also remove any FTS auxiliary files
+        let fts_files = glob::glob(&format!("{}*", path.display()))?;
+        for file in fts_files.flatten() {
+          std::fs::remove_file(file)?;
+        }
+        println!("{} Database files cleaned", style(SUCCESS).green());
+      } else {
+        println!(
+          "{} No database found at: {}",
+          style(WARNING).yellow(),
+          style(path.display()).yellow()
+        );
       }
+      Ok(())
+    },
+
+    Commands::Reindex => {
+      let path = resolve_db_path(cli.path, true);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      println!("{} Rebuilding search index...", style(LOOKING_GLASS).cyan());
+      let progress = ProgressBarSink::new();
+      let result = db.rebuild_search_index(Some(&progress)).await;
+      progress.finish();
+      result?;
 
+      println!("{} Search index rebuilt", style(SUCCESS).green());
       Ok(())
     },
 
-    Commands::Remove { source, identifier } => {
-      let path = cli.path.unwrap_or_else(|| {
-        let default_path = Database::default_path();
-        println!(
-          "{} Using default database path: {}",
-          style(BOOKS).cyan(),
-          style(default_path.display()).yellow()
-        );
-        default_path
-      });
+    Commands::Gc => {
+      let path = resolve_db_path(cli.path, true);
       trace!("Using database at: {}", path.display());
-      let _db = Database::open(&path).await?;
+      let db = Database::open(&path).await?;
 
-      println!("{} Remove functionality not yet implemented", style(WARNING).yellow());
-      println!(
-        "Would remove paper from {} with ID {}",
-        style(source).cyan(),
-        style(identifier).yellow()
-      );
+      println!("{} Cleaning up database...", style(BOOKS).cyan());
+      let progress = ProgressBarSink::new();
+      let result = db.collect_garbage(Some(&progress)).await;
+      progress.finish();
+
+      println!("{} Removed {} stale PDF records", style(SUCCESS).green(), style(result?).yellow());
       Ok(())
     },
 
-    Commands::Get { source, identifier } => {
-      let path = cli.path.unwrap_or_else(|| {
-        let default_path = Database::default_path();
-        println!(
-          "{} Using default database path: {}",
-          style(BOOKS).cyan(),
-          style(default_path.display()).yellow()
-        );
-        default_path
-      });
+    Commands::Stats => {
+      let pretty = cli.output == OutputFormat::Pretty;
+      let path = resolve_db_path(cli.path, pretty);
       trace!("Using database at: {}", path.display());
       let db = Database::open(&path).await?;
 
+      let stats = db.get_statistics().await?;
+      if !pretty {
+        return print_json(&stats);
+      }
+      println!("{} Library statistics", style(BOOKS).cyan());
+      println!(
+        "   {:<16} {:>6}",
+        style("Total papers:").green(),
+        style(stats.total_papers).yellow()
+      );
+      println!("   {:<16} {:>6}", style("Authors:").green(), style(stats.total_authors).yellow());
+      println!(
+        "   {:<16} {:>6}",
+        style("With PDF:").green(),
+        style(stats.papers_with_pdf).yellow()
+      );
       println!(
-        "{} Fetching paper from {} with ID {}",
-        style(LOOKING_GLASS).cyan(),
-        style(&source).cyan(),
-        style(&identifier).yellow()
+        "   {:<16} {:>6}",
+        style("Database size:").green(),
+        style(HumanBytes(stats.database_size)).yellow()
       );
 
-      match db.get_paper_by_source_id(&source, &identifier).await? {
-        Some(paper) => {
-          debug!("Found paper: {:?}", paper);
-          println!("\n{} Paper details:", style(PAPER).green());
-          println!("   {} {}", style("Title:").green().bold(), style(&paper.title).white());
-          println!(
-            "   {} {}",
-            style("Authors:").green().bold(),
-            style(paper.authors.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", "))
-              .white()
-          );
-          println!(
-            "   {} {}",
-            style("Abstract:").green().bold(),
-            style(&paper.abstract_text).white()
-          );
+      if stats.total_papers == 0 {
+        return Ok(());
+      }
+
+      let mut by_source = stats.by_source.into_iter().collect::<Vec<_>>();
+      by_source.sort_by_key(|(source, _)| source.to_string());
+      println!("\n   {:<16} {:>6}", style("Source").bold(), style("Papers").bold());
+      for (source, count) in by_source {
+        println!("   {:<16} {:>6}", style(source).cyan(), count);
+      }
+
+      println!("\n   {:<16} {:>6}", style("Year").bold(), style("Papers").bold());
+      for (year, count) in stats.by_year {
+        println!("   {:<16} {:>6}", style(year).cyan(), count);
+      }
+
+      println!("\n   {:<16} {:>6}", style("Added in").bold(), style("Papers").bold());
+      for (month, count) in stats.added_by_month {
+        println!("   {:<16} {:>6}", style(month).cyan(), count);
+      }
+      Ok(())
+    },
+
+    Commands::Config { cmd } => {
+      let path = resolve_db_path(cli.path, true);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      match cmd {
+        ConfigCommands::Set { key, value } => {
+          match key {
+            ConfigKey::PdfFilenameTemplate => validate_filename_template(&value)?,
+            ConfigKey::CacheTtlSecs => {
+              parse_ttl(&value)?;
+            },
+            ConfigKey::DaemonPollIntervalSecs
+              if !value.trim().parse::<u64>().is_ok_and(|secs| secs > 0) =>
+              return Err(LearnerdErrors::InvalidConfig(format!(
+                "{POLL_INTERVAL_CONFIG_KEY} must be a positive number of seconds: {value}"
+              ))),
+            _ => {},
+          }
+          db.set_config(key.as_str(), &value).await?;
           println!(
-            "   {} {}",
-            style("Published:").green().bold(),
-            style(&paper.publication_date).white()
+            "{} Set {} to {}",
+            style(SUCCESS).green(),
+            style(key.as_str()).cyan(),
+            style(&value).yellow()
           );
-          if let Some(url) = &paper.pdf_url {
-            println!("   {} {}", style("PDF URL:").green().bold(), style(url).blue().underlined());
-          }
-          if let Some(doi) = &paper.doi {
-            println!("   {} {}", style("DOI:").green().bold(), style(doi).blue().underlined());
-          }
         },
-        None => {
-          println!("{} Paper not found", style(WARNING).yellow());
+        ConfigCommands::Get { key } => {
+          let value = match key {
+            ConfigKey::PdfDir => Some(db.get_pdf_dir().await?.display().to_string()),
+            ConfigKey::PdfFilenameTemplate => Some(db.get_pdf_filename_template().await?),
+            ConfigKey::CrossrefEmail | ConfigKey::DaemonPollIntervalSecs =>
+              db.get_config(key.as_str()).await?,
+            ConfigKey::CacheTtlSecs =>
+              Some(db.get_config(key.as_str()).await?.unwrap_or(DEFAULT_TTL.as_secs().to_string())),
+          };
+          match value {
+            Some(value) => println!("{}", value),
+            None => println!("{} {} is not set", style("ℹ").blue(), style(key.as_str()).cyan()),
+          }
         },
       }
       Ok(())
     },
 
-    Commands::Search { query } => {
-      let path = cli.path.unwrap_or_else(|| {
-        let default_path = Database::default_path();
-        println!(
-          "{} Using default database path: {}",
-          style(BOOKS).cyan(),
-          style(default_path.display()).yellow()
-        );
-        default_path
-      });
+    Commands::VerifyPdfs => {
+      let path = resolve_db_path(cli.path, true);
       trace!("Using database at: {}", path.display());
       let db = Database::open(&path).await?;
-
-      println!("{} Searching for: {}", style(LOOKING_GLASS).cyan(), style(&query).yellow());
-
-      // Modify query to use FTS5 syntax for better matching
-      let search_query = query.split_whitespace().collect::<Vec<_>>().join(" OR ");
-      debug!("Modified search query: {}", search_query);
-
-      let papers = db.search_papers(&search_query).await?;
-      if papers.is_empty() {
+      let pdf_dir = pdf_dir(&db).await?;
+
+      println!("{} Checking downloaded PDFs...", style(LOOKING_GLASS).cyan());
+      let results = db.verify_pdfs(&pdf_dir).await?;
+      let (mut valid, mut missing, mut invalid) = (0, 0, 0);
+      for (paper, status) in &results {
+        let problem = match status {
+          PdfStatus::Valid => {
+            valid += 1;
+            continue;
+          },
+          PdfStatus::Missing => {
+            missing += 1;
+            "missing"
+          },
+          PdfStatus::Invalid => {
+            invalid += 1;
+            "not a complete PDF"
+          },
+        };
         println!(
-          "{} No papers found matching: {}",
+          "{} {} ({} {}): {}",
           style(WARNING).yellow(),
-          style(&query).yellow()
+          style(&paper.title).white().bold(),
+          paper.source,
+          paper.source_identifier,
+          style(problem).red()
         );
-      } else {
-        println!("\n{} Found {} papers:", style(SUCCESS).green(), style(papers.len()).yellow());
-
-        for (i, paper) in papers.iter().enumerate() {
-          debug!("Paper details: {:?}", paper);
-          println!("\n{}. {}", style(i + 1).yellow(), style(&paper.title).white().bold());
-
-          let authors = paper.authors.iter().map(|a| a.name.as_str()).collect::<Vec<_>>();
+      }
 
-          let author_display = if authors.is_empty() {
-            style("No authors listed").red().italic().to_string()
-          } else {
-            style(authors.join(", ")).white().to_string()
-          };
+      println!(
+        "{} Valid: {}, Missing: {}, Invalid: {}",
+        style(SUCCESS).green(),
+        style(valid).yellow(),
+        style(missing).yellow(),
+        style(invalid).yellow()
+      );
+      if missing + invalid > 0 {
+        println!(
+          "   {} Use `learnerd download` to fetch them again",
+          style("Tip:").blue().italic()
+        );
+      }
+      Ok(())
+    },
 
-          println!("   {} {}", style("Authors:").green(), author_display);
+    Commands::Deduplicate { threshold, dry_run } => {
+      let pretty = cli.output == OutputFormat::Pretty;
+      let path = resolve_db_path(cli.path, pretty);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
 
-          if let Some(doi) = &paper.doi {
-            println!("   {} {}", style("DOI:").green(), style(doi).blue().underlined());
-          }
+      if pretty {
+        println!("{} Looking for duplicate papers...", style(LOOKING_GLASS).cyan());
+      }
+      let progress = ProgressBarSink::new();
+      let result = db.find_duplicates(threshold, Some(&progress)).await;
+      progress.finish();
+      let pairs = result?;
+      let prompt = !dry_run && !cli.accept_defaults;
+
+      // A paper may be in several pairs, and once removed those pairs are moot
+      let mut removed = HashSet::new();
+      let mut results = Vec::new();
+      for (i, (first, second, similarity)) in pairs.iter().enumerate() {
+        let key = |paper: &Paper| (paper.source.clone(), paper.source_identifier.clone());
+        if removed.contains(&key(first)) || removed.contains(&key(second)) {
+          continue;
+        }
 
+        if pretty {
           println!(
-            "   {} {} {}",
-            style("Source:").green(),
-            style(&paper.source).cyan(),
-            style(&paper.source_identifier).yellow()
+            "\n{} {} {}",
+            style(WARNING).yellow(),
+            style(format!("Possible duplicates {}/{}", i + 1, pairs.len())).bold(),
+            style(format!("(similarity {similarity:.2})")).dim()
           );
+          print_paper_summary(1, first);
+          print_paper_summary(2, second);
+        }
+
+        let remove = if prompt {
+          let selection = dialoguer::Select::new()
+            .with_prompt("Which should be kept?")
+            .items(&["Both", "The first, removing the second", "The second, removing the first"])
+            .default(0)
+            .interact()?;
+          [None, Some((second, "second")), Some((first, "first"))][selection]
+        } else {
+          None
+        };
 
-          // Show a preview of the abstract
-          if !paper.abstract_text.is_empty() {
-            let preview = paper.abstract_text.chars().take(100).collect::<String>();
-            let preview =
-              if paper.abstract_text.len() > 100 { format!("{}...", preview) } else { preview };
-            println!("   {} {}", style("Abstract:").green(), style(preview).white().italic());
+        if let Some((paper, _)) = remove {
+          paper.remove(&db).await?;
+          removed.insert(key(paper));
+          if pretty {
+            println!(
+              "{} Removed {} {}",
+              style(SUCCESS).green(),
+              style(&paper.source).cyan(),
+              style(&paper.source_identifier).yellow()
+            );
           }
         }
+        results.push(serde_json::json!({
+          "similarity": similarity,
+          "first": first,
+          "second": second,
+          "removed": remove.map(|(_, which)| which),
+        }));
+      }
 
-        // If we have multiple results, show a tip about refining the search
-        if papers.len() > 1 {
+      if !pretty {
+        return print_json(&results);
+      }
+      if pairs.is_empty() {
+        println!("{} No likely duplicates found", style("ℹ").blue());
+      } else {
+        println!(
+          "\n{} Pairs: {}, Removed: {}",
+          style(SUCCESS).green(),
+          style(results.len()).yellow(),
+          style(removed.len()).yellow()
+        );
+        if !dry_run && cli.accept_defaults {
           println!(
-            "\n{} Tip: Use quotes for exact phrases, e.g. {}",
-            style("💡").yellow(),
-            style("\"exact phrase\"").yellow().italic()
+            "   {} Run without --accept-defaults to choose which papers to keep",
+            style("Tip:").blue().italic()
           );
         }
       }
       Ok(())
     },
 
-    Commands::Clean => {
-      let path = cli.path.unwrap_or_else(|| {
-        let default_path = Database::default_path();
-        println!(
-          "{} Using default database path: {}",
-          style(BOOKS).cyan(),
-          style(default_path.display()).yellow()
-        );
-        default_path
-      });
-      if path.exists() {
-        println!(
-          "{} Database found at: {}",
-          style(WARNING).yellow(),
-          style(path.display()).yellow()
-        );
-
-        // Skip confirmations if force flag is set
-        if !cli.accept_defaults {
-          // First confirmation
-          if !dialoguer::Confirm::new()
-            .with_prompt("Are you sure you want to delete this database?")
-            .default(false)
-            .wait_for_newline(true)
-            .interact()?
-          {
-            println!("{} Operation cancelled", style("✖").red());
-            return Ok(());
-          }
-
-          // Require typing DELETE for final confirmation
-          let input = dialoguer::Input::<String>::new()
-            .with_prompt(format!(
-              "{} Type {} to confirm deletion",
-              style("⚠️").red(),
-              style("DELETE").red().bold()
-            ))
-            .interact_text()?;
+    Commands::Update { source, identifier, all } => {
+      let path = resolve_db_path(cli.path, true);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+      use_crossref_email(&db).await?;
+
+      let targets = match (all, source, identifier) {
+        (false, Some(source), Some(identifier)) => vec![(source, identifier)],
+        (true, ..) => db
+          .list_papers(PaperSort::Date, false, usize::MAX, 0)
+          .await?
+          .into_iter()
+          .map(|paper| (paper.source, paper.source_identifier))
+          .collect(),
+        _ => unreachable!("clap requires a paper unless --all is given"),
+      };
 
-          if input != "DELETE" {
-            println!("{} Operation cancelled", style("✖").red());
-            return Ok(());
-          }
+      let (mut updated, mut last_error) = (0, None);
+      for (i, (source, identifier)) in targets.iter().enumerate() {
+        let progress = style(format!("[{}/{}]", i + 1, targets.len())).dim();
+        match db.update_paper_metadata(source, identifier).await {
+          Ok(paper) => {
+            updated += 1;
+            println!(
+              "{progress} {} {} {}: {}",
+              style(SUCCESS).green(),
+              style(source).cyan(),
+              style(identifier).yellow(),
+              style(&paper.title).white().bold()
+            );
+          },
+          Err(e) => {
+            println!(
+              "{progress} {} {} {}: {}",
+              style(WARNING).yellow(),
+              style(source).cyan(),
+              style(identifier).yellow(),
+              style(e.to_string()).red()
+            );
+            last_error = Some(e);
+          },
         }
+      }
 
-        // Proceed with deletion
+      let failed = targets.len() - updated;
+      if targets.len() > 1 {
         println!(
-          "{} Removing database: {}",
-          style(WARNING).yellow(),
-          style(path.display()).yellow()
+          "\n{} Updated: {}, Failed: {}",
+          style(BOOKS).cyan(),
+          style(updated).green(),
+          style(failed).red()
         );
-        std::fs::remove_file(&path)?;
+      }
 
-        // Also remove any FTS auxiliary files
-        let fts_files = glob::glob(&format!("{}*", path.display()))?;
-        for file in fts_files.flatten() {
-          std::fs::remove_file(file)?;
-        }
-        println!("{} Database files cleaned", style(SUCCESS).green());
-      } else {
-        println!(
-          "{} No database found at: {}",
-          style(WARNING).yellow(),
-          style(path.display()).yellow()
-        );
+      // Only fail the command as a whole if nothing could be updated
+      match last_error {
+        Some(e) if failed == targets.len() => Err(e.into()),
+        _ => Ok(()),
       }
-      Ok(())
     },
 
-    Commands::Download { source, identifier } => {
-      let path = cli.path.unwrap_or_else(|| {
-        let default_path = Database::default_path();
-        println!(
-          "{} Using default database path: {}",
-          style(BOOKS).cyan(),
-          style(default_path.display()).yellow()
-        );
-        default_path
-      });
+    Commands::Download { source, identifier, all, concurrency } => {
+      let path = resolve_db_path(cli.path, true);
       let db = Database::open(&path).await?;
 
+      // clap requires a paper unless --all is given
+      let (false, Some(source), Some(identifier)) = (all, source, identifier) else {
+        return download_all(&db, concurrency).await;
+      };
+
       let paper = match db.get_paper_by_source_id(&source, &identifier).await? {
         Some(p) => p,
         None => {
@@ -718,17 +3444,7 @@ async fn main() -> Result<(), LearnerdErrors> {
         return Ok(());
       };
 
-      let pdf_dir = match db.get_config("pdf_dir").await? {
-        Some(dir) => PathBuf::from(dir),
-        None => {
-          println!(
-            "{} PDF directory not configured. Run {} first",
-            style(WARNING).yellow(),
-            style("learnerd init").cyan()
-          );
-          return Ok(());
-        },
-      };
+      let pdf_dir = pdf_dir(&db).await?;
 
       if !pdf_dir.exists() {
         println!(
@@ -739,20 +3455,23 @@ async fn main() -> Result<(), LearnerdErrors> {
         std::fs::create_dir_all(&pdf_dir)?;
       }
 
-      let formatted_title = learner::format::format_title(&paper.title, Some(50));
-      let pdf_path = pdf_dir.join(format!("{}.pdf", formatted_title));
+      let template = db.get_pdf_filename_template().await?;
+      let pdf_path = paper.pdf_path.clone().unwrap_or_else(|| {
+        pdf_dir.join(format!("{}.pdf", paper.format_filename(&template, Some(50))))
+      });
 
-      let should_download = if pdf_path.exists() && !cli.accept_defaults {
+      let should_download = if pdf_path.exists() && !cli.force {
         println!(
           "{} PDF already exists at: {}",
           style("ℹ").blue(),
           style(&pdf_path.display()).yellow()
         );
 
-        dialoguer::Confirm::new()
-          .with_prompt("Download fresh copy? (This will overwrite the existing file)")
-          .default(false)
-          .interact()?
+        confirm(
+          "Download fresh copy? (This will overwrite the existing file)",
+          false,
+          cli.accept_defaults,
+        )?
       } else {
         true
       };
@@ -764,8 +3483,8 @@ async fn main() -> Result<(), LearnerdErrors> {
           println!("{} Downloading PDF...", style(LOOKING_GLASS).cyan());
         }
 
-        match paper.download_pdf(pdf_dir.clone()).await {
-          Ok(_) => {
+        match download_pdf_with_bar(&paper, &db, pdf_dir.clone(), true).await {
+          Ok(pdf_path) => {
             println!("{} PDF downloaded successfully!", style(SUCCESS).green());
             println!("   {} Saved to: {}", style("📄").cyan(), style(&pdf_path.display()).yellow());
           },
@@ -787,6 +3506,25 @@ async fn main() -> Result<(), LearnerdErrors> {
                   style("Tip:").blue()
                 );
               },
+              LearnerError::InvalidPdf(_) => {
+                println!(
+                  "   {} The publisher didn't send a complete PDF, likely due to a login or bot \
+                   check",
+                  style("Note:").blue()
+                );
+                println!(
+                  "   {} You may need to download this paper directly from the publisher's website",
+                  style("Tip:").blue()
+                );
+              },
+              LearnerError::Timeout(_) => {
+                println!(
+                  "   {} The server took too long to respond. Try again later, or allow more time \
+                   with {}",
+                  style("Tip:").blue(),
+                  style("LEARNER_HTTP_TIMEOUT_SECS").yellow()
+                );
+              },
               LearnerError::Network(_) => {
                 println!(
                   "   {} Check your internet connection and try again",
@@ -804,7 +3542,7 @@ async fn main() -> Result<(), LearnerdErrors> {
                 println!(
                   "   {} Try using {} to skip prompts",
                   style("Tip:").blue(),
-                  style("--accept-defaults").yellow()
+                  style("--yes").yellow()
                 );
               },
             }
@@ -815,13 +3553,137 @@ async fn main() -> Result<(), LearnerdErrors> {
       Ok(())
     },
 
+    Commands::Open { source, identifier, print_path } => {
+      let path = resolve_db_path(cli.path, true);
+      let db = Database::open(&path).await?;
+
+      let Some(paper) = db.get_paper_by_source_id(&source, &identifier).await? else {
+        println!(
+          "{} Paper not found in database. Add it first with: {} {}",
+          style(WARNING).yellow(),
+          style("learnerd add").yellow(),
+          style(&identifier).cyan()
+        );
+        return Ok(());
+      };
+
+      let mut pdf_path = pdf_location(&db, &paper).await?;
+      if !pdf_path.exists() {
+        let Some(pdf_url) = &paper.pdf_url else {
+          println!("{} No PDF available for this paper", style(WARNING).yellow());
+          return Ok(());
+        };
+        if !confirm("PDF not downloaded yet. Download it now?", true, cli.accept_defaults)? {
+          println!("   {} Read it online at: {}", style("Tip:").blue(), style(pdf_url).blue());
+          return Ok(());
+        }
+
+        println!("{} Downloading PDF...", style(LOOKING_GLASS).cyan());
+        let pdf_dir = pdf_dir(&db).await?;
+        std::fs::create_dir_all(&pdf_dir)?;
+        match download_pdf_with_bar(&paper, &db, pdf_dir, true).await {
+          Ok(downloaded) => pdf_path = downloaded,
+          Err(e) => {
+            println!(
+              "{} Failed to download PDF: {}",
+              style(WARNING).yellow(),
+              style(e.to_string()).red()
+            );
+            println!("   {} Read it online at: {}", style("Tip:").blue(), style(pdf_url).blue());
+            return Ok(());
+          },
+        }
+      }
+
+      if print_path {
+        println!("{}", pdf_path.display());
+        return Ok(());
+      }
+
+      println!("{} Opening {}", style(PAPER).green(), style(pdf_path.display()).yellow());
+      if let Err(e) = open_with_system_viewer(&pdf_path) {
+        println!("{} Couldn't open the PDF: {}", style(WARNING).yellow(), style(e).red());
+        if let Some(pdf_url) = &paper.pdf_url {
+          println!("   {} Read it online at: {}", style("Tip:").blue(), style(pdf_url).blue());
+        }
+      }
+      Ok(())
+    },
+
+    Commands::Recommend { source, identifier, limit } => {
+      let pretty = cli.output == OutputFormat::Pretty;
+      let path = resolve_db_path(cli.path, pretty);
+      trace!("Using database at: {}", path.display());
+      let db = Database::open(&path).await?;
+
+      if pretty {
+        println!(
+          "{} Finding papers related to {} {}",
+          style(LOOKING_GLASS).cyan(),
+          style(&source).cyan(),
+          style(&identifier).yellow()
+        );
+      }
+      // Papers in the database can also be looked up by their DOI
+      let client = SemanticScholarClient::new();
+      let papers = match db.get_paper_by_source_id(&source, &identifier).await? {
+        Some(paper) => paper.find_related(&client, limit).await?,
+        None => {
+          let paper_id = lookup_id(&source, &identifier).ok_or_else(|| {
+            LearnerError::ApiError(format!(
+              "{source} {identifier} can't be looked up on Semantic Scholar; add it first to look \
+               it up by its DOI"
+            ))
+          })?;
+          client.recommendations(&paper_id, limit).await?
+        },
+      };
+
+      if !pretty {
+        print_json(&papers)?;
+      } else if papers.is_empty() {
+        println!("{} No related papers found", style(WARNING).yellow());
+      } else {
+        println!("\n{} {} related papers:", style(SUCCESS).green(), style(papers.len()).yellow());
+        for (i, paper) in papers.iter().enumerate() {
+          print_paper_summary(i + 1, paper);
+        }
+        println!(
+          "\n{} Add one with: {}",
+          style("Tip:").blue().italic(),
+          style(format!("learnerd add {}", papers[0].source_identifier)).yellow()
+        );
+      }
+      Ok(())
+    },
+
+    Commands::Cache { cmd } => {
+      let path = resolve_db_path(cli.path, false);
+      let cache = Cache::new(cache_dir(&path));
+      match cmd {
+        CacheCommands::Clear => {
+          let removed = cache.clear()?;
+          if cli.output == OutputFormat::Json {
+            return print_json(&serde_json::json!({ "removed": removed }));
+          }
+          println!(
+            "{} Removed {} cached papers from {}",
+            style(SUCCESS).green(),
+            style(removed).yellow(),
+            style(cache.dir().display()).yellow()
+          );
+        },
+      }
+      Ok(())
+    },
+
     Commands::Daemon { cmd } => {
       let daemon = daemon::Daemon::new();
 
       match cmd {
         DaemonCommands::Start => {
           println!("{} Starting daemon...", style(ROCKET).cyan());
-          match daemon.start() {
+          match daemon.start().await {
             Ok(_) => println!("{} Daemon started successfully", style(SUCCESS).green()),
             Err(e) => {
               println!("{} Failed to start daemon: {}", style(WARNING).yellow(), style(&e).red());
@@ -841,7 +3703,7 @@ async fn main() -> Result<(), LearnerdErrors> {
         },
         DaemonCommands::Restart => {
           println!("{} Restarting daemon...", style(ROCKET).cyan());
-          match daemon.restart() {
+          match daemon.restart().await {
             Ok(_) => println!("{} Daemon restarted successfully", style(SUCCESS).green()),
             Err(e) => {
               println!("{} Failed to restart daemon: {}", style(WARNING).yellow(), style(&e).red());
@@ -917,9 +3779,124 @@ async fn main() -> Result<(), LearnerdErrors> {
               style("Tip:").blue(),
               style("sudo launchctl list | grep learnerd").yellow()
             );
+
+            #[cfg(target_os = "windows")]
+            println!(
+              "\n{} For detailed status, run: {}",
+              style("Tip:").blue(),
+              style("sc.exe query learnerd").yellow()
+            );
           } else {
             println!("{} Daemon is not running", style(WARNING).yellow());
           }
+
+          // Only look at the database if there is one, rather than creating it
+          let config = WatchConfig::load(&daemon.working_dir)?.unwrap_or_default();
+          let path = cli.path.unwrap_or_else(|| config.database_path());
+          let mut categories = config.categories.clone();
+          let mut interval = config.interval();
+          if path.exists() {
+            let db = Database::open(&path).await?;
+            interval = poll_interval(&db).await.unwrap_or(interval);
+            for target in db.list_watch_targets().await? {
+              if !categories.contains(&target.category) {
+                categories.push(target.category);
+              }
+            }
+          }
+          if categories.is_empty() {
+            println!(
+              "\n{} Not watching any categories. Add one with {}",
+              style("ℹ").blue(),
+              style("learnerd daemon watch add <category>").yellow()
+            );
+          } else {
+            println!(
+              "\n{} Watching {} every {}s",
+              style(LOOKING_GLASS).cyan(),
+              style(categories.join(", ")).cyan(),
+              style(interval.as_secs()).yellow()
+            );
+          }
+          match SyncState::load(&daemon.working_dir)? {
+            Some(state) => println!(
+              "   Last sync: {}, papers added: {}",
+              style(state.last_sync.format("%Y-%m-%d %H:%M")).yellow(),
+              style(state.papers_added).green()
+            ),
+            None => println!("   Never synced"),
+          }
+        },
+        DaemonCommands::Watch { cmd } => {
+          let path = match cli.path {
+            Some(path) => path,
+            None => WatchConfig::load(&daemon.working_dir)?.unwrap_or_default().database_path(),
+          };
+          trace!("Using database at: {}", path.display());
+          let db = Database::open(&path).await?;
+
+          match cmd {
+            WatchCommands::Add { category, source } => {
+              if source != Source::Arxiv {
+                return Err(LearnerdErrors::InvalidConfig(format!(
+                  "only arxiv categories can be watched, not {source}"
+                )));
+              }
+              if db.add_watch_target(&category, &source).await? {
+                println!(
+                  "{} Watching {} {} for new papers",
+                  style(SUCCESS).green(),
+                  style(&source).cyan(),
+                  style(category.trim()).yellow()
+                );
+              } else {
+                println!(
+                  "{} Already watching {} {}",
+                  style("ℹ").blue(),
+                  style(&source).cyan(),
+                  style(category.trim()).yellow()
+                );
+              }
+            },
+            WatchCommands::Remove { category, source } => {
+              if db.remove_watch_target(&category, &source).await? {
+                println!(
+                  "{} Stopped watching {} {}",
+                  style(SUCCESS).green(),
+                  style(&source).cyan(),
+                  style(category.trim()).yellow()
+                );
+              } else {
+                println!(
+                  "{} Not watching {} {}",
+                  style(WARNING).yellow(),
+                  style(&source).cyan(),
+                  style(category.trim()).yellow()
+                );
+              }
+            },
+            WatchCommands::List => {
+              let targets = db.list_watch_targets().await?;
+              if targets.is_empty() {
+                println!(
+                  "{} Not watching any categories. Add one with {}",
+                  style("ℹ").blue(),
+                  style("learnerd daemon watch add <category>").yellow()
+                );
+              }
+              for target in targets {
+                let last_checked = target.last_checked.map_or("never".to_string(), |checked| {
+                  checked.format("%Y-%m-%d %H:%M").to_string()
+                });
+                println!(
+                  "   {} {} (last checked: {})",
+                  style(&target.source).cyan(),
+                  style(&target.category).yellow(),
+                  style(last_checked).dim()
+                );
+              }
+            },
+          }
         },
       }
       Ok(())
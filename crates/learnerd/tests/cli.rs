@@ -5,6 +5,10 @@
 use std::path::PathBuf;
 
 use assert_cmd::Command;
+use learner::{
+  database::Database,
+  paper::{Paper, Source},
+};
 use predicates::prelude::*;
 use serial_test::serial;
 use tempfile::tempdir;
@@ -19,34 +23,1393 @@ fn temp_db() -> (tempfile::TempDir, PathBuf) {
   (dir, db_path)
 }
 
+// Helper to build an arXiv paper without fetching it
+fn test_paper(identifier: &str, title: &str, authors: &[&str]) -> Paper {
+  serde_json::from_value(serde_json::json!({
+    "title": title,
+    "authors": authors.iter().map(|name| serde_json::json!({"name": name})).collect::<Vec<_>>(),
+    "abstract_text": "An abstract",
+    "publication_date": "2024-01-01T00:00:00Z",
+    "source": "Arxiv",
+    "source_identifier": identifier,
+    "pdf_url": null,
+    "doi": null
+  }))
+  .unwrap()
+}
+
+// Helper to serve a small PDF to every request from a local server, returning its URL
+fn serve_pdf() -> String {
+  use std::io::{Read, Write};
+
+  let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+  let url = format!("http://{}/paper.pdf", listener.local_addr().unwrap());
+  std::thread::spawn(move || {
+    for mut socket in listener.incoming().flatten() {
+      let _ = socket.read(&mut [0; 4096]);
+      let body = "%PDF-1.7 test\n%%EOF\n";
+      let _ = write!(
+        socket,
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+      );
+    }
+  });
+  url
+}
+
+// Helper to save papers straight to a database, so tests don't need the network
+async fn seed_db(db_path: &PathBuf, papers: &[Paper]) {
+  let db = Database::open(db_path).await.unwrap();
+  for paper in papers {
+    db.save_paper(paper).await.unwrap();
+  }
+}
+
+#[test]
+#[serial]
+fn test_init_and_clean() {
+  let (dir, db_path) = temp_db();
+
+  // Initialize database
+  learnerd()
+    .arg("init")
+    .arg("--path")
+    .arg(&db_path)
+    .arg("--accept-defaults")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("initialized successfully"));
+
+  assert!(db_path.exists());
+
+  // Clean with force flag
+  learnerd()
+    .arg("clean")
+    .arg("--path")
+    .arg(&db_path)
+    .arg("--force")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Database files cleaned"));
+
+  assert!(!db_path.exists());
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_clean_accept_defaults_requires_force() {
+  let (dir, db_path) = temp_db();
+
+  learnerd().arg("init").arg("--path").arg(&db_path).arg("--accept-defaults").assert().success();
+
+  // Accepting defaults declines the destructive confirmation
+  learnerd()
+    .arg("clean")
+    .arg("--path")
+    .arg(&db_path)
+    .arg("--accept-defaults")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("--force"))
+    .stdout(predicate::str::contains("Operation cancelled"));
+
+  assert!(db_path.exists());
+
+  // The same holds for reinitialization
+  learnerd()
+    .arg("init")
+    .arg("--path")
+    .arg(&db_path)
+    .arg("-y")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Keeping existing database"));
+
+  assert!(db_path.exists());
+
+  learnerd()
+    .arg("clean")
+    .arg("--path")
+    .arg(&db_path)
+    .arg("--yes")
+    .arg("--force")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Database files cleaned"));
+
+  assert!(!db_path.exists());
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_remove_missing_paper() {
+  let (dir, db_path) = temp_db();
+
+  learnerd().arg("init").arg("--path").arg(&db_path).arg("-y").assert().success();
+
+  learnerd()
+    .arg("remove")
+    .arg("iacr")
+    .arg("2016/260")
+    .arg("--path")
+    .arg(&db_path)
+    .assert()
+    .code(2)
+    .stdout(predicate::str::contains("Paper not found"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_remove_deletes_recorded_pdf() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[test_paper("2301.07041", "Verifiable Fully Homomorphic Encryption", &[
+    "Alexander Viand",
+  ])])
+  .await;
+
+  // A PDF whose name doesn't follow the current naming rules is still found
+  let pdf_path = dir.path().join("renamed.pdf");
+  std::fs::write(&pdf_path, b"%PDF-1.7 test").unwrap();
+  let db = Database::open(&db_path).await.unwrap();
+  let paper_id = db.get_paper_id(&Source::Arxiv, "2301.07041").await.unwrap().unwrap();
+  db.record_pdf(paper_id, pdf_path.clone(), "renamed.pdf".into(), "success", None).await.unwrap();
+
+  learnerd()
+    .args(["get", "arxiv", "2301.07041", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("downloaded at"))
+    .stdout(predicate::str::contains("renamed.pdf"));
+  learnerd()
+    .args(["remove", "arxiv", "2301.07041", "--with-pdf", "--force", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Deleted PDF"));
+  assert!(!pdf_path.exists());
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_remove_accept_defaults_requires_force() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[test_paper("2301.07041", "Verifiable Fully Homomorphic Encryption", &[
+    "Alexander Viand",
+  ])])
+  .await;
+
+  learnerd()
+    .args(["remove", "arxiv", "2301.07041", "-y", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("pass --force"))
+    .stdout(predicate::str::contains("Removed paper").not());
+  learnerd().args(["get", "arxiv", "2301.07041", "--path"]).arg(&db_path).assert().success();
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_config_set_and_get() {
+  let (dir, db_path) = temp_db();
+
+  learnerd()
+    .args(["config", "get", "pdf_filename_template", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("{title}"));
+  learnerd()
+    .args(["config", "set", "pdf_filename_template", "{year}_{first_author}_{title}", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success();
+  learnerd()
+    .args(["config", "get", "pdf_filename_template", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("{year}_{first_author}_{title}"));
+
+  // Misspelled placeholders are rejected rather than ending up in file names
+  learnerd()
+    .args(["config", "set", "pdf_filename_template", "{yaer}_{title}", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("unknown placeholder {yaer}"));
+  learnerd()
+    .args(["config", "get", "pdf_filename_template", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("{year}_{first_author}_{title}"));
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_cache() {
+  let (dir, db_path) = temp_db();
+  let cache_dir = dir.path().join("cache");
+
+  learnerd()
+    .args(["config", "get", "cache_ttl_secs", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("3600"));
+  learnerd()
+    .args(["config", "set", "cache_ttl_secs", "soon", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("cache_ttl_secs must be a number of seconds"));
+  learnerd()
+    .args(["config", "set", "cache_ttl_secs", "7200", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success();
+
+  // A paper fetched within the time to live is added from the cache, without the network
+  let entry = serde_json::json!({
+    "fetched_at": chrono::Utc::now(),
+    "paper": test_paper("2401.00001", "Cached Lattices", &["Alice"]),
+  });
+  std::fs::create_dir_all(&cache_dir).unwrap();
+  std::fs::write(cache_dir.join("arxiv_2401.00001.json"), entry.to_string()).unwrap();
+  learnerd()
+    .args(["add", "2401.00001", "--no-pdf", "--path"])
+    .arg(&db_path)
+    .arg("-y")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Cached Lattices"));
+
+  learnerd()
+    .args(["--output", "json", "cache", "clear", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("\"removed\": 1"));
+  assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 0);
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_verify_pdfs() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[
+    test_paper("2401.00001", "Lattice Signatures", &["Alice"]),
+    test_paper("2401.00002", "Graph Neural Networks", &["Bob"]),
+    test_paper("2401.00003", "Network Protocols", &["Carol"]),
+  ])
+  .await;
+
+  let db = Database::open(&db_path).await.unwrap();
+  for (id, content) in
+    [("2401.00001", &b"%PDF-1.7 test\n%%EOF\n"[..]), ("2401.00002", &b"<html>Sign in</html>"[..])]
+  {
+    let pdf_path = dir.path().join(format!("{id}.pdf"));
+    std::fs::write(&pdf_path, content).unwrap();
+    let paper_id = db.get_paper_id(&Source::Arxiv, id).await.unwrap().unwrap();
+    db.record_pdf(paper_id, pdf_path, format!("{id}.pdf"), "success", None).await.unwrap();
+  }
+
+  learnerd()
+    .args(["verify-pdfs", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(
+      "Graph Neural Networks (arxiv 2401.00002): not a complete PDF",
+    ))
+    .stdout(predicate::str::contains("Lattice Signatures").not())
+    .stdout(predicate::str::contains("Valid: 1, Missing: 0, Invalid: 1"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_recent() {
+  let (dir, db_path) = temp_db();
+
+  learnerd()
+    .args(["recent", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("No papers saved yet"));
+
+  seed_db(&db_path, &[
+    test_paper("2401.00001", "On Computable Numbers", &["Alan Turing"]),
+    test_paper("2401.00002", "An Unsolvable Problem", &["Alonzo Church"]),
+  ])
+  .await;
+
+  learnerd()
+    .args(["recent", "--limit", "1", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("An Unsolvable Problem"))
+    .stdout(predicate::str::contains("Added:"))
+    .stdout(predicate::str::contains("On Computable Numbers").not());
+
+  let output =
+    learnerd().args(["--output", "json", "recent", "--path"]).arg(&db_path).output().unwrap();
+  assert!(output.status.success());
+  let papers: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+  assert_eq!(papers[0]["source_identifier"], "2401.00002");
+  assert_eq!(papers[1]["source_identifier"], "2401.00001");
+  assert!(papers[0]["added_at"].is_string());
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_daemon_watch_list() {
+  let (dir, db_path) = temp_db();
+
+  learnerd()
+    .args(["daemon", "watch", "add", "cs.CR", "arxiv", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Watching arxiv cs.CR"));
+  learnerd()
+    .args(["daemon", "watch", "add", "cs.CR", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Already watching"));
+  learnerd()
+    .args(["daemon", "watch", "add", "crypto", "iacr", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("only arxiv categories can be watched"));
+  learnerd()
+    .args(["daemon", "watch", "list", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("cs.CR (last checked: never)"))
+    .stdout(predicate::str::contains("crypto").not());
+
+  learnerd()
+    .args(["config", "set", "daemon_poll_interval_secs", "0", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("must be a positive number of seconds"));
+  learnerd()
+    .args(["config", "set", "daemon_poll_interval_secs", "1800", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success();
+  learnerd()
+    .args(["daemon", "status", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Watching cs.CR every 1800s"));
+
+  learnerd()
+    .args(["daemon", "watch", "remove", "cs.CR", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Stopped watching arxiv cs.CR"));
+  learnerd()
+    .args(["daemon", "watch", "list", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Not watching any categories"));
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_list_empty_database() {
+  let (dir, db_path) = temp_db();
+
+  learnerd().arg("init").arg("--path").arg(&db_path).arg("-y").assert().success();
+
+  learnerd()
+    .args(["list", "--sort", "title", "--reverse", "--limit", "5", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("No papers to show"));
+
+  learnerd().args(["list", "--sort", "size", "--path"]).arg(&db_path).assert().failure();
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_get_by_doi() {
+  let (dir, db_path) = temp_db();
+  let mut preprint = test_paper("0000.00001", "MapReduce", &["Jeffrey Dean"]);
+  preprint.doi = Some("10.1145/1327452.1327492".to_string());
+  seed_db(&db_path, &[preprint]).await;
+
+  learnerd()
+    .args(["get", "--doi", "10.1145/1327452.1327492", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("MapReduce"))
+    .stdout(predicate::str::contains("0000.00001"));
+  learnerd()
+    .args(["get", "--doi", "10.1145/0000000", "--path"])
+    .arg(&db_path)
+    .assert()
+    .code(2)
+    .stdout(predicate::str::contains("Paper not found"));
+
+  // Either a DOI or a source and identifier
+  learnerd()
+    .args(["get", "arxiv", "0000.00001", "--doi", "10.1145/1327452.1327492", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure();
+  learnerd().args(["get", "--path"]).arg(&db_path).assert().failure();
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_paths_from_environment() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[test_paper("2401.00001", "On Computable Numbers", &["Alan Turing"])]).await;
+
+  learnerd()
+    .env("LEARNER_DB_PATH", &db_path)
+    .args(["get", "arxiv", "2401.00001"])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("On Computable Numbers"))
+    .stdout(predicate::str::contains("Using default database path").not());
+
+  // --path takes precedence over the environment
+  let other_db = dir.path().join("other.db");
+  learnerd()
+    .env("LEARNER_DB_PATH", &db_path)
+    .args(["get", "arxiv", "2401.00001", "--path"])
+    .arg(&other_db)
+    .assert()
+    .code(2)
+    .stdout(predicate::str::contains("Paper not found"));
+
+  // init stores PDFs in LEARNER_PDF_DIR by default
+  let (new_db, pdf_dir) = (dir.path().join("new.db"), dir.path().join("pdfs"));
+  learnerd()
+    .env("LEARNER_DB_PATH", &new_db)
+    .env("LEARNER_PDF_DIR", &pdf_dir)
+    .args(["init", "-y"])
+    .assert()
+    .success();
+  learnerd()
+    .args(["config", "get", "pdf_dir", "--path"])
+    .arg(&new_db)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(pdf_dir.to_string_lossy()));
+  assert!(pdf_dir.is_dir());
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_export_bibtex() {
+  let (dir, db_path) = temp_db();
+  let output = dir.path().join("refs.bib");
+
+  learnerd().arg("init").arg("--path").arg(&db_path).arg("-y").assert().success();
+
+  learnerd()
+    .args(["export", "bibtex", "--output"])
+    .arg(&output)
+    .arg("--path")
+    .arg(&db_path)
+    .assert()
+    .success();
+  assert!(std::fs::read_to_string(&output).unwrap().is_empty());
+
+  learnerd()
+    .args(["export", "bibtex", "arxiv", "2301.07041", "--path"])
+    .arg(&db_path)
+    .assert()
+    .code(2)
+    .stdout(predicate::str::is_empty());
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_collections() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[
+    test_paper("2401.00001", "Lattice Signatures", &["Alice"]),
+    test_paper("2401.00002", "Graph Neural Networks", &["Bob"]),
+  ])
+  .await;
+
+  learnerd()
+    .args(["collection", "create", "Cryptography", "--description", "For the survey", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success();
+  learnerd()
+    .args(["collection", "add", "arxiv", "2401.00001", "Cryptography", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Added to Cryptography"));
+
+  learnerd()
+    .args(["collection", "list", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Cryptography (1 papers): For the survey"));
+  learnerd()
+    .args(["collection", "list", "Cryptography", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Lattice Signatures"))
+    .stdout(predicate::str::contains("Graph Neural Networks").not());
+
+  learnerd()
+    .args(["export", "bibtex", "--collection", "cryptography", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("title = {Lattice Signatures}"))
+    .stdout(predicate::str::contains("Graph Neural Networks").not());
+  learnerd()
+    .args(["export", "bibtex", "--collection", "Biology", "--path"])
+    .arg(&db_path)
+    .assert()
+    .code(2)
+    .stderr(predicate::str::contains("Biology"));
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_export_formats_empty_database() {
+  let (dir, db_path) = temp_db();
+  let output = dir.path().join("papers.json");
+
+  learnerd().arg("init").arg("--path").arg(&db_path).arg("-y").assert().success();
+
+  learnerd()
+    .args(["export", "json", "--output"])
+    .arg(&output)
+    .arg("--path")
+    .arg(&db_path)
+    .assert()
+    .success();
+  let papers: Vec<Paper> =
+    serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+  assert!(papers.is_empty());
+
+  learnerd()
+    .args(["export", "ris", "--query", "homomorphic", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::is_empty());
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_export_markdown_and_html() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[
+    test_paper("2401.00001", "On Computable Numbers", &["Alan Turing"]),
+    test_paper("2401.00002", "An Unsolvable Problem", &["Alonzo Church"]),
+  ])
+  .await;
+
+  learnerd()
+    .args(["export", "markdown", "--sort", "title", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::starts_with("# Papers\n\n## arXiv\n"))
+    .stdout(predicate::str::contains(
+      "### [An Unsolvable Problem](<https://arxiv.org/abs/2401.00002>)",
+    ))
+    .stdout(predicate::str::is_match("(?s)Unsolvable.*Computable").unwrap());
+
+  let output = dir.path().join("papers.html");
+  learnerd()
+    .args(["export", "html", "--group-by", "year", "--output"])
+    .arg(&output)
+    .arg("--path")
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stderr(predicate::str::contains("Exported 2 papers"));
+  let html = std::fs::read_to_string(&output).unwrap();
+  assert!(html.starts_with("<!DOCTYPE html>"));
+  assert_eq!(html.matches("<article>").count(), 2);
+
+  learnerd()
+    .args(["export", "markdown", "--group-by", "author", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure();
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_export_json_round_trip() {
+  let (dir, db_path) = temp_db();
+  let output = dir.path().join("papers.json");
+
+  learnerd().arg("init").arg("--path").arg(&db_path).arg("-y").assert().success();
+  learnerd()
+    .args(["add", "2301.07041", "--no-pdf", "--path"])
+    .arg(&db_path)
+    .arg("-y")
+    .assert()
+    .success();
+
+  learnerd()
+    .args(["export", "json", "--output"])
+    .arg(&output)
+    .arg("--path")
+    .arg(&db_path)
+    .assert()
+    .success();
+
+  let papers: Vec<Paper> =
+    serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+  assert_eq!(papers.len(), 1);
+  assert_eq!(papers[0].source_identifier, "2301.07041");
+  assert!(papers[0].title.contains("Verifiable Fully Homomorphic"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_add_yes_downloads_pdf() {
+  let (dir, db_path) = temp_db();
+
+  learnerd().arg("init").arg("--path").arg(&db_path).arg("-y").assert().success();
+
+  // Downloading is the default answer, so `-y` downloads the PDF
+  learnerd()
+    .arg("add")
+    .arg("2301.07041")
+    .arg("--path")
+    .arg(&db_path)
+    .arg("-y")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("PDF downloaded successfully"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_search_by_author() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[
+    test_paper("2401.00001", "On Computable Numbers", &["Alan Turing"]),
+    test_paper("2401.00002", "Computing Machinery and Intelligence", &["Alan Turing"]),
+    test_paper("2401.00003", "An Unsolvable Problem", &["Alonzo Church"]),
+  ])
+  .await;
+
+  learnerd()
+    .args(["search", "--author", "tur", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 2 papers"))
+    .stdout(predicate::str::contains("On Computable Numbers"))
+    .stdout(predicate::str::contains("Computing Machinery"))
+    .stdout(predicate::str::contains("Unsolvable").not());
+
+  // Combined with a query, only papers matching both are shown
+  learnerd()
+    .args(["search", "intelligence", "--author", "TURING", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 1 papers"))
+    .stdout(predicate::str::contains("Computing Machinery"));
+
+  learnerd()
+    .args(["search", "--author", "Hopper", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("No papers found"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_json_output() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[
+    test_paper("2401.00001", "On Computable Numbers", &["Alan Turing"]),
+    test_paper("2401.00002", "An Unsolvable Problem", &["Alonzo Church"]),
+  ])
+  .await;
+
+  let json = |args: &[&str]| -> serde_json::Value {
+    let output = learnerd()
+      .args(["--output", "json"])
+      .args(args)
+      .arg("--path")
+      .arg(&db_path)
+      .output()
+      .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+  };
+
+  let papers = json(&["list", "--sort", "title"]);
+  assert_eq!(papers.as_array().unwrap().len(), 2);
+  assert_eq!(papers[0]["title"], "An Unsolvable Problem");
+
+  let papers = json(&["search", "computable"]);
+  assert_eq!(papers.as_array().unwrap().len(), 1);
+  assert_eq!(papers[0]["source_identifier"], "2401.00001");
+
+  assert_eq!(json(&["search", "nothing"]), serde_json::json!([]));
+
+  let paper = json(&["get", "arxiv", "2401.00002"]);
+  assert_eq!(paper["authors"][0]["name"], "Alonzo Church");
+
+  let removed = json(&["remove", "arxiv", "2401.00002", "--force"]);
+  assert_eq!(removed["removed"], true);
+  assert_eq!(removed["paper"]["title"], "An Unsolvable Problem");
+
+  // Errors are reported as JSON on stderr, with a failing exit code
+  let output = learnerd()
+    .args(["--output", "json", "get", "arxiv", "2401.00002", "--path"])
+    .arg(&db_path)
+    .output()
+    .unwrap();
+  assert_eq!(output.status.code(), Some(2));
+  assert!(output.stdout.is_empty());
+  let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+  assert!(error["error"].is_string());
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_search_by_keyword() {
+  let (dir, db_path) = temp_db();
+  let mut papers = [
+    test_paper("2401.00001", "Lattice Signatures", &["Alice"]),
+    test_paper("2401.00002", "Graph Neural Networks", &["Bob"]),
+    test_paper("10.1000/xyz", "Network Protocols", &["Carol"]),
+  ];
+  papers[0].keywords = vec!["cs.CR".to_string()];
+  papers[1].keywords = vec!["cs.LG".to_string()];
+  papers[2].source = Source::DOI;
+  papers[2].keywords = vec!["Computer Networks and Communications".to_string()];
+  seed_db(&db_path, &papers).await;
+
+  learnerd()
+    .args(["search", "--keyword", "cs.cr", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 1 papers"))
+    .stdout(predicate::str::contains("Lattice Signatures"));
+
+  learnerd()
+    .args(["search", "networks", "--keyword", "cs.LG", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 1 papers"))
+    .stdout(predicate::str::contains("Graph Neural Networks"));
+
+  learnerd()
+    .args(["get", "doi", "10.1000/xyz", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Keywords: Computer Networks and Communications"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_search_date_range() {
+  let (dir, db_path) = temp_db();
+  let mut papers = [
+    test_paper("2001.00001", "Lattice Signatures", &["Alice"]),
+    test_paper("2101.00001", "Lattice Encryption", &["Bob"]),
+    test_paper("2201.00001", "Lattice Reduction", &["Alice"]),
+  ];
+  papers[0].publication_date = "2020-03-01T00:00:00Z".parse().unwrap();
+  papers[1].publication_date = "2021-12-31T18:00:00Z".parse().unwrap();
+  papers[2].publication_date = "2022-03-01T00:00:00Z".parse().unwrap();
+  seed_db(&db_path, &papers).await;
+
+  // The last day of the range is included in full
+  learnerd()
+    .args(["search", "lattice", "--from", "2021-01-01", "--to", "2021-12-31", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 1 papers"))
+    .stdout(predicate::str::contains("Lattice Encryption"));
+  learnerd()
+    .args(["search", "lattice", "--from", "2021-01-01", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 2 papers"))
+    .stdout(predicate::str::contains("Lattice Signatures").not());
+  learnerd()
+    .args(["search", "--author", "Alice", "--to", "2021-01-01", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 1 papers"))
+    .stdout(predicate::str::contains("Lattice Signatures"));
+
+  learnerd()
+    .args(["search", "lattice", "--from", "01/01/2021", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("expected a date like 2020-01-31"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_stats() {
+  let (dir, db_path) = temp_db();
+  let mut papers = [
+    test_paper("2401.00001", "Lattice Signatures", &["Alice"]),
+    test_paper("2401.00002", "Graph Neural Networks", &["Bob"]),
+    test_paper("10.1000/xyz", "Network Protocols", &["Carol"]),
+  ];
+  papers[1].publication_date = "2019-05-01T00:00:00Z".parse().unwrap();
+  papers[2].source = Source::DOI;
+  seed_db(&db_path, &papers).await;
+
+  learnerd()
+    .args(["stats", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::is_match(r"Total papers:\s+3\n").unwrap())
+    .stdout(predicate::str::is_match(r"With PDF:\s+0\n").unwrap())
+    .stdout(predicate::str::is_match(r"arxiv\s+2\n").unwrap())
+    .stdout(predicate::str::is_match(r"doi\s+1\n").unwrap())
+    .stdout(predicate::str::is_match(r"2019\s+1\n\s+2024\s+2\n").unwrap())
+    .stdout(predicate::str::is_match(r"Authors:\s+3\n").unwrap())
+    .stdout(predicate::str::contains("Database size:"));
+
+  let output = learnerd()
+    .args(["--output", "json", "stats", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+  let stats: serde_json::Value = serde_json::from_slice(&output).unwrap();
+  assert_eq!(stats["total_papers"], 3);
+  assert_eq!(stats["by_source"]["Arxiv"], 2);
+  assert_eq!(stats["added_by_month"].as_object().unwrap().len(), 1);
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_filter_by_tag() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[
+    test_paper("2401.00001", "Lattice Signatures", &["Alice"]),
+    test_paper("2401.00002", "Lattice Attacks", &["Bob"]),
+    test_paper("2401.00003", "Graph Isomorphism", &["Carol"]),
+  ])
+  .await;
+
+  for id in ["2401.00001", "2401.00003"] {
+    learnerd()
+      .args(["tag", "add", "arxiv", id, " Crypto ", "--path"])
+      .arg(&db_path)
+      .assert()
+      .success();
+  }
+  // Tags are normalized, so this is a duplicate
+  learnerd()
+    .args(["tag", "add", "arxiv", "2401.00001", "CRYPTO", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Already tagged as crypto"));
+
+  learnerd()
+    .args(["list", "--tag", "crypto", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Showing papers 1 to 2"))
+    .stdout(predicate::str::contains("Lattice Signatures"))
+    .stdout(predicate::str::contains("Graph Isomorphism"))
+    .stdout(predicate::str::contains("Lattice Attacks").not());
+
+  learnerd()
+    .args(["search", "lattice", "--tag", "Crypto", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 1 papers"))
+    .stdout(predicate::str::contains("Lattice Signatures"));
+
+  learnerd()
+    .args(["search", "--tag", "crypto", "--author", "Carol", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 1 papers"))
+    .stdout(predicate::str::contains("Graph Isomorphism"));
+
+  learnerd()
+    .args(["tag", "remove", "arxiv", "2401.00003", "crypto", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success();
+  learnerd()
+    .args(["search", "--tag", "crypto", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Found 1 papers"))
+    .stdout(predicate::str::contains("Lattice Signatures"));
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_tag_missing_paper() {
+  let (dir, db_path) = temp_db();
+
+  learnerd().arg("init").arg("--path").arg(&db_path).arg("-y").assert().success();
+
+  learnerd()
+    .args(["tag", "add", "arxiv", "2301.07041", "important", "--path"])
+    .arg(&db_path)
+    .assert()
+    .code(2);
+  learnerd()
+    .args(["tag", "list", "arxiv", "2301.07041", "--path"])
+    .arg(&db_path)
+    .assert()
+    .code(2)
+    .stdout(predicate::str::contains("Paper not found"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_reading_status() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[
+    test_paper("2401.00001", "On Computable Numbers", &["Alan Turing"]),
+    test_paper("2401.00002", "An Unsolvable Problem", &["Alonzo Church"]),
+  ])
+  .await;
+
+  learnerd()
+    .args(["status", "set", "arxiv", "2401.00001", "reading", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Marked as"));
+  learnerd()
+    .args(["status", "get", "arxiv", "2401.00001", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("reading"))
+    .stdout(predicate::str::contains("Started:"));
+  learnerd()
+    .args(["status", "set", "arxiv", "2401.00001", "finished", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure();
+
+  learnerd()
+    .args(["list", "--filter", "unread", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("An Unsolvable Problem"))
+    .stdout(predicate::str::contains("On Computable Numbers").not());
+  learnerd()
+    .args(["list", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("◐ reading"))
+    .stdout(predicate::str::contains("○ unread"));
+
+  // `mark` is a shorthand for `status set`, and search and list filter by status
+  learnerd()
+    .args(["mark", "read", "ARXIV", "2401.00002", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Marked as"));
+  learnerd()
+    .args(["search", "Unsolvable", "--status", "read", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("An Unsolvable Problem"));
+  learnerd()
+    .args(["search", "Unsolvable", "--status", "unread", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("An Unsolvable Problem").not());
+  learnerd()
+    .args(["search", "--author", "Turing", "--status", "reading", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("On Computable Numbers"));
+  learnerd()
+    .args(["list", "--status", "read", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("An Unsolvable Problem"))
+    .stdout(predicate::str::contains("On Computable Numbers").not());
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_note_shown_and_searchable() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[test_paper("2301.07041", "Verifiable Fully Homomorphic Encryption", &[
+    "Alexander Viand",
+  ])])
+  .await;
+
+  learnerd()
+    .args(["note", "add", "arxiv", "2301.07041", "Read for journal club", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Added note #1"));
+  learnerd()
+    .args(["note", "add", "arxiv", "2301.07041", "Nice construction in §4", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Added note #2"));
+  learnerd()
+    .args(["get", "arxiv", "2301.07041", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Notes:"))
+    .stdout(predicate::str::contains("Read for journal club"))
+    .stdout(predicate::str::contains("Nice construction in §4"));
+  learnerd()
+    .args(["search", "journal", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Verifiable Fully Homomorphic Encryption"));
+
+  learnerd()
+    .args(["note", "delete", "1", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Note deleted"));
+  learnerd()
+    .args(["note", "list", "arxiv", "2301.07041", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("#2"))
+    .stdout(predicate::str::contains("Read for journal club").not());
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_update_missing_paper() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[test_paper("2401.00001", "On Computable Numbers", &["Alan Turing"])]).await;
+
+  // Nothing is fetched for a paper that isn't in the database
+  learnerd()
+    .args(["update", "arxiv", "2401.99999", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure()
+    .stdout(predicate::str::contains("[1/1]"))
+    .stderr(predicate::str::contains("NotFound"));
+  learnerd().args(["update", "--path"]).arg(&db_path).assert().failure();
+
+  learnerd()
+    .args(["get", "arxiv", "2401.00001", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Updated:"));
+
+  dir.close().unwrap();
+}
+
+/// BibTeX file with two entries for the same DOI and one entry without a DOI
+const LIBRARY_BIB: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/library.bib");
+
+#[tokio::test]
+#[serial]
+async fn test_import_bibtex_skips_duplicates() {
+  let (dir, db_path) = temp_db();
+  let mut paper = test_paper("", "MapReduce", &["Jeffrey Dean"]);
+  paper.source = Source::DOI;
+  paper.source_identifier = "10.1145/1327452.1327492".to_string();
+  seed_db(&db_path, &[paper]).await;
+
+  // Nothing is fetched: both DOI entries are already in the database
+  learnerd()
+    .args(["import", "--format", "bibtex", LIBRARY_BIB, "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("[1/3]"))
+    .stdout(predicate::str::contains("turing1936: no DOI"))
+    .stdout(predicate::str::contains("Imported: 0, Skipped (duplicate): 2, Failed: 1"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_import_bibtex_by_eprint_and_url() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[test_paper("1706.03762", "Attention Is All You Need", &["Ashish Vaswani"])])
+    .await;
+  let bib_path = dir.path().join("preprints.bib");
+  std::fs::write(
+    &bib_path,
+    "@misc{vaswani2017, eprint = {1706.03762}, archivePrefix = {arXiv}}
+     @misc{vaswani2017url, url \
+     = {https://arxiv.org/abs/1706.03762}}
+     @misc{hal2020, eprint = {hal-01234567}, eprinttype = {HAL}}
+",
+  )
+  .unwrap();
+
+  // Both arXiv entries resolve to the paper already in the database, and the HAL entry is
+  // reported as not imported
+  learnerd()
+    .args(["import", "--path"])
+    .arg(&db_path)
+    .arg(&bib_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Imported: 0, Skipped (duplicate): 2, Failed: 1"))
+    .stdout(predicate::str::contains("Not imported:"))
+    .stdout(predicate::str::contains("hal2020: no DOI, arXiv eprint, or paper URL"));
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_import_bibtex_fetches_papers() {
+  let (dir, db_path) = temp_db();
+
+  learnerd()
+    .args(["import", LIBRARY_BIB, "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Imported: 1, Skipped (duplicate): 1, Failed: 1"));
+
+  learnerd()
+    .args(["get", "doi", "10.1145/1327452.1327492", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("MapReduce"));
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_import_invalid_bibtex() {
+  let (dir, db_path) = temp_db();
+  let bib_path = dir.path().join("broken.bib");
+  std::fs::write(&bib_path, "@article{broken,\n  title = {Unbalanced\n").unwrap();
+
+  learnerd()
+    .args(["import", "--path"])
+    .arg(&db_path)
+    .arg(&bib_path)
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("InvalidBibtex"));
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_add_many_all_invalid_fails() {
+  let (dir, db_path) = temp_db();
+
+  learnerd().arg("init").arg("--path").arg(&db_path).arg("-y").assert().success();
+
+  // Every identifier is reported, and the command fails only because none could be added
+  learnerd()
+    .args(["add", "not-an-id", "also/not/an/id", "--no-pdf", "--path"])
+    .arg(&db_path)
+    .arg("-y")
+    .assert()
+    .failure()
+    .stdout(predicate::str::contains("Failed to add not-an-id"))
+    .stdout(predicate::str::contains("Failed to add also/not/an/id"))
+    .stdout(predicate::str::contains("Added 0 papers (0 already present, 2 failed)"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_add_many_continues_past_failures() {
+  let (dir, db_path) = temp_db();
+
+  learnerd().arg("init").arg("--path").arg(&db_path).arg("-y").assert().success();
+
+  let output = learnerd()
+    .args(["add", "2301.07041", "not-an-id", "2016/260", "--no-pdf", "--path"])
+    .arg(&db_path)
+    .arg("-y")
+    .assert()
+    .success()
+    .get_output()
+    .stdout
+    .clone();
+  let stdout = String::from_utf8(output).unwrap();
+
+  assert_eq!(stdout.matches("Saved paper with ID").count(), 2);
+  assert_eq!(stdout.matches("Failed to add").count(), 1);
+  assert!(stdout.contains("Added 2 papers (0 already present, 1 failed)"));
+
+  dir.close().unwrap();
+}
+
 #[test]
 #[serial]
-fn test_init_and_clean() {
+fn test_add_dry_run() {
   let (dir, db_path) = temp_db();
 
-  // Initialize database
   learnerd()
-    .arg("init")
-    .arg("--path")
+    .args(["add", "not-an-id", "--dry-run", "--path"])
     .arg(&db_path)
-    .arg("--accept-defaults")
     .assert()
-    .success()
-    .stdout(predicate::str::contains("initialized successfully"));
+    .failure()
+    .stdout(predicate::str::contains("Failed to fetch not-an-id"));
 
-  assert!(db_path.exists());
+  let output = learnerd()
+    .args(["--output", "json", "add", "not-an-id", "--dry-run", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure()
+    .get_output()
+    .stdout
+    .clone();
+  let results: serde_json::Value = serde_json::from_slice(&output).unwrap();
+  assert_eq!(results[0]["identifier"], "not-an-id");
+  assert_eq!(results[0]["status"], "failed");
+
+  // Nothing was written, not even an empty database
+  assert!(!db_path.exists());
+
+  dir.close().unwrap();
+}
+
+/// Five identifiers in assorted formats and comments: one invalid and one repeated
+const IDENTIFIERS_TXT: &str =
+  concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/identifiers.txt");
+
+#[test]
+#[serial]
+fn test_add_from_file() {
+  let (dir, db_path) = temp_db();
 
-  // Clean with force flag
   learnerd()
-    .arg("clean")
-    .arg("--path")
+    .args(["add", "--from-file", IDENTIFIERS_TXT, "--no-pdf", "--path"])
     .arg(&db_path)
-    .arg("--accept-defaults")
     .assert()
     .success()
-    .stdout(predicate::str::contains("Database files cleaned"));
+    .stdout(predicate::str::contains("Fetching 5 papers"))
+    .stdout(predicate::str::contains("Failed to add not-an-id"))
+    .stdout(predicate::str::contains("Added: 3, Skipped (duplicate): 1, Failed: 1"));
+
+  learnerd().args(["get", "iacr", "2016/260", "--path"]).arg(&db_path).assert().success();
 
-  assert!(!db_path.exists());
   dir.close().unwrap();
 }
 
@@ -94,6 +1457,32 @@ async fn test_basic_paper_workflow() {
     .stdout(predicate::str::contains("Paper details"))
     .stdout(predicate::str::contains("Verifiable Fully Homomorphic"));
 
+  // Tag the paper and see the tags listed, including by `get`
+  learnerd()
+    .args(["tag", "add", "arxiv", "2301.07041", "important", "to-read", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Tagged as important"));
+  learnerd()
+    .args(["tag", "list", "arxiv", "2301.07041", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("important"))
+    .stdout(predicate::str::contains("to-read"));
+  learnerd()
+    .args(["tag", "remove", "arxiv", "2301.07041", "to-read", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success();
+  learnerd()
+    .args(["get", "arxiv", "2301.07041", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Tags: important"));
+
   // Search for the paper
   learnerd()
     .arg("search")
@@ -106,6 +1495,32 @@ async fn test_basic_paper_workflow() {
     .stdout(predicate::str::contains("Found"))
     .stdout(predicate::str::contains("Verifiable Fully Homomorphic"));
 
+  // Removing declines by default, so --accept-defaults alone keeps the paper
+  learnerd()
+    .arg("remove")
+    .arg("arxiv")
+    .arg("2301.07041")
+    .arg("--path")
+    .arg(&db_path)
+    .arg("--accept-defaults")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("pass --force"))
+    .stdout(predicate::str::contains("Removed paper").not());
+  learnerd().args(["get", "arxiv", "2301.07041", "--path"]).arg(&db_path).assert().success();
+
+  // Remove the paper
+  learnerd()
+    .arg("remove")
+    .arg("arxiv")
+    .arg("2301.07041")
+    .arg("--path")
+    .arg(&db_path)
+    .arg("--force")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Removed paper"));
+
   // Search for nonexistent paper
   learnerd()
     .arg("search")
@@ -119,3 +1534,342 @@ async fn test_basic_paper_workflow() {
 
   dir.close().unwrap();
 }
+
+#[test]
+#[serial]
+fn test_fetch_search_add() {
+  let (dir, db_path) = temp_db();
+
+  learnerd().arg("init").arg("--path").arg(&db_path).arg("-y").assert().success();
+
+  learnerd()
+    .args(["fetch-search", "verifiable fully homomorphic encryption", "--max", "2", "--add"])
+    .arg("--path")
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Added 2 papers (0 already present)"));
+
+  // Saving the same results again finds them already present
+  learnerd()
+    .args(["fetch-search", "verifiable fully homomorphic encryption", "--max", "2", "--add"])
+    .arg("--path")
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Added 0 papers (2 already present)"));
+
+  learnerd()
+    .args(["fetch-search", "anything", "--sort", "random"])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("Invalid sort order"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_search_live() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[test_paper("2401.00001", "Threshold Signatures", &["Alice"])]).await;
+
+  // Saved papers are shown whether or not the services can be reached
+  learnerd()
+    .args(["search", "threshold", "--live", "--limit", "2", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Threshold Signatures"))
+    .stdout(predicate::str::contains("Found 1 saved and"));
+
+  // Live searches need words to look for
+  learnerd()
+    .args(["search", "--author", "Alice", "--live", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure();
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_fetch_search_arguments() {
+  // Only arXiv and Crossref can be searched
+  learnerd()
+    .args(["fetch-search", "anything", "--source", "pubmed"])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("possible values: arxiv, doi"));
+
+  // Either every match is saved or one is picked
+  learnerd()
+    .args(["fetch-search", "anything", "--source", "DOI", "--add", "--pick"])
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_author() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[
+    test_paper("2401.00001", "Groth16", &["Jens Groth"]),
+    test_paper("2401.00002", "Sonic", &["Mary Maller", "Jens  Groth"]),
+    test_paper("2401.00003", "Graph Neural Networks", &["Bob"]),
+  ])
+  .await;
+
+  learnerd()
+    .args(["author", "jens groth", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("2 papers by jens groth"))
+    .stdout(predicate::str::contains("Sonic"))
+    .stdout(predicate::str::contains("Graph Neural Networks").not());
+  learnerd()
+    .args(["author", "Grace Hopper", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("No papers by Grace Hopper"));
+
+  learnerd()
+    .args(["author", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Jens Groth (2 papers)"))
+    .stdout(predicate::str::contains("Bob (1 papers)"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_deduplicate() {
+  let (dir, db_path) = temp_db();
+  seed_db(&db_path, &[
+    test_paper("2401.00001", "Verifiable Fully Homomorphic Encryption", &["Alice"]),
+    test_paper("2401.00002", "Graph Neural Networks", &["Bob"]),
+    test_paper("2401.00003", "Verifiable fully-homomorphic encryption.", &["Alice"]),
+  ])
+  .await;
+
+  learnerd()
+    .args(["deduplicate", "--dry-run", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Possible duplicates 1/1 (similarity 1.00)"))
+    .stdout(predicate::str::contains("Graph Neural Networks").not())
+    .stdout(predicate::str::contains("Pairs: 1, Removed: 0"));
+
+  // Without prompts, every paper is kept
+  let output = learnerd()
+    .args(["--output", "json", "--accept-defaults", "deduplicate", "--path"])
+    .arg(&db_path)
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  let pairs: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+  assert_eq!(pairs.as_array().unwrap().len(), 1);
+  assert_eq!(pairs[0]["first"]["source_identifier"], "2401.00001");
+  assert_eq!(pairs[0]["second"]["source_identifier"], "2401.00003");
+  assert_eq!(pairs[0]["removed"], serde_json::Value::Null);
+
+  learnerd()
+    .args(["deduplicate", "--threshold", "1.5", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("between 0 and 1"));
+  learnerd()
+    .args(["deduplicate", "--threshold", "1", "--dry-run", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Pairs: 1, Removed: 0"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_get_raw_abstract() {
+  let (dir, db_path) = temp_db();
+  let mut paper = test_paper("2401.00001", "Fast Sorting", &["Alice"]);
+  paper.abstract_text = "Sorting in \\mathcal{O}(n \\log n) time".to_string();
+  paper.raw_abstract = Some("Sorting in $\\mathcal{O}(n \\log n)$ time".to_string());
+  seed_db(&db_path, &[paper]).await;
+
+  learnerd()
+    .args(["get", "arxiv", "2401.00001", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Sorting in \\mathcal{O}(n \\log n) time"));
+  learnerd()
+    .args(["get", "arxiv", "2401.00001", "--raw", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Sorting in $\\mathcal{O}(n \\log n)$ time"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_source_names() {
+  let (dir, db_path) = temp_db();
+  let mut paper = test_paper("2401.00001", "Fast Sorting", &["Alice"]);
+  paper.source = Source::Arxiv;
+  seed_db(&db_path, &[paper]).await;
+
+  // The canonical lowercase name, whatever the case, finds the paper
+  for source in ["arxiv", "Arxiv", "ARXIV"] {
+    learnerd()
+      .args(["get", source, "2401.00001", "--path"])
+      .arg(&db_path)
+      .assert()
+      .success()
+      .stdout(predicate::str::contains("Fast Sorting"));
+  }
+  learnerd()
+    .args(["--output", "json", "get", "arxiv", "2401.00001", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("\"source_identifier\": \"2401.00001\""));
+
+  // Aliases are accepted, unknown sources list the canonical names
+  learnerd()
+    .args(["get", "s2", "204e3073870fae3d05bcbc2f6a8e263d9b72e776", "--path"])
+    .arg(&db_path)
+    .assert()
+    .stdout(predicate::str::contains("Fetching paper from semanticscholar"));
+  learnerd()
+    .args(["get", "jstor", "1", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("arxiv, iacr, doi"));
+
+  dir.close().unwrap();
+}
+
+#[test]
+#[serial]
+fn test_recommend_without_network() {
+  let (dir, db_path) = temp_db();
+
+  // IACR papers are only found on Semantic Scholar by the DOI of a saved paper
+  learnerd()
+    .args(["recommend", "iacr", "2023/123", "--path"])
+    .arg(&db_path)
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("can't be looked up on Semantic Scholar"));
+
+  learnerd()
+    .args(["recommend", "arxiv", "2301.07041", "--limit", "0", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("No related papers found"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_open_print_path() {
+  let (dir, db_path) = temp_db();
+  let pdf_dir = dir.path().join("pdfs");
+  let mut papers = [
+    test_paper("2401.00001", "Lattice Signatures", &["Alice"]),
+    test_paper("2401.00002", "Graph Neural Networks", &["Bob"]),
+    test_paper("2401.00003", "No PDF Anywhere", &["Carol"]),
+  ];
+  papers[1].pdf_url = Some(serve_pdf());
+  seed_db(&db_path, &papers).await;
+  for (key, value) in
+    [("pdf_dir", pdf_dir.to_str().unwrap()), ("pdf_filename_template", "{id}_{title}")]
+  {
+    learnerd().args(["config", "set", key, value, "--path"]).arg(&db_path).assert().success();
+  }
+  let open = |id: &str| {
+    let mut command = learnerd();
+    command.args(["open", "--print-path", "arxiv", id, "-y", "--path"]).arg(&db_path);
+    command
+  };
+
+  // A PDF already in the PDF directory under the configured name
+  let expected = pdf_dir.join("2401.00001_lattice_signatures.pdf");
+  std::fs::create_dir_all(&pdf_dir).unwrap();
+  std::fs::write(&expected, "%PDF-1.7 test\n%%EOF\n").unwrap();
+  open("2401.00001")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains(expected.display().to_string()));
+
+  // A missing PDF is downloaded first
+  let expected = pdf_dir.join("2401.00002_graph_neural_networks.pdf");
+  open("2401.00002")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Downloading PDF"))
+    .stdout(predicate::str::contains(expected.display().to_string()));
+  assert!(expected.exists());
+
+  open("2401.00003").assert().success().stdout(predicate::str::contains("No PDF available"));
+  open("2401.99999").assert().success().stdout(predicate::str::contains("Paper not found"));
+
+  dir.close().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_download_all() {
+  let (dir, db_path) = temp_db();
+  let pdf_dir = dir.path().join("pdfs");
+  let url = serve_pdf();
+  let mut papers = [
+    test_paper("2401.00001", "Lattice Signatures", &["Alice"]),
+    test_paper("2401.00002", "Graph Neural Networks", &["Bob"]),
+    test_paper("2401.00003", "No PDF Anywhere", &["Carol"]),
+  ];
+  papers[0].pdf_url = Some(url.clone());
+  papers[1].pdf_url = Some(url);
+  seed_db(&db_path, &papers).await;
+
+  learnerd()
+    .args(["config", "set", "pdf_dir"])
+    .arg(&pdf_dir)
+    .arg("--path")
+    .arg(&db_path)
+    .assert()
+    .success();
+  learnerd()
+    .args(["download", "--all", "--concurrency", "1", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Downloaded: 2, Failed: 0"));
+  assert!(pdf_dir.join("lattice_signatures.pdf").exists());
+  assert!(pdf_dir.join("graph_neural_networks.pdf").exists());
+
+  // Nothing is left to download
+  learnerd()
+    .args(["download", "--all", "--path"])
+    .arg(&db_path)
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Every paper with a PDF URL has its PDF"));
+
+  dir.close().unwrap();
+}